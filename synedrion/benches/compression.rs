@@ -0,0 +1,131 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use rand_core::OsRng;
+
+use synedrion::{
+    make_aux_gen_session, AuxGenResult, FinalizeOutcome, MessageBundle, ProtocolResult, Session,
+    SessionId, TestParams,
+};
+
+type AuxGenSession =
+    Session<AuxGenResult<TestParams, VerifyingKey>, Signature, SigningKey, VerifyingKey>;
+
+fn serialize(message: &MessageBundle<Signature>) -> Vec<u8> {
+    bincode::serde::encode_to_vec(message, bincode::config::standard()).unwrap()
+}
+
+fn deserialize(bytes: &[u8]) -> MessageBundle<Signature> {
+    bincode::serde::decode_borrowed_from_slice(bytes, bincode::config::standard()).unwrap()
+}
+
+/// Runs the sessions in lockstep in a single thread,
+/// returning the total size of the serialized messages sent.
+fn run_sessions<Res: ProtocolResult>(
+    sessions: Vec<Session<Res, Signature, SigningKey, VerifyingKey>>,
+) -> usize {
+    let mut sessions = sessions
+        .into_iter()
+        .map(|session| (session.verifier(), session))
+        .collect::<BTreeMap<_, _>>();
+    let mut total_size = 0;
+
+    loop {
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut messages = Vec::new();
+        for (id, session) in sessions.iter() {
+            for destination in session.message_destinations().iter() {
+                let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+                messages.push((*id, *destination, serialize(&message)));
+            }
+        }
+
+        for (from, to, bytes) in messages {
+            total_size += bytes.len();
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            let preprocessed = session
+                .preprocess_message(accum, &from, deserialize(&bytes))
+                .unwrap()
+                .unwrap();
+            let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(_) => {}
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+
+        if next_sessions.is_empty() {
+            return total_size;
+        }
+        sessions = next_sessions;
+    }
+}
+
+fn make_sessions(num_parties: usize, compression: bool) -> Vec<AuxGenSession> {
+    let signers = (0..num_parties)
+        .map(|_| SigningKey::random(&mut OsRng))
+        .collect::<Vec<_>>();
+    let verifiers = signers
+        .iter()
+        .map(|signer| *signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    signers
+        .into_iter()
+        .map(|signer| {
+            let mut session = make_aux_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng, session_id, signer, &verifiers,
+            )
+            .unwrap();
+            session.set_compression(compression);
+            session
+        })
+        .collect()
+}
+
+fn bench_compression(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compression");
+    group.sample_size(10);
+
+    let num_parties = 3;
+
+    for compression in [false, true] {
+        let total_size = run_sessions(make_sessions(num_parties, compression));
+        println!(
+            "AuxGen, {num_parties} parties, compression: {compression}, sent {total_size} bytes"
+        );
+
+        group.bench_function(
+            format!("AuxGen, {num_parties} parties, compression: {compression}"),
+            |b| {
+                b.iter_batched(
+                    || make_sessions(num_parties, compression),
+                    run_sessions,
+                    criterion::BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    group.finish()
+}
+
+criterion_group!(benches, bench_compression);
+
+criterion_main!(benches);
@@ -0,0 +1,164 @@
+//! Measures how the number and the total size of the messages sent in a protocol
+//! grow with the number of parties.
+//!
+//! Every round sends a message from each party to each other party, so the number of messages
+//! is `O(n^2)` per round; the messages of the echo rounds contain the broadcasts
+//! from all the other parties, so their total size is `O(n^3)`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use rand_core::OsRng;
+
+use synedrion::{
+    make_aux_gen_session, make_key_init_session, sessions::SessionInitError, FinalizeOutcome,
+    MessageBundle, ProtocolResult, Session, SessionId, TestParams,
+};
+
+fn serialize(message: &MessageBundle<Signature>) -> Vec<u8> {
+    bincode::serde::encode_to_vec(message, bincode::config::standard()).unwrap()
+}
+
+fn deserialize(bytes: &[u8]) -> MessageBundle<Signature> {
+    bincode::serde::decode_borrowed_from_slice(bytes, bincode::config::standard()).unwrap()
+}
+
+/// The traffic of a protocol run.
+#[derive(Default)]
+struct Traffic {
+    rounds: usize,
+    messages: usize,
+    bytes: usize,
+}
+
+/// Runs the sessions in lockstep in a single thread, returning the traffic generated.
+fn run_sessions<Res: ProtocolResult>(
+    sessions: Vec<Session<Res, Signature, SigningKey, VerifyingKey>>,
+) -> Traffic {
+    let mut sessions = sessions
+        .into_iter()
+        .map(|session| (session.verifier(), session))
+        .collect::<BTreeMap<_, _>>();
+    let mut traffic = Traffic::default();
+
+    loop {
+        traffic.rounds += 1;
+
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut messages = Vec::new();
+        for (id, session) in sessions.iter() {
+            for destination in session.message_destinations().iter() {
+                let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+                messages.push((*id, *destination, serialize(&message)));
+            }
+        }
+
+        for (from, to, bytes) in messages {
+            traffic.messages += 1;
+            traffic.bytes += bytes.len();
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            let preprocessed = session
+                .preprocess_message(accum, &from, deserialize(&bytes))
+                .unwrap()
+                .unwrap();
+            let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(_) => {}
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+
+        if next_sessions.is_empty() {
+            return traffic;
+        }
+        sessions = next_sessions;
+    }
+}
+
+type MakeSession<Res> =
+    fn(
+        SessionId,
+        SigningKey,
+        &BTreeSet<VerifyingKey>,
+    ) -> Result<Session<Res, Signature, SigningKey, VerifyingKey>, SessionInitError>;
+
+fn make_sessions<Res: ProtocolResult>(
+    num_parties: usize,
+    make_session: MakeSession<Res>,
+) -> Vec<Session<Res, Signature, SigningKey, VerifyingKey>> {
+    let signers = (0..num_parties)
+        .map(|_| SigningKey::random(&mut OsRng))
+        .collect::<Vec<_>>();
+    let verifiers = signers
+        .iter()
+        .map(|signer| *signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    signers
+        .into_iter()
+        .map(|signer| make_session(session_id, signer, &verifiers).unwrap())
+        .collect()
+}
+
+fn bench_protocol<Res: ProtocolResult>(
+    c: &mut Criterion,
+    name: &str,
+    parties: &[usize],
+    make_session: MakeSession<Res>,
+) {
+    let mut group = c.benchmark_group(format!("{name} scaling"));
+    group.sample_size(10);
+
+    for &num_parties in parties {
+        let traffic = run_sessions(make_sessions(num_parties, make_session));
+        println!(
+            "{name}, {num_parties} parties: {} rounds, {} messages, {} bytes",
+            traffic.rounds, traffic.messages, traffic.bytes
+        );
+
+        group.bench_function(format!("{name}, {num_parties} parties"), |b| {
+            b.iter_batched(
+                || make_sessions(num_parties, make_session),
+                run_sessions,
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish()
+}
+
+fn bench_scaling(c: &mut Criterion) {
+    bench_protocol(c, "KeyInit", &[10, 20], |session_id, signer, verifiers| {
+        make_key_init_session::<TestParams, Signature, _, _>(
+            &mut OsRng, session_id, signer, verifiers,
+        )
+    });
+
+    // Paillier key generation and the associated proofs make this one considerably slower.
+    bench_protocol(c, "AuxGen", &[10, 20], |session_id, signer, verifiers| {
+        make_aux_gen_session::<TestParams, Signature, _, _>(
+            &mut OsRng, session_id, signer, verifiers,
+        )
+    });
+}
+
+criterion_group!(benches, bench_scaling);
+
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+//! Using the Paillier encryption on its own: a tally of encrypted votes.
+//!
+//! The key holder proves that its key was generated correctly,
+//! the voters encrypt their votes, and the tallier adds them up
+//! without learning the individual votes.
+//!
+//! Run with `cargo run --example paillier --features paillier`.
+
+use rand_core::OsRng;
+
+use synedrion::{
+    crypto_bigint::U1024,
+    paillier::{ModulusProof, PaillierTest, SecretKey},
+    TestParams,
+};
+
+fn main() {
+    // `PaillierTest` uses small primes to keep the example fast;
+    // use `PaillierProduction` (with `ProductionParams` for the proof) in real applications.
+    let sk = SecretKey::<PaillierTest>::random(&mut OsRng);
+    let pk = sk.public_key();
+
+    let proof = ModulusProof::<TestParams>::new(&mut OsRng, &sk, b"election #1");
+    assert!(proof.verify(&mut OsRng, &pk, b"election #1"));
+    println!("The Paillier key is generated correctly");
+
+    let votes = [1u64, 0, 1, 1, 0];
+    let encrypted_votes = votes
+        .iter()
+        .map(|vote| pk.encrypt(&mut OsRng, &U1024::from_u64(*vote)))
+        .collect::<Vec<_>>();
+
+    let tally = encrypted_votes[1..]
+        .iter()
+        .fold(encrypted_votes[0].clone(), |acc, vote| {
+            pk.add(&acc, vote).unwrap()
+        });
+
+    let result = sk.decrypt(&tally).unwrap();
+    assert_eq!(result, U1024::from_u64(3));
+    println!("Votes in favor: {}", result.as_words()[0]);
+}
@@ -0,0 +1,328 @@
+//! Certificates attesting to the result of a key generation,
+//! verifiable by a third party knowing only the identities of the participants.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::ToString;
+use core::fmt::Debug;
+
+use displaydoc::Display;
+use k256::ecdsa::VerifyingKey;
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+use signature::hazmat::{PrehashVerifier, RandomizedPrehashSigner};
+
+use crate::cggmp21::{AuxInfo, KeyShare, PublicAuxInfo, SchemeParams, VerifierBundle};
+use crate::curve::Point;
+use crate::sessions::{LocalError, SessionId};
+use crate::tools::hashing::{Chain, FofHasher, HashOutput};
+use crate::www02::ThresholdKeyShare;
+
+/// The statement about the result of a key generation
+/// that each of the participating parties signs with its long-term identity key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyGenStatement<I: Ord> {
+    session_id: SessionId,
+    verifying_key: Point,
+    parties: BTreeSet<I>,
+    threshold: u32,
+    /// The hash of the public data of all the parties (public key shares and auxiliary info).
+    fingerprint: HashOutput,
+}
+
+impl<I: Ord + Clone + Debug + Serialize> KeyGenStatement<I> {
+    /// Creates the statement for a key share and the auxiliary info
+    /// obtained in the session with the given ID.
+    ///
+    /// All the parties holding the shares from the set are required for signing,
+    /// so the threshold is equal to their number.
+    pub fn new<P: SchemeParams>(
+        session_id: SessionId,
+        key_share: &KeyShare<P, I>,
+        aux_info: &AuxInfo<P, I>,
+    ) -> Self {
+        Self::from_public_data(session_id, &key_share.public_shares, &aux_info.public_aux)
+    }
+
+    /// Creates the statement for the public data of a key generation result
+    /// obtained in the session with the given ID.
+    ///
+    /// Allows a party not holding a share to check that a certificate describes the given key.
+    pub fn from_bundle<P: SchemeParams>(
+        session_id: SessionId,
+        bundle: &VerifierBundle<P, I>,
+    ) -> Self {
+        Self::from_public_data(session_id, &bundle.public_shares, &bundle.public_aux)
+    }
+
+    fn from_public_data<P: SchemeParams>(
+        session_id: SessionId,
+        public_shares: &BTreeMap<I, Point>,
+        public_aux: &BTreeMap<I, PublicAuxInfo<P>>,
+    ) -> Self {
+        let fingerprint = FofHasher::new_with_dst(b"KeyGenStatement")
+            .chain(&session_id)
+            .chain(public_shares)
+            .chain(public_aux)
+            .finalize();
+        Self {
+            session_id,
+            verifying_key: public_shares.values().sum(),
+            parties: public_shares.keys().cloned().collect(),
+            // TODO (#68): the number of parties should be checked on key share creation
+            threshold: public_shares.len() as u32,
+            fingerprint,
+        }
+    }
+
+    /// Creates the statement for a threshold key share and the auxiliary info
+    /// obtained in the session with the given ID.
+    pub fn new_threshold<P: SchemeParams>(
+        session_id: SessionId,
+        key_share: &ThresholdKeyShare<P, I>,
+        aux_info: &AuxInfo<P, I>,
+    ) -> Self {
+        let fingerprint = FofHasher::new_with_dst(b"KeyGenStatement")
+            .chain(&session_id)
+            .chain(&key_share.share_ids)
+            .chain(&key_share.public_shares)
+            .chain(&aux_info.public_aux)
+            .finalize();
+        Self {
+            session_id,
+            verifying_key: key_share.verifying_key_as_point(),
+            parties: key_share.public_shares.keys().cloned().collect(),
+            threshold: key_share.threshold,
+            fingerprint,
+        }
+    }
+
+    /// The ID of the session where the key was generated.
+    pub fn session_id(&self) -> SessionId {
+        self.session_id
+    }
+
+    /// The joint verifying key.
+    ///
+    /// Returns `None` if the public shares sum to the identity point,
+    /// in which case the statement does not describe a valid key.
+    pub fn verifying_key(&self) -> Option<VerifyingKey> {
+        self.verifying_key.to_verifying_key()
+    }
+
+    /// The parties holding the shares of the key.
+    pub fn parties(&self) -> &BTreeSet<I> {
+        &self.parties
+    }
+
+    /// The number of shares required for signing.
+    pub fn threshold(&self) -> usize {
+        self.threshold as usize
+    }
+
+    fn hash(&self) -> HashOutput {
+        FofHasher::new_with_dst(b"KeyGenCertificate")
+            .chain(self)
+            .finalize()
+    }
+
+    /// Signs the statement with the long-term identity key of a party.
+    pub fn sign<Sig>(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        signer: &impl RandomizedPrehashSigner<Sig>,
+    ) -> Result<Sig, LocalError> {
+        signer
+            .sign_prehash_with_rng(rng, self.hash().as_ref())
+            .map_err(|err| LocalError(err.to_string()))
+    }
+}
+
+/// Possible errors when verifying a [`KeyGenCertificate`].
+#[derive(Debug, Clone, Display)]
+pub enum CertificateError<I> {
+    /// The set of signers does not match the set of parties in the statement.
+    MismatchedSigners,
+    /// The signature of a party is invalid.
+    InvalidSignature(I),
+    /// The verifying key in the statement is the identity point.
+    InvalidVerifyingKey,
+}
+
+/// A key generation statement signed by all the participating parties.
+///
+/// The party identifiers are used as the verifiers of their signatures.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyGenCertificate<Sig, I: Ord> {
+    statement: KeyGenStatement<I>,
+    signatures: BTreeMap<I, Sig>,
+}
+
+impl<Sig, I> KeyGenCertificate<Sig, I>
+where
+    I: Ord + Clone + Debug + Serialize + PrehashVerifier<Sig>,
+{
+    /// Assembles a certificate from the statement and the signatures of all the parties,
+    /// verifying it in the process.
+    pub fn new(
+        statement: KeyGenStatement<I>,
+        signatures: BTreeMap<I, Sig>,
+    ) -> Result<Self, CertificateError<I>> {
+        let certificate = Self {
+            statement,
+            signatures,
+        };
+        certificate.verify()?;
+        Ok(certificate)
+    }
+
+    /// Verifies that the statement is signed by every party listed in it, and only by them,
+    /// and that it describes a valid verifying key.
+    pub fn verify(&self) -> Result<(), CertificateError<I>> {
+        if self.statement.verifying_key == Point::IDENTITY {
+            return Err(CertificateError::InvalidVerifyingKey);
+        }
+
+        if !self.signatures.keys().eq(self.statement.parties.iter()) {
+            return Err(CertificateError::MismatchedSigners);
+        }
+
+        let hash = self.statement.hash();
+        for (party, signature) in self.signatures.iter() {
+            party
+                .verify_prehash(hash.as_ref(), signature)
+                .map_err(|_| CertificateError::InvalidSignature(party.clone()))?;
+        }
+
+        Ok(())
+    }
+
+    /// The signed statement.
+    pub fn statement(&self) -> &KeyGenStatement<I> {
+        &self.statement
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+    use alloc::vec::Vec;
+
+    use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+
+    use super::{CertificateError, KeyGenCertificate, KeyGenStatement};
+    use crate::cggmp21::{AuxInfo, KeyShare, TestParams, VerifierBundle};
+    use crate::curve::Point;
+    use crate::sessions::SessionId;
+    use crate::www02::ThresholdKeyShare;
+
+    #[test]
+    fn sign_and_verify() {
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let session_id = SessionId::from_seed(b"abcde");
+
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+
+        let statements = ids
+            .iter()
+            .map(|id| {
+                (
+                    *id,
+                    KeyGenStatement::new(session_id, &key_shares[id], &aux_infos[id]),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        // All the parties arrive at the same statement
+        let statement = statements.values().next().unwrap().clone();
+        assert!(statements.values().all(|s| s == &statement));
+        assert_eq!(statement.parties(), &ids);
+        assert_eq!(statement.threshold(), 3);
+        assert_eq!(
+            statement.verifying_key(),
+            Some(key_shares.values().next().unwrap().verifying_key())
+        );
+
+        let signatures = signers
+            .iter()
+            .map(|signer| {
+                (
+                    *signer.verifying_key(),
+                    statement.sign::<Signature>(&mut OsRng, signer).unwrap(),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let certificate = KeyGenCertificate::new(statement.clone(), signatures.clone()).unwrap();
+        certificate.verify().unwrap();
+
+        // A party without a share can check that the certificate is about the given public data
+        let id = ids.first().unwrap();
+        let bundle = VerifierBundle::new(&key_shares[id], &aux_infos[id]).unwrap();
+        assert_eq!(
+            &KeyGenStatement::from_bundle(certificate.statement().session_id(), &bundle),
+            certificate.statement()
+        );
+
+        // A missing signature
+        let mut incomplete = signatures.clone();
+        incomplete.pop_first();
+        assert!(matches!(
+            KeyGenCertificate::new(statement.clone(), incomplete),
+            Err(CertificateError::MismatchedSigners)
+        ));
+
+        // A signature of a different statement
+        let other_statement = KeyGenStatement::new(
+            SessionId::from_seed(b"fghij"),
+            key_shares.values().next().unwrap(),
+            aux_infos.values().next().unwrap(),
+        );
+        let (id, signer) = (signers[0].verifying_key(), &signers[0]);
+        let mut wrong = signatures;
+        wrong.insert(*id, other_statement.sign(&mut OsRng, signer).unwrap());
+        assert!(matches!(
+            KeyGenCertificate::new(statement.clone(), wrong),
+            Err(CertificateError::InvalidSignature(party)) if &party == id
+        ));
+
+        // A statement about the identity point is rejected before the signatures are checked
+        let mut degenerate = statement;
+        degenerate.verifying_key = Point::IDENTITY;
+        assert_eq!(degenerate.verifying_key(), None);
+        assert!(matches!(
+            KeyGenCertificate::<Signature, _>::new(degenerate, BTreeMap::new()),
+            Err(CertificateError::InvalidVerifyingKey)
+        ));
+    }
+
+    #[test]
+    fn threshold_statement() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let session_id = SessionId::from_seed(b"abcde");
+
+        let key_shares = ThresholdKeyShare::<TestParams, VerifyingKey>::new_centralized(
+            &mut OsRng, &ids, 2, None,
+        );
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+
+        let id = ids.first().unwrap();
+        let statement = KeyGenStatement::new_threshold(session_id, &key_shares[id], &aux_infos[id]);
+        assert_eq!(statement.threshold(), 2);
+        assert_eq!(statement.parties(), &ids);
+        assert_eq!(
+            statement.verifying_key(),
+            Some(key_shares[id].verifying_key())
+        );
+    }
+}
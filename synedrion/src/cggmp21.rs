@@ -7,21 +7,65 @@
 //! The equation and figure numbers in the comments, and the notation used
 //! refers to the version of the paper published at <https://eprint.iacr.org/2021/060.pdf>
 
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+mod aux_data;
 mod entities;
 mod params;
+#[cfg(feature = "signing")]
+mod presignature_store;
 mod protocols;
 mod sigma;
 
-pub use entities::{AuxInfo, KeyShare, KeyShareChange, PresigningData};
-pub(crate) use entities::{PublicAuxInfo, SecretAuxInfo};
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+pub use aux_data::{AuxDataError, AuxDataProof};
+#[cfg(feature = "signing")]
+pub use entities::PresigningData;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+pub(crate) use entities::SecretAuxInfo;
+pub use entities::{
+    AuxInfo, KeyShare, KeyShareChange, KeySharePart, PresigningPublic, PublicAuxInfo,
+    VerifierBundle,
+};
+pub(crate) use params::params_fingerprint;
 pub use params::{ProductionParams, SchemeParams, TestParams};
-pub(crate) use protocols::{aux_gen, interactive_signing, key_gen, key_init, key_refresh};
+#[cfg(feature = "signing")]
+pub use presignature_store::PresignatureStore;
+#[cfg(feature = "aux-gen")]
+pub(crate) use protocols::aux_gen;
+#[cfg(feature = "signing")]
+pub(crate) use protocols::batch_signing;
+#[cfg(feature = "signing")]
+pub(crate) use protocols::interactive_signing;
+#[cfg(feature = "key-gen")]
+pub(crate) use protocols::key_init;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+pub use protocols::KeyRefreshResult;
+#[cfg(feature = "aux-gen")]
+pub use protocols::{AuxGenError, AuxGenResult};
+#[cfg(feature = "signing")]
+pub use protocols::{
+    BatchSigningError, BatchSigningProof, BatchSigningResult, InteractiveSigningError,
+    InteractiveSigningProof, InteractiveSigningResult, PartialSignature, PresigningError,
+    PresigningProof, PresigningResult, SigningError, SigningProof, SigningResult,
+};
+#[cfg(feature = "key-gen")]
 pub use protocols::{
-    AuxGenError, AuxGenResult, InteractiveSigningError, InteractiveSigningProof,
-    InteractiveSigningResult, KeyGenError, KeyGenProof, KeyGenResult, KeyInitError, KeyInitResult,
-    KeyRefreshResult, PresigningError, PresigningProof, PresigningResult, SigningProof,
-    SigningResult,
+    FastKeyInitError, FastKeyInitResult, KeyGenError, KeyGenProof, KeyGenResult, KeyInitError,
+    KeyInitResult, UnsafeAssumeReliableBroadcast,
 };
 
-#[cfg(feature = "bench-internals")]
-pub(crate) use protocols::{presigning, signing};
+#[cfg(feature = "paillier")]
+pub use params::{PaillierProduction, PaillierTest};
+#[cfg(feature = "signing")]
+pub(crate) use protocols::signing;
+#[cfg(any(feature = "bench-internals", feature = "spec"))]
+pub(crate) use protocols::{key_refresh, presigning};
+#[cfg(feature = "paillier")]
+pub(crate) use sigma::ModProof;
+
+#[cfg(feature = "spec")]
+pub(crate) use params::PARAMS_HASH_TAG;
+#[cfg(feature = "spec")]
+pub(crate) use protocols::{fast_key_init, key_gen};
+#[cfg(feature = "spec")]
+pub(crate) use sigma::proof_types;
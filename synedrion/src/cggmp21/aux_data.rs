@@ -0,0 +1,181 @@
+//! Re-verifying the stored public auxiliary data outside of a protocol session.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::fmt::Debug;
+
+use displaydoc::Display;
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    entities::{AuxInfo, VerifierBundle},
+    sigma::ModProof,
+    SchemeParams,
+};
+use crate::curve::Point;
+use crate::uint::UintLike;
+
+/// A proof that the Paillier modulus of a party is a product of two suitable primes,
+/// created with [`AuxInfo::prove_aux_data`].
+///
+/// The proof is bound to the owner of the auxiliary data and to the application-defined `aux` bytes
+/// (e.g. the ID of the key), so it cannot be replayed for another party or another key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "ModProof<P>: Serialize"))]
+#[serde(bound(deserialize = "ModProof<P>: for<'x> Deserialize<'x>"))]
+pub struct AuxDataProof<P: SchemeParams>(ModProof<P>);
+
+/// Possible errors when verifying the public auxiliary data.
+#[derive(Debug, Clone, Display)]
+pub enum AuxDataError<I: Debug> {
+    /// The Paillier modulus of {0:?} is too small.
+    ModulusTooSmall(I),
+    /// The Paillier modulus of {0:?} is the same as the one of another party.
+    DuplicateModulus(I),
+    /// The ring-Pedersen parameters of {0:?} are not reduced, trivial, or not invertible.
+    InvalidRingPedersenParams(I),
+    /// The El-Gamal public key of {0:?} is the identity.
+    InvalidElGamalKey(I),
+    /// There is no proof for the auxiliary data of {0:?}.
+    MissingProof(I),
+    /// There is a proof for {0:?}, who is not one of the share holders.
+    UnexpectedProof(I),
+    /// The proof for the Paillier modulus of {0:?} is invalid.
+    InvalidProof(I),
+}
+
+impl<P: SchemeParams, I: Ord + Clone + Serialize> AuxInfo<P, I> {
+    /// Creates a proof that the Paillier modulus of the owner is well-formed,
+    /// which can later be checked against the stored public data with
+    /// [`VerifierBundle::verify_aux_data`].
+    ///
+    /// `aux` must be the same bytes the verifier will use.
+    pub fn prove_aux_data(&self, rng: &mut impl CryptoRngCore, aux: &[u8]) -> AuxDataProof<P> {
+        let sk = self.secret_aux.paillier_sk.to_precomputed();
+        AuxDataProof(ModProof::new(rng, &sk, &(&self.owner, aux)))
+    }
+}
+
+impl<P: SchemeParams, I: Ord + Clone + Debug + Serialize> VerifierBundle<P, I> {
+    /// Checks that the public auxiliary data of every party is well-formed.
+    ///
+    /// These are the checks of the AuxGen protocol that do not require the proofs
+    /// exchanged during it. The ring-Pedersen parameters are only checked to be well-formed,
+    /// since the secret needed to prove their correctness is not kept after AuxGen.
+    pub fn check_aux_data(&self) -> Result<(), AuxDataError<I>> {
+        let mut moduli = BTreeSet::new();
+        for (id, public_aux) in self.public_aux.iter() {
+            let paillier_pk = public_aux.paillier_pk.to_precomputed();
+            if paillier_pk.modulus().bits_vartime() < 8 * P::SECURITY_PARAMETER {
+                return Err(AuxDataError::ModulusTooSmall(id.clone()));
+            }
+            if !moduli.insert(*paillier_pk.modulus()) {
+                return Err(AuxDataError::DuplicateModulus(id.clone()));
+            }
+            if public_aux.rp_params.to_mod_checked(&paillier_pk).is_none()
+                || !public_aux.rp_params.is_nontrivial(&paillier_pk)
+            {
+                return Err(AuxDataError::InvalidRingPedersenParams(id.clone()));
+            }
+            if public_aux.el_gamal_pk == Point::IDENTITY {
+                return Err(AuxDataError::InvalidElGamalKey(id.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Performs the checks of [`check_aux_data`](`Self::check_aux_data`),
+    /// and verifies the proofs of the Paillier moduli of all the parties,
+    /// created with [`AuxInfo::prove_aux_data`] using the same `aux` bytes.
+    pub fn verify_aux_data(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        proofs: &BTreeMap<I, AuxDataProof<P>>,
+        aux: &[u8],
+    ) -> Result<(), AuxDataError<I>> {
+        self.check_aux_data()?;
+
+        if let Some(id) = proofs.keys().find(|id| !self.public_aux.contains_key(id)) {
+            return Err(AuxDataError::UnexpectedProof(id.clone()));
+        }
+
+        for (id, public_aux) in self.public_aux.iter() {
+            let proof = proofs
+                .get(id)
+                .ok_or_else(|| AuxDataError::MissingProof(id.clone()))?;
+            let paillier_pk = public_aux.paillier_pk.to_precomputed();
+            if !proof.0.verify(rng, &paillier_pk, &(id, aux)) {
+                return Err(AuxDataError::InvalidProof(id.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    use k256::ecdsa::{SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+
+    use super::AuxDataError;
+    use crate::cggmp21::{AuxInfo, KeyShare, TestParams, VerifierBundle};
+    use crate::curve::Point;
+
+    #[test]
+    fn verify_aux_data() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<VerifyingKey>>();
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+        let id = *ids.first().unwrap();
+        let bundle = VerifierBundle::new(&key_shares[&id], &aux_infos[&id]).unwrap();
+
+        let proofs = aux_infos
+            .iter()
+            .map(|(id, aux_info)| (*id, aux_info.prove_aux_data(&mut OsRng, b"key")))
+            .collect::<BTreeMap<_, _>>();
+        assert!(bundle.verify_aux_data(&mut OsRng, &proofs, b"key").is_ok());
+
+        // The proofs are bound to `aux`
+        assert!(matches!(
+            bundle.verify_aux_data(&mut OsRng, &proofs, b"another key"),
+            Err(AuxDataError::InvalidProof(_))
+        ));
+
+        // The proofs are bound to the owner
+        let mut swapped = proofs.clone();
+        let other_id = *ids.last().unwrap();
+        swapped.insert(id, proofs[&other_id].clone());
+        assert!(matches!(
+            bundle.verify_aux_data(&mut OsRng, &swapped, b"key"),
+            Err(AuxDataError::InvalidProof(_))
+        ));
+
+        let mut missing = proofs.clone();
+        missing.remove(&id);
+        assert!(matches!(
+            bundle.verify_aux_data(&mut OsRng, &missing, b"key"),
+            Err(AuxDataError::MissingProof(missing_id)) if missing_id == id
+        ));
+
+        // Corrupted stored data
+        let mut corrupted = bundle.clone();
+        let other_aux = corrupted.public_aux[&other_id].clone();
+        corrupted.public_aux.insert(id, other_aux);
+        assert!(matches!(
+            corrupted.check_aux_data(),
+            Err(AuxDataError::DuplicateModulus(_))
+        ));
+
+        let mut corrupted = bundle.clone();
+        corrupted.public_aux.get_mut(&id).unwrap().el_gamal_pk = Point::IDENTITY;
+        assert!(matches!(
+            corrupted.check_aux_data(),
+            Err(AuxDataError::InvalidElGamalKey(bad_id)) if bad_id == id
+        ));
+    }
+}
@@ -3,25 +3,41 @@ use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec::Vec;
 use core::fmt::Debug;
 use core::marker::PhantomData;
+#[cfg(feature = "signing")]
+use core::time::Duration;
 
-use k256::ecdsa::VerifyingKey;
+use k256::ecdsa::{SigningKey, VerifyingKey};
 use rand_core::CryptoRngCore;
 use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
+use signature::hazmat::PrehashVerifier;
 
 use crate::cggmp21::SchemeParams;
-use crate::curve::{Point, Scalar};
+use crate::constructors::PrehashedMessage;
+use crate::curve::{deserialize_points_or_identity, Point, RecoverableSignature, Scalar};
+use crate::lineage::{outcome_fingerprint, Ceremony, Lineage};
+#[cfg(feature = "signing")]
 use crate::paillier::{
-    CiphertextMod, PaillierParams, PublicKeyPaillier, PublicKeyPaillierPrecomputed, RPParams,
-    RPParamsMod, Randomizer, SecretKeyPaillier, SecretKeyPaillierPrecomputed,
+    CiphertextMod, PublicKeyPaillierPrecomputed, Randomizer, SecretKeyPaillierPrecomputed,
 };
+use crate::paillier::{
+    PaillierParams, PublicKeyPaillier, RPParams, RPParamsMod, SecretKeyPaillier,
+};
+#[cfg(feature = "signing")]
+use crate::sessions::Clock;
+use crate::tools::hashing::HashOutput;
+use crate::uint::subtle::{Choice, ConstantTimeEq};
+#[cfg(feature = "signing")]
 use crate::uint::Signed;
 
 #[cfg(any(test, feature = "bench-internals"))]
 use crate::paillier::RandomizerMod;
 
 /// The result of the KeyInit protocol.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Does not implement [`Serialize`] to prevent the secret share from being stored
+/// or logged in plaintext by accident; use [`KeyShare::expose_for_encryption`].
+#[derive(Debug, Clone, Deserialize)]
 pub struct KeyShare<P, I: Ord> {
     pub(crate) owner: I,
     /// Secret key share of this node.
@@ -29,28 +45,49 @@ pub struct KeyShare<P, I: Ord> {
     pub(crate) public_shares: BTreeMap<I, Point>, // `X_j`
     // TODO (#27): this won't be needed when Scalar/Point are a part of `P`
     pub(crate) phantom: PhantomData<P>,
+    pub(crate) lineage: Option<Lineage>,
+}
+
+/// One of the two parts of a key share obtained with [`KeyShare::split_secret`].
+///
+/// A single part reveals nothing about the secret share,
+/// so the parts can be kept in separate storage locations
+/// (e.g. on a device and in a backup) and joined with [`KeySharePart::reassemble`] when needed.
+/// It is serialized with [`KeySharePart::expose_for_encryption`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeySharePart<P, I: Ord> {
+    pub(crate) owner: I,
+    /// An additive part of the secret share of this node.
+    pub(crate) secret_part: SecretBox<Scalar>,
+    pub(crate) public_shares: BTreeMap<I, Point>,
+    // TODO (#27): this won't be needed when Scalar/Point are a part of `P`
+    pub(crate) phantom: PhantomData<P>,
+    pub(crate) lineage: Option<Lineage>,
 }
 
 /// The result of the AuxGen protocol.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Does not implement [`Serialize`] to prevent the secret keys from being stored
+/// or logged in plaintext by accident; use [`AuxInfo::expose_for_encryption`].
+#[derive(Debug, Clone, Deserialize)]
 pub struct AuxInfo<P: SchemeParams, I: Ord> {
     pub(crate) owner: I,
     pub(crate) secret_aux: SecretAuxInfo<P>,
     pub(crate) public_aux: BTreeMap<I, PublicAuxInfo<P>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(bound(serialize = "SecretKeyPaillier<P::Paillier>: Serialize"))]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(bound(deserialize = "SecretKeyPaillier<P::Paillier>: for <'x> Deserialize<'x>"))]
 pub(crate) struct SecretAuxInfo<P: SchemeParams> {
     pub(crate) paillier_sk: SecretKeyPaillier<P::Paillier>,
     pub(crate) el_gamal_sk: SecretBox<Scalar>, // `y_i`
 }
 
+/// The public auxiliary data of one of the parties, resulting from the AuxGen protocol.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(bound(serialize = "PublicKeyPaillier<P::Paillier>: Serialize"))]
-#[serde(bound(deserialize = "PublicKeyPaillier<P::Paillier>: for <'x> Deserialize<'x>"))]
-pub(crate) struct PublicAuxInfo<P: SchemeParams> {
+#[serde(bound(serialize = "<P::Paillier as PaillierParams>::Uint: Serialize"))]
+#[serde(bound(deserialize = "<P::Paillier as PaillierParams>::Uint: for <'x> Deserialize<'x>"))]
+pub struct PublicAuxInfo<P: SchemeParams> {
     pub(crate) el_gamal_pk: Point, // `Y_i`
     /// The Paillier public key.
     pub(crate) paillier_pk: PublicKeyPaillier<P::Paillier>,
@@ -58,12 +95,50 @@ pub(crate) struct PublicAuxInfo<P: SchemeParams> {
     pub(crate) rp_params: RPParams<P::Paillier>, // `s_i` and `t_i`
 }
 
+/// The public data resulting from the KeyGen protocol (or from KeyInit and AuxGen),
+/// allowing a party that does not hold a share to verify the signatures
+/// and the statements about the key.
+///
+/// Deserialization performs the same consistency checks as [`VerifierBundle::new`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedVerifierBundle<P, I>")]
+pub struct VerifierBundle<P: SchemeParams, I: Ord> {
+    pub(crate) public_shares: BTreeMap<I, Point>,
+    pub(crate) public_aux: BTreeMap<I, PublicAuxInfo<P>>,
+}
+
+// A bundle as it was deserialized, before the checks.
+#[derive(Deserialize)]
+struct UncheckedVerifierBundle<P: SchemeParams, I: Ord> {
+    public_shares: BTreeMap<I, Point>,
+    public_aux: BTreeMap<I, PublicAuxInfo<P>>,
+}
+
+impl<P: SchemeParams, I: Ord> TryFrom<UncheckedVerifierBundle<P, I>> for VerifierBundle<P, I> {
+    type Error = &'static str;
+
+    fn try_from(bundle: UncheckedVerifierBundle<P, I>) -> Result<Self, Self::Error> {
+        if !bundle.public_shares.keys().eq(bundle.public_aux.keys()) {
+            return Err("The public shares and the auxiliary data belong to different parties");
+        }
+        if bundle.public_shares.values().sum::<Point>() == Point::IDENTITY {
+            return Err("The public shares sum to the identity point");
+        }
+        Ok(Self {
+            public_shares: bundle.public_shares,
+            public_aux: bundle.public_aux,
+        })
+    }
+}
+
+#[cfg(feature = "signing")]
 #[derive(Clone)]
 pub(crate) struct AuxInfoPrecomputed<P: SchemeParams, I> {
     pub(crate) secret_aux: SecretAuxInfoPrecomputed<P>,
     pub(crate) public_aux: BTreeMap<I, PublicAuxInfoPrecomputed<P>>,
 }
 
+#[cfg(feature = "signing")]
 #[derive(Clone)]
 pub(crate) struct SecretAuxInfoPrecomputed<P: SchemeParams> {
     pub(crate) paillier_sk: SecretKeyPaillierPrecomputed<P::Paillier>,
@@ -71,6 +146,7 @@ pub(crate) struct SecretAuxInfoPrecomputed<P: SchemeParams> {
     pub(crate) el_gamal_sk: SecretBox<Scalar>, // `y_i`
 }
 
+#[cfg(feature = "signing")]
 #[derive(Clone)]
 pub(crate) struct PublicAuxInfoPrecomputed<P: SchemeParams> {
     #[allow(dead_code)] // TODO (#36): this will be needed for the 6-round presigning protocol.
@@ -80,17 +156,24 @@ pub(crate) struct PublicAuxInfoPrecomputed<P: SchemeParams> {
 }
 
 /// The result of the Auxiliary Info & Key Refresh protocol - the update to the key share.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// It is serialized with [`KeyShareChange::expose_for_encryption`].
+#[derive(Debug, Clone, Deserialize)]
 pub struct KeyShareChange<P: SchemeParams, I: Ord> {
     pub(crate) owner: I,
     /// The value to be added to the secret share.
     pub(crate) secret_share_change: SecretBox<Scalar>, // `x_i^* - x_i == \sum_{j} x_j^i`
     /// The values to be added to the public shares of remote nodes.
+    // With a single party the change is zero, so the identity point is allowed here.
+    #[serde(deserialize_with = "deserialize_points_or_identity")]
     pub(crate) public_share_changes: BTreeMap<I, Point>, // `X_k^* - X_k == \sum_j X_j^k`, for all nodes
     // TODO (#27): this won't be needed when Scalar/Point are a part of `P`
     pub(crate) phantom: PhantomData<P>,
+    /// The fingerprint of the session, recorded in the lineage of the updated share.
+    pub(crate) session: HashOutput,
 }
 
+#[cfg(feature = "signing")]
 /// The result of the Presigning protocol.
 #[derive(Debug, Clone)]
 pub struct PresigningData<P: SchemeParams, I> {
@@ -109,8 +192,51 @@ pub struct PresigningData<P: SchemeParams, I> {
 
     // The values for $j$, $j != i$.
     pub(crate) values: BTreeMap<I, PresigningValues<P>>,
+
+    pub(crate) public: PresigningPublic<I>,
+
+    /// The creation time, as measured by the clock given to [`PresigningData::set_timestamp`].
+    pub(crate) created_at: Option<Duration>,
+    pub(crate) ttl: Option<Duration>,
+}
+
+/// The public part of the presigning data of all the parties,
+/// allowing to verify their partial signatures individually.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(deserialize = "I: Ord + Deserialize<'de>"))]
+pub struct PresigningPublic<I> {
+    pub(crate) cap_r: Point, // $R$
+    pub(crate) shares: BTreeMap<I, PresigningPublicShare>,
+}
+
+#[cfg(feature = "signing")]
+impl<I> PresigningPublic<I> {
+    /// Checks that the shares add up to the values determined by the verifying key,
+    /// that is $\sum_i R^{k_i} = G$ and $\sum_i R^{\chi_i} = X$.
+    ///
+    /// This does not confirm the shares of the individual parties.
+    pub(crate) fn is_consistent_with(&self, verifying_key: &Point) -> bool {
+        let cap_r_k = self
+            .shares
+            .values()
+            .map(|share| share.cap_r_k)
+            .sum::<Point>();
+        let cap_r_chi = self
+            .shares
+            .values()
+            .map(|share| share.cap_r_chi)
+            .sum::<Point>();
+        cap_r_k == Point::GENERATOR && &cap_r_chi == verifying_key
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct PresigningPublicShare {
+    pub(crate) cap_r_k: Point,   // $R^{k_i}$
+    pub(crate) cap_r_chi: Point, // $R^{\chi_i}$
 }
 
+#[cfg(feature = "signing")]
 #[derive(Debug, Clone)]
 pub(crate) struct PresigningValues<P: SchemeParams> {
     pub(crate) hat_beta: Signed<<P::Paillier as PaillierParams>::Uint>,
@@ -124,9 +250,125 @@ pub(crate) struct PresigningValues<P: SchemeParams> {
     pub(crate) hat_cap_f: CiphertextMod<P::Paillier>,
 }
 
+// The serializable forms of the secret-bearing types, borrowing their contents.
+// They produce the same output as the types would if they derived `Serialize`,
+// so that the values can be deserialized directly.
+
+#[derive(Serialize)]
+#[serde(rename = "KeyShare", bound(serialize = "I: Serialize"))]
+struct ExposedKeyShare<'a, P, I> {
+    owner: &'a I,
+    secret_share: &'a Scalar,
+    public_shares: &'a BTreeMap<I, Point>,
+    phantom: PhantomData<P>,
+    lineage: &'a Option<Lineage>,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "KeySharePart", bound(serialize = "I: Serialize"))]
+struct ExposedKeySharePart<'a, P, I> {
+    owner: &'a I,
+    secret_part: &'a Scalar,
+    public_shares: &'a BTreeMap<I, Point>,
+    phantom: PhantomData<P>,
+    lineage: &'a Option<Lineage>,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "AuxInfo", bound(serialize = "I: Serialize"))]
+struct ExposedAuxInfo<'a, P: SchemeParams, I> {
+    owner: &'a I,
+    secret_aux: ExposedSecretAuxInfo<'a, P>,
+    public_aux: &'a BTreeMap<I, PublicAuxInfo<P>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "SecretAuxInfo")]
+#[serde(bound(serialize = "SecretKeyPaillier<P::Paillier>: Serialize"))]
+struct ExposedSecretAuxInfo<'a, P: SchemeParams> {
+    paillier_sk: &'a SecretKeyPaillier<P::Paillier>,
+    el_gamal_sk: &'a Scalar,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "KeyShareChange", bound(serialize = "I: Serialize"))]
+struct ExposedKeyShareChange<'a, P, I> {
+    owner: &'a I,
+    secret_share_change: &'a Scalar,
+    public_share_changes: &'a BTreeMap<I, Point>,
+    phantom: PhantomData<P>,
+    session: &'a HashOutput,
+}
+
+impl<P, I: Ord + Serialize> KeyShare<P, I> {
+    /// Returns the serializable form of the key share, including the secret share.
+    ///
+    /// The result should be encrypted before it leaves the memory
+    /// (e.g. with `compat::encode_bound_share`).
+    pub fn expose_for_encryption(&self) -> impl Serialize + '_ {
+        ExposedKeyShare {
+            owner: &self.owner,
+            secret_share: self.secret_share.expose_secret(),
+            public_shares: &self.public_shares,
+            phantom: PhantomData::<P>,
+            lineage: &self.lineage,
+        }
+    }
+}
+
+impl<P, I: Ord + Serialize> KeySharePart<P, I> {
+    /// Returns the serializable form of the key share part, including the secret part.
+    pub fn expose_for_encryption(&self) -> impl Serialize + '_ {
+        ExposedKeySharePart {
+            owner: &self.owner,
+            secret_part: self.secret_part.expose_secret(),
+            public_shares: &self.public_shares,
+            phantom: PhantomData::<P>,
+            lineage: &self.lineage,
+        }
+    }
+}
+
+impl<P: SchemeParams, I: Ord + Serialize> AuxInfo<P, I> {
+    /// Returns the serializable form of the auxiliary data,
+    /// including the secret Paillier and El-Gamal keys.
+    ///
+    /// The result should be encrypted before it leaves the memory
+    /// (e.g. with `compat::encode_bound_share`).
+    pub fn expose_for_encryption(&self) -> impl Serialize + '_ {
+        ExposedAuxInfo {
+            owner: &self.owner,
+            secret_aux: ExposedSecretAuxInfo {
+                paillier_sk: &self.secret_aux.paillier_sk,
+                el_gamal_sk: self.secret_aux.el_gamal_sk.expose_secret(),
+            },
+            public_aux: &self.public_aux,
+        }
+    }
+}
+
+impl<P: SchemeParams, I: Ord + Serialize> KeyShareChange<P, I> {
+    /// Returns the serializable form of the key share change, including the secret part.
+    pub fn expose_for_encryption(&self) -> impl Serialize + '_ {
+        ExposedKeyShareChange {
+            owner: &self.owner,
+            secret_share_change: self.secret_share_change.expose_secret(),
+            public_share_changes: &self.public_share_changes,
+            phantom: PhantomData::<P>,
+            session: &self.session,
+        }
+    }
+}
+
 impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> KeyShare<P, I> {
-    /// Updates a key share with a change obtained from KeyRefresh protocol.
-    pub(crate) fn update(self, change: KeyShareChange<P, I>) -> Self {
+    /// Updates a key share with a change obtained from KeyRefresh protocol,
+    /// recording the refresh in the lineage of the share (if it has one).
+    ///
+    /// Panics if the change belongs to a different party.
+    pub fn update(self, change: KeyShareChange<P, I>) -> Self
+    where
+        I: Serialize,
+    {
         // TODO (#68): check that party_idx is the same for both, and the number of parties is the same
         assert_eq!(self.owner, change.owner);
 
@@ -137,13 +379,23 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> KeyShare<P, I> {
             .public_shares
             .iter()
             .map(|(id, public_share)| (id.clone(), public_share + &change.public_share_changes[id]))
-            .collect();
+            .collect::<BTreeMap<_, _>>();
+        let lineage = self.lineage.map(|lineage| {
+            // A refresh is performed by all the holders of the key.
+            let all_ids = public_shares.keys().cloned().collect();
+            lineage.extend(
+                Ceremony::KeyRefresh,
+                change.session,
+                outcome_fingerprint(&all_ids, &public_shares),
+            )
+        });
 
         Self {
             owner: self.owner,
             secret_share,
             public_shares,
             phantom: PhantomData,
+            lineage,
         }
     }
 
@@ -152,7 +404,7 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> KeyShare<P, I> {
     pub fn new_centralized(
         rng: &mut impl CryptoRngCore,
         ids: &BTreeSet<I>,
-        signing_key: Option<&k256::ecdsa::SigningKey>,
+        signing_key: Option<&SigningKey>,
     ) -> BTreeMap<I, Self> {
         let secret = match signing_key {
             None => Scalar::random(rng),
@@ -176,12 +428,38 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> KeyShare<P, I> {
                         secret_share: SecretBox::new(Box::new(secret_share)),
                         public_shares: public_shares.clone(),
                         phantom: PhantomData,
+                        lineage: None,
                     },
                 )
             })
             .collect()
     }
 
+    /// Creates a key share for the degenerate 1-of-1 case,
+    /// where `owner` is the only party and holds the whole signing key.
+    pub fn from_signing_key(owner: I, signing_key: &SigningKey) -> Self {
+        let secret = Scalar::from_signing_key(signing_key);
+        Self {
+            owner: owner.clone(),
+            secret_share: SecretBox::new(Box::new(secret)),
+            public_shares: BTreeMap::from([(owner, secret.mul_by_generator())]),
+            phantom: PhantomData,
+            lineage: None,
+        }
+    }
+
+    /// Returns the full signing key if this is the only share in the set
+    /// (the degenerate 1-of-1 case), or `None` otherwise.
+    ///
+    /// Signatures produced by this key and by the signing protocol run by the single party
+    /// verify against the same `k256` verifying key.
+    pub fn to_signing_key(&self) -> Option<SigningKey> {
+        if self.public_shares.len() != 1 {
+            return None;
+        }
+        self.secret_share.expose_secret().to_signing_key()
+    }
+
     pub(crate) fn verifying_key_as_point(&self) -> Point {
         self.public_shares.values().sum()
     }
@@ -198,10 +476,113 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> KeyShare<P, I> {
         &self.owner
     }
 
+    /// Returns the record of the ceremonies this key share went through,
+    /// or `None` if it was not created by one of the protocols.
+    pub fn lineage(&self) -> Option<&Lineage> {
+        self.lineage.as_ref()
+    }
+
     /// Returns the set of parties holding other shares from the set.
     pub fn all_parties(&self) -> BTreeSet<I> {
         self.public_shares.keys().cloned().collect()
     }
+
+    /// Splits the secret share into two additive parts
+    /// which can be stored separately and reassembled with [`KeySharePart::reassemble`].
+    ///
+    /// This is a local operation not involving the other parties.
+    pub fn split_secret(
+        self,
+        rng: &mut impl CryptoRngCore,
+    ) -> (KeySharePart<P, I>, KeySharePart<P, I>) {
+        let part_a = Scalar::random(rng);
+        let part_b = self.secret_share.expose_secret() - &part_a;
+
+        let make_part = |secret_part| KeySharePart {
+            owner: self.owner.clone(),
+            secret_part: SecretBox::new(Box::new(secret_part)),
+            public_shares: self.public_shares.clone(),
+            phantom: PhantomData,
+            lineage: self.lineage.clone(),
+        };
+
+        (make_part(part_a), make_part(part_b))
+    }
+}
+
+impl<P, I: Ord> ConstantTimeEq for KeyShare<P, I> {
+    /// Compares the key shares, with the secret shares compared in constant time
+    /// (the rest of the data is public).
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let public_eq = self.owner == other.owner && self.public_shares == other.public_shares;
+        Choice::from(u8::from(public_eq))
+            & self
+                .secret_share
+                .expose_secret()
+                .ct_eq(other.secret_share.expose_secret())
+    }
+}
+
+impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> KeySharePart<P, I> {
+    /// Joins two parts obtained from [`KeyShare::split_secret`] back into a key share.
+    ///
+    /// Returns `None` if the parts do not belong to the same key share.
+    pub fn reassemble(self, other: Self) -> Option<KeyShare<P, I>> {
+        if self.owner != other.owner
+            || self.public_shares != other.public_shares
+            || self.lineage != other.lineage
+        {
+            return None;
+        }
+
+        let secret_share = self.secret_part.expose_secret() + other.secret_part.expose_secret();
+        if !bool::from(
+            secret_share
+                .mul_by_generator()
+                .ct_eq(self.public_shares.get(&self.owner)?),
+        ) {
+            return None;
+        }
+
+        Some(KeyShare {
+            owner: self.owner,
+            secret_share: SecretBox::new(Box::new(secret_share)),
+            public_shares: self.public_shares,
+            phantom: PhantomData,
+            lineage: self.lineage,
+        })
+    }
+
+    /// Returns the owner of the key share this part belongs to.
+    pub fn owner(&self) -> &I {
+        &self.owner
+    }
+}
+
+impl<P: SchemeParams> PublicAuxInfo<P> {
+    /// Returns the El-Gamal public key (`Y`),
+    /// or `None` if it is the identity (which the protocols never produce).
+    pub fn el_gamal_key(&self) -> Option<VerifyingKey> {
+        self.el_gamal_pk.to_verifying_key()
+    }
+
+    /// Returns the modulus of the Paillier public key (`N`).
+    #[cfg(feature = "paillier")]
+    pub fn paillier_modulus(&self) -> &<P::Paillier as PaillierParams>::Uint {
+        self.paillier_pk.modulus()
+    }
+
+    /// Returns the base of the ring-Pedersen parameters (`t`).
+    #[cfg(feature = "paillier")]
+    pub fn rp_base(&self) -> &<P::Paillier as PaillierParams>::Uint {
+        &self.rp_params.base
+    }
+
+    /// Returns the power of the ring-Pedersen parameters (`s`).
+    #[cfg(feature = "paillier")]
+    pub fn rp_power(&self) -> &<P::Paillier as PaillierParams>::Uint {
+        &self.rp_params.power
+    }
 }
 
 impl<P: SchemeParams, I: Ord + Clone> AuxInfo<P, I> {
@@ -210,6 +591,17 @@ impl<P: SchemeParams, I: Ord + Clone> AuxInfo<P, I> {
         &self.owner
     }
 
+    /// Returns the public auxiliary data of the given party,
+    /// or `None` if the party is not one of the holders of the auxiliary data.
+    pub fn public_aux(&self, party: &I) -> Option<&PublicAuxInfo<P>> {
+        self.public_aux.get(party)
+    }
+
+    /// Returns the public auxiliary data of all the parties.
+    pub fn public_aux_all(&self) -> &BTreeMap<I, PublicAuxInfo<P>> {
+        &self.public_aux
+    }
+
     /// Creates a set of random self-consistent auxiliary data.
     /// (which in a decentralized case would be the output of AuxGen protocol).
     pub fn new_centralized(rng: &mut impl CryptoRngCore, ids: &BTreeSet<I>) -> BTreeMap<I, Self> {
@@ -251,6 +643,7 @@ impl<P: SchemeParams, I: Ord + Clone> AuxInfo<P, I> {
             .collect()
     }
 
+    #[cfg(feature = "signing")]
     pub(crate) fn to_precomputed(&self) -> AuxInfoPrecomputed<P, I> {
         AuxInfoPrecomputed {
             secret_aux: SecretAuxInfoPrecomputed {
@@ -276,7 +669,115 @@ impl<P: SchemeParams, I: Ord + Clone> AuxInfo<P, I> {
     }
 }
 
+impl<P: SchemeParams, I: Ord + Clone> VerifierBundle<P, I> {
+    /// Extracts the public data from a key share and the auxiliary data of any of the parties.
+    ///
+    /// Returns `None` if the key share and the auxiliary data belong to different sets of parties,
+    /// or if the public shares sum to the identity point.
+    pub fn new(key_share: &KeyShare<P, I>, aux_info: &AuxInfo<P, I>) -> Option<Self> {
+        UncheckedVerifierBundle {
+            public_shares: key_share.public_shares.clone(),
+            public_aux: aux_info.public_aux.clone(),
+        }
+        .try_into()
+        .ok()
+    }
+
+    /// Return the verifying key to which the shares correspond,
+    /// or `None` if the public shares sum to the identity point.
+    pub fn verifying_key(&self) -> Option<VerifyingKey> {
+        self.public_shares
+            .values()
+            .sum::<Point>()
+            .to_verifying_key()
+    }
+
+    /// Returns the set of parties holding the shares.
+    pub fn all_parties(&self) -> BTreeSet<I> {
+        self.public_shares.keys().cloned().collect()
+    }
+
+    /// Returns the public share of the given party,
+    /// or `None` if the party is not one of the share holders.
+    pub fn public_share(&self, party: &I) -> Option<VerifyingKey> {
+        self.public_shares.get(party)?.to_verifying_key()
+    }
+
+    /// Returns the public auxiliary data of the given party,
+    /// or `None` if the party is not one of the share holders.
+    pub fn public_aux(&self, party: &I) -> Option<&PublicAuxInfo<P>> {
+        self.public_aux.get(party)
+    }
+
+    /// Checks that the signature of a prehashed message is valid for the verifying key,
+    /// and that the verifying key can be recovered from it.
+    pub fn verify_signature(
+        &self,
+        prehashed_message: &PrehashedMessage,
+        signature: &RecoverableSignature,
+    ) -> bool {
+        let verifying_key = match self.verifying_key() {
+            Some(verifying_key) => verifying_key,
+            None => return false,
+        };
+        let (signature, recovery_id) = signature.to_backend();
+        if verifying_key
+            .verify_prehash(prehashed_message, &signature)
+            .is_err()
+        {
+            return false;
+        }
+        VerifyingKey::recover_from_prehash(prehashed_message, &signature, recovery_id)
+            .is_ok_and(|recovered| recovered == verifying_key)
+    }
+}
+
+#[cfg(feature = "signing")]
 impl<P: SchemeParams, I: Ord + Clone + PartialEq> PresigningData<P, I> {
+    /// Returns the public part of the presigning data of all the parties.
+    pub fn public(&self) -> &PresigningPublic<I> {
+        &self.public
+    }
+
+    /// Records the current time of `clock` as the creation time,
+    /// and the time-to-live after which the data can no longer be used for signing
+    /// (`None` for unlimited).
+    ///
+    /// A presigning data share is only useful to an attacker until it is used,
+    /// so limiting its lifetime limits the window in which a leaked share can be exploited.
+    ///
+    /// The timestamp can only be set once, so that the lifetime cannot be extended:
+    /// returns `false` and leaves the data unchanged if it is already set.
+    pub fn set_timestamp(&mut self, clock: &dyn Clock, ttl: Option<Duration>) -> bool {
+        if self.created_at.is_some() {
+            return false;
+        }
+        self.created_at = Some(clock.now());
+        self.ttl = ttl;
+        true
+    }
+
+    /// Returns the creation time set with [`Self::set_timestamp`].
+    pub fn created_at(&self) -> Option<Duration> {
+        self.created_at
+    }
+
+    /// Returns the time-to-live set with [`Self::set_timestamp`].
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    /// Returns `true` if the time-to-live has passed according to `clock`
+    /// (which must be the one the timestamp was set with).
+    pub fn is_expired(&self, clock: &dyn Clock) -> bool {
+        match (self.created_at, self.ttl) {
+            (Some(created_at), Some(ttl)) => created_at
+                .checked_add(ttl)
+                .is_some_and(|expires_at| clock.now() >= expires_at),
+            _ => false,
+        }
+    }
+
     /// Creates a consistent set of presigning data for testing purposes.
     #[cfg(any(test, feature = "bench-internals"))]
     pub(crate) fn new_centralized(
@@ -287,11 +788,8 @@ impl<P: SchemeParams, I: Ord + Clone + PartialEq> PresigningData<P, I> {
         let ids = key_shares.keys().cloned().collect::<BTreeSet<_>>();
 
         let ephemeral_scalar = Scalar::random(rng);
-        let nonce = ephemeral_scalar
-            .invert()
-            .unwrap()
-            .mul_by_generator()
-            .x_coordinate();
+        let cap_r = ephemeral_scalar.invert().unwrap().mul_by_generator();
+        let nonce = cap_r.x_coordinate();
         let ephemeral_scalar_shares = ephemeral_scalar.split(rng, key_shares.len());
 
         let ephemeral_scalar_shares = ids
@@ -402,34 +900,58 @@ impl<P: SchemeParams, I: Ord + Clone + PartialEq> PresigningData<P, I> {
                 + alpha_sum
                 + beta_sum;
 
-            presigning.insert(
-                id_i.clone(),
-                PresigningData {
+            presigning.insert(id_i.clone(), (k_i, product_share_nonreduced, values));
+        }
+
+        let public = PresigningPublic {
+            cap_r,
+            shares: presigning
+                .iter()
+                .map(|(id, (k_i, product_share_nonreduced, _values))| {
+                    let share = PresigningPublicShare {
+                        cap_r_k: cap_r * k_i,
+                        cap_r_chi: cap_r * P::scalar_from_signed(product_share_nonreduced),
+                    };
+                    (id.clone(), share)
+                })
+                .collect(),
+        };
+
+        presigning
+            .into_iter()
+            .map(|(id, (k_i, product_share_nonreduced, values))| {
+                let data = PresigningData {
                     nonce,
                     ephemeral_scalar_share: SecretBox::new(Box::new(k_i)),
                     product_share: SecretBox::new(Box::new(P::scalar_from_signed(
                         &product_share_nonreduced,
                     ))),
                     product_share_nonreduced,
-                    cap_k: all_cap_k[&id_i].clone(),
+                    cap_k: all_cap_k[&id].clone(),
                     values,
-                },
-            );
-        }
-
-        presigning
+                    public: public.clone(),
+                    created_at: None,
+                    ttl: None,
+                };
+                (id, data)
+            })
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::boxed::Box;
     use alloc::collections::BTreeSet;
 
     use k256::ecdsa::{SigningKey, VerifyingKey};
     use rand_core::OsRng;
+    use secrecy::{ExposeSecret, SecretBox};
 
-    use super::KeyShare;
+    use super::{AuxInfo, KeyShare, PublicAuxInfo, VerifierBundle};
     use crate::cggmp21::TestParams;
+    use crate::curve::{Point, Scalar};
+    use crate::uint::subtle::ConstantTimeEq;
 
     #[test]
     fn key_share_centralized() {
@@ -445,4 +967,216 @@ mod tests {
             .values()
             .all(|share| &share.verifying_key() == sk.verifying_key()));
     }
+
+    #[test]
+    fn key_share_one_of_one() {
+        let sk = SigningKey::random(&mut OsRng);
+        let id = *SigningKey::random(&mut OsRng).verifying_key();
+        let ids = BTreeSet::from([id]);
+
+        let shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, Some(&sk));
+        let share = &shares[&id];
+        assert_eq!(share.to_signing_key(), Some(sk.clone()));
+        assert_eq!(&share.verifying_key(), sk.verifying_key());
+
+        let share = KeyShare::<TestParams, VerifyingKey>::from_signing_key(id, &sk);
+        assert_eq!(share.to_signing_key(), Some(sk.clone()));
+        assert_eq!(share.all_parties(), ids);
+
+        // More than one share: the full key is not available.
+        let ids = (0..2)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, Some(&sk));
+        assert!(shares
+            .values()
+            .all(|share| share.to_signing_key().is_none()));
+    }
+
+    #[test]
+    fn key_share_split_secret() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let mut shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let id = *ids.first().unwrap();
+        let share = shares.remove(&id).unwrap();
+        let other_share = shares.values().next().unwrap().clone();
+
+        let (part_a, part_b) = share.clone().split_secret(&mut OsRng);
+        let (part_c, _part_d) = share.clone().split_secret(&mut OsRng);
+        assert_eq!(part_a.owner(), &id);
+
+        let reassembled = part_a.clone().reassemble(part_b.clone()).unwrap();
+        assert_eq!(
+            reassembled.secret_share.expose_secret(),
+            share.secret_share.expose_secret()
+        );
+        assert_eq!(reassembled.public_shares, share.public_shares);
+
+        // The order of parts does not matter
+        assert!(part_b.clone().reassemble(part_a.clone()).is_some());
+
+        // Parts from different splits do not match
+        assert!(part_a.clone().reassemble(part_c).is_none());
+
+        // Parts of different shares do not match
+        let (other_part_a, _other_part_b) = other_share.split_secret(&mut OsRng);
+        assert!(part_a.clone().reassemble(other_part_a).is_none());
+
+        // A tampered part is rejected
+        let mut tampered = part_b;
+        tampered.secret_part = SecretBox::new(Box::new(Scalar::random(&mut OsRng)));
+        assert!(part_a.reassemble(tampered).is_none());
+    }
+
+    #[test]
+    fn key_share_ct_eq() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let shares = KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let mut shares = shares.into_values();
+        let share = shares.next().unwrap();
+        let other_share = shares.next().unwrap();
+
+        assert!(bool::from(share.ct_eq(&share.clone())));
+        assert!(!bool::from(share.ct_eq(&other_share)));
+
+        let mut tampered = share.clone();
+        tampered.secret_share = SecretBox::new(Box::new(Scalar::random(&mut OsRng)));
+        assert!(!bool::from(share.ct_eq(&tampered)));
+    }
+
+    #[test]
+    fn public_aux_info() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+        let id = ids.first().unwrap();
+        let aux_info = &aux_infos[id];
+        let bundle = VerifierBundle::new(&key_shares[id], aux_info).unwrap();
+
+        assert_eq!(aux_info.public_aux_all().len(), ids.len());
+        for other_id in ids.iter() {
+            let public_aux = aux_info.public_aux(other_id).unwrap();
+            let bundle_public_aux = bundle.public_aux(other_id).unwrap();
+            assert_eq!(
+                public_aux.paillier_pk.modulus(),
+                bundle_public_aux.paillier_pk.modulus()
+            );
+            assert!(public_aux.el_gamal_key().is_some());
+            assert_ne!(public_aux.rp_params.base, public_aux.rp_params.power);
+
+            // The secret parts match the public ones of their owner
+            let secret_aux = &aux_infos[other_id].secret_aux;
+            let sk = secret_aux.paillier_sk.to_precomputed();
+            assert_eq!(public_aux.paillier_pk.modulus(), sk.public_key().modulus());
+            let el_gamal_pk = secret_aux.el_gamal_sk.expose_secret().mul_by_generator();
+            assert_eq!(public_aux.el_gamal_key(), el_gamal_pk.to_verifying_key());
+        }
+
+        let unknown_id = *SigningKey::random(&mut OsRng).verifying_key();
+        assert!(aux_info.public_aux(&unknown_id).is_none());
+        assert!(bundle.public_aux(&unknown_id).is_none());
+
+        // The public part can be stored on its own
+        let public_aux = aux_info.public_aux(id).unwrap();
+        let serialized =
+            bincode::serde::encode_to_vec(public_aux, bincode::config::standard()).unwrap();
+        let (deserialized, _): (PublicAuxInfo<TestParams>, _) =
+            bincode::serde::decode_from_slice(&serialized, bincode::config::standard()).unwrap();
+        assert_eq!(
+            deserialized.paillier_pk.modulus(),
+            public_aux.paillier_pk.modulus()
+        );
+        assert_eq!(deserialized.el_gamal_key(), public_aux.el_gamal_key());
+    }
+
+    #[test]
+    fn verifier_bundle_deserialization() {
+        use bincode::serde::decode_from_slice;
+        type Bundle = VerifierBundle<TestParams, VerifyingKey>;
+
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let id = ids.first().unwrap();
+        let config = bincode::config::standard();
+
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+        let bundle = VerifierBundle::new(&key_shares[id], &aux_infos[id]).unwrap();
+
+        let serialized = bincode::serde::encode_to_vec(&bundle, config).unwrap();
+        let (deserialized, _): (Bundle, _) = decode_from_slice(&serialized, config).unwrap();
+        assert_eq!(deserialized.verifying_key(), bundle.verifying_key());
+
+        // The public shares summing to the identity
+        let mut tampered = bundle.clone();
+        let others_sum = tampered.public_shares.values().skip(1).sum::<Point>();
+        *tampered.public_shares.values_mut().next().unwrap() = others_sum * (-Scalar::ONE);
+        assert_eq!(tampered.verifying_key(), None);
+        let serialized = bincode::serde::encode_to_vec(&tampered, config).unwrap();
+        assert!(decode_from_slice::<Bundle, _>(&serialized, config).is_err());
+
+        // The public shares and the auxiliary data of different parties
+        let mut tampered = bundle.clone();
+        tampered.public_aux.pop_first();
+        let serialized = bincode::serde::encode_to_vec(&tampered, config).unwrap();
+        assert!(decode_from_slice::<Bundle, _>(&serialized, config).is_err());
+    }
+
+    #[test]
+    fn expose_for_encryption() {
+        let ids = (0..2)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let id = ids.first().unwrap();
+        let config = bincode::config::standard();
+
+        let key_share =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None)
+                .remove(id)
+                .unwrap();
+        let serialized =
+            bincode::serde::encode_to_vec(key_share.expose_for_encryption(), config).unwrap();
+        let (deserialized, _): (KeyShare<TestParams, VerifyingKey>, _) =
+            bincode::serde::decode_from_slice(&serialized, config).unwrap();
+        assert!(bool::from(deserialized.ct_eq(&key_share)));
+
+        let aux_info = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids)
+            .remove(id)
+            .unwrap();
+        let serialized =
+            bincode::serde::encode_to_vec(aux_info.expose_for_encryption(), config).unwrap();
+        let (deserialized, _): (AuxInfo<TestParams, VerifyingKey>, _) =
+            bincode::serde::decode_from_slice(&serialized, config).unwrap();
+        assert_eq!(deserialized.owner(), aux_info.owner());
+        assert_eq!(
+            deserialized.secret_aux.el_gamal_sk.expose_secret(),
+            aux_info.secret_aux.el_gamal_sk.expose_secret()
+        );
+        assert_eq!(
+            deserialized
+                .secret_aux
+                .paillier_sk
+                .to_precomputed()
+                .public_key()
+                .modulus(),
+            aux_info
+                .secret_aux
+                .paillier_sk
+                .to_precomputed()
+                .public_key()
+                .modulus()
+        );
+    }
 }
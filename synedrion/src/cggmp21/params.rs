@@ -2,7 +2,7 @@ use core::fmt::Debug;
 
 use crate::curve::{Curve, Scalar, ORDER};
 use crate::paillier::PaillierParams;
-use crate::tools::hashing::{Chain, HashableType};
+use crate::tools::hashing::{Chain, FofHasher, HashOutput, HashableType};
 use crate::uint::{
     subtle::ConditionallySelectable, upcast_uint, Bounded, Encoding, NonZero, Signed, U1024Mod,
     U2048Mod, U4096Mod, U512Mod, Zero, U1024, U2048, U4096, U512, U8192,
@@ -10,6 +10,7 @@ use crate::uint::{
 
 use serde::{Deserialize, Serialize};
 
+/// Paillier parameters **for testing purposes only** (used in [`TestParams`]).
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PaillierTest;
 
@@ -65,6 +66,7 @@ impl PaillierParams for PaillierTest {
     type ExtraWideUint = U4096;
 }
 
+/// Paillier parameters with 1024-bit primes (used in [`ProductionParams`]).
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PaillierProduction;
 
@@ -122,16 +124,23 @@ pub trait SchemeParams: Debug + Clone + Send + PartialEq + Eq + Send + Sync + 's
         Self::bounded_from_scalar(value).into_signed().unwrap()
     }
 
-    /// Converts an integer to the associated curve scalar type.
+    /// Converts an integer to the associated curve scalar type, reducing it modulo the curve order.
     fn scalar_from_uint(value: &<Self::Paillier as PaillierParams>::Uint) -> Scalar {
         let r = *value % Self::CURVE_ORDER;
+        scalar_from_reduced_repr(r.to_be_bytes().as_ref())
+    }
 
-        let repr = r.to_be_bytes();
-        let uint_len = repr.as_ref().len();
-        let scalar_len = Scalar::repr_len();
-
-        // Can unwrap here since the value is within the Scalar range
-        Scalar::try_from_bytes(&repr.as_ref()[uint_len - scalar_len..]).unwrap()
+    /// Converts an integer to the associated curve scalar type,
+    /// returning `None` if it is not in the range `[0, q)` instead of reducing it.
+    ///
+    /// Should be used where a value out of range indicates an error,
+    /// e.g. for a decrypted plaintext that was supposed to be a scalar.
+    fn try_scalar_from_uint(value: &<Self::Paillier as PaillierParams>::Uint) -> Option<Scalar> {
+        if value < Self::CURVE_ORDER.as_ref() {
+            Some(scalar_from_reduced_repr(value.to_be_bytes().as_ref()))
+        } else {
+            None
+        }
     }
 
     /// Converts a `Signed`-wrapped integer to the associated curve scalar type.
@@ -140,16 +149,11 @@ pub trait SchemeParams: Debug + Clone + Send + PartialEq + Eq + Send + Sync + 's
         Scalar::conditional_select(&abs_value, &-abs_value, value.is_negative())
     }
 
-    /// Converts a wide integer to the associated curve scalar type.
+    /// Converts a wide integer to the associated curve scalar type,
+    /// reducing it modulo the curve order.
     fn scalar_from_wide_uint(value: &<Self::Paillier as PaillierParams>::WideUint) -> Scalar {
         let r = *value % Self::CURVE_ORDER_WIDE;
-
-        let repr = r.to_be_bytes();
-        let uint_len = repr.as_ref().len();
-        let scalar_len = Scalar::repr_len();
-
-        // Can unwrap here since the value is within the Scalar range
-        Scalar::try_from_bytes(&repr.as_ref()[uint_len - scalar_len..]).unwrap()
+        scalar_from_reduced_repr(r.to_be_bytes().as_ref())
     }
 
     /// Converts a `Signed`-wrapped wide integer to the associated curve scalar type.
@@ -161,12 +165,37 @@ pub trait SchemeParams: Debug + Clone + Send + PartialEq + Eq + Send + Sync + 's
     }
 }
 
+/// Converts a big-endian integer representation known to be in the range `[0, q)` to a scalar.
+fn scalar_from_reduced_repr(repr: &[u8]) -> Scalar {
+    let scalar_len = Scalar::repr_len();
+    // Can unwrap here since the value is within the Scalar range
+    Scalar::try_from_bytes(&repr[repr.len() - scalar_len..]).unwrap()
+}
+
 impl<P: SchemeParams> HashableType for P {
     fn chain_type<C: Chain>(digest: C) -> C {
-        digest.chain_type::<Curve>()
+        // The integer types are fully determined by the curve and the bounds,
+        // so it is enough to hash those.
+        digest
+            .chain_type::<Curve>()
+            .chain(&(P::SECURITY_PARAMETER as u64))
+            .chain(&(P::L_BOUND as u64))
+            .chain(&(P::LP_BOUND as u64))
+            .chain(&(P::EPS_BOUND as u64))
+            .chain(&(P::Paillier::PRIME_BITS as u64))
     }
 }
 
+/// The domain of the scheme parameters fingerprint.
+pub(crate) const PARAMS_HASH_TAG: &[u8] = b"SchemeParams";
+
+/// Returns a hash identifying the scheme parameters.
+pub(crate) fn params_fingerprint<P: SchemeParams>() -> HashOutput {
+    FofHasher::new_with_dst(PARAMS_HASH_TAG)
+        .chain_type::<P>()
+        .finalize()
+}
+
 /// Scheme parameters **for testing purposes only**.
 /// Security is weakened to allow for faster execution.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -207,3 +236,57 @@ impl SchemeParams for ProductionParams {
     const CURVE_ORDER_WIDE: NonZero<<Self::Paillier as PaillierParams>::WideUint> =
         NonZero::<<Self::Paillier as PaillierParams>::WideUint>::const_new(upcast_uint(ORDER)).0;
 }
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use super::{SchemeParams, TestParams};
+    use crate::curve::Scalar;
+    use crate::paillier::PaillierParams;
+    use crate::uint::HasWide;
+
+    type Uint = <<TestParams as SchemeParams>::Paillier as PaillierParams>::Uint;
+
+    #[test]
+    fn scalar_uint_conversions() {
+        let scalar = Scalar::random(&mut OsRng);
+        let uint = TestParams::uint_from_scalar(&scalar);
+        assert_eq!(TestParams::scalar_from_uint(&uint), scalar);
+        assert_eq!(TestParams::try_scalar_from_uint(&uint), Some(scalar));
+        assert_eq!(TestParams::scalar_from_wide_uint(&uint.into_wide()), scalar);
+        assert_eq!(
+            TestParams::scalar_from_signed(&-TestParams::signed_from_scalar(&scalar)),
+            -scalar
+        );
+
+        // Values outside of the scalar range are reduced by the regular conversion,
+        // but rejected by the checked one.
+        let order = *TestParams::CURVE_ORDER.as_ref();
+        let unreduced = uint.wrapping_add(&order);
+        assert_eq!(TestParams::scalar_from_uint(&unreduced), scalar);
+        assert_eq!(TestParams::try_scalar_from_uint(&unreduced), None);
+        assert_eq!(TestParams::try_scalar_from_uint(&order), None);
+        assert_eq!(
+            TestParams::try_scalar_from_uint(&order.wrapping_sub(&Uint::ONE)),
+            Some(-Scalar::ONE)
+        );
+    }
+
+    #[test]
+    fn scalar_from_wide_bytes() {
+        // `2^256 = q + δ`, so a 64-byte value of `2^256` reduces to `δ`,
+        // the same as `2^256` truncated to 32 bytes with a modular reduction.
+        let mut wide = [0u8; 64];
+        wide[31] = 1;
+        assert_eq!(
+            Scalar::from_wide_bytes(&wide),
+            Scalar::from_reduced_bytes(&[0xff; 32]) + Scalar::ONE
+        );
+
+        // Values below `q` are kept as is
+        let mut wide = [0u8; 64];
+        wide[32..].copy_from_slice(&Scalar::ONE.to_bytes());
+        assert_eq!(Scalar::from_wide_bytes(&wide), Scalar::ONE);
+    }
+}
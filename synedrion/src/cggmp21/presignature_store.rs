@@ -0,0 +1,138 @@
+//! A pool of presigning data waiting to be used for signing.
+
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use core::fmt::Debug;
+use core::time::Duration;
+
+use super::{PresigningData, SchemeParams};
+use crate::sessions::Clock;
+
+/// A pool of presigning data (the results of [`make_presigning_session`](`crate::make_presigning_session`))
+/// handed out for signing in the order they were added.
+///
+/// Each added presigning data is timestamped with the clock of the store
+/// (unless it already has a timestamp), and the expired ones are never handed out.
+/// Since the store keeps the secret shares in memory, [`Self::purge_expired`]
+/// should be called periodically to drop the ones that cannot be used anymore.
+pub struct PresignatureStore<P: SchemeParams, I> {
+    clock: Arc<dyn Clock + Send + Sync>,
+    ttl: Option<Duration>,
+    items: VecDeque<PresigningData<P, I>>,
+}
+
+impl<P: SchemeParams, I> Debug for PresignatureStore<P, I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.debug_struct("PresignatureStore")
+            .field("ttl", &self.ttl)
+            .field("len", &self.items.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P: SchemeParams, I: Ord + Clone + PartialEq> PresignatureStore<P, I> {
+    /// Creates an empty store assigning the given time-to-live
+    /// (`None` for unlimited) to the added presigning data.
+    pub fn new(clock: Arc<dyn Clock + Send + Sync>, ttl: Option<Duration>) -> Self {
+        Self {
+            clock,
+            ttl,
+            items: VecDeque::new(),
+        }
+    }
+
+    /// Adds a presigning data to the store.
+    ///
+    /// If it does not have a timestamp yet, the current time and the TTL of the store are recorded
+    /// (see [`PresigningData::set_timestamp`]).
+    pub fn insert(&mut self, mut presigning: PresigningData<P, I>) {
+        // Keeps the existing timestamp, if any.
+        presigning.set_timestamp(self.clock.as_ref(), self.ttl);
+        self.items.push_back(presigning);
+    }
+
+    /// Removes and returns the oldest presigning data that has not expired.
+    ///
+    /// The returned data must be used for signing shortly,
+    /// since [`make_batch_signing_session`](`crate::make_batch_signing_session`) refuses the expired data.
+    pub fn take(&mut self) -> Option<PresigningData<P, I>> {
+        self.purge_expired();
+        self.items.pop_front()
+    }
+
+    /// Removes all the expired presigning data, returning their number.
+    pub fn purge_expired(&mut self) -> usize {
+        let len = self.items.len();
+        let clock = self.clock.as_ref();
+        self.items
+            .retain(|presigning| !presigning.is_expired(clock));
+        len - self.items.len()
+    }
+
+    /// The number of the presigning data in the store, including the expired ones not purged yet.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::sync::Arc;
+    use core::time::Duration;
+
+    use k256::ecdsa::{SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+
+    use super::PresignatureStore;
+    use crate::cggmp21::{AuxInfo, KeyShare, PresigningData, TestParams};
+    use crate::sessions::ManualClock;
+
+    #[test]
+    fn expiry() {
+        let ids = (0..2)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+        let id = ids.first().unwrap();
+        let make_presigning = || {
+            PresigningData::new_centralized(&mut OsRng, &key_shares, &aux_infos)
+                .remove(id)
+                .unwrap()
+        };
+
+        let clock = Arc::new(ManualClock::new(Duration::from_secs(100)));
+        let mut store = PresignatureStore::new(clock.clone(), Some(Duration::from_secs(60)));
+
+        store.insert(make_presigning());
+        clock.advance(Duration::from_secs(30));
+        let mut unlimited = make_presigning();
+        assert!(unlimited.set_timestamp(clock.as_ref(), None));
+        // The lifetime cannot be changed afterwards
+        assert!(!unlimited.set_timestamp(clock.as_ref(), Some(Duration::from_secs(1))));
+        store.insert(unlimited);
+        store.insert(make_presigning());
+        assert_eq!(store.len(), 3);
+
+        // The first one expires at 160s
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(store.purge_expired(), 1);
+        assert_eq!(store.len(), 2);
+
+        // The third one expires at 190s, the second one never does
+        clock.advance(Duration::from_secs(100));
+        let presigning = store.take().unwrap();
+        assert_eq!(presigning.created_at(), Some(Duration::from_secs(130)));
+        assert_eq!(presigning.ttl(), None);
+        assert!(!presigning.is_expired(clock.as_ref()));
+        assert!(store.take().is_none());
+        assert!(store.is_empty());
+    }
+}
@@ -1,17 +1,40 @@
+#[cfg(feature = "aux-gen")]
 pub(crate) mod aux_gen;
+#[cfg(feature = "signing")]
+pub(crate) mod batch_signing;
+#[cfg(feature = "key-gen")]
+pub(crate) mod fast_key_init;
+#[cfg(feature = "signing")]
 pub(crate) mod interactive_signing;
+#[cfg(feature = "key-gen")]
 pub(crate) mod key_gen;
+#[cfg(feature = "key-gen")]
 pub(crate) mod key_init;
+// Used both on its own, and as a part of KeyGen
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 pub(crate) mod key_refresh;
+#[cfg(feature = "signing")]
 pub(crate) mod presigning;
+#[cfg(feature = "signing")]
 pub(crate) mod signing;
 
+#[cfg(feature = "aux-gen")]
 pub use aux_gen::{AuxGenError, AuxGenResult};
+#[cfg(feature = "signing")]
+pub use batch_signing::{BatchSigningError, BatchSigningProof, BatchSigningResult};
+#[cfg(feature = "key-gen")]
+pub use fast_key_init::{FastKeyInitError, FastKeyInitResult, UnsafeAssumeReliableBroadcast};
+#[cfg(feature = "signing")]
 pub use interactive_signing::{
     InteractiveSigningError, InteractiveSigningProof, InteractiveSigningResult,
 };
+#[cfg(feature = "key-gen")]
 pub use key_gen::{KeyGenError, KeyGenProof, KeyGenResult};
+#[cfg(feature = "key-gen")]
 pub use key_init::{KeyInitError, KeyInitResult};
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 pub use key_refresh::KeyRefreshResult;
+#[cfg(feature = "signing")]
 pub use presigning::{PresigningError, PresigningProof, PresigningResult};
-pub use signing::{SigningProof, SigningResult};
+#[cfg(feature = "signing")]
+pub use signing::{PartialSignature, SigningError, SigningProof, SigningResult};
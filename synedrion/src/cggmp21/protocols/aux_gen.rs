@@ -12,6 +12,7 @@ use secrecy::SecretBox;
 use serde::{Deserialize, Serialize};
 
 use super::super::{
+    params_fingerprint,
     sigma::{FacProof, ModProof, PrmProof, SchCommitment, SchProof, SchSecret},
     AuxInfo, PublicAuxInfo, SchemeParams, SecretAuxInfo,
 };
@@ -21,23 +22,35 @@ use crate::paillier::{
     SecretKeyPaillier, SecretKeyPaillierPrecomputed,
 };
 use crate::rounds::{
-    no_broadcast_messages, no_direct_messages, FinalizableToNextRound, FinalizableToResult,
-    FinalizeError, FirstRound, InitError, ProtocolResult, Round, ToNextRound, ToResult,
+    no_broadcast_messages, no_direct_messages, BroadcastTranscript, Check, FinalizableToNextRound,
+    FinalizableToResult, FinalizeError, FirstRound, InitError, Protocol, ProtocolId,
+    ProtocolResult, Round, ToNextRound, ToResult,
 };
+#[cfg(feature = "testing")]
+use crate::testing::malicious::{Behavior, Misbehave};
 use crate::tools::bitvec::BitVec;
 use crate::tools::hashing::{Chain, FofHasher, HashOutput};
-use crate::uint::UintLike;
+use crate::uint::{subtle::ConstantTimeEq, UintLike};
 
 /// Possible results of the AuxGen protocol.
 #[derive(Debug, Clone, Copy)]
 pub struct AuxGenResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
 
 impl<P: SchemeParams, I: Debug + Ord> ProtocolResult for AuxGenResult<P, I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::AuxGen;
     type Success = AuxInfo<P, I>;
     type ProvableError = AuxGenError;
     type CorrectnessProof = ();
 }
 
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Protocol<I> for AuxGenResult<P, I> {
+    type FirstRound = Round1<P, I>;
+
+    fn params_fingerprint() -> Option<HashOutput> {
+        Some(params_fingerprint::<P>())
+    }
+}
+
 /// Possible errors for AuxGen protocol.
 #[derive(Debug, Clone)]
 pub struct AuxGenError(#[allow(dead_code)] AuxGenErrorEnum);
@@ -82,14 +95,60 @@ struct Context<P: SchemeParams, I> {
     sid_hash: HashOutput,
 }
 
+/// The domain of the commitments to the public data.
+pub(crate) const COMMITMENT_HASH_TAG: &[u8] = b"Auxiliary";
+
+/// The domain of the session identifier hash.
+pub(crate) const SID_HASH_TAG: &[u8] = b"SID";
+
 impl<P: SchemeParams> PublicData1<P> {
     fn hash<I: Serialize>(&self, sid_hash: &HashOutput, my_id: &I) -> HashOutput {
-        FofHasher::new_with_dst(b"Auxiliary")
+        FofHasher::new_with_dst(COMMITMENT_HASH_TAG)
             .chain(sid_hash)
             .chain(my_id)
             .chain(self)
             .finalize()
     }
+
+    /// Checks the Paillier modulus size and the ring-Pedersen parameters,
+    /// returning their precomputed forms.
+    #[allow(clippy::type_complexity)]
+    fn precompute_checked(
+        &self,
+    ) -> Result<
+        (
+            PublicKeyPaillierPrecomputed<P::Paillier>,
+            RPParamsMod<P::Paillier>,
+        ),
+        &'static str,
+    > {
+        let paillier_pk = self.paillier_pk.to_precomputed();
+
+        if paillier_pk.modulus().bits_vartime() < 8 * P::SECURITY_PARAMETER {
+            return Err("Paillier modulus is too small");
+        }
+
+        let rp_params = self
+            .rp_params
+            .to_mod_checked(&paillier_pk)
+            .ok_or("Ring-Pedersen parameters are not reduced")?;
+        if !self.rp_params.is_nontrivial(&paillier_pk) {
+            return Err("Ring-Pedersen parameters are trivial or not invertible");
+        }
+
+        Ok((paillier_pk, rp_params))
+    }
+}
+
+fn sid_hash<P: SchemeParams, I: Serialize>(
+    shared_randomness: &[u8],
+    all_ids: &BTreeSet<I>,
+) -> HashOutput {
+    FofHasher::new_with_dst(SID_HASH_TAG)
+        .chain_type::<P>()
+        .chain(&shared_randomness)
+        .chain(all_ids)
+        .finalize()
 }
 
 pub struct Round1<P: SchemeParams, I> {
@@ -108,11 +167,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
         let mut all_ids = other_ids.clone();
         all_ids.insert(my_id.clone());
 
-        let sid_hash = FofHasher::new_with_dst(b"SID")
-            .chain_type::<P>()
-            .chain(&shared_randomness)
-            .chain(&all_ids)
-            .finalize();
+        let sid_hash = sid_hash::<P, I>(shared_randomness, &all_ids);
 
         // $p_i$, $q_i$
         let paillier_sk = SecretKeyPaillier::<P::Paillier>::random(rng).to_precomputed();
@@ -290,30 +345,42 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
 
     no_direct_messages!(I);
 
-    fn verify_message(
+    fn pre_verify_message(
         &self,
-        _rng: &mut impl CryptoRngCore,
         from: &I,
-        broadcast_msg: Self::BroadcastMessage,
-        _direct_msg: Self::DirectMessage,
-    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
-        if &broadcast_msg.data.hash(&self.context.sid_hash, from)
-            != self.others_cap_v.get(from).unwrap()
-        {
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> Result<(), <Self::Result as ProtocolResult>::ProvableError> {
+        if !bool::from(
+            broadcast_msg
+                .data
+                .hash(&self.context.sid_hash, from)
+                .ct_eq(self.others_cap_v.get(from).unwrap()),
+        ) {
             return Err(AuxGenError(AuxGenErrorEnum::Round2("Hash mismatch".into())));
         }
-
-        let paillier_pk = broadcast_msg.data.paillier_pk.to_precomputed();
-
-        if paillier_pk.modulus().bits_vartime() < 8 * P::SECURITY_PARAMETER {
+        // Committed to, but would make the combined `rho` impossible to compute.
+        if !broadcast_msg.data.rho.has_length(P::SECURITY_PARAMETER) {
             return Err(AuxGenError(AuxGenErrorEnum::Round2(
-                "Paillier modulus is too small".into(),
+                "Wrong length of rho".into(),
             )));
         }
+        Ok(())
+    }
 
-        let aux = (&self.context.sid_hash, &from);
+    fn verify_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        from: &I,
+        broadcast_msg: Self::BroadcastMessage,
+        _direct_msg: Self::DirectMessage,
+    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
+        let (paillier_pk, rp_params) = broadcast_msg
+            .data
+            .precompute_checked()
+            .map_err(|err| AuxGenError(AuxGenErrorEnum::Round2(err.into())))?;
 
-        let rp_params = broadcast_msg.data.rp_params.to_mod(&paillier_pk);
+        let aux = (&self.context.sid_hash, &from);
         if !broadcast_msg.data.hat_psi.verify(&rp_params, &aux) {
             return Err(AuxGenError(AuxGenErrorEnum::Round2(
                 "PRM verification failed".into(),
@@ -328,6 +395,20 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
             },
         })
     }
+
+    fn verify_own_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        let aux = (&self.context.sid_hash, self.my_id());
+        broadcast_msg
+            .data
+            .hat_psi
+            .verify(&self.context.data_precomp.rp_params, &aux)
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -498,6 +579,32 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
 
         Ok(())
     }
+
+    fn verify_own_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &I,
+        _broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        let my_data = &self.context.data_precomp;
+        let destination_data = &self.others_data[destination];
+
+        let aux = (&self.context.sid_hash, self.my_id(), &self.rho);
+
+        direct_msg
+            .data2
+            .psi_mod
+            .verify(rng, &my_data.paillier_pk, &aux)
+            && direct_msg
+                .data2
+                .phi
+                .verify(&my_data.paillier_pk, &destination_data.rp_params, &aux)
+            && direct_msg
+                .data2
+                .pi
+                .verify(&my_data.data.cap_b, &my_data.data.cap_y, &aux)
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round3<P, I> {
@@ -540,19 +647,125 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
     }
 }
 
+#[cfg(feature = "testing")]
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Misbehave<I> for Round1<P, I> {
+    fn tamper_state(&mut self, rng: &mut impl CryptoRngCore, behavior: &Behavior) {
+        if behavior == &Behavior::InvalidPrmProof {
+            // A proof made with a secret unrelated to the ring-Pedersen parameters
+            let lambda = RPSecret::random(rng, &self.context.paillier_sk);
+            let aux = (&self.context.sid_hash, &self.context.my_id);
+            let hat_psi = PrmProof::<P>::new(
+                rng,
+                &self.context.paillier_sk,
+                &lambda,
+                &self.context.data_precomp.rp_params,
+                &aux,
+            );
+            self.context.data_precomp.data.hat_psi = hat_psi;
+        }
+    }
+
+    fn equivocate_broadcast(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        destination: &I,
+        message: Self::BroadcastMessage,
+    ) -> Self::BroadcastMessage {
+        // A different commitment for each destination; none of them will be opened.
+        let cap_v = FofHasher::new_with_dst(b"Equivocation")
+            .chain(&message.cap_v)
+            .chain(destination)
+            .finalize();
+        Round1Message { cap_v }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Misbehave<I> for Round2<P, I> {}
+
+#[cfg(feature = "testing")]
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Misbehave<I> for Round3<P, I> {}
+
+/// Checks the broadcasts of an AuxGen run by a third party,
+/// returning the public auxiliary data of the parties if all of them are present and valid.
+///
+/// The proofs of the last round are sent in direct messages, and cannot be checked here.
+pub(crate) fn verify_transcript<P: SchemeParams, I: Clone + Ord + Serialize>(
+    shared_randomness: &[u8],
+    all_ids: &BTreeSet<I>,
+    transcript: &mut impl BroadcastTranscript<I>,
+) -> Option<BTreeMap<I, PublicAuxInfo<P>>> {
+    let sid_hash = sid_hash::<P, I>(shared_randomness, all_ids);
+
+    let r1 = transcript.broadcasts::<Round1Message>(1);
+    let r2 = transcript.broadcasts::<Round2Message<P>>(2);
+
+    let mut valid = r1.len() == all_ids.len() && r2.len() == all_ids.len();
+    for (id, message) in r2.iter() {
+        let passed = match r1.get(id) {
+            Some(r1_message) => message
+                .data
+                .hash(&sid_hash, id)
+                .ct_eq(&r1_message.cap_v)
+                .into(),
+            None => false,
+        };
+        transcript.record(id, 2, Check::Commitment, passed);
+        valid &= passed;
+
+        let rp_params = match message.data.precompute_checked() {
+            Ok((_paillier_pk, rp_params)) => {
+                transcript.record(id, 2, Check::AuxParams, true);
+                rp_params
+            }
+            Err(_) => {
+                transcript.record(id, 2, Check::AuxParams, false);
+                valid = false;
+                continue;
+            }
+        };
+
+        let aux = (&sid_hash, id);
+        let passed = message.data.hat_psi.verify(&rp_params, &aux);
+        transcript.record(id, 2, Check::PrmProof, passed);
+        valid &= passed;
+    }
+
+    if !valid {
+        return None;
+    }
+
+    Some(
+        r2.into_iter()
+            .map(|(id, message)| {
+                let public_aux = PublicAuxInfo {
+                    el_gamal_pk: message.data.cap_y,
+                    paillier_pk: message.data.paillier_pk,
+                    rp_params: message.data.rp_params,
+                };
+                (id, public_aux)
+            })
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
-    use alloc::collections::BTreeSet;
+    use alloc::collections::{BTreeMap, BTreeSet};
 
     use rand_core::{OsRng, RngCore};
     use secrecy::ExposeSecret;
 
-    use super::Round1;
+    use super::{Round1, Round2, Round3};
     use crate::cggmp21::TestParams;
     use crate::rounds::{
-        test_utils::{step_next_round, step_result, step_round, Id, Without},
-        FirstRound,
+        test_utils::{
+            round_info, step_next_round, step_result, step_round, Id, StepError, Without,
+        },
+        FirstRound, ProtocolId,
     };
+    use crate::tools::bitvec::BitVec;
+    use crate::uint::subtle::ConstantTimeEq;
 
     #[test]
     fn execute_aux_gen() {
@@ -580,6 +793,11 @@ mod tests {
         let r2 = step_next_round(&mut OsRng, r1a).unwrap();
         let r2a = step_round(&mut OsRng, r2).unwrap();
         let r3 = step_next_round(&mut OsRng, r2a).unwrap();
+
+        // All the parties combined the same `rho`
+        let rho = &r3[&Id(0)].rho;
+        assert!(r3.values().all(|round| bool::from(round.rho.ct_eq(rho))));
+
         let r3a = step_round(&mut OsRng, r3).unwrap();
         let aux_infos = step_result(&mut OsRng, r3a).unwrap();
 
@@ -596,4 +814,49 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn wrong_rho_length() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        let mut r1 = ids
+            .iter()
+            .map(|id| {
+                let round = Round1::<TestParams, Id>::new(
+                    &mut OsRng,
+                    &shared_randomness,
+                    ids.clone().without(id),
+                    *id,
+                    (),
+                )
+                .unwrap();
+                (*id, round)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        // The party commits to a `rho` of a wrong length, so the hash check passes
+        r1.get_mut(&Id(0)).unwrap().context.data_precomp.data.rho = BitVec::random(&mut OsRng, 8);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        match step_round(&mut OsRng, r2) {
+            Err(StepError::Receive(err)) => assert!(err.contains("Wrong length of rho")),
+            _ => panic!("Expected a receive error"),
+        }
+    }
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [
+                round_info::<Id, Round1<TestParams, Id>>(),
+                round_info::<Id, Round2<TestParams, Id>>(),
+                round_info::<Id, Round3<TestParams, Id>>(),
+            ],
+            ProtocolId::AuxGen.rounds()
+        );
+    }
 }
@@ -0,0 +1,344 @@
+//! Signing several messages at once, each using its own previously calculated presigning data,
+//! with the partial signatures for all of them sent in a single message.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use rand_core::CryptoRngCore;
+use serde::Serialize;
+
+use super::super::{params_fingerprint, AuxInfo, KeyShare, PresigningData, SchemeParams};
+use super::signing::{PartialSignature, SigningContext, SigningItem, SigningProof};
+use crate::curve::{RecoverableSignature, Scalar};
+use crate::rounds::{
+    no_direct_messages, FinalizableToResult, FinalizeError, FirstRound, InitError, Protocol,
+    ProtocolId, ProtocolResult, Round, ToResult,
+};
+use crate::tools::hashing::{Chain, FofHasher, HashOutput};
+
+/// Possible results of the BatchSigning protocol.
+#[derive(Debug)]
+pub struct BatchSigningResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
+
+impl<P: SchemeParams, I: Debug> ProtocolResult for BatchSigningResult<P, I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::BatchSigning;
+    /// The signatures of the messages, in the order they were given.
+    type Success = Vec<RecoverableSignature>;
+    type ProvableError = BatchSigningError;
+    type CorrectnessProof = BatchSigningProof<P, I>;
+}
+
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Protocol<I> for BatchSigningResult<P, I> {
+    type FirstRound = Round1<P, I>;
+
+    fn params_fingerprint() -> Option<HashOutput> {
+        Some(params_fingerprint::<P>())
+    }
+}
+
+/// Possible verifiable errors of the BatchSigning protocol.
+#[derive(Debug, Clone, Copy)]
+pub enum BatchSigningError {
+    /// The number of the partial signatures is different from the number of the messages.
+    WrongBatchSize,
+    /// The partial signature of the message with the given index
+    /// does not match the public presigning data of the sender.
+    InvalidPartialSignature(usize),
+}
+
+/// A proof of a node's correct behavior for the BatchSigning protocol.
+#[derive(Debug, Clone)]
+pub struct BatchSigningProof<P: SchemeParams, I> {
+    /// The index of the message for which the signature could not be assembled.
+    pub index: usize,
+    /// The proof for the signing of that message.
+    pub proof: SigningProof<P, I>,
+}
+
+/// Returns the index of the first presigning data with the same nonce as a previous one.
+pub(crate) fn find_repeated_presigning<'a, P: SchemeParams + 'a, I: 'a>(
+    presignings: impl Iterator<Item = &'a PresigningData<P, I>>,
+) -> Option<usize> {
+    let mut nonces = BTreeSet::new();
+    presignings
+        .enumerate()
+        .find(|(_idx, presigning)| !nonces.insert(presigning.nonce))
+        .map(|(idx, _presigning)| idx)
+}
+
+/// The domain of the hash binding the correctness proofs to the index of the message in the batch.
+pub(crate) const ITEM_HASH_TAG: &[u8] = b"BatchSigningItem";
+
+pub struct Round1<P: SchemeParams, I: Ord> {
+    // The key share, the precomputed auxiliary data and the share set ID
+    // are shared by all the messages.
+    context: SigningContext<P, I>,
+    items: Vec<SigningItem<P, I>>,
+}
+
+#[derive(Clone)]
+pub struct Inputs<P: SchemeParams, I: Ord> {
+    /// The messages to sign, each with the presigning data to use for it.
+    pub items: Vec<(Scalar, PresigningData<P, I>)>,
+    pub key_share: KeyShare<P, I>,
+    pub aux_info: AuxInfo<P, I>,
+}
+
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Round1<P, I> {
+    type Inputs = Inputs<P, I>;
+    fn new(
+        _rng: &mut impl CryptoRngCore,
+        shared_randomness: &[u8],
+        other_ids: BTreeSet<I>,
+        my_id: I,
+        inputs: Self::Inputs,
+    ) -> Result<Self, InitError> {
+        if inputs.items.is_empty() {
+            return Err(InitError("The batch of messages is empty".into()));
+        }
+        if find_repeated_presigning(inputs.items.iter().map(|(_message, presigning)| presigning))
+            .is_some()
+        {
+            return Err(InitError(
+                "The same presigning data is used for several messages".into(),
+            ));
+        }
+
+        let context = SigningContext::new(
+            shared_randomness,
+            other_ids,
+            my_id,
+            inputs.key_share,
+            inputs.aux_info,
+        );
+        let items = inputs
+            .items
+            .into_iter()
+            .map(|(message, presigning)| SigningItem::new(message, presigning))
+            .collect();
+
+        Ok(Self { context, items })
+    }
+}
+
+/// The share set ID the correctness proofs for the message with the given index are bound to,
+/// so that the proofs for different messages are not interchangeable.
+fn item_ssid_hash<P: SchemeParams, I: Clone + Ord + Serialize>(
+    context: &SigningContext<P, I>,
+    index: usize,
+) -> HashOutput {
+    FofHasher::new_with_dst(ITEM_HASH_TAG)
+        .chain(context.ssid_hash())
+        .chain(&(index as u64))
+        .finalize()
+}
+
+pub struct Round1Payload {
+    sigmas: Vec<Scalar>,
+}
+
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P, I> {
+    type Type = ToResult;
+    type Result = BatchSigningResult<P, I>;
+    const ROUND_NUM: u8 = 1;
+    const NEXT_ROUND_NUM: Option<u8> = None;
+
+    fn other_ids(&self) -> &BTreeSet<I> {
+        self.context.other_ids()
+    }
+
+    fn my_id(&self) -> &I {
+        self.context.my_id()
+    }
+
+    type BroadcastMessage = Vec<PartialSignature>;
+    type DirectMessage = ();
+    type Payload = Round1Payload;
+    type Artifact = ();
+
+    fn make_broadcast_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+    ) -> Option<Self::BroadcastMessage> {
+        Some(
+            self.items
+                .iter()
+                .map(SigningItem::partial_signature)
+                .collect(),
+        )
+    }
+
+    no_direct_messages!(I);
+
+    fn verify_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        from: &I,
+        broadcast_msg: Self::BroadcastMessage,
+        _direct_msg: Self::DirectMessage,
+    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
+        if broadcast_msg.len() != self.items.len() {
+            return Err(BatchSigningError::WrongBatchSize);
+        }
+
+        for (index, (item, partial)) in self.items.iter().zip(broadcast_msg.iter()).enumerate() {
+            if !item.verify_partial_signature(from, partial) {
+                return Err(BatchSigningError::InvalidPartialSignature(index));
+            }
+        }
+
+        Ok(Round1Payload {
+            sigmas: broadcast_msg.iter().map(PartialSignature::sigma).collect(),
+        })
+    }
+
+    fn verify_own_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        broadcast_msg.len() == self.items.len()
+            && self
+                .items
+                .iter()
+                .zip(broadcast_msg)
+                .all(|(item, partial)| item.verify_partial_signature(self.my_id(), partial))
+    }
+}
+
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round1<P, I> {
+    fn finalize_to_result(
+        self,
+        rng: &mut impl CryptoRngCore,
+        payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
+        _artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
+    ) -> Result<<Self::Result as ProtocolResult>::Success, FinalizeError<Self::Result>> {
+        let mut sigmas = payloads
+            .into_values()
+            .map(|payload| payload.sigmas.into_iter())
+            .collect::<Vec<_>>();
+
+        let mut signatures = Vec::with_capacity(self.items.len());
+        for (index, item) in self.items.into_iter().enumerate() {
+            // `verify_message()` ensures that every payload has an element for every message.
+            let item_sigmas = sigmas.iter_mut().map(|sigmas| sigmas.next().unwrap());
+            let signature = item
+                .finalize(
+                    rng,
+                    &self.context,
+                    &item_ssid_hash(&self.context, index),
+                    item_sigmas,
+                )
+                .map_err(|proof| FinalizeError::Proof(BatchSigningProof { index, proof }))?;
+            signatures.push(signature);
+        }
+
+        Ok(signatures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+    use alloc::vec::Vec;
+
+    use k256::ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
+    use rand_core::{OsRng, RngCore};
+
+    use super::{BatchSigningError, Inputs, Round1};
+    use crate::cggmp21::{AuxInfo, KeyShare, PresigningData, TestParams};
+    use crate::curve::Scalar;
+    use crate::rounds::{
+        test_utils::{round_info, step_result, step_round, Id, Without},
+        FirstRound, ProtocolId, Round,
+    };
+
+    #[test]
+    fn execute_batch_signing() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let batch_size = 3;
+        let presigning_datas = (0..batch_size)
+            .map(|_| PresigningData::new_centralized(&mut OsRng, &key_shares, &aux_infos))
+            .collect::<Vec<_>>();
+        let messages = (0..batch_size)
+            .map(|_| Scalar::random(&mut OsRng))
+            .collect::<Vec<_>>();
+
+        let make_round = |id: &Id, items: Vec<(Scalar, PresigningData<TestParams, Id>)>| {
+            Round1::<TestParams, Id>::new(
+                &mut OsRng,
+                &shared_randomness,
+                ids.clone().without(id),
+                *id,
+                Inputs {
+                    items,
+                    key_share: key_shares[id].clone(),
+                    aux_info: aux_infos[id].clone(),
+                },
+            )
+        };
+
+        let r1 = ids
+            .iter()
+            .map(|id| {
+                let items = messages
+                    .iter()
+                    .zip(presigning_datas.iter())
+                    .map(|(message, presigning)| (*message, presigning[id].clone()))
+                    .collect();
+                (*id, make_round(id, items).unwrap())
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        // A batch of a different size or with a different message is rejected
+        let round = &r1[&Id(0)];
+        let broadcast = r1[&Id(1)].make_broadcast_message(&mut OsRng).unwrap();
+        assert!(matches!(
+            round.verify_message(&mut OsRng, &Id(1), broadcast[..2].to_vec(), ()),
+            Err(BatchSigningError::WrongBatchSize)
+        ));
+        let mut swapped = broadcast.clone();
+        swapped.swap(0, 2);
+        assert!(matches!(
+            round.verify_message(&mut OsRng, &Id(1), swapped, ()),
+            Err(BatchSigningError::InvalidPartialSignature(0))
+        ));
+        assert!(round
+            .verify_message(&mut OsRng, &Id(1), broadcast, ())
+            .is_ok());
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let signatures = step_result(&mut OsRng, r1a).unwrap();
+
+        let vkey: VerifyingKey = key_shares[&Id(0)].verifying_key();
+        for batch in signatures.values() {
+            assert_eq!(batch.len(), batch_size);
+            for (signature, message) in batch.iter().zip(messages.iter()) {
+                let (sig, _rec_id) = signature.to_backend();
+                vkey.verify_prehash(&message.to_bytes(), &sig).unwrap();
+            }
+        }
+
+        // An empty batch cannot be signed
+        assert!(make_round(&Id(0), Vec::new()).is_err());
+    }
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [round_info::<Id, Round1<TestParams, Id>>()],
+            ProtocolId::BatchSigning.rounds()
+        );
+    }
+}
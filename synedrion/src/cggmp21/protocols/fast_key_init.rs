@@ -0,0 +1,397 @@
+//! A variant of the KeyInit protocol without the commitment round,
+//! for the deployments where all the messages go through a reliable broadcast channel.
+//!
+//! In the KeyInit protocol the parties first commit to their public data
+//! and make sure (with an echo round) that everyone received the same commitments,
+//! so that no party can choose its contribution after seeing the ones of the others.
+//! Here the public data is sent right away, saving a round trip,
+//! so these guarantees must be provided by the channel instead.
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use rand_core::CryptoRngCore;
+use secrecy::SecretBox;
+use serde::{Deserialize, Serialize};
+
+use super::super::{
+    params_fingerprint,
+    sigma::{SchCommitment, SchProof, SchSecret},
+    KeyShare, SchemeParams,
+};
+use crate::curve::{Point, Scalar};
+use crate::lineage::{outcome_fingerprint, session_fingerprint, Ceremony, Lineage};
+use crate::rounds::{
+    no_direct_messages, FinalizableToNextRound, FinalizableToResult, FinalizeError, FirstRound,
+    InitError, Protocol, ProtocolId, ProtocolResult, Round, ToNextRound, ToResult,
+};
+use crate::tools::bitvec::BitVec;
+use crate::tools::hashing::{Chain, FofHasher, HashOutput};
+
+/// An explicit acknowledgement required to run the FastKeyInit protocol.
+///
+/// By providing it, the application guarantees that every message of the protocol
+/// is delivered to all the parties unchanged (e.g. because all the messages
+/// are posted to a BFT ledger), which is not checked by the protocol.
+///
+/// Even with such a channel, a party that gets to see the messages of the others
+/// before sending its own can choose its public share depending on them,
+/// and thus bias the resulting verifying key (e.g. by trying many candidates
+/// until the key has some property); it can neither learn nor choose the secret key.
+/// If the channel does not hide the messages until everyone has sent theirs,
+/// use the KeyInit protocol instead.
+#[derive(Debug, Clone, Copy)]
+pub struct UnsafeAssumeReliableBroadcast;
+
+/// Possible results of the FastKeyInit protocol.
+#[derive(Debug, Clone, Copy)]
+pub struct FastKeyInitResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
+
+impl<P: SchemeParams, I: Debug + Ord> ProtocolResult for FastKeyInitResult<P, I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::FastKeyInit;
+    type Success = KeyShare<P, I>;
+    type ProvableError = FastKeyInitError;
+    type CorrectnessProof = ();
+}
+
+impl<P: SchemeParams, I: Clone + Ord + Serialize + Debug> Protocol<I> for FastKeyInitResult<P, I> {
+    type FirstRound = Round1<P, I>;
+
+    fn params_fingerprint() -> Option<HashOutput> {
+        Some(params_fingerprint::<P>())
+    }
+}
+
+/// Possible verifiable errors of the FastKeyInit protocol.
+#[derive(Debug, Clone, Copy)]
+pub enum FastKeyInitError {
+    /// The random identifier `rid` in Round 1 has a wrong length.
+    R1WrongRidLength,
+    /// Failed to verify `П^{sch}` in Round 2.
+    R2InvalidSchProof,
+}
+
+/// The domain of the session identifier hash.
+pub(crate) const SID_HASH_TAG: &[u8] = b"FastKeyInitSID";
+
+struct Context<I> {
+    other_ids: BTreeSet<I>,
+    my_id: I,
+    x: Scalar,
+    tau: SchSecret,
+    cap_x: Point,
+    cap_a: SchCommitment,
+    rid: BitVec,
+    sid_hash: HashOutput,
+    // Recorded in the lineage of the resulting key share.
+    session: HashOutput,
+}
+
+pub struct Round1<P: SchemeParams, I> {
+    context: Context<I>,
+    phantom: PhantomData<P>,
+}
+
+impl<P: SchemeParams, I: Clone + Ord + Serialize + Debug> FirstRound<I> for Round1<P, I> {
+    type Inputs = UnsafeAssumeReliableBroadcast;
+
+    fn new(
+        rng: &mut impl CryptoRngCore,
+        shared_randomness: &[u8],
+        other_ids: BTreeSet<I>,
+        my_id: I,
+        _inputs: Self::Inputs,
+    ) -> Result<Self, InitError> {
+        let mut all_ids = other_ids.clone();
+        all_ids.insert(my_id.clone());
+
+        let sid_hash = FofHasher::new_with_dst(SID_HASH_TAG)
+            .chain_type::<P>()
+            .chain(&shared_randomness)
+            .chain(&all_ids)
+            .finalize();
+
+        let x = Scalar::random(rng);
+        let tau = SchSecret::random(rng);
+
+        let context = Context {
+            other_ids,
+            my_id,
+            cap_x: x.mul_by_generator(),
+            cap_a: SchCommitment::new(&tau),
+            rid: BitVec::random(rng, P::SECURITY_PARAMETER),
+            x,
+            tau,
+            sid_hash,
+            session: session_fingerprint(shared_randomness),
+        };
+
+        Ok(Self {
+            context,
+            phantom: PhantomData,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Round1Message {
+    cap_x: Point,
+    cap_a: SchCommitment,
+    rid: BitVec,
+}
+
+impl<P: SchemeParams, I: Clone + Ord + Serialize + Debug> Round<I> for Round1<P, I> {
+    type Type = ToNextRound;
+    type Result = FastKeyInitResult<P, I>;
+    const ROUND_NUM: u8 = 1;
+    const NEXT_ROUND_NUM: Option<u8> = Some(2);
+
+    fn other_ids(&self) -> &BTreeSet<I> {
+        &self.context.other_ids
+    }
+
+    fn my_id(&self) -> &I {
+        &self.context.my_id
+    }
+
+    type BroadcastMessage = Round1Message;
+    type DirectMessage = ();
+    type Payload = Round1Message;
+    type Artifact = ();
+
+    fn make_broadcast_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+    ) -> Option<Self::BroadcastMessage> {
+        Some(Round1Message {
+            cap_x: self.context.cap_x,
+            cap_a: self.context.cap_a.clone(),
+            rid: self.context.rid.clone(),
+        })
+    }
+
+    no_direct_messages!(I);
+
+    fn pre_verify_message(
+        &self,
+        _from: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> Result<(), <Self::Result as ProtocolResult>::ProvableError> {
+        if !broadcast_msg.rid.has_length(P::SECURITY_PARAMETER) {
+            return Err(FastKeyInitError::R1WrongRidLength);
+        }
+        Ok(())
+    }
+
+    fn verify_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _from: &I,
+        broadcast_msg: Self::BroadcastMessage,
+        _direct_msg: Self::DirectMessage,
+    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
+        Ok(broadcast_msg)
+    }
+}
+
+impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> FinalizableToNextRound<I>
+    for Round1<P, I>
+{
+    type NextRound = Round2<P, I>;
+    fn finalize_to_next_round(
+        self,
+        _rng: &mut impl CryptoRngCore,
+        payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
+        _artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
+    ) -> Result<Self::NextRound, FinalizeError<Self::Result>> {
+        let mut rid = self.context.rid.clone();
+        for payload in payloads.values() {
+            rid ^= &payload.rid;
+        }
+
+        Ok(Round2 {
+            context: self.context,
+            others_data: payloads,
+            rid,
+            phantom: PhantomData,
+        })
+    }
+}
+
+pub struct Round2<P: SchemeParams, I> {
+    context: Context<I>,
+    others_data: BTreeMap<I, Round1Message>,
+    rid: BitVec,
+    phantom: PhantomData<P>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Round2Message {
+    psi: SchProof,
+}
+
+impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> Round<I> for Round2<P, I> {
+    type Type = ToResult;
+    type Result = FastKeyInitResult<P, I>;
+    const ROUND_NUM: u8 = 2;
+    const NEXT_ROUND_NUM: Option<u8> = None;
+
+    fn other_ids(&self) -> &BTreeSet<I> {
+        &self.context.other_ids
+    }
+
+    fn my_id(&self) -> &I {
+        &self.context.my_id
+    }
+
+    type BroadcastMessage = Round2Message;
+    type DirectMessage = ();
+    type Payload = ();
+    type Artifact = ();
+
+    fn make_broadcast_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+    ) -> Option<Self::BroadcastMessage> {
+        let aux = (&self.context.sid_hash, self.my_id(), &self.rid);
+        let psi = SchProof::new(
+            &self.context.tau,
+            &self.context.x,
+            &self.context.cap_a,
+            &self.context.cap_x,
+            &aux,
+        );
+        Some(Round2Message { psi })
+    }
+
+    no_direct_messages!(I);
+
+    fn verify_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        from: &I,
+        broadcast_msg: Self::BroadcastMessage,
+        _direct_msg: Self::DirectMessage,
+    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
+        let data = self.others_data.get(from).unwrap();
+
+        let aux = (&self.context.sid_hash, from, &self.rid);
+        if !broadcast_msg.psi.verify(&data.cap_a, &data.cap_x, &aux) {
+            return Err(FastKeyInitError::R2InvalidSchProof);
+        }
+        Ok(())
+    }
+
+    fn verify_own_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        let aux = (&self.context.sid_hash, self.my_id(), &self.rid);
+        broadcast_msg
+            .psi
+            .verify(&self.context.cap_a, &self.context.cap_x, &aux)
+    }
+}
+
+impl<P: SchemeParams, I: Serialize + Clone + Ord + Debug> FinalizableToResult<I> for Round2<P, I> {
+    fn finalize_to_result(
+        self,
+        _rng: &mut impl CryptoRngCore,
+        _payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
+        _artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
+    ) -> Result<<Self::Result as ProtocolResult>::Success, FinalizeError<Self::Result>> {
+        let my_id = self.my_id().clone();
+        let mut public_shares = self
+            .others_data
+            .into_iter()
+            .map(|(k, v)| (k, v.cap_x))
+            .collect::<BTreeMap<_, _>>();
+        public_shares.insert(my_id.clone(), self.context.cap_x);
+        let all_ids = public_shares.keys().cloned().collect();
+        let lineage = Lineage::new(
+            Ceremony::FastKeyInit,
+            self.context.session,
+            outcome_fingerprint(&all_ids, &public_shares),
+            public_shares.values().sum(),
+        );
+        Ok(KeyShare {
+            owner: my_id,
+            secret_share: SecretBox::new(Box::new(self.context.x)),
+            public_shares,
+            phantom: PhantomData,
+            lineage: Some(lineage),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    use rand_core::{OsRng, RngCore};
+    use secrecy::ExposeSecret;
+
+    use super::{Round1, Round2, UnsafeAssumeReliableBroadcast};
+    use crate::cggmp21::TestParams;
+    use crate::rounds::{
+        test_utils::{round_info, step_next_round, step_result, step_round, Id, Without},
+        FirstRound, ProtocolId,
+    };
+
+    #[test]
+    fn execute_fast_keygen() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        let r1 = ids
+            .iter()
+            .map(|id| {
+                let round = Round1::<TestParams, Id>::new(
+                    &mut OsRng,
+                    &shared_randomness,
+                    ids.clone().without(id),
+                    *id,
+                    UnsafeAssumeReliableBroadcast,
+                )
+                .unwrap();
+                (*id, round)
+            })
+            .collect();
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        let r2a = step_round(&mut OsRng, r2).unwrap();
+        let shares = step_result(&mut OsRng, r2a).unwrap();
+
+        let public_sets = shares
+            .iter()
+            .map(|(id, share)| (*id, share.public_shares.clone()))
+            .collect::<BTreeMap<_, _>>();
+        assert!(public_sets.values().all(|pk| pk == &public_sets[&Id(0)]));
+
+        let public_from_secret = shares
+            .into_iter()
+            .map(|(id, share)| (id, share.secret_share.expose_secret().mul_by_generator()))
+            .collect();
+        assert!(public_sets[&Id(0)] == public_from_secret);
+    }
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [
+                round_info::<Id, Round1<TestParams, Id>>(),
+                round_info::<Id, Round2<TestParams, Id>>(),
+            ],
+            ProtocolId::FastKeyInit.rounds()
+        );
+    }
+}
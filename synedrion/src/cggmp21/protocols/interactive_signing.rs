@@ -6,27 +6,39 @@ use core::marker::PhantomData;
 use rand_core::CryptoRngCore;
 use serde::Serialize;
 
-use super::super::params::SchemeParams;
+use super::super::params::{params_fingerprint, SchemeParams};
 use super::super::{AuxInfo, KeyShare};
 use super::presigning::{self, PresigningResult};
 use super::signing::{self, SigningResult};
 use crate::curve::{RecoverableSignature, Scalar};
 use crate::rounds::{
     wrap_finalize_error, CorrectnessProofWrapper, FinalizableToNextRound, FinalizableToResult,
-    FinalizeError, FirstRound, InitError, ProtocolResult, ProvableErrorWrapper, Round,
-    RoundWrapper, ToNextRound, ToResult, WrappedRound,
+    FinalizeError, FirstRound, InitError, Protocol, ProtocolId, ProtocolResult,
+    ProvableErrorWrapper, Round, RoundWrapper, ToNextRound, ToResult, WrappedRound,
 };
+use crate::tools::hashing::HashOutput;
 
 /// Possible results of the merged Presigning and Signing protocols.
 #[derive(Debug)]
 pub struct InteractiveSigningResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
 
 impl<P: SchemeParams, I: Debug> ProtocolResult for InteractiveSigningResult<P, I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::InteractiveSigning;
     type Success = RecoverableSignature;
     type ProvableError = InteractiveSigningError<P, I>;
     type CorrectnessProof = InteractiveSigningProof<P, I>;
 }
 
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Protocol<I>
+    for InteractiveSigningResult<P, I>
+{
+    type FirstRound = Round1<P, I>;
+
+    fn params_fingerprint() -> Option<HashOutput> {
+        Some(params_fingerprint::<P>())
+    }
+}
+
 /// Possible verifiable errors of the merged Presigning and Signing protocols.
 #[derive(Debug)]
 pub enum InteractiveSigningError<P: SchemeParams, I: Debug> {
@@ -295,12 +307,12 @@ mod tests {
     use k256::ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
     use rand_core::{OsRng, RngCore};
 
-    use super::{Inputs, Round1};
+    use super::{Inputs, Round1, Round2, Round3, Round4};
     use crate::cggmp21::{AuxInfo, KeyShare, TestParams};
     use crate::curve::Scalar;
     use crate::rounds::{
-        test_utils::{step_next_round, step_result, step_round, Id, Without},
-        FirstRound,
+        test_utils::{round_info, step_next_round, step_result, step_round, Id, Without},
+        FirstRound, ProtocolId,
     };
 
     #[test]
@@ -357,4 +369,17 @@ mod tests {
             assert_eq!(recovered_key, vkey);
         }
     }
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [
+                round_info::<Id, Round1<TestParams, Id>>(),
+                round_info::<Id, Round2<TestParams, Id>>(),
+                round_info::<Id, Round3<TestParams, Id>>(),
+                round_info::<Id, Round4<TestParams, Id>>(),
+            ],
+            ProtocolId::InteractiveSigning.rounds()
+        );
+    }
 }
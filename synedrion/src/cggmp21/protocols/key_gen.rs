@@ -8,25 +8,35 @@ use core::marker::PhantomData;
 use rand_core::CryptoRngCore;
 use serde::Serialize;
 
-use super::super::{AuxInfo, KeyShare, SchemeParams};
+use super::super::{params_fingerprint, AuxInfo, KeyShare, SchemeParams};
 use super::key_init::{self, KeyInitResult};
 use super::key_refresh::{self, KeyRefreshResult};
 use crate::rounds::{
     no_direct_messages, wrap_finalize_error, CorrectnessProofWrapper, FinalizableToNextRound,
-    FinalizableToResult, FinalizeError, FirstRound, InitError, ProtocolResult, Round, ToNextRound,
-    ToResult,
+    FinalizableToResult, FinalizeError, FirstRound, InitError, Protocol, ProtocolId,
+    ProtocolResult, Round, ToNextRound, ToResult,
 };
+use crate::tools::hashing::HashOutput;
 
 /// Possible results of the merged KeyGen and KeyRefresh protocols.
 #[derive(Debug)]
 pub struct KeyGenResult<P: SchemeParams, I>(PhantomData<P>, PhantomData<I>);
 
 impl<P: SchemeParams, I: Debug + Ord> ProtocolResult for KeyGenResult<P, I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::KeyGen;
     type Success = (KeyShare<P, I>, AuxInfo<P, I>);
     type ProvableError = KeyGenError<P, I>;
     type CorrectnessProof = KeyGenProof<P, I>;
 }
 
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Protocol<I> for KeyGenResult<P, I> {
+    type FirstRound = Round1<P, I>;
+
+    fn params_fingerprint() -> Option<HashOutput> {
+        Some(params_fingerprint::<P>())
+    }
+}
+
 /// Possible verifiable errors of the merged KeyGen and KeyRefresh protocols.
 #[derive(Debug)]
 pub enum KeyGenError<P: SchemeParams, I: Debug + Ord> {
@@ -148,6 +158,21 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P,
             .map_err(KeyGenError::KeyRefresh)?;
         Ok((key_init_payload, key_refresh_payload))
     }
+
+    fn verify_own_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        let (key_init_message, key_refresh_message) = broadcast_msg;
+        self.key_init_round
+            .verify_own_message(rng, destination, key_init_message, &())
+            && self
+                .key_refresh_round
+                .verify_own_message(rng, destination, key_refresh_message, &())
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -245,6 +270,21 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
             .map_err(KeyGenError::KeyRefresh)?;
         Ok((key_init_payload, key_refresh_payload))
     }
+
+    fn verify_own_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        let (key_init_message, key_refresh_message) = broadcast_msg;
+        self.key_init_round
+            .verify_own_message(rng, destination, key_init_message, &())
+            && self
+                .key_refresh_round
+                .verify_own_message(rng, destination, key_refresh_message, &())
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -341,6 +381,20 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
             .map_err(KeyGenError::KeyRefresh)?;
         Ok((key_init_payload, key_refresh_payload))
     }
+
+    fn verify_own_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        self.key_init_round
+            .verify_own_message(rng, destination, broadcast_msg, &())
+            && self
+                .key_refresh_round
+                .verify_own_message(rng, destination, &(), direct_msg)
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round3<P, I> {
@@ -368,3 +422,25 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
         Ok((key_share.update(key_share_change), aux_info))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Round1, Round2, Round3};
+    use crate::cggmp21::TestParams;
+    use crate::rounds::{
+        test_utils::{round_info, Id},
+        ProtocolId,
+    };
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [
+                round_info::<Id, Round1<TestParams, Id>>(),
+                round_info::<Id, Round2<TestParams, Id>>(),
+                round_info::<Id, Round3<TestParams, Id>>(),
+            ],
+            ProtocolId::KeyGen.rounds()
+        );
+    }
+}
@@ -12,32 +12,49 @@ use secrecy::SecretBox;
 use serde::{Deserialize, Serialize};
 
 use super::super::{
+    params_fingerprint,
     sigma::{SchCommitment, SchProof, SchSecret},
     KeyShare, SchemeParams,
 };
 use crate::curve::{Point, Scalar};
+use crate::lineage::{outcome_fingerprint, session_fingerprint, Ceremony, Lineage};
 use crate::rounds::{
-    no_direct_messages, FinalizableToNextRound, FinalizableToResult, FinalizeError, FirstRound,
-    InitError, ProtocolResult, Round, ToNextRound, ToResult,
+    no_direct_messages, BroadcastTranscript, Check, FinalizableToNextRound, FinalizableToResult,
+    FinalizeError, FirstRound, InitError, Protocol, ProtocolId, ProtocolResult, Round, ToNextRound,
+    ToResult,
 };
+#[cfg(feature = "testing")]
+use crate::testing::malicious::{Behavior, Misbehave};
 use crate::tools::bitvec::BitVec;
 use crate::tools::hashing::{Chain, FofHasher, HashOutput};
+use crate::uint::subtle::ConstantTimeEq;
 
 /// Possible results of the KeyGen protocol.
 #[derive(Debug, Clone, Copy)]
 pub struct KeyInitResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
 
 impl<P: SchemeParams, I: Debug + Ord> ProtocolResult for KeyInitResult<P, I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::KeyInit;
     type Success = KeyShare<P, I>;
     type ProvableError = KeyInitError;
     type CorrectnessProof = ();
 }
 
+impl<P: SchemeParams, I: Clone + Ord + Serialize + Debug> Protocol<I> for KeyInitResult<P, I> {
+    type FirstRound = Round1<P, I>;
+
+    fn params_fingerprint() -> Option<HashOutput> {
+        Some(params_fingerprint::<P>())
+    }
+}
+
 /// Possible verifiable errors of the KeyGen protocol.
 #[derive(Debug, Clone, Copy)]
 pub enum KeyInitError {
     /// A hash mismatch in Round 2.
     R2HashMismatch,
+    /// The random identifier `rid` in Round 2 has a wrong length.
+    R2WrongRidLength,
     /// Failed to verify `П^{sch}` in Round 3.
     R3InvalidSchProof,
 }
@@ -51,9 +68,15 @@ struct PublicData<P: SchemeParams> {
     phantom: PhantomData<P>,
 }
 
+/// The domain of the commitments to the public data.
+pub(crate) const COMMITMENT_HASH_TAG: &[u8] = b"KeyInit";
+
+/// The domain of the session identifier hash.
+pub(crate) const SID_HASH_TAG: &[u8] = b"SID";
+
 impl<P: SchemeParams> PublicData<P> {
     fn hash<I: Serialize>(&self, sid_hash: &HashOutput, id: I) -> HashOutput {
-        FofHasher::new_with_dst(b"KeyInit")
+        FofHasher::new_with_dst(COMMITMENT_HASH_TAG)
             .chain(sid_hash)
             .chain(&id)
             .chain(self)
@@ -61,6 +84,17 @@ impl<P: SchemeParams> PublicData<P> {
     }
 }
 
+fn sid_hash<P: SchemeParams, I: Serialize>(
+    shared_randomness: &[u8],
+    all_ids: &BTreeSet<I>,
+) -> HashOutput {
+    FofHasher::new_with_dst(SID_HASH_TAG)
+        .chain_type::<P>()
+        .chain(&shared_randomness)
+        .chain(all_ids)
+        .finalize()
+}
+
 struct Context<P: SchemeParams, I> {
     other_ids: BTreeSet<I>,
     my_id: I,
@@ -68,6 +102,8 @@ struct Context<P: SchemeParams, I> {
     tau: SchSecret,
     public_data: PublicData<P>,
     sid_hash: HashOutput,
+    // Recorded in the lineage of the resulting key share.
+    session: HashOutput,
 }
 
 pub struct Round1<P: SchemeParams, I> {
@@ -87,11 +123,7 @@ impl<P: SchemeParams, I: Clone + Ord + Serialize + Debug> FirstRound<I> for Roun
         let mut all_ids = other_ids.clone();
         all_ids.insert(my_id.clone());
 
-        let sid_hash = FofHasher::new_with_dst(b"SID")
-            .chain_type::<P>()
-            .chain(&shared_randomness)
-            .chain(&all_ids)
-            .finalize();
+        let sid_hash = sid_hash::<P, I>(shared_randomness, &all_ids);
 
         // The secret share
         let x = Scalar::random(rng);
@@ -118,6 +150,7 @@ impl<P: SchemeParams, I: Clone + Ord + Serialize + Debug> FirstRound<I> for Roun
             tau,
             public_data,
             sid_hash,
+            session: session_fingerprint(shared_randomness),
         };
 
         Ok(Self { context })
@@ -244,19 +277,33 @@ impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> Round<I> for Round2<P,
 
     no_direct_messages!(I);
 
+    fn pre_verify_message(
+        &self,
+        from: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> Result<(), <Self::Result as ProtocolResult>::ProvableError> {
+        if !bool::from(
+            broadcast_msg
+                .data
+                .hash(&self.context.sid_hash, from)
+                .ct_eq(self.others_cap_v.get(from).unwrap()),
+        ) {
+            return Err(KeyInitError::R2HashMismatch);
+        }
+        if !broadcast_msg.data.rid.has_length(P::SECURITY_PARAMETER) {
+            return Err(KeyInitError::R2WrongRidLength);
+        }
+        Ok(())
+    }
+
     fn verify_message(
         &self,
         _rng: &mut impl CryptoRngCore,
-        from: &I,
+        _from: &I,
         broadcast_msg: Self::BroadcastMessage,
         _direct_msg: Self::DirectMessage,
     ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
-        if &broadcast_msg.data.hash(&self.context.sid_hash, from)
-            != self.others_cap_v.get(from).unwrap()
-        {
-            return Err(KeyInitError::R2HashMismatch);
-        }
-
         Ok(Round2Payload {
             data: broadcast_msg.data,
         })
@@ -350,6 +397,21 @@ impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> Round<I> for Round3<P,
         }
         Ok(())
     }
+
+    fn verify_own_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        let aux = (&self.context.sid_hash, self.my_id(), &self.rid);
+        broadcast_msg.psi.verify(
+            &self.context.public_data.cap_a,
+            &self.context.public_data.cap_x,
+            &aux,
+        )
+    }
 }
 
 impl<P: SchemeParams, I: Serialize + Clone + Ord + Debug> FinalizableToResult<I> for Round3<P, I> {
@@ -366,15 +428,116 @@ impl<P: SchemeParams, I: Serialize + Clone + Ord + Debug> FinalizableToResult<I>
             .map(|(k, v)| (k, v.cap_x))
             .collect::<BTreeMap<_, _>>();
         public_shares.insert(my_id.clone(), self.context.public_data.cap_x);
+        let all_ids = public_shares.keys().cloned().collect();
+        let lineage = Lineage::new(
+            Ceremony::KeyInit,
+            self.context.session,
+            outcome_fingerprint(&all_ids, &public_shares),
+            public_shares.values().sum(),
+        );
         Ok(KeyShare {
             owner: my_id,
             secret_share: SecretBox::new(Box::new(self.context.x)),
             public_shares,
             phantom: PhantomData,
+            lineage: Some(lineage),
         })
     }
 }
 
+#[cfg(feature = "testing")]
+impl<P: SchemeParams, I: Clone + Ord + Serialize + Debug> Misbehave<I> for Round1<P, I> {
+    fn equivocate_broadcast(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        destination: &I,
+        message: Self::BroadcastMessage,
+    ) -> Self::BroadcastMessage {
+        // A different commitment for each destination; none of them will be opened.
+        let cap_v = FofHasher::new_with_dst(b"Equivocation")
+            .chain(&message.cap_v)
+            .chain(destination)
+            .finalize();
+        Round1Message { cap_v }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> Misbehave<I> for Round2<P, I> {
+    fn tamper_broadcast(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        behavior: &Behavior,
+        mut message: Self::BroadcastMessage,
+    ) -> Self::BroadcastMessage {
+        if behavior == &Behavior::WrongHashReveal {
+            message.data.u = BitVec::random(rng, P::SECURITY_PARAMETER);
+        }
+        message
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> Misbehave<I> for Round3<P, I> {}
+
+/// Checks the broadcasts of a KeyInit run by a third party,
+/// returning the resulting verifying key if all of them are present and valid.
+pub(crate) fn verify_transcript<P: SchemeParams, I: Clone + Ord + Serialize>(
+    shared_randomness: &[u8],
+    all_ids: &BTreeSet<I>,
+    transcript: &mut impl BroadcastTranscript<I>,
+) -> Option<Point> {
+    let sid_hash = sid_hash::<P, I>(shared_randomness, all_ids);
+
+    let r1 = transcript.broadcasts::<Round1Message>(1);
+    let r2 = transcript.broadcasts::<Round2Message<P>>(2);
+    let r3 = transcript.broadcasts::<Round3Message>(3);
+
+    let mut valid = r1.len() == all_ids.len() && r2.len() == all_ids.len();
+    for (id, message) in r2.iter() {
+        let passed = message.data.rid.has_length(P::SECURITY_PARAMETER);
+        transcript.record(id, 2, Check::Format, passed);
+        valid &= passed;
+
+        let passed = match r1.get(id) {
+            Some(r1_message) => message
+                .data
+                .hash(&sid_hash, id)
+                .ct_eq(&r1_message.cap_v)
+                .into(),
+            None => false,
+        };
+        transcript.record(id, 2, Check::Commitment, passed);
+        valid &= passed;
+    }
+
+    // The Schnorr proofs depend on the combined `rid`, so they can only be checked
+    // if the public data of every party is known.
+    if !valid {
+        return None;
+    }
+
+    let mut rid = BitVec::zero(P::SECURITY_PARAMETER);
+    for message in r2.values() {
+        rid ^= &message.data.rid;
+    }
+
+    valid &= r3.len() == all_ids.len();
+    for (id, message) in r3.iter() {
+        let data = &r2[id].data;
+        let aux = (&sid_hash, id, &rid);
+        let passed = message.psi.verify(&data.cap_a, &data.cap_x, &aux);
+        transcript.record(id, 3, Check::SchProof, passed);
+        valid &= passed;
+    }
+
+    if !valid {
+        return None;
+    }
+
+    Some(r2.values().map(|message| message.data.cap_x).sum())
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::collections::{BTreeMap, BTreeSet};
@@ -382,12 +545,13 @@ mod tests {
     use rand_core::{OsRng, RngCore};
     use secrecy::ExposeSecret;
 
-    use super::Round1;
+    use super::{Round1, Round2, Round3};
     use crate::cggmp21::TestParams;
     use crate::rounds::{
-        test_utils::{step_next_round, step_result, step_round, Id, Without},
-        FirstRound,
+        test_utils::{round_info, step_next_round, step_result, step_round, Id, Without},
+        FirstRound, ProtocolId,
     };
+    use crate::uint::subtle::ConstantTimeEq;
 
     #[test]
     fn execute_keygen() {
@@ -415,6 +579,11 @@ mod tests {
         let r2 = step_next_round(&mut OsRng, r1a).unwrap();
         let r2a = step_round(&mut OsRng, r2).unwrap();
         let r3 = step_next_round(&mut OsRng, r2a).unwrap();
+
+        // All the parties combined the same `rid`
+        let rid = &r3[&Id(0)].rid;
+        assert!(r3.values().all(|round| bool::from(round.rid.ct_eq(rid))));
+
         let r3a = step_round(&mut OsRng, r3).unwrap();
         let shares = step_result(&mut OsRng, r3a).unwrap();
 
@@ -437,4 +606,16 @@ mod tests {
 
         assert!(public_set == &public_from_secret);
     }
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [
+                round_info::<Id, Round1<TestParams, Id>>(),
+                round_info::<Id, Round2<TestParams, Id>>(),
+                round_info::<Id, Round3<TestParams, Id>>(),
+            ],
+            ProtocolId::KeyInit.rounds()
+        );
+    }
 }
@@ -14,32 +14,46 @@ use secrecy::SecretBox;
 use serde::{Deserialize, Serialize};
 
 use super::super::{
+    params_fingerprint,
     sigma::{FacProof, ModProof, PrmProof, SchCommitment, SchProof, SchSecret},
     AuxInfo, KeyShareChange, PublicAuxInfo, SchemeParams, SecretAuxInfo,
 };
 use crate::curve::{Point, Scalar};
+use crate::lineage::session_fingerprint;
 use crate::paillier::{
-    Ciphertext, CiphertextMod, PublicKeyPaillier, PublicKeyPaillierPrecomputed, RPParams,
-    RPParamsMod, RPSecret, Randomizer, SecretKeyPaillier, SecretKeyPaillierPrecomputed,
+    Ciphertext, CiphertextMod, PaillierParams, PublicKeyPaillier, PublicKeyPaillierPrecomputed,
+    RPParams, RPParamsMod, RPSecret, Randomizer, SecretKeyPaillier, SecretKeyPaillierPrecomputed,
 };
 use crate::rounds::{
     no_broadcast_messages, no_direct_messages, FinalizableToNextRound, FinalizableToResult,
-    FinalizeError, FirstRound, InitError, ProtocolResult, Round, ToNextRound, ToResult,
+    FinalizeError, FirstRound, InitError, Protocol, ProtocolId, ProtocolResult, Round, ToNextRound,
+    ToResult,
 };
+#[cfg(feature = "testing")]
+use crate::testing::malicious::{Behavior, Misbehave};
 use crate::tools::bitvec::BitVec;
 use crate::tools::hashing::{Chain, FofHasher, HashOutput};
-use crate::uint::UintLike;
+use crate::uint::{subtle::ConstantTimeEq, UintLike};
 
 /// Possible results of the KeyRefresh protocol.
 #[derive(Debug)]
 pub struct KeyRefreshResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
 
 impl<P: SchemeParams, I: Debug + Ord> ProtocolResult for KeyRefreshResult<P, I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::KeyRefresh;
     type Success = (KeyShareChange<P, I>, AuxInfo<P, I>);
     type ProvableError = KeyRefreshError<P>;
     type CorrectnessProof = ();
 }
 
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Protocol<I> for KeyRefreshResult<P, I> {
+    type FirstRound = Round1<P, I>;
+
+    fn params_fingerprint() -> Option<HashOutput> {
+        Some(params_fingerprint::<P>())
+    }
+}
+
 #[derive(Debug)]
 pub struct KeyRefreshError<P: SchemeParams>(KeyRefreshErrorEnum<P>);
 
@@ -51,13 +65,34 @@ enum KeyRefreshErrorEnum<P: SchemeParams> {
     // TODO (#43): this can be removed when error verification is added
     #[allow(dead_code)]
     Round3(String),
-    // TODO (#43): this can be removed when error verification is added
-    #[allow(dead_code)]
-    Round3MismatchedSecret {
-        cap_c: Ciphertext<P::Paillier>,
-        x: Scalar,
-        mu: Randomizer<P::Paillier>,
-    },
+    Round3MismatchedSecret(MismatchedSecret<P>),
+}
+
+/// The evidence of the sender encrypting a wrong secret share in Round 3.
+///
+/// The receiver reveals the decrypted plaintext along with the Paillier randomizer (`\mu`)
+/// of the ciphertext, so that the other parties can check the decryption
+/// with the Paillier key of the receiver, without knowing its secret key.
+// TODO (#43): the fields will be read when error verification is added
+#[allow(dead_code)]
+#[derive(Debug)]
+struct MismatchedSecret<P: SchemeParams> {
+    cap_c: Ciphertext<P::Paillier>,
+    x: <P::Paillier as PaillierParams>::Uint,
+    mu: Randomizer<P::Paillier>,
+}
+
+impl<P: SchemeParams> MismatchedSecret<P> {
+    fn new(
+        cap_c: &CiphertextMod<P::Paillier>,
+        sk: &SecretKeyPaillierPrecomputed<P::Paillier>,
+    ) -> Self {
+        Self {
+            cap_c: cap_c.retrieve(),
+            x: cap_c.decrypt(sk),
+            mu: cap_c.derive_randomizer(sk).retrieve(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,12 +127,22 @@ struct Context<P: SchemeParams, I> {
     my_id: I,
     other_ids: BTreeSet<I>,
     sid_hash: HashOutput,
+    // Recorded in the lineage of the updated key share.
+    session: HashOutput,
+    // The positions of the parties in the canonical order (that of `I: Ord`),
+    // used to index the per-party vectors in `PublicData1`.
     ids_ordering: BTreeMap<I, usize>,
 }
 
+/// The domain of the commitments to the public data.
+pub(crate) const COMMITMENT_HASH_TAG: &[u8] = b"Auxiliary";
+
+/// The domain of the session identifier hash.
+pub(crate) const SID_HASH_TAG: &[u8] = b"SID";
+
 impl<P: SchemeParams> PublicData1<P> {
     fn hash<I: Serialize>(&self, sid_hash: &HashOutput, id: &I) -> HashOutput {
-        FofHasher::new_with_dst(b"Auxiliary")
+        FofHasher::new_with_dst(COMMITMENT_HASH_TAG)
             .chain(sid_hash)
             .chain(id)
             .chain(self)
@@ -128,7 +173,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
             .map(|(idx, id)| (id, idx))
             .collect();
 
-        let sid_hash = FofHasher::new_with_dst(b"SID")
+        let sid_hash = FofHasher::new_with_dst(SID_HASH_TAG)
             .chain_type::<P>()
             .chain(&shared_randomness)
             .chain(&all_ids)
@@ -204,6 +249,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
             my_id,
             other_ids,
             sid_hash,
+            session: session_fingerprint(shared_randomness),
             ids_ordering,
         };
 
@@ -335,21 +381,38 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
 
     no_direct_messages!(I);
 
-    fn verify_message(
+    fn pre_verify_message(
         &self,
-        _rng: &mut impl CryptoRngCore,
         from: &I,
-        broadcast_msg: Self::BroadcastMessage,
-        _direct_msg: Self::DirectMessage,
-    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
-        if &broadcast_msg.data.hash(&self.context.sid_hash, from)
-            != self.others_cap_v.get(from).unwrap()
-        {
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> Result<(), <Self::Result as ProtocolResult>::ProvableError> {
+        if !bool::from(
+            broadcast_msg
+                .data
+                .hash(&self.context.sid_hash, from)
+                .ct_eq(self.others_cap_v.get(from).unwrap()),
+        ) {
             return Err(KeyRefreshError(KeyRefreshErrorEnum::Round2(
                 "Hash mismatch".into(),
             )));
         }
+        // Committed to, but would make the combined `rho` impossible to compute.
+        if !broadcast_msg.data.rho.has_length(P::SECURITY_PARAMETER) {
+            return Err(KeyRefreshError(KeyRefreshErrorEnum::Round2(
+                "Wrong length of rho".into(),
+            )));
+        }
+        Ok(())
+    }
 
+    fn verify_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        from: &I,
+        broadcast_msg: Self::BroadcastMessage,
+        _direct_msg: Self::DirectMessage,
+    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
         let paillier_pk = broadcast_msg.data.paillier_pk.to_precomputed();
 
         if paillier_pk.modulus().bits_vartime() < 8 * P::SECURITY_PARAMETER {
@@ -366,7 +429,20 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
 
         let aux = (&self.context.sid_hash, &from);
 
-        let rp_params = broadcast_msg.data.rp_params.to_mod(&paillier_pk);
+        let rp_params = broadcast_msg
+            .data
+            .rp_params
+            .to_mod_checked(&paillier_pk)
+            .ok_or_else(|| {
+                KeyRefreshError(KeyRefreshErrorEnum::Round2(
+                    "Ring-Pedersen parameters are not reduced".into(),
+                ))
+            })?;
+        if !broadcast_msg.data.rp_params.is_nontrivial(&paillier_pk) {
+            return Err(KeyRefreshError(KeyRefreshErrorEnum::Round2(
+                "Ring-Pedersen parameters are trivial or not invertible".into(),
+            )));
+        }
         if !broadcast_msg.data.hat_psi.verify(&rp_params, &aux) {
             return Err(KeyRefreshError(KeyRefreshErrorEnum::Round2(
                 "PRM verification failed".into(),
@@ -381,6 +457,21 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
             },
         })
     }
+
+    fn verify_own_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        let aux = (&self.context.sid_hash, self.my_id());
+        broadcast_msg.data.cap_x_to_send.iter().sum::<Point>() == Point::IDENTITY
+            && broadcast_msg
+                .data
+                .hat_psi
+                .verify(&self.context.data_precomp.rp_params, &aux)
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -549,20 +640,22 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
             .paillier_enc_x
             .to_mod(self.context.paillier_sk.public_key());
 
-        let x = P::scalar_from_uint(&enc_x.decrypt(&self.context.paillier_sk));
-
         let my_idx = self.context.ids_ordering[self.my_id()];
 
-        if x.mul_by_generator() != sender_data.data.cap_x_to_send[my_idx] {
-            let mu = enc_x.derive_randomizer(&self.context.paillier_sk);
-            return Err(KeyRefreshError(
-                KeyRefreshErrorEnum::Round3MismatchedSecret {
-                    cap_c: direct_msg.data2.paillier_enc_x,
-                    x,
-                    mu: mu.retrieve(),
-                },
-            ));
-        }
+        // If the decrypted secret is out of range or does not match the commitment,
+        // the Paillier randomizer is revealed so that others could verify
+        // that it was the sender who cheated.
+        let x = P::try_scalar_from_uint(&enc_x.decrypt(&self.context.paillier_sk))
+            .filter(|x| {
+                x.mul_by_generator()
+                    .ct_eq(&sender_data.data.cap_x_to_send[my_idx])
+                    .into()
+            })
+            .ok_or_else(|| {
+                KeyRefreshError(KeyRefreshErrorEnum::Round3MismatchedSecret(
+                    MismatchedSecret::new(&enc_x, &self.context.paillier_sk),
+                ))
+            })?;
 
         let aux = (&self.context.sid_hash, &from, &self.rho);
 
@@ -608,6 +701,38 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
 
         Ok(Round3Payload { x })
     }
+
+    fn verify_own_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &I,
+        _broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        let my_data = &self.context.data_precomp;
+        let destination_data = &self.others_data[destination];
+        let destination_idx = self.context.ids_ordering[destination];
+
+        let aux = (&self.context.sid_hash, self.my_id(), &self.rho);
+
+        direct_msg
+            .data2
+            .psi_mod
+            .verify(rng, &my_data.paillier_pk, &aux)
+            && direct_msg
+                .data2
+                .phi
+                .verify(&my_data.paillier_pk, &destination_data.rp_params, &aux)
+            && direct_msg
+                .data2
+                .pi
+                .verify(&my_data.data.cap_b, &my_data.data.cap_y, &aux)
+            && direct_msg.data2.psi_sch.verify(
+                &my_data.data.cap_a_to_send[destination_idx],
+                &my_data.data.cap_x_to_send[destination_idx],
+                &aux,
+            )
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round3<P, I> {
@@ -635,8 +760,8 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
         // The combined public share changes for each node
         let cap_x_star = all_ids
             .iter()
-            .enumerate()
-            .map(|(idx, id)| {
+            .map(|id| {
+                let idx = self.context.ids_ordering[id];
                 (
                     id.clone(),
                     all_data
@@ -671,6 +796,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
             secret_share_change: SecretBox::new(Box::new(x_star)),
             public_share_changes: cap_x_star,
             phantom: PhantomData,
+            session: self.context.session,
         };
 
         let aux_info = AuxInfo {
@@ -683,21 +809,67 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
     }
 }
 
+#[cfg(feature = "testing")]
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Misbehave<I> for Round1<P, I> {
+    fn equivocate_broadcast(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        destination: &I,
+        message: Self::BroadcastMessage,
+    ) -> Self::BroadcastMessage {
+        // A different commitment for each destination; none of them will be opened.
+        let cap_v = FofHasher::new_with_dst(b"Equivocation")
+            .chain(&message.cap_v)
+            .chain(destination)
+            .finalize();
+        Round1Message { cap_v }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Misbehave<I> for Round2<P, I> {}
+
+#[cfg(feature = "testing")]
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Misbehave<I> for Round3<P, I> {
+    fn tamper_direct(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        behavior: &Behavior,
+        destination: &I,
+        mut message: Self::DirectMessage,
+        artifact: Self::Artifact,
+    ) -> (Self::DirectMessage, Self::Artifact) {
+        if behavior == &Behavior::MismatchedSecret {
+            let x = Scalar::random(rng);
+            let paillier_pk = &self.others_data[destination].paillier_pk;
+            message.data2.paillier_enc_x =
+                CiphertextMod::new(rng, paillier_pk, &P::uint_from_scalar(&x)).retrieve();
+        }
+        (message, artifact)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use alloc::collections::{BTreeMap, BTreeSet};
 
+    use rand::{rngs::StdRng, SeedableRng};
     use rand_core::{OsRng, RngCore};
     use secrecy::ExposeSecret;
 
-    use super::Round1;
-    use crate::cggmp21::TestParams;
+    use super::{MismatchedSecret, Round1, Round2, Round3};
+    use crate::cggmp21::{AuxInfo, KeyShareChange, SchemeParams, TestParams};
     use crate::curve::Scalar;
+    use crate::paillier::{CiphertextMod, SecretKeyPaillier};
     use crate::rounds::{
-        test_utils::{step_next_round, step_result, step_round, Id, Without},
-        FirstRound,
+        test_utils::{
+            round_info, step_next_round, step_result, step_round, step_round_with_delivery_order,
+            Id, Without,
+        },
+        FirstRound, ProtocolId,
     };
+    use crate::uint::subtle::ConstantTimeEq;
 
     #[test]
     fn execute_key_refresh() {
@@ -725,6 +897,11 @@ mod tests {
         let r2 = step_next_round(&mut OsRng, r1a).unwrap();
         let r2a = step_round(&mut OsRng, r2).unwrap();
         let r3 = step_next_round(&mut OsRng, r2a).unwrap();
+
+        // All the parties combined the same `rho`
+        let rho = &r3[&Id(0)].rho;
+        assert!(r3.values().all(|round| bool::from(round.rho.ct_eq(rho))));
+
         let r3a = step_round(&mut OsRng, r3).unwrap();
         let results = step_result(&mut OsRng, r3a).unwrap();
 
@@ -767,4 +944,90 @@ mod tests {
             .sum();
         assert_eq!(mask_sum, Scalar::ZERO);
     }
+
+    #[allow(clippy::type_complexity)]
+    fn run_with_delivery_order(
+        seed: u64,
+        reverse_delivery: bool,
+    ) -> BTreeMap<Id, (KeyShareChange<TestParams, Id>, AuxInfo<TestParams, Id>)> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let shared_randomness = [1u8; 32];
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2), Id(3)]);
+
+        let r1 = ids
+            .iter()
+            .map(|id| {
+                let round = Round1::<TestParams, Id>::new(
+                    &mut rng,
+                    &shared_randomness,
+                    ids.clone().without(id),
+                    *id,
+                    (),
+                )
+                .unwrap();
+                (*id, round)
+            })
+            .collect();
+
+        let r1a = step_round_with_delivery_order(&mut rng, r1, reverse_delivery).unwrap();
+        let r2 = step_next_round(&mut rng, r1a).unwrap();
+        let r2a = step_round_with_delivery_order(&mut rng, r2, reverse_delivery).unwrap();
+        let r3 = step_next_round(&mut rng, r2a).unwrap();
+        let r3a = step_round_with_delivery_order(&mut rng, r3, reverse_delivery).unwrap();
+        step_result(&mut rng, r3a).unwrap()
+    }
+
+    #[test]
+    fn delivery_order_independence() {
+        // All the randomness that determines the result is drawn on initialization,
+        // so the same seed must lead to the same result regardless of the delivery order.
+        let results = run_with_delivery_order(123, false);
+        let results_reversed = run_with_delivery_order(123, true);
+
+        for (id, (change, aux_info)) in results.iter() {
+            let (change_reversed, aux_info_reversed) = &results_reversed[id];
+            assert_eq!(
+                change.secret_share_change.expose_secret(),
+                change_reversed.secret_share_change.expose_secret()
+            );
+            assert_eq!(
+                change.public_share_changes,
+                change_reversed.public_share_changes
+            );
+            for (other_id, public_aux) in aux_info.public_aux.iter() {
+                assert_eq!(
+                    public_aux.el_gamal_pk,
+                    aux_info_reversed.public_aux[other_id].el_gamal_pk
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn mismatched_secret_evidence() {
+        type Paillier = <TestParams as SchemeParams>::Paillier;
+
+        let sk = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+        let x = Scalar::random(&mut OsRng);
+        let cap_c = CiphertextMod::new(&mut OsRng, pk, &TestParams::uint_from_scalar(&x));
+
+        // The revealed plaintext and randomizer reproduce the ciphertext
+        let evidence = MismatchedSecret::<TestParams>::new(&cap_c, &sk);
+        assert_eq!(evidence.x, TestParams::uint_from_scalar(&x));
+        assert!(CiphertextMod::new_with_randomizer(pk, &evidence.x, &evidence.mu) == cap_c);
+    }
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [
+                round_info::<Id, Round1<TestParams, Id>>(),
+                round_info::<Id, Round2<TestParams, Id>>(),
+                round_info::<Id, Round3<TestParams, Id>>(),
+            ],
+            ProtocolId::KeyRefresh.rounds()
+        );
+    }
 }
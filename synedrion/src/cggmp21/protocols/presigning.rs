@@ -12,15 +12,16 @@ use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
 
 use super::super::{
-    entities::{AuxInfoPrecomputed, PresigningValues},
-    sigma::{AffGProof, DecProof, EncProof, LogStarProof, MulProof},
+    entities::{AuxInfoPrecomputed, PresigningPublic, PresigningPublicShare, PresigningValues},
+    params_fingerprint,
+    sigma::{AffGProof, DecProof, EncProof, LogStarProof, MulProof, MulStarProof},
     AuxInfo, KeyShare, PresigningData, SchemeParams,
 };
 use crate::curve::{Point, Scalar};
 use crate::paillier::{Ciphertext, CiphertextMod, PaillierParams, Randomizer, RandomizerMod};
 use crate::rounds::{
     no_broadcast_messages, FinalizableToNextRound, FinalizableToResult, FinalizeError, FirstRound,
-    InitError, ProtocolResult, Round, ToNextRound, ToResult,
+    InitError, Protocol, ProtocolId, ProtocolResult, Round, ToNextRound, ToResult,
 };
 use crate::tools::hashing::{Chain, FofHasher, HashOutput};
 use crate::uint::Signed;
@@ -30,11 +31,20 @@ use crate::uint::Signed;
 pub struct PresigningResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
 
 impl<P: SchemeParams, I: Debug> ProtocolResult for PresigningResult<P, I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::Presigning;
     type Success = PresigningData<P, I>;
     type ProvableError = PresigningError;
     type CorrectnessProof = PresigningProof<P, I>;
 }
 
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Protocol<I> for PresigningResult<P, I> {
+    type FirstRound = Round1<P, I>;
+
+    fn params_fingerprint() -> Option<HashOutput> {
+        Some(params_fingerprint::<P>())
+    }
+}
+
 /// Possible verifiable errors of the Presigning protocol.
 #[derive(Debug, Clone)]
 pub enum PresigningError {
@@ -46,6 +56,9 @@ pub enum PresigningError {
     Round3(String),
 }
 
+/// The domain of the share set identifier hash.
+pub(crate) const SSID_HASH_TAG: &[u8] = b"ShareSetID";
+
 struct Context<P: SchemeParams, I: Ord> {
     ssid_hash: HashOutput,
     my_id: I,
@@ -78,7 +91,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
         // This includes the info of $ssid$ in the paper
         // (scheme parameters + public data from all shares - hashed in `share_set_id`),
         // with the session randomness added.
-        let ssid_hash = FofHasher::new_with_dst(b"ShareSetID")
+        let ssid_hash = FofHasher::new_with_dst(SSID_HASH_TAG)
             .chain_type::<P>()
             .chain(&shared_randomness)
             .chain(&key_share.public_shares)
@@ -218,6 +231,26 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P,
             cap_g: broadcast_msg.cap_g,
         })
     }
+
+    fn verify_own_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        let aux = (&self.context.ssid_hash, destination);
+
+        let pk = self.context.aux_info.secret_aux.paillier_sk.public_key();
+        let destination_aux = &self.context.aux_info.public_aux[destination];
+
+        direct_msg.psi0.verify(
+            pk,
+            &broadcast_msg.cap_k.to_mod(pk),
+            &destination_aux.rp_params,
+            &aux,
+        )
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -555,6 +588,9 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound
             + hat_alpha_sum
             + hat_beta_sum;
 
+        // Allows the verification of the partial signatures, see `PartialSignature`.
+        let cap_s = cap_gamma * P::scalar_from_signed(&chi);
+
         let (cap_ds, hat_cap_ds) = payloads
             .into_iter()
             .map(|(id, payload)| ((id.clone(), payload.cap_d), (id, payload.hat_cap_d)))
@@ -566,6 +602,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound
             chi,
             cap_delta,
             cap_gamma,
+            cap_s,
             all_cap_k: self.all_cap_k,
             all_cap_g: self.all_cap_g,
             cap_ds,
@@ -581,6 +618,7 @@ pub struct Round3<P: SchemeParams, I: Ord> {
     chi: Signed<<P::Paillier as PaillierParams>::Uint>,
     cap_delta: Point,
     cap_gamma: Point,
+    cap_s: Point, // $\Gamma^{\chi_i}$
     all_cap_k: BTreeMap<I, CiphertextMod<P::Paillier>>,
     all_cap_g: BTreeMap<I, CiphertextMod<P::Paillier>>,
     cap_ds: BTreeMap<I, CiphertextMod<P::Paillier>>,
@@ -594,12 +632,14 @@ pub struct Round3<P: SchemeParams, I: Ord> {
 pub struct Round3Message<P: SchemeParams> {
     delta: Scalar,
     cap_delta: Point,
+    cap_s: Point,
     psi_pprime: LogStarProof<P>,
 }
 
 pub struct Round3Payload {
     delta: Scalar,
     cap_delta: Point,
+    cap_s: Point,
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P, I> {
@@ -648,6 +688,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
         let message = Round3Message {
             delta: P::scalar_from_signed(&self.delta),
             cap_delta: self.cap_delta,
+            cap_s: self.cap_s,
             psi_pprime,
         };
 
@@ -682,17 +723,203 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
         Ok(Round3Payload {
             delta: direct_msg.delta,
             cap_delta: direct_msg.cap_delta,
+            cap_s: direct_msg.cap_s,
         })
     }
+
+    fn verify_own_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        destination: &I,
+        _broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        let aux = (&self.context.ssid_hash, self.my_id());
+        let pk = self.context.aux_info.secret_aux.paillier_sk.public_key();
+        let destination_aux = &self.context.aux_info.public_aux[destination];
+
+        direct_msg.psi_pprime.verify(
+            pk,
+            &self.all_cap_k[self.my_id()],
+            &self.cap_gamma,
+            &direct_msg.cap_delta,
+            &destination_aux.rp_params,
+            &aux,
+        )
+    }
 }
 
 /// A proof of a node's correct behavior for the Presigning protocol.
+#[derive(Debug, Clone)]
+pub struct PresigningProof<P: SchemeParams, I>(PresigningProofEnum<P, I>);
+
 #[allow(dead_code)] // TODO (#43): this can be removed when error verification is added
 #[derive(Debug, Clone)]
-pub struct PresigningProof<P: SchemeParams, I> {
-    aff_g_proofs: Vec<(I, I, AffGProof<P>)>,
-    mul_proof: MulProof<P>,
-    dec_proofs: Vec<(I, DecProof<P>)>,
+enum PresigningProofEnum<P: SchemeParams, I> {
+    /// The assembled $\delta$ does not match the assembled $\Delta$:
+    /// proves that $\delta_i$ was calculated correctly.
+    Delta {
+        aff_g_proofs: Vec<(I, I, AffGProof<P>)>,
+        mul_proof: MulProof<P>,
+        dec_proofs: Vec<(I, DecProof<P>)>,
+    },
+    /// The values of $S_j$ do not add up to $\Gamma^{k x}$:
+    /// proves that $S_i = \Gamma^{\chi_i}$, where $\chi_i$ is calculated correctly.
+    ///
+    /// `hat_cap_h` is $K_i^{x_i}$ (proved by the mul* proofs), and `cap_c` is
+    /// a fresh encryption of $\chi_i \mod q$ (proved to match $S_i$ by the log* proofs).
+    /// The dec proofs show that the difference between `cap_c` and the ciphertext of $\chi_i$
+    /// assembled from `hat_cap_h` and the MtA ciphertexts (proved by the aff-g proofs)
+    /// decrypts to $0 \mod q$.
+    ProductShare {
+        aff_g_proofs: Vec<(I, I, AffGProof<P>)>,
+        hat_cap_h: Ciphertext<P::Paillier>,
+        mul_star_proofs: Vec<(I, MulStarProof<P>)>,
+        cap_c: Ciphertext<P::Paillier>,
+        log_star_proofs: Vec<(I, LogStarProof<P>)>,
+        dec_proofs: Vec<(I, DecProof<P>)>,
+    },
+}
+
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round3<P, I> {
+    /// Proves that the published $S_i$ is correct
+    /// (see [`PresigningProofEnum::ProductShare`]).
+    fn product_share_proof(&self, rng: &mut impl CryptoRngCore) -> PresigningProof<P, I> {
+        let my_id = self.my_id();
+        let aux = (&self.context.ssid_hash, my_id);
+
+        let sk = &self.context.aux_info.secret_aux.paillier_sk;
+        let pk = sk.public_key();
+        let x = self.context.key_share.secret_share.expose_secret();
+        let cap_x = self.context.key_share.public_shares[my_id];
+        let cap_k = &self.all_cap_k[my_id];
+
+        // Aff-g proofs for $\hat{D}_{j,i}$ and $\hat{F}_{j,i}$
+
+        let mut aff_g_proofs = Vec::new();
+
+        for id_j in self.other_ids() {
+            let artifact = &self.round2_artifacts[id_j];
+            let target_pk = &self.context.aux_info.public_aux[id_j].paillier_pk;
+
+            for id_l in self.other_ids().iter().filter(|id| id != &id_j) {
+                let rp = &self.context.aux_info.public_aux[id_l].rp_params;
+
+                let p_aff_g = AffGProof::<P>::new(
+                    rng,
+                    &P::signed_from_scalar(x),
+                    &artifact.hat_beta,
+                    &artifact.hat_s.to_mod(target_pk),
+                    &artifact.hat_r.to_mod(pk),
+                    target_pk,
+                    pk,
+                    &self.all_cap_k[id_j],
+                    &artifact.hat_cap_d,
+                    &artifact.hat_cap_f,
+                    &cap_x,
+                    rp,
+                    &aux,
+                );
+
+                assert!(p_aff_g.verify(
+                    target_pk,
+                    pk,
+                    &self.all_cap_k[id_j],
+                    &artifact.hat_cap_d,
+                    &artifact.hat_cap_f,
+                    &cap_x,
+                    rp,
+                    &aux,
+                ));
+
+                aff_g_proofs.push((id_j.clone(), id_l.clone(), p_aff_g));
+            }
+        }
+
+        // Mul* proofs for $\hat{H}_i = K_i^{x_i}$
+
+        let rho = RandomizerMod::random(rng, pk);
+        let hat_cap_h = (cap_k * P::bounded_from_scalar(x)).mul_randomizer(&rho.retrieve());
+
+        let mut mul_star_proofs = Vec::new();
+        for id_l in self.other_ids() {
+            let rp = &self.context.aux_info.public_aux[id_l].rp_params;
+            let p_mul = MulStarProof::<P>::new(
+                rng,
+                &P::signed_from_scalar(x),
+                &rho,
+                pk,
+                cap_k,
+                &hat_cap_h,
+                &cap_x,
+                rp,
+                &aux,
+            );
+            assert!(p_mul.verify(pk, cap_k, &hat_cap_h, &cap_x, rp, &aux));
+            mul_star_proofs.push((id_l.clone(), p_mul));
+        }
+
+        // Log* proofs for $S_i = \Gamma^{\chi_i \mod q}$
+
+        let chi_reduced = P::signed_from_scalar(&P::scalar_from_signed(&self.chi));
+        let rho = RandomizerMod::random(rng, pk);
+        let cap_c = CiphertextMod::new_with_randomizer_signed(pk, &chi_reduced, &rho.retrieve());
+
+        let mut log_star_proofs = Vec::new();
+        for id_l in self.other_ids() {
+            let rp = &self.context.aux_info.public_aux[id_l].rp_params;
+            let p_log_star = LogStarProof::<P>::new(
+                rng,
+                &chi_reduced,
+                &rho,
+                pk,
+                &cap_c,
+                &self.cap_gamma,
+                &self.cap_s,
+                rp,
+                &aux,
+            );
+            assert!(p_log_star.verify(pk, &cap_c, &self.cap_gamma, &self.cap_s, rp, &aux));
+            log_star_proofs.push((id_l.clone(), p_log_star));
+        }
+
+        // Dec proofs for the difference of the ciphertexts of $\chi_i$ being $0 \mod q$
+
+        let mut ciphertext = hat_cap_h.clone();
+        for id_j in self.other_ids() {
+            ciphertext =
+                ciphertext + &self.hat_cap_ds[id_j] + &self.round2_artifacts[id_j].hat_cap_f;
+        }
+        let ciphertext = ciphertext + &cap_c * -P::signed_from_scalar(&Scalar::ONE);
+        let rho = ciphertext.derive_randomizer(sk);
+        let difference = self.chi - chi_reduced;
+
+        let mut dec_proofs = Vec::new();
+        for id_l in self.other_ids() {
+            let rp = &self.context.aux_info.public_aux[id_l].rp_params;
+            let p_dec = DecProof::<P>::new(
+                rng,
+                &difference,
+                &rho,
+                pk,
+                &Scalar::ZERO,
+                &ciphertext,
+                rp,
+                &aux,
+            );
+            assert!(p_dec.verify(pk, &Scalar::ZERO, &ciphertext, rp, &aux));
+            dec_proofs.push((id_l.clone(), p_dec));
+        }
+
+        PresigningProof(PresigningProofEnum::ProductShare {
+            aff_g_proofs,
+            hat_cap_h: hat_cap_h.retrieve(),
+            mul_star_proofs,
+            cap_c: cap_c.retrieve(),
+            log_star_proofs,
+            dec_proofs,
+        })
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round3<P, I> {
@@ -702,44 +929,83 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
         payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
         _artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
     ) -> Result<<Self::Result as ProtocolResult>::Success, FinalizeError<Self::Result>> {
-        let (deltas, cap_deltas): (BTreeMap<_, _>, BTreeMap<_, _>) = payloads
-            .into_iter()
-            .map(|(id, payload)| ((id.clone(), payload.delta), (id, payload.cap_delta)))
-            .unzip();
+        let my_id = self.my_id().clone();
+        let mut deltas = BTreeMap::new();
+        let mut cap_deltas = BTreeMap::new();
+        let mut cap_ss = BTreeMap::new();
+        for (id, payload) in payloads {
+            deltas.insert(id.clone(), payload.delta);
+            cap_deltas.insert(id.clone(), payload.cap_delta);
+            cap_ss.insert(id, payload.cap_s);
+        }
+        cap_deltas.insert(my_id.clone(), self.cap_delta);
+        cap_ss.insert(my_id.clone(), self.cap_s);
 
         let scalar_delta = P::scalar_from_signed(&self.delta);
         let assembled_delta: Scalar = scalar_delta + deltas.values().sum::<Scalar>();
-        let assembled_cap_delta: Point = self.cap_delta + cap_deltas.values().sum::<Point>();
+        let assembled_cap_delta: Point = cap_deltas.values().sum::<Point>();
 
         if assembled_delta.mul_by_generator() == assembled_cap_delta {
-            let nonce = (self.cap_gamma * assembled_delta.invert().unwrap()).x_coordinate();
-            let my_id = self.my_id().clone();
-
-            let values = self
-                .round2_artifacts
-                .into_iter()
-                .map(|(id, artifact)| {
-                    let values = PresigningValues {
-                        hat_beta: artifact.hat_beta,
-                        hat_r: artifact.hat_r,
-                        hat_s: artifact.hat_s,
-                        cap_k: self.all_cap_k[&id].clone(),
-                        hat_cap_d_received: self.hat_cap_ds[&id].clone(),
-                        hat_cap_d: artifact.hat_cap_d,
-                        hat_cap_f: artifact.hat_cap_f,
-                    };
-                    (id, values)
-                })
-                .collect();
-
-            return Ok(PresigningData {
-                nonce,
-                ephemeral_scalar_share: SecretBox::new(Box::new(self.context.k)),
-                product_share: SecretBox::new(Box::new(P::scalar_from_signed(&self.chi))),
-                product_share_nonreduced: self.chi,
-                cap_k: self.all_cap_k[&my_id].clone(),
-                values,
-            });
+            let delta_inv = assembled_delta.invert().unwrap();
+            let cap_r = self.cap_gamma * delta_inv;
+            let nonce = cap_r.x_coordinate();
+
+            // $R^{k_j} = \Delta_j^{\delta^{-1}}$ and $R^{\chi_j} = S_j^{\delta^{-1}}$.
+            let public = PresigningPublic {
+                cap_r,
+                shares: cap_deltas
+                    .iter()
+                    .map(|(id, cap_delta)| {
+                        let share = PresigningPublicShare {
+                            cap_r_k: *cap_delta * delta_inv,
+                            cap_r_chi: cap_ss[id] * delta_inv,
+                        };
+                        (id.clone(), share)
+                    })
+                    .collect(),
+            };
+
+            // Since $R^k = g$, $\sum_j R^{\chi_j} = R^{k x} = X$ if all the parties sent correct $S_j$.
+            // This ensures that a signature assembled from the partial signatures
+            // passing the verification is valid.
+            let cap_x = self.context.key_share.verifying_key_as_point();
+            let assembled_cap_r_chi = public
+                .shares
+                .values()
+                .map(|share| share.cap_r_chi)
+                .sum::<Point>();
+            if assembled_cap_r_chi == cap_x {
+                let values = self
+                    .round2_artifacts
+                    .into_iter()
+                    .map(|(id, artifact)| {
+                        let values = PresigningValues {
+                            hat_beta: artifact.hat_beta,
+                            hat_r: artifact.hat_r,
+                            hat_s: artifact.hat_s,
+                            cap_k: self.all_cap_k[&id].clone(),
+                            hat_cap_d_received: self.hat_cap_ds[&id].clone(),
+                            hat_cap_d: artifact.hat_cap_d,
+                            hat_cap_f: artifact.hat_cap_f,
+                        };
+                        (id, values)
+                    })
+                    .collect();
+
+                return Ok(PresigningData {
+                    nonce,
+                    ephemeral_scalar_share: SecretBox::new(Box::new(self.context.k)),
+                    product_share: SecretBox::new(Box::new(P::scalar_from_signed(&self.chi))),
+                    product_share_nonreduced: self.chi,
+                    cap_k: self.all_cap_k[&my_id].clone(),
+                    values,
+                    public,
+                    created_at: None,
+                    ttl: None,
+                });
+            }
+
+            return Err(FinalizeError::Proof(self.product_share_proof(rng)));
         }
 
         // Construct the correctness proofs
@@ -856,11 +1122,13 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
             dec_proofs.push((id_j.clone(), p_dec));
         }
 
-        Err(FinalizeError::Proof(PresigningProof {
-            aff_g_proofs,
-            dec_proofs,
-            mul_proof: p_mul,
-        }))
+        Err(FinalizeError::Proof(PresigningProof(
+            PresigningProofEnum::Delta {
+                aff_g_proofs,
+                dec_proofs,
+                mul_proof: p_mul,
+            },
+        )))
     }
 }
 
@@ -871,12 +1139,12 @@ mod tests {
     use rand_core::{OsRng, RngCore};
     use secrecy::ExposeSecret;
 
-    use super::Round1;
+    use super::{PresigningProof, PresigningProofEnum, Round1, Round2, Round3};
     use crate::cggmp21::{AuxInfo, KeyShare, TestParams};
-    use crate::curve::Scalar;
+    use crate::curve::{Point, Scalar};
     use crate::rounds::{
-        test_utils::{step_next_round, step_result, step_round, Id, Without},
-        FirstRound,
+        test_utils::{round_info, step_next_round, step_result, step_round, Id, Without},
+        FinalizeError, FirstRound, ProtocolId,
     };
 
     #[test]
@@ -939,5 +1207,98 @@ mod tests {
             k.invert().unwrap().mul_by_generator().x_coordinate(),
             presigning_datas[&Id(0)].nonce
         );
+
+        // Check that the public parts match the secret shares.
+        let cap_r = k.invert().unwrap().mul_by_generator();
+        for data in presigning_datas.values() {
+            assert_eq!(data.public.cap_r, cap_r);
+            for (id, share) in data.public.shares.iter() {
+                let other_data = &presigning_datas[id];
+                assert_eq!(
+                    share.cap_r_k,
+                    cap_r * other_data.ephemeral_scalar_share.expose_secret()
+                );
+                assert_eq!(
+                    share.cap_r_chi,
+                    cap_r * other_data.product_share.expose_secret()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_product_share_commitment() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let r1 = ids
+            .iter()
+            .map(|id| {
+                let round = Round1::<TestParams, Id>::new(
+                    &mut OsRng,
+                    &shared_randomness,
+                    ids.clone().without(id),
+                    *id,
+                    (key_shares[id].clone(), aux_infos[id].clone()),
+                )
+                .unwrap();
+                (*id, round)
+            })
+            .collect();
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        let r2a = step_round(&mut OsRng, r2).unwrap();
+        let mut r3 = step_next_round(&mut OsRng, r2a).unwrap();
+
+        // `Id(0)` publishes a wrong $S_i$
+        r3.get_mut(&Id(0)).unwrap().cap_s = Point::GENERATOR;
+
+        let round = &r3[&Id(1)];
+        let aux = (round.context.ssid_hash, Id(1));
+        let public_aux = round.context.aux_info.public_aux.clone();
+        let (cap_gamma, cap_s) = (round.cap_gamma, round.cap_s);
+
+        // The honest parties fail the final check, and prove that their own $S_i$ are correct.
+        let mut r3a = step_round(&mut OsRng, r3).unwrap();
+        r3a.remove(&Id(0));
+        let result = step_result(&mut OsRng, r3a);
+
+        let proof = match result {
+            Err(FinalizeError::Proof(PresigningProof(proof))) => proof,
+            _ => panic!("expected a correctness proof"),
+        };
+        let (cap_c, log_star_proofs) = match proof {
+            PresigningProofEnum::ProductShare {
+                cap_c,
+                log_star_proofs,
+                ..
+            } => (cap_c, log_star_proofs),
+            PresigningProofEnum::Delta { .. } => panic!("expected a proof for the product share"),
+        };
+
+        let pk = &public_aux[&Id(1)].paillier_pk;
+        let cap_c = cap_c.to_mod(pk);
+        for (id_l, proof) in log_star_proofs {
+            let rp = &public_aux[&id_l].rp_params;
+            assert!(proof.verify(pk, &cap_c, &cap_gamma, &cap_s, rp, &aux));
+        }
+    }
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [
+                round_info::<Id, Round1<TestParams, Id>>(),
+                round_info::<Id, Round2<TestParams, Id>>(),
+                round_info::<Id, Round3<TestParams, Id>>(),
+            ],
+            ProtocolId::Presigning.rounds()
+        );
     }
 }
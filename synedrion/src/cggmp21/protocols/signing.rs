@@ -5,21 +5,25 @@ use alloc::vec::Vec;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
+use k256::ecdsa::VerifyingKey;
 use rand_core::CryptoRngCore;
 use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
 
 use super::super::{
     entities::AuxInfoPrecomputed,
+    params_fingerprint,
     sigma::{AffGProof, DecProof, MulStarProof},
-    AuxInfo, KeyShare, PresigningData, SchemeParams,
+    AuxInfo, KeyShare, PresigningData, PresigningPublic, SchemeParams,
 };
-use crate::curve::{RecoverableSignature, Scalar};
+use crate::constructors::PrehashedMessage;
+use crate::curve::{Point, RecoverableSignature, Scalar};
 use crate::paillier::RandomizerMod;
 use crate::rounds::{
-    no_direct_messages, FinalizableToResult, FinalizeError, FirstRound, InitError, ProtocolResult,
-    Round, ToResult,
+    no_direct_messages, BroadcastTranscript, Check, FinalizableToResult, FinalizeError, FirstRound,
+    InitError, Protocol, ProtocolId, ProtocolResult, Round, ToResult,
 };
+use crate::sessions::EvidenceContext;
 use crate::tools::hashing::{Chain, FofHasher, HashOutput};
 
 /// Possible results of the Signing protocol.
@@ -27,11 +31,72 @@ use crate::tools::hashing::{Chain, FofHasher, HashOutput};
 pub struct SigningResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
 
 impl<P: SchemeParams, I: Debug> ProtocolResult for SigningResult<P, I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::Signing;
     type Success = RecoverableSignature;
-    type ProvableError = ();
+    type ProvableError = SigningError;
     type CorrectnessProof = SigningProof<P, I>;
 }
 
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Protocol<I> for SigningResult<P, I> {
+    type FirstRound = Round1<P, I>;
+
+    fn params_fingerprint() -> Option<HashOutput> {
+        Some(params_fingerprint::<P>())
+    }
+}
+
+/// Possible verifiable errors of the Signing protocol.
+#[derive(Debug, Clone, Copy)]
+pub enum SigningError {
+    /// The partial signature does not match the public presigning data of the sender.
+    InvalidPartialSignature,
+}
+
+/// A share of the signature created by a party in the Signing protocol.
+///
+/// Can be verified individually against the public presigning data,
+/// so that an invalid share can be attributed to its creator.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PartialSignature {
+    sigma: Scalar, // $\sigma_i$
+}
+
+impl PartialSignature {
+    /// Checks that the partial signature of a prehashed message was created by `party`
+    /// using the presigning data with the public part `presigning`.
+    pub fn verify<I: Ord>(
+        &self,
+        presigning: &PresigningPublic<I>,
+        party: &I,
+        prehashed_message: &PrehashedMessage,
+    ) -> bool {
+        self.verify_scalar(
+            presigning,
+            party,
+            &Scalar::from_reduced_bytes(prehashed_message),
+        )
+    }
+
+    pub(crate) fn sigma(&self) -> Scalar {
+        self.sigma
+    }
+
+    fn verify_scalar<I: Ord>(
+        &self,
+        presigning: &PresigningPublic<I>,
+        party: &I,
+        message: &Scalar,
+    ) -> bool {
+        let share = match presigning.shares.get(party) {
+            Some(share) => share,
+            None => return false,
+        };
+        // Since $\sigma_i = k_i m + r \chi_i$, $R^{\sigma_i} = (R^{k_i})^m (R^{\chi_i})^r$.
+        let r = presigning.cap_r.x_coordinate();
+        presigning.cap_r * self.sigma == share.cap_r_k * message + share.cap_r_chi * r
+    }
+}
+
 /// A proof of a node's correct behavior for the Signing protocol.
 #[allow(dead_code)] // TODO (#43): this can be removed when error verification is added
 #[derive(Debug, Clone)]
@@ -41,152 +106,136 @@ pub struct SigningProof<P: SchemeParams, I> {
     dec_proofs: Vec<(I, DecProof<P>)>,
 }
 
-pub struct Round1<P: SchemeParams, I: Ord> {
+/// The domain of the share set identifier hash.
+pub(crate) const SSID_HASH_TAG: &[u8] = b"ShareSetID";
+
+/// The data used for signing that does not depend on the message,
+/// shared by all the messages in a batch.
+pub(crate) struct SigningContext<P: SchemeParams, I: Ord> {
     ssid_hash: HashOutput,
-    r: Scalar,
-    sigma: Scalar,
-    inputs: Inputs<P, I>,
+    key_share: KeyShare<P, I>,
     aux_info: AuxInfoPrecomputed<P, I>,
     other_ids: BTreeSet<I>,
     my_id: I,
 }
 
-#[derive(Clone)]
-pub struct Inputs<P: SchemeParams, I: Ord> {
-    pub message: Scalar,
-    pub presigning: PresigningData<P, I>,
-    pub key_share: KeyShare<P, I>,
-    pub aux_info: AuxInfo<P, I>,
-}
-
-impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Round1<P, I> {
-    type Inputs = Inputs<P, I>;
-    fn new(
-        _rng: &mut impl CryptoRngCore,
+impl<P: SchemeParams, I: Clone + Ord + Serialize> SigningContext<P, I> {
+    pub(crate) fn new(
         shared_randomness: &[u8],
         other_ids: BTreeSet<I>,
         my_id: I,
-        inputs: Self::Inputs,
-    ) -> Result<Self, InitError> {
+        key_share: KeyShare<P, I>,
+        aux_info: AuxInfo<P, I>,
+    ) -> Self {
         // This includes the info of $ssid$ in the paper
         // (scheme parameters + public data from all shares - hashed in `share_set_id`),
         // with the session randomness added.
-        let ssid_hash = FofHasher::new_with_dst(b"ShareSetID")
+        let ssid_hash = FofHasher::new_with_dst(SSID_HASH_TAG)
             .chain_type::<P>()
             .chain(&shared_randomness)
-            .chain(&inputs.key_share.public_shares)
-            .chain(&inputs.aux_info.public_aux)
+            .chain(&key_share.public_shares)
+            .chain(&aux_info.public_aux)
             .finalize();
 
-        let r = inputs.presigning.nonce;
-        let sigma = inputs.presigning.ephemeral_scalar_share.expose_secret() * &inputs.message
-            + r * inputs.presigning.product_share.expose_secret();
-        Ok(Self {
+        Self {
             ssid_hash,
-            r,
-            sigma,
-            aux_info: inputs.aux_info.clone().to_precomputed(),
-            inputs,
+            key_share,
+            aux_info: aux_info.to_precomputed(),
             other_ids,
             my_id,
-        })
+        }
     }
-}
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct Round1Message {
-    sigma: Scalar,
-}
-
-pub struct Round1Payload {
-    sigma: Scalar,
-}
-
-impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P, I> {
-    type Type = ToResult;
-    type Result = SigningResult<P, I>;
-    const ROUND_NUM: u8 = 1;
-    const NEXT_ROUND_NUM: Option<u8> = None;
+    pub(crate) fn ssid_hash(&self) -> &HashOutput {
+        &self.ssid_hash
+    }
 
-    fn other_ids(&self) -> &BTreeSet<I> {
+    pub(crate) fn other_ids(&self) -> &BTreeSet<I> {
         &self.other_ids
     }
 
-    fn my_id(&self) -> &I {
+    pub(crate) fn my_id(&self) -> &I {
         &self.my_id
     }
+}
 
-    type BroadcastMessage = Round1Message;
-    type DirectMessage = ();
-    type Payload = Round1Payload;
-    type Artifact = ();
+/// The signing of a single message with its presigning data.
+pub(crate) struct SigningItem<P: SchemeParams, I: Ord> {
+    message: Scalar,
+    presigning: PresigningData<P, I>,
+    sigma: Scalar,
+}
 
-    fn make_broadcast_message(
-        &self,
-        _rng: &mut impl CryptoRngCore,
-    ) -> Option<Self::BroadcastMessage> {
-        Some(Round1Message { sigma: self.sigma })
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> SigningItem<P, I> {
+    pub(crate) fn new(message: Scalar, presigning: PresigningData<P, I>) -> Self {
+        let sigma = presigning.ephemeral_scalar_share.expose_secret() * &message
+            + presigning.nonce * presigning.product_share.expose_secret();
+        Self {
+            message,
+            presigning,
+            sigma,
+        }
     }
 
-    no_direct_messages!(I);
+    pub(crate) fn partial_signature(&self) -> PartialSignature {
+        PartialSignature { sigma: self.sigma }
+    }
 
-    fn verify_message(
-        &self,
-        _rng: &mut impl CryptoRngCore,
-        _from: &I,
-        broadcast_msg: Self::BroadcastMessage,
-        _direct_msg: Self::DirectMessage,
-    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
-        Ok(Round1Payload {
-            sigma: broadcast_msg.sigma,
-        })
+    pub(crate) fn verify_partial_signature(&self, from: &I, partial: &PartialSignature) -> bool {
+        partial.verify_scalar(&self.presigning.public, from, &self.message)
     }
-}
 
-impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round1<P, I> {
-    fn finalize_to_result(
+    /// The public data allowing a third party to check the partial signatures.
+    pub(crate) fn evidence_context(&self) -> EvidenceContext<I> {
+        EvidenceContext::Signing {
+            presigning: self.presigning.public.clone(),
+            prehashed_message: self.message.to_bytes().into(),
+        }
+    }
+
+    /// Assembles the signature from the partial signatures of the other parties,
+    /// or, if it is invalid, creates the proofs of this party's correct behavior
+    /// bound to `ssid_hash`.
+    pub(crate) fn finalize(
         self,
         rng: &mut impl CryptoRngCore,
-        payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
-        _artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
-    ) -> Result<<Self::Result as ProtocolResult>::Success, FinalizeError<Self::Result>> {
-        let assembled_sigma = payloads
-            .values()
-            .map(|payload| payload.sigma)
-            .sum::<Scalar>()
-            + self.sigma;
+        context: &SigningContext<P, I>,
+        ssid_hash: &HashOutput,
+        sigmas: impl Iterator<Item = Scalar>,
+    ) -> Result<RecoverableSignature, SigningProof<P, I>> {
+        let assembled_sigma = sigmas.sum::<Scalar>() + self.sigma;
 
         let signature = RecoverableSignature::from_scalars(
-            &self.r,
+            &self.presigning.nonce,
             &assembled_sigma,
-            &self.inputs.key_share.verifying_key_as_point(),
-            &self.inputs.message,
+            &context.key_share.verifying_key_as_point(),
+            &self.message,
         );
 
         if let Some(signature) = signature {
             return Ok(signature);
         }
 
-        let my_id = self.my_id().clone();
-        let aux = (&self.ssid_hash, &my_id);
+        let my_id = context.my_id.clone();
+        let aux = (ssid_hash, &my_id);
 
-        let sk = &self.aux_info.secret_aux.paillier_sk;
+        let sk = &context.aux_info.secret_aux.paillier_sk;
         let pk = sk.public_key();
 
         // Aff-g proofs
 
         let mut aff_g_proofs = Vec::new();
 
-        for id_j in self.other_ids() {
-            for id_l in self.other_ids().iter().filter(|id| id != &id_j) {
-                let target_pk = &self.aux_info.public_aux[id_j].paillier_pk;
-                let rp = &self.aux_info.public_aux[id_l].rp_params;
+        for id_j in context.other_ids.iter() {
+            for id_l in context.other_ids.iter().filter(|id| id != &id_j) {
+                let target_pk = &context.aux_info.public_aux[id_j].paillier_pk;
+                let rp = &context.aux_info.public_aux[id_l].rp_params;
 
-                let values = &self.inputs.presigning.values.get(id_j).unwrap();
+                let values = &self.presigning.values.get(id_j).unwrap();
 
                 let p_aff_g = AffGProof::<P>::new(
                     rng,
-                    &P::signed_from_scalar(self.inputs.key_share.secret_share.expose_secret()),
+                    &P::signed_from_scalar(context.key_share.secret_share.expose_secret()),
                     &values.hat_beta,
                     &values.hat_s.to_mod(target_pk),
                     &values.hat_r.to_mod(pk),
@@ -195,7 +244,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
                     &values.cap_k,
                     &values.hat_cap_d,
                     &values.hat_cap_f,
-                    &self.inputs.key_share.public_shares[&my_id],
+                    &context.key_share.public_shares[&my_id],
                     rp,
                     &aux,
                 );
@@ -206,7 +255,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
                     &values.cap_k,
                     &values.hat_cap_d,
                     &values.hat_cap_f,
-                    &self.inputs.key_share.public_shares[&my_id],
+                    &context.key_share.public_shares[&my_id],
                     rp,
                     &aux,
                 ));
@@ -217,36 +266,34 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
 
         // mul* proofs
 
-        let x = &self.inputs.key_share.secret_share;
-        let cap_x = self.inputs.key_share.public_shares[&my_id];
+        let x = &context.key_share.secret_share;
+        let cap_x = context.key_share.public_shares[&my_id];
 
         let rho = RandomizerMod::random(rng, pk);
-        let hat_cap_h = (&self.inputs.presigning.cap_k * P::bounded_from_scalar(x.expose_secret()))
+        let hat_cap_h = (&self.presigning.cap_k * P::bounded_from_scalar(x.expose_secret()))
             .mul_randomizer(&rho.retrieve());
 
-        let aux = (&self.ssid_hash, &my_id);
-
         let mut mul_star_proofs = Vec::new();
 
-        for id_l in self.other_ids() {
+        for id_l in context.other_ids.iter() {
             let p_mul = MulStarProof::<P>::new(
                 rng,
                 &P::signed_from_scalar(x.expose_secret()),
                 &rho,
                 pk,
-                &self.inputs.presigning.cap_k,
+                &self.presigning.cap_k,
                 &hat_cap_h,
                 &cap_x,
-                &self.aux_info.public_aux[id_l].rp_params,
+                &context.aux_info.public_aux[id_l].rp_params,
                 &aux,
             );
 
             assert!(p_mul.verify(
                 pk,
-                &self.inputs.presigning.cap_k,
+                &self.presigning.cap_k,
                 &hat_cap_h,
                 &cap_x,
-                &self.aux_info.public_aux[id_l].rp_params,
+                &context.aux_info.public_aux[id_l].rp_params,
                 &aux,
             ));
 
@@ -256,29 +303,26 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
         // dec proofs
 
         let mut ciphertext = hat_cap_h.clone();
-        for id_j in self.other_ids() {
-            let values = &self.inputs.presigning.values.get(id_j).unwrap();
+        for id_j in context.other_ids.iter() {
+            let values = &self.presigning.values.get(id_j).unwrap();
             ciphertext = ciphertext + &values.hat_cap_d_received + &values.hat_cap_f;
         }
 
-        let r = self.inputs.presigning.nonce;
+        let r = self.presigning.nonce;
 
         let ciphertext = ciphertext * P::bounded_from_scalar(&r)
-            + &self.inputs.presigning.cap_k * P::bounded_from_scalar(&self.inputs.message);
+            + &self.presigning.cap_k * P::bounded_from_scalar(&self.message);
 
         let rho = ciphertext.derive_randomizer(sk);
         // This is the same as `s_part` but if all the calculations were performed
         // without reducing modulo curve order.
-        let s_part_nonreduced = P::signed_from_scalar(
-            self.inputs
-                .presigning
-                .ephemeral_scalar_share
-                .expose_secret(),
-        ) * P::signed_from_scalar(&self.inputs.message)
-            + self.inputs.presigning.product_share_nonreduced * P::signed_from_scalar(&r);
+        let s_part_nonreduced =
+            P::signed_from_scalar(self.presigning.ephemeral_scalar_share.expose_secret())
+                * P::signed_from_scalar(&self.message)
+                + self.presigning.product_share_nonreduced * P::signed_from_scalar(&r);
 
         let mut dec_proofs = Vec::new();
-        for id_l in self.other_ids() {
+        for id_l in context.other_ids.iter() {
             let p_dec = DecProof::<P>::new(
                 rng,
                 &s_part_nonreduced,
@@ -286,42 +330,193 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
                 pk,
                 &self.sigma,
                 &ciphertext,
-                &self.aux_info.public_aux[id_l].rp_params,
+                &context.aux_info.public_aux[id_l].rp_params,
                 &aux,
             );
             assert!(p_dec.verify(
                 pk,
                 &self.sigma,
                 &ciphertext,
-                &self.aux_info.public_aux[id_l].rp_params,
+                &context.aux_info.public_aux[id_l].rp_params,
                 &aux,
             ));
             dec_proofs.push((id_l.clone(), p_dec));
         }
 
-        let proof = SigningProof {
+        Err(SigningProof {
             aff_g_proofs,
             mul_star_proofs,
             dec_proofs,
-        };
+        })
+    }
+}
+
+pub struct Round1<P: SchemeParams, I: Ord> {
+    context: SigningContext<P, I>,
+    item: SigningItem<P, I>,
+}
 
-        Err(FinalizeError::Proof(proof))
+#[derive(Clone)]
+pub struct Inputs<P: SchemeParams, I: Ord> {
+    pub message: Scalar,
+    pub presigning: PresigningData<P, I>,
+    pub key_share: KeyShare<P, I>,
+    pub aux_info: AuxInfo<P, I>,
+}
+
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Round1<P, I> {
+    type Inputs = Inputs<P, I>;
+    fn new(
+        _rng: &mut impl CryptoRngCore,
+        shared_randomness: &[u8],
+        other_ids: BTreeSet<I>,
+        my_id: I,
+        inputs: Self::Inputs,
+    ) -> Result<Self, InitError> {
+        Ok(Self {
+            context: SigningContext::new(
+                shared_randomness,
+                other_ids,
+                my_id,
+                inputs.key_share,
+                inputs.aux_info,
+            ),
+            item: SigningItem::new(inputs.message, inputs.presigning),
+        })
+    }
+}
+
+pub struct Round1Payload {
+    sigma: Scalar,
+}
+
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P, I> {
+    type Type = ToResult;
+    type Result = SigningResult<P, I>;
+    const ROUND_NUM: u8 = 1;
+    const NEXT_ROUND_NUM: Option<u8> = None;
+
+    fn other_ids(&self) -> &BTreeSet<I> {
+        self.context.other_ids()
+    }
+
+    fn my_id(&self) -> &I {
+        self.context.my_id()
+    }
+
+    type BroadcastMessage = PartialSignature;
+    type DirectMessage = ();
+    type Payload = Round1Payload;
+    type Artifact = ();
+
+    fn make_broadcast_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+    ) -> Option<Self::BroadcastMessage> {
+        Some(self.item.partial_signature())
+    }
+
+    no_direct_messages!(I);
+
+    fn verify_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        from: &I,
+        broadcast_msg: Self::BroadcastMessage,
+        _direct_msg: Self::DirectMessage,
+    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
+        if !self.item.verify_partial_signature(from, &broadcast_msg) {
+            return Err(SigningError::InvalidPartialSignature);
+        }
+        Ok(Round1Payload {
+            sigma: broadcast_msg.sigma,
+        })
+    }
+
+    fn verify_own_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        self.item
+            .verify_partial_signature(self.my_id(), broadcast_msg)
+    }
+
+    fn evidence_context(&self) -> EvidenceContext<I> {
+        self.item.evidence_context()
+    }
+}
+
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round1<P, I> {
+    fn finalize_to_result(
+        self,
+        rng: &mut impl CryptoRngCore,
+        payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
+        _artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
+    ) -> Result<<Self::Result as ProtocolResult>::Success, FinalizeError<Self::Result>> {
+        self.item
+            .finalize(
+                rng,
+                &self.context,
+                self.context.ssid_hash(),
+                payloads.into_values().map(|payload| payload.sigma),
+            )
+            .map_err(FinalizeError::Proof)
     }
 }
 
+/// Checks the partial signatures broadcasted in the given round by a third party,
+/// returning the resulting signature if all of them are present and valid.
+pub(crate) fn verify_transcript<I: Clone + Ord>(
+    round: u8,
+    presigning: &PresigningPublic<I>,
+    verifying_key: &VerifyingKey,
+    prehashed_message: &PrehashedMessage,
+    transcript: &mut impl BroadcastTranscript<I>,
+) -> Option<RecoverableSignature> {
+    let message = Scalar::from_reduced_bytes(prehashed_message);
+
+    let r1 = transcript.broadcasts::<PartialSignature>(round);
+
+    let mut valid = r1.len() == presigning.shares.len();
+    for (id, partial_signature) in r1.iter() {
+        let passed = partial_signature.verify_scalar(presigning, id, &message);
+        transcript.record(id, round, Check::PartialSignature, passed);
+        valid &= passed;
+    }
+
+    if !valid {
+        return None;
+    }
+
+    let sigma = r1
+        .values()
+        .map(|partial_signature| partial_signature.sigma)
+        .sum();
+    RecoverableSignature::from_scalars(
+        &presigning.cap_r.x_coordinate(),
+        &sigma,
+        &Point::from_verifying_key(verifying_key),
+        &message,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
 
     use k256::ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
     use rand_core::{OsRng, RngCore};
 
-    use super::{Inputs, Round1};
+    use super::{Inputs, PartialSignature, Round1};
     use crate::cggmp21::{AuxInfo, KeyShare, PresigningData, TestParams};
     use crate::curve::Scalar;
     use crate::rounds::{
-        test_utils::{step_result, step_round, Id, Without},
-        FirstRound,
+        test_utils::{round_info, step_result, step_round, Id, Without},
+        FirstRound, ProtocolId, Round,
     };
 
     #[test]
@@ -375,4 +570,60 @@ mod tests {
             assert_eq!(recovered_key, vkey);
         }
     }
+
+    #[test]
+    fn partial_signatures() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+        let presigning_datas = PresigningData::new_centralized(&mut OsRng, &key_shares, &aux_infos);
+        let public = presigning_datas[&Id(0)].public().clone();
+
+        let message = Scalar::random(&mut OsRng);
+
+        let partials = ids
+            .iter()
+            .map(|id| {
+                let round = Round1::<TestParams, Id>::new(
+                    &mut OsRng,
+                    b"",
+                    ids.clone().without(id),
+                    *id,
+                    Inputs {
+                        presigning: presigning_datas[id].clone(),
+                        message,
+                        key_share: key_shares[id].clone(),
+                        aux_info: aux_infos[id].clone(),
+                    },
+                )
+                .unwrap();
+                (*id, round.make_broadcast_message(&mut OsRng).unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        for (id, partial) in partials.iter() {
+            assert!(partial.verify_scalar(&public, id, &message));
+            assert!(!partial.verify_scalar(&public, id, &Scalar::random(&mut OsRng)));
+            assert!(!partial.verify_scalar(&public, &Id(3), &message));
+        }
+
+        // A partial signature is bound to its creator
+        let (id0, partial0) = &partials[0];
+        let (id1, _) = &partials[1];
+        assert!(!partial0.verify_scalar(&public, id1, &message));
+
+        let tampered = PartialSignature {
+            sigma: partial0.sigma + Scalar::ONE,
+        };
+        assert!(!tampered.verify_scalar(&public, id0, &message));
+    }
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [round_info::<Id, Round1<TestParams, Id>>()],
+            ProtocolId::Signing.rounds()
+        );
+    }
 }
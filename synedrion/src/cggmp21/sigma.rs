@@ -1,23 +1,92 @@
 //! Sigma-protocols
 
+#[cfg(feature = "spec")]
+use core::any::type_name;
+
+#[cfg(feature = "spec")]
+use super::SchemeParams;
+
+/// Converts a value received from another party to its modulo representation,
+/// making the enclosing `verify()` return `false` if the value is not reduced.
+#[cfg(any(
+    feature = "key-gen",
+    feature = "aux-gen",
+    feature = "signing",
+    feature = "paillier"
+))]
+macro_rules! to_mod_or_reject {
+    ($value: expr, $modulus: expr) => {
+        match $value.to_mod_checked($modulus) {
+            Some(value) => value,
+            None => return false,
+        }
+    };
+}
+
+#[cfg(feature = "signing")]
 mod aff_g;
+#[cfg(feature = "signing")]
 mod dec;
+#[cfg(feature = "signing")]
 mod enc;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 mod fac;
+#[cfg(feature = "signing")]
 mod log_star;
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
 mod mod_;
+#[cfg(feature = "signing")]
 mod mul;
+#[cfg(feature = "signing")]
 mod mul_star;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 mod prm;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 mod sch;
 
+#[cfg(feature = "signing")]
 pub(crate) use aff_g::AffGProof;
+#[cfg(feature = "signing")]
 pub(crate) use dec::DecProof;
+#[cfg(feature = "signing")]
 pub(crate) use enc::EncProof;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 pub(crate) use fac::FacProof;
+#[cfg(feature = "signing")]
 pub(crate) use log_star::LogStarProof;
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
 pub(crate) use mod_::ModProof;
+#[cfg(feature = "signing")]
 pub(crate) use mul::MulProof;
+#[cfg(feature = "signing")]
 pub(crate) use mul_star::MulStarProof;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 pub(crate) use prm::PrmProof;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 pub(crate) use sch::{SchCommitment, SchProof, SchSecret};
+
+/// The sigma-protocols, as tuples of the type of the proof, its name in the paper,
+/// and the domain of its Fiat-Shamir challenge.
+#[cfg(feature = "spec")]
+pub(crate) fn proof_types<P: SchemeParams>() -> [(&'static str, &'static str, &'static [u8]); 10] {
+    [
+        (type_name::<AffGProof<P>>(), "Π^{aff-g}", aff_g::HASH_TAG),
+        (type_name::<DecProof<P>>(), "Π^{dec}", dec::HASH_TAG),
+        (type_name::<EncProof<P>>(), "Π^{enc}", enc::HASH_TAG),
+        (type_name::<FacProof<P>>(), "Π^{fac}", fac::HASH_TAG),
+        (
+            type_name::<LogStarProof<P>>(),
+            "Π^{log*}",
+            log_star::HASH_TAG,
+        ),
+        (type_name::<ModProof<P>>(), "Π^{mod}", mod_::HASH_TAG),
+        (type_name::<MulProof<P>>(), "Π^{mul}", mul::HASH_TAG),
+        (
+            type_name::<MulStarProof<P>>(),
+            "Π^{mul*}",
+            mul_star::HASH_TAG,
+        ),
+        (type_name::<PrmProof<P>>(), "Π^{prm}", prm::HASH_TAG),
+        (type_name::<SchProof>(), "Π^{sch}", sch::HASH_TAG),
+    ]
+}
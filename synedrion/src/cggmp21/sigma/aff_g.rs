@@ -10,9 +10,10 @@ use crate::paillier::{
     RPParamsMod, Randomizer, RandomizerMod,
 };
 use crate::tools::hashing::{Chain, Hashable, XofHasher};
+use crate::tools::profile::profile_scope;
 use crate::uint::Signed;
 
-const HASH_TAG: &[u8] = b"P_aff_g";
+pub(crate) const HASH_TAG: &[u8] = b"P_aff_g";
 
 /**
 ZK proof: Paillier Affine Operation with Group Commitment in Range.
@@ -71,6 +72,7 @@ impl<P: SchemeParams> AffGProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
+        profile_scope!("AffGProof::new");
         x.assert_bound(P::L_BOUND);
         y.assert_bound(P::LP_BOUND);
         assert!(cap_c.public_key() == pk0);
@@ -117,11 +119,11 @@ impl<P: SchemeParams> AffGProof<P> {
             // public parameters
             .chain(pk0.as_minimal())
             .chain(pk1.as_minimal())
-            .chain(&cap_c.retrieve())
-            .chain(&cap_d.retrieve())
-            .chain(&cap_y.retrieve())
+            .chain(cap_c)
+            .chain(cap_d)
+            .chain(cap_y)
             .chain(cap_x)
-            .chain(&setup.retrieve())
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -177,6 +179,7 @@ impl<P: SchemeParams> AffGProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
+        profile_scope!("AffGProof::verify");
         assert!(cap_c.public_key() == pk0);
         assert!(cap_d.public_key() == pk0);
         assert!(cap_y.public_key() == pk1);
@@ -193,11 +196,11 @@ impl<P: SchemeParams> AffGProof<P> {
             // public parameters
             .chain(pk0.as_minimal())
             .chain(pk1.as_minimal())
-            .chain(&cap_c.retrieve())
-            .chain(&cap_d.retrieve())
-            .chain(&cap_y.retrieve())
+            .chain(cap_c)
+            .chain(cap_d)
+            .chain(cap_y)
             .chain(cap_x)
-            .chain(&setup.retrieve())
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -222,8 +225,9 @@ impl<P: SchemeParams> AffGProof<P> {
 
         // C^{z_1} (1 + N_0)^{z_2} \omega^{N_0} = A D^e \mod N_0^2
         // => C (*) z_1 (+) encrypt_0(z_2, \omega) = A (+) D (*) e
+        let cap_a_mod = to_mod_or_reject!(self.cap_a, pk0);
         if cap_c * self.z1 + CiphertextMod::new_with_randomizer_signed(pk0, &self.z2, &self.omega)
-            != cap_d * e + self.cap_a.to_mod(pk0)
+            != cap_d * e + cap_a_mod
         {
             return false;
         }
@@ -240,22 +244,23 @@ impl<P: SchemeParams> AffGProof<P> {
         // Original: `Y^e`. Modified `Y^{-e}`.
         // (1 + N_1)^{z_2} \omega_y^{N_1} = B_y Y^(-e) \mod N_1^2
         // => encrypt_1(z_2, \omega_y) = B_y (+) Y (*) (-e)
+        let cap_b_y_mod = to_mod_or_reject!(self.cap_b_y, pk1);
         if CiphertextMod::new_with_randomizer_signed(pk1, &self.z2, &self.omega_y)
-            != cap_y * (-e) + self.cap_b_y.to_mod(pk1)
+            != cap_y * (-e) + cap_b_y_mod
         {
             return false;
         }
 
         // s^{z_1} t^{z_3} = E S^e \mod \hat{N}
-        let cap_e_mod = self.cap_e.to_mod(aux_pk);
-        let cap_s_mod = self.cap_s.to_mod(aux_pk);
+        let cap_e_mod = to_mod_or_reject!(self.cap_e, aux_pk);
+        let cap_s_mod = to_mod_or_reject!(self.cap_s, aux_pk);
         if setup.commit(&self.z1, &self.z3) != &cap_e_mod * &cap_s_mod.pow_signed_vartime(&e) {
             return false;
         }
 
         // s^{z_2} t^{z_4} = F T^e \mod \hat{N}
-        let cap_f_mod = self.cap_f.to_mod(aux_pk);
-        let cap_t_mod = self.cap_t.to_mod(aux_pk);
+        let cap_f_mod = to_mod_or_reject!(self.cap_f, aux_pk);
+        let cap_t_mod = to_mod_or_reject!(self.cap_t, aux_pk);
         if setup.commit(&self.z2, &self.z4) != &cap_f_mod * &cap_t_mod.pow_signed_vartime(&e) {
             return false;
         }
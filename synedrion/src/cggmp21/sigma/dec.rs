@@ -10,9 +10,10 @@ use crate::paillier::{
     RPParamsMod, Randomizer, RandomizerMod,
 };
 use crate::tools::hashing::{Chain, Hashable, XofHasher};
+use crate::tools::profile::profile_scope;
 use crate::uint::Signed;
 
-const HASH_TAG: &[u8] = b"P_dec";
+pub(crate) const HASH_TAG: &[u8] = b"P_dec";
 
 /**
 ZK proof: Paillier decryption modulo $q$.
@@ -52,6 +53,7 @@ impl<P: SchemeParams> DecProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
+        profile_scope!("DecProof::new");
         assert_eq!(cap_c.public_key(), pk0);
 
         let hat_cap_n = &setup.public_key().modulus_bounded(); // $\hat{N}$
@@ -79,8 +81,8 @@ impl<P: SchemeParams> DecProof<P> {
             // public parameters
             .chain(pk0.as_minimal())
             .chain(x)
-            .chain(&cap_c.retrieve())
-            .chain(&setup.retrieve())
+            .chain(cap_c)
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -112,6 +114,7 @@ impl<P: SchemeParams> DecProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
+        profile_scope!("DecProof::verify");
         assert_eq!(cap_c.public_key(), pk0);
 
         let mut reader = XofHasher::new_with_dst(HASH_TAG)
@@ -123,8 +126,8 @@ impl<P: SchemeParams> DecProof<P> {
             // public parameters
             .chain(pk0.as_minimal())
             .chain(x)
-            .chain(&cap_c.retrieve())
-            .chain(&setup.retrieve())
+            .chain(cap_c)
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -136,8 +139,9 @@ impl<P: SchemeParams> DecProof<P> {
         }
 
         // enc(z_1, \omega) == A (+) C (*) e
+        let cap_a_mod = to_mod_or_reject!(self.cap_a, pk0);
         if CiphertextMod::new_with_randomizer_wide(pk0, &self.z1, &self.omega)
-            != self.cap_a.to_mod(pk0) + cap_c * e
+            != cap_a_mod + cap_c * e
         {
             return false;
         }
@@ -148,8 +152,14 @@ impl<P: SchemeParams> DecProof<P> {
         }
 
         // s^{z_1} t^{z_2} == T S^e
-        let cap_s_mod = self.cap_s.to_mod(setup.public_key());
-        let cap_t_mod = self.cap_t.to_mod(setup.public_key());
+        let cap_s_mod = match self.cap_s.to_mod_checked(setup.public_key()) {
+            Some(cap_s_mod) => cap_s_mod,
+            None => return false,
+        };
+        let cap_t_mod = match self.cap_t.to_mod_checked(setup.public_key()) {
+            Some(cap_t_mod) => cap_t_mod,
+            None => return false,
+        };
         if setup.commit_wide(&self.z1, &self.z2) != &cap_t_mod * &cap_s_mod.pow_signed_vartime(&e) {
             return false;
         }
@@ -9,9 +9,10 @@ use crate::paillier::{
     RPParamsMod, Randomizer, RandomizerMod,
 };
 use crate::tools::hashing::{Chain, Hashable, XofHasher};
+use crate::tools::profile::profile_scope;
 use crate::uint::Signed;
 
-const HASH_TAG: &[u8] = b"P_enc";
+pub(crate) const HASH_TAG: &[u8] = b"P_enc";
 
 /**
 ZK proof: Paillier encryption in range.
@@ -46,6 +47,7 @@ impl<P: SchemeParams> EncProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
+        profile_scope!("EncProof::new");
         k.assert_bound(P::L_BOUND);
         assert_eq!(cap_k.public_key(), pk0);
 
@@ -70,8 +72,8 @@ impl<P: SchemeParams> EncProof<P> {
             .chain(&cap_c)
             // public parameters
             .chain(pk0.as_minimal())
-            .chain(&cap_k.retrieve())
-            .chain(&setup.retrieve())
+            .chain(cap_k)
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -100,6 +102,7 @@ impl<P: SchemeParams> EncProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
+        profile_scope!("EncProof::verify");
         assert_eq!(cap_k.public_key(), pk0);
 
         let mut reader = XofHasher::new_with_dst(HASH_TAG)
@@ -109,8 +112,8 @@ impl<P: SchemeParams> EncProof<P> {
             .chain(&self.cap_c)
             // public parameters
             .chain(pk0.as_minimal())
-            .chain(&cap_k.retrieve())
-            .chain(&setup.retrieve())
+            .chain(cap_k)
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -127,14 +130,21 @@ impl<P: SchemeParams> EncProof<P> {
         }
 
         // enc_0(z1, z2) == A (+) K (*) e
+        let cap_a_mod = to_mod_or_reject!(self.cap_a, pk0);
         let c = CiphertextMod::new_with_randomizer_signed(pk0, &self.z1, &self.z2);
-        if c != self.cap_a.to_mod(pk0) + cap_k * e {
+        if c != cap_a_mod + cap_k * e {
             return false;
         }
 
         // s^{z_1} t^{z_3} == C S^e \mod \hat{N}
-        let cap_c_mod = self.cap_c.to_mod(setup.public_key());
-        let cap_s_mod = self.cap_s.to_mod(setup.public_key());
+        let cap_c_mod = match self.cap_c.to_mod_checked(setup.public_key()) {
+            Some(cap_c_mod) => cap_c_mod,
+            None => return false,
+        };
+        let cap_s_mod = match self.cap_s.to_mod_checked(setup.public_key()) {
+            Some(cap_s_mod) => cap_s_mod,
+            None => return false,
+        };
         if setup.commit(&self.z1, &self.z3) != &cap_c_mod * &cap_s_mod.pow_signed_vartime(&e) {
             return false;
         }
@@ -9,9 +9,10 @@ use crate::paillier::{
     SecretKeyPaillierPrecomputed,
 };
 use crate::tools::hashing::{Chain, Hashable, XofHasher};
+use crate::tools::profile::profile_scope;
 use crate::uint::{Bounded, Integer, Signed};
 
-const HASH_TAG: &[u8] = b"P_fac";
+pub(crate) const HASH_TAG: &[u8] = b"P_fac";
 
 /**
 ZK proof: No small factor proof.
@@ -46,6 +47,7 @@ impl<P: SchemeParams> FacProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
+        profile_scope!("FacProof::new");
         let pk0 = sk0.public_key();
 
         let hat_cap_n = &setup.public_key().modulus_bounded(); // $\hat{N}$
@@ -104,7 +106,7 @@ impl<P: SchemeParams> FacProof<P> {
             .chain(&sigma)
             // public parameters
             .chain(pk0.as_minimal())
-            .chain(&setup.retrieve())
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -141,6 +143,7 @@ impl<P: SchemeParams> FacProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
+        profile_scope!("FacProof::verify");
         let mut reader = XofHasher::new_with_dst(HASH_TAG)
             // commitments
             .chain(&self.cap_p)
@@ -151,7 +154,7 @@ impl<P: SchemeParams> FacProof<P> {
             .chain(&self.sigma)
             // public parameters
             .chain(pk0.as_minimal())
-            .chain(&setup.retrieve())
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -168,8 +171,8 @@ impl<P: SchemeParams> FacProof<P> {
         let cap_r = &setup.commit_xwide(&pk0.modulus_bounded(), &self.sigma);
 
         // s^{z_1} t^{\omega_1} == A * P^e \mod \hat{N}
-        let cap_a_mod = self.cap_a.to_mod(aux_pk);
-        let cap_p_mod = self.cap_p.to_mod(aux_pk);
+        let cap_a_mod = to_mod_or_reject!(self.cap_a, aux_pk);
+        let cap_p_mod = to_mod_or_reject!(self.cap_p, aux_pk);
         if setup.commit_wide(&self.z1, &self.omega1)
             != &cap_a_mod * &cap_p_mod.pow_signed_vartime(&e)
         {
@@ -177,8 +180,8 @@ impl<P: SchemeParams> FacProof<P> {
         }
 
         // s^{z_2} t^{\omega_2} == B * Q^e \mod \hat{N}
-        let cap_b_mod = self.cap_b.to_mod(aux_pk);
-        let cap_q_mod = self.cap_q.to_mod(aux_pk);
+        let cap_b_mod = to_mod_or_reject!(self.cap_b, aux_pk);
+        let cap_q_mod = to_mod_or_reject!(self.cap_q, aux_pk);
         if setup.commit_wide(&self.z2, &self.omega2)
             != &cap_b_mod * &cap_q_mod.pow_signed_vartime(&e)
         {
@@ -186,7 +189,7 @@ impl<P: SchemeParams> FacProof<P> {
         }
 
         // Q^{z_1} * t^v == T * R^e \mod \hat{N}
-        let cap_t_mod = self.cap_t.to_mod(aux_pk);
+        let cap_t_mod = to_mod_or_reject!(self.cap_t, aux_pk);
         if &cap_q_mod.pow_signed_wide(&self.z1) * &setup.commit_base_xwide(&self.v)
             != &cap_t_mod * &cap_r.pow_signed_vartime(&e)
         {
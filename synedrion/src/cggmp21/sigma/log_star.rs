@@ -10,9 +10,10 @@ use crate::paillier::{
     RPParamsMod, Randomizer, RandomizerMod,
 };
 use crate::tools::hashing::{Chain, Hashable, XofHasher};
+use crate::tools::profile::profile_scope;
 use crate::uint::Signed;
 
-const HASH_TAG: &[u8] = b"P_log*";
+pub(crate) const HASH_TAG: &[u8] = b"P_log*";
 
 /**
 ZK proof: Knowledge of Exponent vs Paillier Encryption.
@@ -53,6 +54,7 @@ impl<P: SchemeParams> LogStarProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
+        profile_scope!("LogStarProof::new");
         x.assert_bound(P::L_BOUND);
         assert_eq!(cap_c.public_key(), pk0);
 
@@ -77,10 +79,10 @@ impl<P: SchemeParams> LogStarProof<P> {
             .chain(&cap_d)
             // public parameters
             .chain(pk0.as_minimal())
-            .chain(&cap_c.retrieve())
+            .chain(cap_c)
             .chain(g)
             .chain(cap_x)
-            .chain(&setup.retrieve())
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -113,6 +115,7 @@ impl<P: SchemeParams> LogStarProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
+        profile_scope!("LogStarProof::verify");
         assert_eq!(cap_c.public_key(), pk0);
 
         let mut reader = XofHasher::new_with_dst(HASH_TAG)
@@ -123,10 +126,10 @@ impl<P: SchemeParams> LogStarProof<P> {
             .chain(&self.cap_d)
             // public parameters
             .chain(pk0.as_minimal())
-            .chain(&cap_c.retrieve())
+            .chain(cap_c)
             .chain(g)
             .chain(cap_x)
-            .chain(&setup.retrieve())
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -143,8 +146,9 @@ impl<P: SchemeParams> LogStarProof<P> {
         }
 
         // enc_0(z1, z2) == A (+) C (*) e
+        let cap_a_mod = to_mod_or_reject!(self.cap_a, pk0);
         let c = CiphertextMod::new_with_randomizer_signed(pk0, &self.z1, &self.z2);
-        if c != self.cap_a.to_mod(pk0) + cap_c * e {
+        if c != cap_a_mod + cap_c * e {
             return false;
         }
 
@@ -154,8 +158,14 @@ impl<P: SchemeParams> LogStarProof<P> {
         }
 
         // s^{z_1} t^{z_3} == D S^e \mod \hat{N}
-        let cap_d_mod = self.cap_d.to_mod(setup.public_key());
-        let cap_s_mod = self.cap_s.to_mod(setup.public_key());
+        let cap_d_mod = match self.cap_d.to_mod_checked(setup.public_key()) {
+            Some(cap_d_mod) => cap_d_mod,
+            None => return false,
+        };
+        let cap_s_mod = match self.cap_s.to_mod_checked(setup.public_key()) {
+            Some(cap_s_mod) => cap_s_mod,
+            None => return false,
+        };
         if setup.commit(&self.z1, &self.z3) != &cap_d_mod * &cap_s_mod.pow_signed_vartime(&e) {
             return false;
         }
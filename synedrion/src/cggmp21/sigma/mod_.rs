@@ -8,9 +8,10 @@ use serde::{Deserialize, Serialize};
 use super::super::SchemeParams;
 use crate::paillier::{PaillierParams, PublicKeyPaillierPrecomputed, SecretKeyPaillierPrecomputed};
 use crate::tools::hashing::{Chain, Hashable, XofHasher};
+use crate::tools::profile::profile_scope;
 use crate::uint::{RandomPrimeWithRng, Retrieve, UintLike, UintModLike};
 
-const HASH_TAG: &[u8] = b"P_mod";
+pub(crate) const HASH_TAG: &[u8] = b"P_mod";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ModCommitment<P: SchemeParams>(<P::Paillier as PaillierParams>::Uint);
@@ -81,6 +82,7 @@ impl<P: SchemeParams> ModProof<P> {
         sk: &SecretKeyPaillierPrecomputed<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
+        profile_scope!("ModProof::new");
         let pk = sk.public_key();
         let commitment = ModCommitment::<P>::random(rng, sk);
         let challenge = ModChallenge::<P>::new(pk, &commitment, aux);
@@ -141,6 +143,7 @@ impl<P: SchemeParams> ModProof<P> {
         pk: &PublicKeyPaillierPrecomputed<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
+        profile_scope!("ModProof::verify");
         let challenge = ModChallenge::new(pk, &self.commitment, aux);
         if challenge != self.challenge {
             return false;
@@ -159,9 +162,9 @@ impl<P: SchemeParams> ModProof<P> {
         }
 
         let precomputed = pk.precomputed_modulus();
-        let omega_mod = self.commitment.0.to_mod(precomputed);
+        let omega_mod = to_mod_or_reject!(self.commitment.0, precomputed);
         for (elem, y) in self.proof.iter().zip(self.challenge.0.iter()) {
-            let z_m = elem.z.to_mod(precomputed);
+            let z_m = to_mod_or_reject!(elem.z, precomputed);
             let mut y_m = y.to_mod(precomputed);
             if z_m.pow_bounded(&pk.modulus_bounded()) != y_m {
                 return false;
@@ -173,7 +176,7 @@ impl<P: SchemeParams> ModProof<P> {
             if elem.b {
                 y_m = y_m * omega_mod;
             }
-            let x = elem.x.to_mod(precomputed);
+            let x = to_mod_or_reject!(elem.x, precomputed);
             let x_4 = x.square().square();
             if y_m != x_4 {
                 return false;
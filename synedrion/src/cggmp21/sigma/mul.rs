@@ -9,9 +9,10 @@ use crate::paillier::{
     RandomizerMod,
 };
 use crate::tools::hashing::{Chain, Hashable, XofHasher};
+use crate::tools::profile::profile_scope;
 use crate::uint::{Bounded, Retrieve, Signed};
 
-const HASH_TAG: &[u8] = b"P_mul";
+pub(crate) const HASH_TAG: &[u8] = b"P_mul";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct MulProof<P: SchemeParams> {
@@ -52,6 +53,7 @@ impl<P: SchemeParams> MulProof<P> {
         cap_c: &CiphertextMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
+        profile_scope!("MulProof::new");
         assert_eq!(cap_x.public_key(), pk);
         assert_eq!(cap_y.public_key(), pk);
         assert_eq!(cap_c.public_key(), pk);
@@ -77,9 +79,9 @@ impl<P: SchemeParams> MulProof<P> {
             .chain(&cap_b)
             // public parameters
             .chain(pk.as_minimal())
-            .chain(&cap_x.retrieve())
-            .chain(&cap_y.retrieve())
-            .chain(&cap_c.retrieve())
+            .chain(cap_x)
+            .chain(cap_y)
+            .chain(cap_c)
             .chain(aux)
             .finalize_to_reader();
 
@@ -108,6 +110,7 @@ impl<P: SchemeParams> MulProof<P> {
         cap_c: &CiphertextMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
+        profile_scope!("MulProof::verify");
         assert_eq!(cap_x.public_key(), pk);
         assert_eq!(cap_y.public_key(), pk);
         assert_eq!(cap_c.public_key(), pk);
@@ -118,9 +121,9 @@ impl<P: SchemeParams> MulProof<P> {
             .chain(&self.cap_b)
             // public parameters
             .chain(pk.as_minimal())
-            .chain(&cap_x.retrieve())
-            .chain(&cap_y.retrieve())
-            .chain(&cap_c.retrieve())
+            .chain(cap_x)
+            .chain(cap_y)
+            .chain(cap_c)
             .chain(aux)
             .finalize_to_reader();
 
@@ -132,17 +135,15 @@ impl<P: SchemeParams> MulProof<P> {
         }
 
         // Y^z u^N = A * C^e \mod N^2
-        if cap_y.homomorphic_mul_wide(&self.z).mul_randomizer(&self.u)
-            != self.cap_a.to_mod(pk) + cap_c * e
-        {
+        let cap_a_mod = to_mod_or_reject!(self.cap_a, pk);
+        if cap_y.homomorphic_mul_wide(&self.z).mul_randomizer(&self.u) != cap_a_mod + cap_c * e {
             return false;
         }
 
         // enc(z, v) == B * X^e \mod N^2
         // (Note: typo in the paper, it uses `c` and not `v` here)
-        if CiphertextMod::new_with_randomizer_wide(pk, &self.z, &self.v)
-            != self.cap_b.to_mod(pk) + cap_x * e
-        {
+        let cap_b_mod = to_mod_or_reject!(self.cap_b, pk);
+        if CiphertextMod::new_with_randomizer_wide(pk, &self.z, &self.v) != cap_b_mod + cap_x * e {
             return false;
         }
 
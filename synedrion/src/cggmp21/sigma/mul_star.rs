@@ -10,9 +10,10 @@ use crate::paillier::{
     RPParamsMod, Randomizer, RandomizerMod,
 };
 use crate::tools::hashing::{Chain, Hashable, XofHasher};
+use crate::tools::profile::profile_scope;
 use crate::uint::Signed;
 
-const HASH_TAG: &[u8] = b"P_mul*";
+pub(crate) const HASH_TAG: &[u8] = b"P_mul*";
 
 /**
 ZK proof: Multiplication Paillier vs Group.
@@ -53,6 +54,7 @@ impl<P: SchemeParams> MulStarProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
+        profile_scope!("MulStarProof::new");
         /*
         NOTE: Several issues with the proof description in the paper:
         - the prover creates $B_x$, but sends $B$ - a typo, and they're the same thing
@@ -84,10 +86,10 @@ impl<P: SchemeParams> MulStarProof<P> {
             .chain(&cap_s)
             // public parameters
             .chain(pk0.as_minimal())
-            .chain(&cap_c.retrieve())
-            .chain(&cap_d.retrieve())
+            .chain(cap_c)
+            .chain(cap_d)
             .chain(cap_x)
-            .chain(&setup.retrieve())
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -121,6 +123,7 @@ impl<P: SchemeParams> MulStarProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
+        profile_scope!("MulStarProof::verify");
         assert_eq!(cap_c.public_key(), pk0);
         assert_eq!(cap_d.public_key(), pk0);
 
@@ -132,10 +135,10 @@ impl<P: SchemeParams> MulStarProof<P> {
             .chain(&self.cap_s)
             // public parameters
             .chain(pk0.as_minimal())
-            .chain(&cap_c.retrieve())
-            .chain(&cap_d.retrieve())
+            .chain(cap_c)
+            .chain(cap_d)
             .chain(cap_x)
-            .chain(&setup.retrieve())
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
 
@@ -154,7 +157,8 @@ impl<P: SchemeParams> MulStarProof<P> {
         }
 
         // C (*) z_1 * \omega^{N_0} == A (+) D (*) e
-        if (cap_c * self.z1).mul_randomizer(&self.omega) != self.cap_a.to_mod(pk0) + cap_d * e {
+        let cap_a_mod = to_mod_or_reject!(self.cap_a, pk0);
+        if (cap_c * self.z1).mul_randomizer(&self.omega) != cap_a_mod + cap_d * e {
             return false;
         }
 
@@ -166,8 +170,8 @@ impl<P: SchemeParams> MulStarProof<P> {
         }
 
         // s^{z_1} t^{z_2} == E S^e
-        let cap_e_mod = self.cap_e.to_mod(aux_pk);
-        let cap_s_mod = self.cap_s.to_mod(aux_pk);
+        let cap_e_mod = to_mod_or_reject!(self.cap_e, aux_pk);
+        let cap_s_mod = to_mod_or_reject!(self.cap_s, aux_pk);
         if setup.commit(&self.z1, &self.z2) != &cap_e_mod * &cap_s_mod.pow_signed_vartime(&e) {
             return false;
         }
@@ -12,12 +12,13 @@ use serde::{Deserialize, Serialize};
 use super::super::SchemeParams;
 use crate::paillier::{PaillierParams, RPParamsMod, RPSecret, SecretKeyPaillierPrecomputed};
 use crate::tools::hashing::{Chain, Hashable, XofHasher};
+use crate::tools::profile::profile_scope;
 use crate::uint::{
     subtle::{Choice, ConditionallySelectable},
     Bounded, Retrieve, UintLike, UintModLike,
 };
 
-const HASH_TAG: &[u8] = b"P_prm";
+pub(crate) const HASH_TAG: &[u8] = b"P_prm";
 
 /// Secret data the proof is based on ($a_i$).
 #[derive(Clone)]
@@ -61,7 +62,7 @@ impl PrmChallenge {
         // TODO: use BitVec here?
         let mut reader = XofHasher::new_with_dst(HASH_TAG)
             .chain(commitment)
-            .chain(&setup.retrieve())
+            .chain(setup)
             .chain(aux)
             .finalize_to_reader();
         let mut bytes = vec![0u8; P::SECURITY_PARAMETER];
@@ -99,6 +100,7 @@ impl<P: SchemeParams> PrmProof<P> {
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
+        profile_scope!("PrmProof::new");
         let proof_secret = PrmSecret::<P>::random(rng, sk);
         let commitment = PrmCommitment::new(&proof_secret, &setup.base);
 
@@ -123,6 +125,7 @@ impl<P: SchemeParams> PrmProof<P> {
 
     /// Verify that the proof is correct for a secret corresponding to the given RP parameters.
     pub fn verify(&self, setup: &RPParamsMod<P::Paillier>, aux: &impl Hashable) -> bool {
+        profile_scope!("PrmProof::verify");
         let precomputed = setup.public_key().precomputed_modulus();
 
         let challenge = PrmChallenge::new(&self.commitment, setup, aux);
@@ -133,7 +136,7 @@ impl<P: SchemeParams> PrmProof<P> {
         for i in 0..challenge.0.len() {
             let z = self.proof[i];
             let e = challenge.0[i];
-            let a = self.commitment.0[i].to_mod(precomputed);
+            let a = to_mod_or_reject!(self.commitment.0[i], precomputed);
             let pwr = setup.base.pow_bounded(&z);
             let test = if e { pwr == a * setup.power } else { pwr == a };
             if !test {
@@ -7,9 +7,10 @@ use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
 
 use crate::curve::{Point, Scalar};
-use crate::tools::hashing::{Chain, FofHasher, Hashable};
+use crate::tools::hashing::{Chain, Hashable, XofHasher};
+use crate::tools::profile::profile_scope;
 
-const HASH_TAG: &[u8] = b"P_sch";
+pub(crate) const HASH_TAG: &[u8] = b"P_sch";
 
 /// Secret data the proof is based on (~ signing key)
 #[derive(Clone)]
@@ -40,7 +41,7 @@ struct SchChallenge(Scalar);
 impl SchChallenge {
     fn new(public: &Point, commitment: &SchCommitment, aux: &impl Hashable) -> Self {
         Self(
-            FofHasher::new_with_dst(HASH_TAG)
+            XofHasher::new_with_dst(HASH_TAG)
                 .chain(aux)
                 .chain(public)
                 .chain(commitment)
@@ -72,12 +73,14 @@ impl SchProof {
         cap_x: &Point,
         aux: &impl Hashable,
     ) -> Self {
+        profile_scope!("SchProof::new");
         let challenge = SchChallenge::new(cap_x, commitment, aux);
         let proof = proof_secret.0 + challenge.0 * x;
         Self { challenge, proof }
     }
 
     pub fn verify(&self, commitment: &SchCommitment, cap_x: &Point, aux: &impl Hashable) -> bool {
+        profile_scope!("SchProof::verify");
         let challenge = SchChallenge::new(cap_x, commitment, aux);
         challenge == self.challenge
             && self.proof.mul_by_generator() == commitment.0 + cap_x * &challenge.0
@@ -0,0 +1,469 @@
+//! Checking and upgrading the storage format of key shares,
+//! so that the stored shares survive an update of the crate.
+//!
+//! The shares are stored in a versioned envelope (see [`encode_share`])
+//! recording the kind of the share and the scheme parameters it was created with.
+//! The shares serialized directly with `bincode` (standard configuration),
+//! as was done before the envelope was introduced, are recognized as the legacy format
+//! and can be converted with [`upgrade_share`], as can the shares stored in an older version of the envelope.
+//!
+//! With the `device-binding` feature, a share can also be stored encrypted
+//! with a device-specific secret (see [`encode_bound_share`]).
+
+#[cfg(feature = "device-binding")]
+mod device_binding;
+mod v1;
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+
+use displaydoc::Display;
+use serde::{Deserialize, Serialize};
+use zeroize::ZeroizeOnDrop;
+
+use crate::cggmp21::{params_fingerprint, AuxInfo, KeyShare, SchemeParams};
+use crate::tools::hashing::HashOutput;
+use crate::tools::serde_bytes;
+use crate::www02::ThresholdKeyShare;
+use v1::{KeyShareV1, ThresholdKeyShareV1};
+
+#[cfg(feature = "device-binding")]
+pub use device_binding::{decode_bound_share, encode_bound_share};
+
+/// The version of the share format produced by [`encode_share`].
+///
+/// Version 2 added the lineage of the key shares; the shares of version 1 are upgraded
+/// to ones without a lineage.
+pub const SHARE_FORMAT_VERSION: u16 = 2;
+
+// Distinguishes the envelope from the legacy format.
+const MAGIC: [u8; 4] = *b"SYNS";
+// Marks a share bound to a device-specific secret.
+const BOUND_MAGIC: [u8; 4] = *b"SYNB";
+
+/// The kind of a stored share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareKind {
+    /// [`KeyShare`]
+    KeyShare,
+    /// [`ThresholdKeyShare`]
+    ThresholdKeyShare,
+    /// [`AuxInfo`]
+    AuxInfo,
+}
+
+/// A share that can be stored in the versioned format.
+pub trait StoredShare: for<'de> Deserialize<'de> {
+    /// The kind of the share.
+    const KIND: ShareKind;
+    /// The scheme parameters the share is created with.
+    type Params: SchemeParams;
+
+    /// Returns the serializable form of the share, including the secrets
+    /// (the shares do not implement [`Serialize`] themselves).
+    fn expose_for_encryption(&self) -> impl Serialize + '_;
+
+    /// Deserializes the share stored in the given format version (`None` for the legacy format).
+    ///
+    /// The default implementation assumes that the layout of the share has not changed.
+    fn from_payload(_version: Option<u16>, payload: &[u8]) -> Result<Self, CompatError> {
+        deserialize(payload)
+    }
+}
+
+impl<P: SchemeParams, I: Ord + Serialize> StoredShare for KeyShare<P, I>
+where
+    Self: for<'de> Deserialize<'de>,
+    KeyShareV1<P, I>: for<'de> Deserialize<'de>,
+{
+    const KIND: ShareKind = ShareKind::KeyShare;
+    type Params = P;
+
+    fn expose_for_encryption(&self) -> impl Serialize + '_ {
+        KeyShare::expose_for_encryption(self)
+    }
+
+    fn from_payload(version: Option<u16>, payload: &[u8]) -> Result<Self, CompatError> {
+        match version {
+            Some(SHARE_FORMAT_VERSION) => deserialize(payload),
+            Some(_) => deserialize::<KeyShareV1<P, I>>(payload).map(Into::into),
+            // The legacy format follows the layout of the crate version the share was stored with.
+            None => deserialize(payload)
+                .or_else(|_| deserialize::<KeyShareV1<P, I>>(payload).map(Into::into)),
+        }
+    }
+}
+
+impl<P: SchemeParams, I: Ord + Serialize> StoredShare for ThresholdKeyShare<P, I>
+where
+    Self: for<'de> Deserialize<'de>,
+    ThresholdKeyShareV1<P, I>: for<'de> Deserialize<'de>,
+{
+    const KIND: ShareKind = ShareKind::ThresholdKeyShare;
+    type Params = P;
+
+    fn expose_for_encryption(&self) -> impl Serialize + '_ {
+        ThresholdKeyShare::expose_for_encryption(self)
+    }
+
+    fn from_payload(version: Option<u16>, payload: &[u8]) -> Result<Self, CompatError> {
+        match version {
+            Some(SHARE_FORMAT_VERSION) => deserialize(payload),
+            Some(_) => deserialize::<ThresholdKeyShareV1<P, I>>(payload).map(Into::into),
+            None => deserialize(payload)
+                .or_else(|_| deserialize::<ThresholdKeyShareV1<P, I>>(payload).map(Into::into)),
+        }
+    }
+}
+
+impl<P: SchemeParams, I: Ord + Serialize> StoredShare for AuxInfo<P, I>
+where
+    Self: for<'de> Deserialize<'de>,
+{
+    const KIND: ShareKind = ShareKind::AuxInfo;
+    type Params = P;
+
+    fn expose_for_encryption(&self) -> impl Serialize + '_ {
+        AuxInfo::expose_for_encryption(self)
+    }
+}
+
+// The payload is the serialized secret share, so it is zeroized
+// when the envelope is dropped, both after encoding and after decoding.
+#[derive(Serialize, Deserialize, ZeroizeOnDrop)]
+struct ShareEnvelope {
+    magic: [u8; 4],
+    version: u16,
+    #[zeroize(skip)]
+    kind: ShareKind,
+    #[zeroize(skip)]
+    params: HashOutput,
+    #[serde(with = "serde_bytes::as_base64")]
+    payload: Box<[u8]>,
+}
+
+fn serialize<T: Serialize>(value: &T) -> Result<Box<[u8]>, CompatError> {
+    bincode::serde::encode_to_vec(value, bincode::config::standard())
+        .map(|bytes| bytes.into())
+        .map_err(|err| CompatError::Serialization(format!("{err:?}")))
+}
+
+/// Deserializes a value, requiring all the bytes to be consumed.
+fn deserialize<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Result<T, CompatError> {
+    let (value, read) =
+        bincode::serde::decode_from_slice::<T, _>(bytes, bincode::config::standard())
+            .map_err(|err| CompatError::Serialization(format!("{err:?}")))?;
+    if read != bytes.len() {
+        return Err(CompatError::Serialization(
+            "Trailing bytes after the serialized value".into(),
+        ));
+    }
+    Ok(value)
+}
+
+/// Whether a stored share can be loaded as a share of a given type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareStatus {
+    /// The share is in the current format and can be loaded with [`decode_share`].
+    Current,
+    /// The share is in the legacy format or an older version of the current one,
+    /// and can be converted with [`upgrade_share`].
+    Upgradable,
+    /// The share is of a different kind.
+    MismatchedKind,
+    /// The share was created with different scheme parameters.
+    MismatchedParams,
+    /// The share is bound to a device-specific secret
+    /// and can only be loaded with `decode_bound_share` (requires the `device-binding` feature).
+    DeviceBound,
+    /// The format is not recognized, or is newer than the one supported by this version.
+    Unsupported,
+}
+
+/// The result of [`check_share`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShareFormatReport {
+    /// The format version, or `None` for the legacy format or an unrecognized one.
+    pub version: Option<u16>,
+    /// The kind of the share, if it could be determined.
+    pub kind: Option<ShareKind>,
+    /// Whether the share can be loaded as the requested type.
+    pub status: ShareStatus,
+}
+
+/// Possible errors when encoding or decoding a share.
+#[derive(Debug, Clone, Display)]
+pub enum CompatError {
+    /// The share cannot be loaded as the requested type ({0:?}).
+    Incompatible(ShareStatus),
+    /// Failed to (de)serialize the share: {0}.
+    Serialization(String),
+    /// Failed to open the share bound to a device: wrong device secret or modified data.
+    DeviceBinding,
+}
+
+/// Serializes a share in the current versioned format.
+pub fn encode_share<S: StoredShare>(share: &S) -> Result<Box<[u8]>, CompatError> {
+    let envelope = ShareEnvelope {
+        magic: MAGIC,
+        version: SHARE_FORMAT_VERSION,
+        kind: S::KIND,
+        params: params_fingerprint::<S::Params>(),
+        payload: serialize(&share.expose_for_encryption())?,
+    };
+    serialize(&envelope)
+}
+
+/// Identifies the format of a serialized share and whether it can be loaded as `S`.
+pub fn check_share<S: StoredShare>(bytes: &[u8]) -> ShareFormatReport {
+    open_share::<S>(bytes).0
+}
+
+/// Identifies the format of a serialized share, and deserializes it
+/// if it can be loaded as `S` (so that the payload is only deserialized once).
+fn open_share<S: StoredShare>(bytes: &[u8]) -> (ShareFormatReport, Option<S>) {
+    // The magic bytes are serialized as is, so they can be checked before deserializing.
+    if bytes.starts_with(&BOUND_MAGIC) {
+        // The contents cannot be checked without the device secret.
+        let report = ShareFormatReport {
+            version: None,
+            kind: None,
+            status: ShareStatus::DeviceBound,
+        };
+        return (report, None);
+    }
+    let envelope = if bytes.starts_with(&MAGIC) {
+        deserialize::<ShareEnvelope>(bytes).ok()
+    } else {
+        None
+    };
+    let envelope = match envelope {
+        Some(envelope) => envelope,
+        None => {
+            let share = S::from_payload(None, bytes).ok();
+            let report = ShareFormatReport {
+                version: None,
+                kind: share.is_some().then_some(S::KIND),
+                status: if share.is_some() {
+                    ShareStatus::Upgradable
+                } else {
+                    ShareStatus::Unsupported
+                },
+            };
+            return (report, share);
+        }
+    };
+
+    let mut share = None;
+    let status = if envelope.version > SHARE_FORMAT_VERSION {
+        ShareStatus::Unsupported
+    } else if envelope.kind != S::KIND {
+        ShareStatus::MismatchedKind
+    } else if envelope.params != params_fingerprint::<S::Params>() {
+        ShareStatus::MismatchedParams
+    } else {
+        share = S::from_payload(Some(envelope.version), &envelope.payload).ok();
+        if share.is_none() {
+            ShareStatus::Unsupported
+        } else if envelope.version < SHARE_FORMAT_VERSION {
+            ShareStatus::Upgradable
+        } else {
+            ShareStatus::Current
+        }
+    };
+
+    let report = ShareFormatReport {
+        version: Some(envelope.version),
+        kind: Some(envelope.kind),
+        status,
+    };
+    (report, share)
+}
+
+/// Deserializes a share in the current format.
+pub fn decode_share<S: StoredShare>(bytes: &[u8]) -> Result<S, CompatError> {
+    let (report, share) = open_share::<S>(bytes);
+    match (report.status, share) {
+        (ShareStatus::Current, Some(share)) => Ok(share),
+        (status, _) => Err(CompatError::Incompatible(status)),
+    }
+}
+
+#[cfg(feature = "device-binding")]
+/// Deserializes a share in any supported format.
+fn decode_any_share<S: StoredShare>(bytes: &[u8]) -> Result<S, CompatError> {
+    let (report, share) = open_share::<S>(bytes);
+    match (report.status, share) {
+        (ShareStatus::Current | ShareStatus::Upgradable, Some(share)) => Ok(share),
+        (status, _) => Err(CompatError::Incompatible(status)),
+    }
+}
+
+/// Converts a share in any supported format to the current format.
+///
+/// A share that is already in the current format is returned unchanged.
+pub fn upgrade_share<S: StoredShare>(bytes: &[u8]) -> Result<Box<[u8]>, CompatError> {
+    let (report, share) = open_share::<S>(bytes);
+    match (report.status, share) {
+        (ShareStatus::Current, _) => Ok(bytes.into()),
+        (ShareStatus::Upgradable, Some(share)) => encode_share(&share),
+        (status, _) => Err(CompatError::Incompatible(status)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+    use core::marker::PhantomData;
+
+    use k256::ecdsa::{SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+    use secrecy::ExposeSecret;
+    use serde::Serialize;
+
+    use super::{
+        check_share, decode_share, encode_share, serialize, upgrade_share, ShareEnvelope,
+        ShareFormatReport, ShareKind, ShareStatus, MAGIC, SHARE_FORMAT_VERSION,
+    };
+    use crate::cggmp21::{params_fingerprint, KeyShare, ProductionParams, TestParams};
+    use crate::curve::{Point, Scalar};
+    use crate::www02::ThresholdKeyShare;
+
+    type Share = KeyShare<TestParams, VerifyingKey>;
+
+    fn make_share() -> Share {
+        let ids = (0..2)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let shares = Share::new_centralized(&mut OsRng, &ids, None);
+        shares.into_values().next().unwrap()
+    }
+
+    #[test]
+    fn current_format() {
+        let share = make_share();
+        let bytes = encode_share(&share).unwrap();
+
+        assert_eq!(
+            check_share::<Share>(&bytes),
+            ShareFormatReport {
+                version: Some(SHARE_FORMAT_VERSION),
+                kind: Some(ShareKind::KeyShare),
+                status: ShareStatus::Current,
+            }
+        );
+        let decoded = decode_share::<Share>(&bytes).unwrap();
+        assert_eq!(decoded.verifying_key(), share.verifying_key());
+        assert_eq!(upgrade_share::<Share>(&bytes).unwrap(), bytes);
+
+        assert_eq!(
+            check_share::<KeyShare<ProductionParams, VerifyingKey>>(&bytes).status,
+            ShareStatus::MismatchedParams
+        );
+        assert_eq!(
+            check_share::<ThresholdKeyShare<TestParams, VerifyingKey>>(&bytes).status,
+            ShareStatus::MismatchedKind
+        );
+    }
+
+    #[test]
+    fn legacy_format() {
+        let share = make_share();
+        let legacy = serialize(&share.expose_for_encryption()).unwrap();
+
+        let report = check_share::<Share>(&legacy);
+        assert_eq!(report.version, None);
+        assert_eq!(report.status, ShareStatus::Upgradable);
+        assert!(decode_share::<Share>(&legacy).is_err());
+
+        let upgraded = upgrade_share::<Share>(&legacy).unwrap();
+        assert_eq!(check_share::<Share>(&upgraded).status, ShareStatus::Current);
+        let decoded = decode_share::<Share>(&upgraded).unwrap();
+        assert_eq!(decoded.verifying_key(), share.verifying_key());
+    }
+
+    #[test]
+    fn legacy_fixture() {
+        // A share serialized directly with `bincode` in the layout preceding the envelope,
+        // owned by the first of the two parties with the signing keys `[1; 32]` and `[2; 32]`,
+        // with the secret shares 12345 and 67890.
+        let bytes = include_bytes!("compat/fixtures/legacy_key_share.bin");
+        let ids =
+            [1u8, 2].map(|byte| *SigningKey::from_slice(&[byte; 32]).unwrap().verifying_key());
+
+        assert_eq!(
+            check_share::<Share>(bytes),
+            ShareFormatReport {
+                version: None,
+                kind: Some(ShareKind::KeyShare),
+                status: ShareStatus::Upgradable,
+            }
+        );
+
+        let share = decode_share::<Share>(&upgrade_share::<Share>(bytes).unwrap()).unwrap();
+        assert_eq!(share.owner(), &ids[0]);
+        assert_eq!(share.secret_share.expose_secret(), &Scalar::from(12345u32));
+        assert_eq!(
+            share.public_shares[&ids[1]],
+            Scalar::from(67890u32).mul_by_generator()
+        );
+        assert!(share.lineage().is_none());
+    }
+
+    #[test]
+    fn previous_version() {
+        // The layout of `KeyShare` in the format version 1
+        #[derive(Serialize)]
+        #[serde(rename = "KeyShare")]
+        struct KeyShareV1<'a> {
+            owner: &'a VerifyingKey,
+            secret_share: &'a Scalar,
+            public_shares: &'a BTreeMap<VerifyingKey, Point>,
+            phantom: PhantomData<TestParams>,
+        }
+
+        let share = make_share();
+        let envelope = ShareEnvelope {
+            magic: MAGIC,
+            version: 1,
+            kind: ShareKind::KeyShare,
+            params: params_fingerprint::<TestParams>(),
+            payload: serialize(&KeyShareV1 {
+                owner: &share.owner,
+                secret_share: share.secret_share.expose_secret(),
+                public_shares: &share.public_shares,
+                phantom: PhantomData,
+            })
+            .unwrap(),
+        };
+        let bytes = serialize(&envelope).unwrap();
+
+        let report = check_share::<Share>(&bytes);
+        assert_eq!(report.version, Some(1));
+        assert_eq!(report.status, ShareStatus::Upgradable);
+        assert!(decode_share::<Share>(&bytes).is_err());
+
+        // The legacy format with the same layout is recognized too
+        let legacy = envelope.payload.clone();
+        assert_eq!(
+            check_share::<Share>(&legacy).status,
+            ShareStatus::Upgradable
+        );
+
+        for bytes in [bytes, legacy] {
+            let upgraded = upgrade_share::<Share>(&bytes).unwrap();
+            assert_eq!(check_share::<Share>(&upgraded).status, ShareStatus::Current);
+            let decoded = decode_share::<Share>(&upgraded).unwrap();
+            assert_eq!(decoded.verifying_key(), share.verifying_key());
+            assert!(decoded.lineage().is_none());
+        }
+    }
+
+    #[test]
+    fn unsupported_format() {
+        let report = check_share::<Share>(b"not a share");
+        assert_eq!(report.status, ShareStatus::Unsupported);
+        assert!(upgrade_share::<Share>(b"not a share").is_err());
+    }
+}
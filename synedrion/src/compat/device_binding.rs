@@ -0,0 +1,165 @@
+//! Binding a stored share to a device-specific secret.
+
+use alloc::boxed::Box;
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use super::{decode_any_share, deserialize, encode_share, serialize, CompatError, StoredShare};
+use super::{BOUND_MAGIC, SHARE_FORMAT_VERSION};
+use crate::tools::hashing::{Chain, FofHasher};
+use crate::tools::serde_bytes;
+
+#[derive(Serialize, Deserialize)]
+struct BoundEnvelope {
+    magic: [u8; 4],
+    version: u16,
+    salt: [u8; 32],
+    /// The encrypted share in the format of [`encode_share`].
+    #[serde(with = "serde_bytes::as_base64")]
+    ciphertext: Box<[u8]>,
+}
+
+impl BoundEnvelope {
+    fn key(&self, device_secret: &[u8]) -> Key {
+        let hash = FofHasher::new_with_dst(b"DeviceBinding")
+            .chain_bytes(device_secret)
+            .chain_bytes(&self.salt)
+            .finalize();
+        Key::clone_from_slice(hash.as_ref())
+    }
+
+    fn associated_data(&self) -> [u8; 38] {
+        let mut data = [0u8; 38];
+        data[..4].copy_from_slice(&self.magic);
+        data[4..6].copy_from_slice(&self.version.to_be_bytes());
+        data[6..].copy_from_slice(&self.salt);
+        data
+    }
+}
+
+/// Serializes a share in the current versioned format (see [`encode_share`])
+/// and encrypts it with a key derived from a device-specific secret,
+/// so that the stored share cannot be used without that secret.
+///
+/// The key is derived with a hash function, not a password hashing function,
+/// so `device_secret` must have high entropy (e.g. be a key kept in a secure element).
+/// Use [`decode_bound_share`] to load the share.
+pub fn encode_bound_share<S: StoredShare>(
+    rng: &mut impl CryptoRngCore,
+    share: &S,
+    device_secret: &[u8],
+) -> Result<Box<[u8]>, CompatError> {
+    let mut salt = [0u8; 32];
+    rng.fill_bytes(&mut salt);
+    let mut envelope = BoundEnvelope {
+        magic: BOUND_MAGIC,
+        version: SHARE_FORMAT_VERSION,
+        salt,
+        ciphertext: Box::new([]),
+    };
+
+    let mut plaintext = encode_share(share)?;
+    // The key is unique for each salt, so a constant nonce can be used.
+    let ciphertext = ChaCha20Poly1305::new(&envelope.key(device_secret)).encrypt(
+        &Nonce::default(),
+        Payload {
+            msg: &plaintext,
+            aad: &envelope.associated_data(),
+        },
+    );
+    plaintext.zeroize();
+
+    envelope.ciphertext = ciphertext.map_err(|_| CompatError::DeviceBinding)?.into();
+    serialize(&envelope)
+}
+
+/// Decrypts and deserializes a share encoded with [`encode_bound_share`].
+///
+/// The shares bound with an older version of the crate are loaded as well;
+/// bind them again to store them in the current format.
+///
+/// Returns [`CompatError::DeviceBinding`] if `device_secret` is not the one
+/// the share was bound to, or the stored data was modified.
+pub fn decode_bound_share<S: StoredShare>(
+    bytes: &[u8],
+    device_secret: &[u8],
+) -> Result<S, CompatError> {
+    let envelope = deserialize::<BoundEnvelope>(bytes)?;
+    if envelope.magic != BOUND_MAGIC || envelope.version > SHARE_FORMAT_VERSION {
+        return Err(CompatError::DeviceBinding);
+    }
+
+    let mut plaintext = ChaCha20Poly1305::new(&envelope.key(device_secret))
+        .decrypt(
+            &Nonce::default(),
+            Payload {
+                msg: &envelope.ciphertext,
+                aad: &envelope.associated_data(),
+            },
+        )
+        .map_err(|_| CompatError::DeviceBinding)?;
+    let share = decode_any_share(&plaintext);
+    plaintext.zeroize();
+    share
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use k256::ecdsa::{SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+
+    use super::{decode_bound_share, encode_bound_share};
+    use crate::cggmp21::{KeyShare, TestParams};
+    use crate::compat::{check_share, decode_share, CompatError, ShareStatus};
+
+    type Share = KeyShare<TestParams, VerifyingKey>;
+
+    #[test]
+    fn bound_share() {
+        let ids = (0..2)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let share = Share::new_centralized(&mut OsRng, &ids, None)
+            .into_values()
+            .next()
+            .unwrap();
+
+        let device_secret = b"a secret kept on the device";
+        let bytes = encode_bound_share(&mut OsRng, &share, device_secret).unwrap();
+
+        let decoded = decode_bound_share::<Share>(&bytes, device_secret).unwrap();
+        assert_eq!(decoded.verifying_key(), share.verifying_key());
+
+        // The share cannot be loaded without the device secret
+        assert!(matches!(
+            decode_bound_share::<Share>(&bytes, b"another secret"),
+            Err(CompatError::DeviceBinding)
+        ));
+        assert_eq!(
+            check_share::<Share>(&bytes).status,
+            ShareStatus::DeviceBound
+        );
+        assert!(matches!(
+            decode_share::<Share>(&bytes),
+            Err(CompatError::Incompatible(ShareStatus::DeviceBound))
+        ));
+
+        // Any modification of the stored data is detected
+        let mut modified = bytes.clone();
+        let last = modified.len() - 1;
+        modified[last] ^= 1;
+        assert!(decode_bound_share::<Share>(&modified, device_secret).is_err());
+
+        // The encryption is randomized
+        let bytes2 = encode_bound_share(&mut OsRng, &share, device_secret).unwrap();
+        assert_ne!(bytes, bytes2);
+    }
+}
@@ -0,0 +1,57 @@
+//! The layouts of the shares in the format version 1 (and the legacy format),
+//! before the lineage was recorded.
+
+use alloc::collections::BTreeMap;
+use core::marker::PhantomData;
+
+use secrecy::SecretBox;
+use serde::Deserialize;
+
+use crate::cggmp21::{KeyShare, SchemeParams};
+use crate::curve::{Point, Scalar};
+use crate::tools::sss::ShareId;
+use crate::www02::ThresholdKeyShare;
+
+#[derive(Deserialize)]
+pub(super) struct KeyShareV1<P, I: Ord> {
+    owner: I,
+    secret_share: SecretBox<Scalar>,
+    public_shares: BTreeMap<I, Point>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, I: Ord> From<KeyShareV1<P, I>> for KeyShare<P, I> {
+    fn from(share: KeyShareV1<P, I>) -> Self {
+        Self {
+            owner: share.owner,
+            secret_share: share.secret_share,
+            public_shares: share.public_shares,
+            phantom: share.phantom,
+            lineage: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(super) struct ThresholdKeyShareV1<P: SchemeParams, I: Ord> {
+    owner: I,
+    threshold: u32,
+    secret_share: SecretBox<Scalar>,
+    share_ids: BTreeMap<I, ShareId>,
+    public_shares: BTreeMap<I, Point>,
+    phantom: PhantomData<P>,
+}
+
+impl<P: SchemeParams, I: Ord> From<ThresholdKeyShareV1<P, I>> for ThresholdKeyShare<P, I> {
+    fn from(share: ThresholdKeyShareV1<P, I>) -> Self {
+        Self {
+            owner: share.owner,
+            threshold: share.threshold,
+            secret_share: share.secret_share,
+            share_ids: share.share_ids,
+            public_shares: share.public_shares,
+            phantom: share.phantom,
+            lineage: None,
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use alloc::collections::BTreeSet;
+#[cfg(feature = "signing")]
+use alloc::vec::Vec;
 use core::fmt::Debug;
 
 use rand_core::CryptoRngCore;
@@ -8,25 +10,77 @@ use signature::{
     Keypair,
 };
 
+#[cfg(any(
+    feature = "key-gen",
+    feature = "aux-gen",
+    feature = "signing",
+    feature = "key-resharing"
+))]
+use crate::cggmp21::SchemeParams;
+use crate::handshake::{HandshakeConfig, HandshakeResult};
+#[cfg(any(feature = "signing", feature = "key-resharing"))]
+use crate::sessions::LocalError;
+use crate::sessions::{Session, SessionId, SessionInitError};
+
+#[cfg(feature = "aux-gen")]
+use crate::cggmp21::AuxGenResult;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+use crate::cggmp21::KeyRefreshResult;
+#[cfg(feature = "key-gen")]
 use crate::cggmp21::{
-    aux_gen, interactive_signing, key_gen, key_init, key_refresh, AuxGenResult, AuxInfo,
-    InteractiveSigningResult, KeyGenResult, KeyInitResult, KeyRefreshResult, KeyShare,
-    SchemeParams,
+    FastKeyInitResult, KeyGenResult, KeyInitResult, UnsafeAssumeReliableBroadcast,
+};
+#[cfg(feature = "key-resharing")]
+use crate::www02::{KeyResharingInputs, KeyResharingResult};
+#[cfg(feature = "signing")]
+use crate::{
+    cggmp21::{
+        batch_signing, interactive_signing, AuxInfo, BatchSigningResult, InteractiveSigningResult,
+        KeyShare, PresigningData, PresigningResult,
+    },
+    curve::Scalar,
+    sessions::Clock,
 };
-use crate::curve::Scalar;
-use crate::sessions::{LocalError, Session, SessionId};
-use crate::www02::{key_resharing, KeyResharingInputs, KeyResharingResult};
 
 /// Prehashed message to sign.
 pub type PrehashedMessage = [u8; 32];
 
+/// Creates the initial state for the Handshake protocol,
+/// where the parties confirm to each other that they have the same `config`
+/// before running the protocol it describes.
+///
+/// The parties of the session must be the same as the ones in `config`.
+pub fn make_handshake_session<Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    config: HandshakeConfig<Verifier>,
+) -> Result<Session<HandshakeResult<Verifier>, Sig, Signer, Verifier>, SessionInitError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PrehashVerifier<Sig>
+        + Debug
+        + Clone
+        + Ord
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+{
+    Session::new(rng, session_id, signer, verifiers, config)
+}
+
 /// Creates the initial state for the joined KeyGen and KeyRefresh+Auxiliary protocols.
+#[cfg(feature = "key-gen")]
 pub fn make_key_init_session<P, Sig, Signer, Verifier>(
     rng: &mut impl CryptoRngCore,
     session_id: SessionId,
     signer: Signer,
     verifiers: &BTreeSet<Verifier>,
-) -> Result<Session<KeyInitResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+) -> Result<Session<KeyInitResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
 where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
@@ -41,16 +95,47 @@ where
         + Sync
         + 'static,
 {
-    Session::new::<key_init::Round1<P, Verifier>>(rng, session_id, signer, verifiers, ())
+    Session::new(rng, session_id, signer, verifiers, ())
+}
+
+/// Creates the initial state for the FastKeyInit protocol,
+/// a variant of KeyInit with one round less.
+///
+/// Only safe to use if the messages are delivered through a reliable broadcast channel,
+/// see [`UnsafeAssumeReliableBroadcast`] for the details.
+#[cfg(feature = "key-gen")]
+pub fn make_fast_key_init_session<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    assumption: UnsafeAssumeReliableBroadcast,
+) -> Result<Session<FastKeyInitResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PrehashVerifier<Sig>
+        + Debug
+        + Clone
+        + Ord
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+{
+    Session::new(rng, session_id, signer, verifiers, assumption)
 }
 
 /// Creates the initial state for the joined KeyGen and KeyRefresh+Auxiliary protocols.
+#[cfg(feature = "key-gen")]
 pub fn make_key_gen_session<P, Sig, Signer, Verifier>(
     rng: &mut impl CryptoRngCore,
     session_id: SessionId,
     signer: Signer,
     verifiers: &BTreeSet<Verifier>,
-) -> Result<Session<KeyGenResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+) -> Result<Session<KeyGenResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
 where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
@@ -65,16 +150,17 @@ where
         + Sync
         + 'static,
 {
-    Session::new::<key_gen::Round1<P, Verifier>>(rng, session_id, signer, verifiers, ())
+    Session::new(rng, session_id, signer, verifiers, ())
 }
 
 /// Creates the initial state for the joined KeyGen and KeyRefresh+Auxiliary protocols.
+#[cfg(feature = "aux-gen")]
 pub fn make_aux_gen_session<P, Sig, Signer, Verifier>(
     rng: &mut impl CryptoRngCore,
     session_id: SessionId,
     signer: Signer,
     verifiers: &BTreeSet<Verifier>,
-) -> Result<Session<AuxGenResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+) -> Result<Session<AuxGenResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
 where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
@@ -89,16 +175,17 @@ where
         + Sync
         + 'static,
 {
-    Session::new::<aux_gen::Round1<P, Verifier>>(rng, session_id, signer, verifiers, ())
+    Session::new(rng, session_id, signer, verifiers, ())
 }
 
 /// Creates the initial state for the KeyRefresh+Auxiliary protocol.
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 pub fn make_key_refresh_session<P, Sig, Signer, Verifier>(
     rng: &mut impl CryptoRngCore,
     session_id: SessionId,
     signer: Signer,
     verifiers: &BTreeSet<Verifier>,
-) -> Result<Session<KeyRefreshResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+) -> Result<Session<KeyRefreshResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
 where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
@@ -113,10 +200,51 @@ where
         + Sync
         + 'static,
 {
-    Session::new::<key_refresh::Round1<P, Verifier>>(rng, session_id, signer, verifiers, ())
+    Session::new(rng, session_id, signer, verifiers, ())
+}
+
+/// Creates the initial state for the KeyRefresh+Auxiliary protocol
+/// with all the randomness derived from `seed`.
+///
+/// Given the same seeds, session ID and verifiers, the parties produce
+/// the same messages and results, so that a refresh can be rehearsed
+/// (e.g. on a staging cluster) and then reproduced.
+/// The RNGs passed to the methods of the session are ignored.
+///
+/// The randomness of every round is derived from the seed, the session ID,
+/// the set of verifiers and a hash of all the messages received so far,
+/// so a run with different verifiers, or in which a party sent a different message,
+/// does not reuse the secret values and proof nonces of the original run.
+///
+/// The seed must still be a high-entropy secret (at least as secret as the key share),
+/// unique to the party, and should not be used for any session other than
+/// the reproduction of the same run.
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+pub fn make_deterministic_key_refresh_session<P, Sig, Signer, Verifier>(
+    seed: &[u8; 32],
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+) -> Result<Session<KeyRefreshResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PrehashVerifier<Sig>
+        + Debug
+        + Clone
+        + Ord
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+{
+    Session::new_seeded(seed, session_id, signer, verifiers, ())
 }
 
 /// Creates the initial state for the joined Presigning and Signing protocols.
+#[cfg(feature = "signing")]
 pub fn make_interactive_signing_session<P, Sig, Signer, Verifier>(
     rng: &mut impl CryptoRngCore,
     session_id: SessionId,
@@ -125,7 +253,7 @@ pub fn make_interactive_signing_session<P, Sig, Signer, Verifier>(
     key_share: &KeyShare<P, Verifier>,
     aux_info: &AuxInfo<P, Verifier>,
     prehashed_message: &PrehashedMessage,
-) -> Result<Session<InteractiveSigningResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+) -> Result<Session<InteractiveSigningResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
 where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
@@ -144,7 +272,8 @@ where
     if !verifiers.is_subset(&key_share.all_parties()) {
         return Err(LocalError(
             "The given verifiers are not a subset of the ones in the key share".into(),
-        ));
+        )
+        .into());
     }
 
     let scalar_message = Scalar::from_reduced_bytes(prehashed_message);
@@ -155,19 +284,132 @@ where
         message: scalar_message,
     };
 
-    Session::new::<interactive_signing::Round1<P, Verifier>>(
-        rng, session_id, signer, verifiers, inputs,
+    Session::new(rng, session_id, signer, verifiers, inputs)
+}
+
+/// Creates the initial state for the Presigning protocol,
+/// the result of which can be used to sign a message later with [`make_batch_signing_session`].
+#[cfg(feature = "signing")]
+pub fn make_presigning_session<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    key_share: &KeyShare<P, Verifier>,
+    aux_info: &AuxInfo<P, Verifier>,
+) -> Result<Session<PresigningResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PrehashVerifier<Sig>
+        + Debug
+        + Clone
+        + Ord
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+{
+    // TODO (#68): check that key share and aux data owner corresponds to the signer
+    if !verifiers.is_subset(&key_share.all_parties()) {
+        return Err(LocalError(
+            "The given verifiers are not a subset of the ones in the key share".into(),
+        )
+        .into());
+    }
+
+    Session::new(
+        rng,
+        session_id,
+        signer,
+        verifiers,
+        (key_share.clone(), aux_info.clone()),
     )
 }
 
+/// Creates the initial state for the BatchSigning protocol,
+/// signing each of the given messages with its own presigning data
+/// (obtained with [`make_presigning_session`] by the same set of parties) in a single round.
+///
+/// The result is the list of the signatures in the same order as the messages.
+/// Each presigning data must only be used once (signing two messages with the same one
+/// reveals the secret key), so the items are consumed by this function.
+///
+/// Returns [`SessionInitError::ExpiredPresigning`] if any of the presigning data
+/// has expired according to `clock` (see [`PresigningData::set_timestamp`]),
+/// and [`SessionInitError::RepeatedPresigning`] if two items share the same presigning data.
+#[cfg(feature = "signing")]
+#[allow(clippy::too_many_arguments)]
+pub fn make_batch_signing_session<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    key_share: &KeyShare<P, Verifier>,
+    aux_info: &AuxInfo<P, Verifier>,
+    items: Vec<(PrehashedMessage, PresigningData<P, Verifier>)>,
+    clock: &dyn Clock,
+) -> Result<Session<BatchSigningResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PrehashVerifier<Sig>
+        + Debug
+        + Clone
+        + Ord
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+{
+    // TODO (#68): check that key share and aux data owner corresponds to the signer
+    if !verifiers.is_subset(&key_share.all_parties()) {
+        return Err(LocalError(
+            "The given verifiers are not a subset of the ones in the key share".into(),
+        )
+        .into());
+    }
+
+    if let Some(idx) = items
+        .iter()
+        .position(|(_message, presigning)| presigning.is_expired(clock))
+    {
+        return Err(SessionInitError::ExpiredPresigning(idx));
+    }
+
+    if let Some(idx) = batch_signing::find_repeated_presigning(
+        items.iter().map(|(_message, presigning)| presigning),
+    ) {
+        return Err(SessionInitError::RepeatedPresigning(idx));
+    }
+
+    let inputs = batch_signing::Inputs {
+        items: items
+            .into_iter()
+            .map(|(prehashed_message, presigning)| {
+                (Scalar::from_reduced_bytes(&prehashed_message), presigning)
+            })
+            .collect(),
+        key_share: key_share.clone(),
+        aux_info: aux_info.clone(),
+    };
+
+    Session::new(rng, session_id, signer, verifiers, inputs)
+}
+
 /// Creates the initial state for the Key Resharing protocol.
+#[cfg(feature = "key-resharing")]
 pub fn make_key_resharing_session<P, Sig, Signer, Verifier>(
     rng: &mut impl CryptoRngCore,
     session_id: SessionId,
     signer: Signer,
     verifiers: &BTreeSet<Verifier>,
     inputs: KeyResharingInputs<P, Verifier>,
-) -> Result<Session<KeyResharingResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+) -> Result<Session<KeyResharingResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
 where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
@@ -185,18 +427,16 @@ where
     let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
 
     if !inputs.new_holders.is_subset(&verifiers_set) {
-        return Err(LocalError(
-            "The new holders must be a subset of all parties".into(),
-        ));
+        return Err(LocalError("The new holders must be a subset of all parties".into()).into());
     }
 
     if let Some(new_holder) = inputs.new_holder.as_ref() {
         if !new_holder.old_holders.is_subset(&verifiers_set) {
-            return Err(LocalError(
-                "The old holders must be a subset of all parties".into(),
-            ));
+            return Err(
+                LocalError("The old holders must be a subset of all parties".into()).into(),
+            );
         }
     }
 
-    Session::new::<key_resharing::Round1<P, Verifier>>(rng, session_id, signer, verifiers, inputs)
+    Session::new(rng, session_id, signer, verifiers, inputs)
 }
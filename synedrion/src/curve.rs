@@ -9,4 +9,4 @@ mod ecdsa;
 pub(crate) use arithmetic::ORDER;
 
 pub use self::ecdsa::RecoverableSignature;
-pub(crate) use arithmetic::{Curve, Point, Scalar};
+pub(crate) use arithmetic::{deserialize_points_or_identity, Curve, Point, Scalar};
@@ -1,19 +1,19 @@
+use alloc::collections::BTreeMap;
 use alloc::format;
 use alloc::string::String;
 use alloc::{vec, vec::Vec};
 use core::default::Default;
 use core::ops::{Add, Mul, Neg, Sub};
 
-use digest::Digest;
 use k256::elliptic_curve::group::ff::PrimeField;
 use k256::elliptic_curve::{
-    bigint::U256, // Note that this type is different from typenum::U256
-    generic_array::typenum::marker_traits::Unsigned,
+    bigint::{U256, U512}, // Note that these types are different from typenum::{U256, U512}
+    generic_array::typenum::{marker_traits::Unsigned, U64},
     generic_array::GenericArray,
     ops::Reduce,
     point::AffineCoordinates,
     sec1::{EncodedPoint, FromEncodedPoint, ModulusSize, ToEncodedPoint},
-    subtle::{Choice, ConditionallySelectable, CtOption},
+    subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption},
     Curve as _,
     Field,
     FieldBytesSize,
@@ -24,7 +24,7 @@ use k256::{
     Secp256k1,
 };
 use rand_core::CryptoRngCore;
-use secrecy::{CloneableSecret, SerializableSecret};
+use secrecy::CloneableSecret;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use zeroize::DefaultIsZeroes;
 
@@ -68,11 +68,14 @@ impl Scalar {
         self.0.invert().map(Self)
     }
 
-    pub fn from_digest(d: impl Digest<OutputSize = FieldBytesSize<Secp256k1>>) -> Self {
-        // There's currently no way to make the required digest output size
-        // depend on the target scalar size, so we are hardcoding it to 256 bit
-        // (that is, equal to the scalar size).
-        Self(<BackendScalar as Reduce<U256>>::reduce_bytes(&d.finalize()))
+    /// Converts 64 big-endian bytes into a scalar by reducing them modulo the curve order.
+    ///
+    /// For uniformly distributed bytes the statistical distance of the result
+    /// from the uniform distribution is below `q / 2^512 < 2^{-256}`,
+    /// so this is the preferred way of obtaining a scalar from a hash.
+    pub fn from_wide_bytes(bytes: &[u8; 64]) -> Self {
+        let arr = GenericArray::<u8, U64>::from(*bytes);
+        Self(<BackendScalar as Reduce<U512>>::reduce_bytes(&arr))
     }
 
     /// Convert a 32-byte hash digest into a scalar as per SEC1:
@@ -93,6 +96,7 @@ impl Scalar {
         <FieldBytesSize<Secp256k1> as Unsigned>::to_usize()
     }
 
+    #[cfg(feature = "signing")]
     pub(crate) fn to_backend(self) -> BackendScalar {
         self.0
     }
@@ -162,12 +166,16 @@ impl<'de> Deserialize<'de> for Scalar {
     }
 }
 
+impl ConstantTimeEq for Scalar {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
 impl DefaultIsZeroes for Scalar {}
 
 impl CloneableSecret for Scalar {}
 
-impl SerializableSecret for Scalar {}
-
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Point(BackendPoint);
 
@@ -189,7 +197,23 @@ impl Point {
         VerifyingKey::from_affine(self.0.to_affine()).ok()
     }
 
+    /// Decodes a point, including the identity, from its only accepted encoding
+    /// (see [`to_compressed_array`](`Self::to_compressed_array`)).
     pub(crate) fn try_from_compressed_bytes(bytes: &[u8]) -> Result<Self, String> {
+        // Other lengths would allow other SEC1 encodings of the same point
+        // (the single zero byte of the identity, or the uncompressed form).
+        if bytes.len() != CompressedPointSize::USIZE {
+            return Err(format!(
+                "Invalid length of a compressed point: {}",
+                bytes.len()
+            ));
+        }
+
+        // The identity point is encoded as an all-zero array of the compressed point size
+        if bytes.iter().all(|byte| *byte == 0) {
+            return Ok(Self::IDENTITY);
+        }
+
         let ep = EncodedPoint::<Secp256k1>::from_bytes(bytes).map_err(|err| format!("{err}"))?;
 
         // Unwrap CtOption into Option
@@ -200,23 +224,75 @@ impl Point {
     }
 
     pub(crate) fn to_compressed_array(self) -> GenericArray<u8, CompressedPointSize> {
+        // SEC1 encodes the identity as a single zero byte, which does not fit a fixed-size array.
+        // It appears legitimately in the degenerate 1-of-1 case (e.g. the share changes
+        // in the key refresh), so it gets an all-zero array instead.
+        if self == Self::IDENTITY {
+            return GenericArray::default();
+        }
         *GenericArray::<u8, CompressedPointSize>::from_slice(
             self.0.to_affine().to_encoded_point(true).as_bytes(),
         )
     }
 
+    #[cfg(feature = "signing")]
     pub(crate) fn to_backend(self) -> BackendPoint {
         self.0
     }
 }
 
+impl ConstantTimeEq for Point {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+// The identity point only appears legitimately in the degenerate 1-of-1 case,
+// where no messages are received, so it is rejected on deserialization
+// (except in the places where it is expected, see `deserialize_points_or_identity`).
 impl<'a> TryFrom<&'a [u8]> for Point {
     type Error = String;
     fn try_from(val: &'a [u8]) -> Result<Self, Self::Error> {
-        Self::try_from_compressed_bytes(val)
+        let point = Self::try_from_compressed_bytes(val)?;
+        if point == Self::IDENTITY {
+            return Err("Unexpected identity point".into());
+        }
+        Ok(point)
+    }
+}
+
+/// A point that may be the identity, for deserialization.
+struct PointOrIdentity(Point);
+
+impl<'a> TryFrom<&'a [u8]> for PointOrIdentity {
+    type Error = String;
+    fn try_from(val: &'a [u8]) -> Result<Self, Self::Error> {
+        Point::try_from_compressed_bytes(val).map(Self)
     }
 }
 
+impl<'de> Deserialize<'de> for PointOrIdentity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_bytes::as_hex::deserialize(deserializer)
+    }
+}
+
+/// Deserializes a map of points that can contain the identity
+/// (e.g. the public share changes of the key refresh for a single party).
+pub(crate) fn deserialize_points_or_identity<'de, I, D>(
+    deserializer: D,
+) -> Result<BTreeMap<I, Point>, D::Error>
+where
+    I: Ord + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let points = BTreeMap::<I, PointOrIdentity>::deserialize(deserializer)?;
+    Ok(points
+        .into_iter()
+        .map(|(id, point)| (id, point.0))
+        .collect())
+}
+
 impl Serialize for Point {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         serde_bytes::as_hex::serialize(&self.to_compressed_array(), serializer)
@@ -373,3 +449,161 @@ impl<'a> core::iter::Sum<&'a Self> for Point {
         iter.cloned().sum()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use rand_core::{OsRng, RngCore};
+
+    use super::{Point, Scalar, ToEncodedPoint};
+
+    // The order of the curve group, big-endian.
+    const ORDER_BYTES: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+
+    fn to_hex(point: &Point) -> String {
+        hex::encode(point.to_compressed_array())
+    }
+
+    #[test]
+    fn point_test_vectors() {
+        assert_eq!(
+            to_hex(&Point::GENERATOR),
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        );
+        assert_eq!(
+            to_hex(&Scalar::from(2u32).mul_by_generator()),
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+        );
+        assert_eq!(
+            to_hex(&Scalar::from(3u32).mul_by_generator()),
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"
+        );
+        assert_eq!(
+            to_hex(&Scalar::from(0xdeadbeefu32).mul_by_generator()),
+            "0276d2fdf1302d1fa9556f4df94ec84cefba6d482e54f47c6c2a238c1baa560f0e"
+        );
+        assert_eq!(
+            Point::GENERATOR + Point::GENERATOR,
+            Scalar::from(2u32).mul_by_generator()
+        );
+    }
+
+    #[test]
+    fn generator_order() {
+        let order: [u8; 32] = hex::decode(ORDER_BYTES).unwrap().try_into().unwrap();
+
+        // The order reduces to zero, and it is not a valid scalar representation
+        assert_eq!(Scalar::from_reduced_bytes(&order), Scalar::ZERO);
+        assert!(Scalar::try_from_bytes(&order).is_err());
+
+        // `(q - 1) G = -G`
+        let minus_one = -Scalar::ONE;
+        assert_eq!(
+            minus_one.mul_by_generator() + Point::GENERATOR,
+            Point::IDENTITY
+        );
+        assert_eq!(Scalar::ZERO.mul_by_generator(), Point::IDENTITY);
+    }
+
+    #[test]
+    fn identity() {
+        let x = Scalar::random(&mut OsRng);
+        let point = x.mul_by_generator();
+
+        assert_eq!(point + Point::IDENTITY, point);
+        assert_eq!(Point::IDENTITY * x, Point::IDENTITY);
+        assert_eq!(point + (-x).mul_by_generator(), Point::IDENTITY);
+        assert_eq!(Point::IDENTITY.to_verifying_key(), None);
+        assert_eq!(
+            Point::try_from_compressed_bytes(&Point::IDENTITY.to_compressed_array()),
+            Ok(Point::IDENTITY)
+        );
+        // Only the all-zero array encodes the identity, and it is not accepted on deserialization
+        assert!(Point::try_from_compressed_bytes(&[0u8]).is_err());
+        assert!(Point::try_from(Point::IDENTITY.to_compressed_array().as_slice()).is_err());
+        assert_eq!(x + Scalar::ZERO, x);
+        assert_eq!(x * Scalar::ONE, x);
+        assert_eq!(Scalar::ZERO.invert().is_some().unwrap_u8(), 0);
+        assert_eq!(x * x.invert().unwrap(), Scalar::ONE);
+    }
+
+    #[test]
+    fn serialization_round_trip() {
+        let config = bincode::config::standard();
+        for _ in 0..16 {
+            let x = Scalar::random(&mut OsRng);
+            let point = x.mul_by_generator();
+
+            assert_eq!(Scalar::try_from_bytes(&x.to_bytes()).unwrap(), x);
+            assert_eq!(
+                Point::try_from_compressed_bytes(&point.to_compressed_array()).unwrap(),
+                point
+            );
+
+            let serialized = bincode::serde::encode_to_vec(x, config).unwrap();
+            let (deserialized, _): (Scalar, _) =
+                bincode::serde::decode_from_slice(&serialized, config).unwrap();
+            assert_eq!(deserialized, x);
+
+            let serialized = bincode::serde::encode_to_vec(point, config).unwrap();
+            let (deserialized, _): (Point, _) =
+                bincode::serde::decode_from_slice(&serialized, config).unwrap();
+            assert_eq!(deserialized, point);
+        }
+
+        // The uncompressed encoding is not accepted
+        let point = Scalar::random(&mut OsRng).mul_by_generator();
+        let uncompressed = point.0.to_affine().to_encoded_point(false);
+        assert!(Point::try_from_compressed_bytes(uncompressed.as_bytes()).is_err());
+
+        // There is no point with `x = 5` on the curve
+        let mut bytes = [0u8; 33];
+        bytes[0] = 2;
+        bytes[32] = 5;
+        assert!(Point::try_from_compressed_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn wide_reduction() {
+        let order = hex::decode(ORDER_BYTES).unwrap();
+
+        // `q` and `q + 1` in 64 bytes
+        let mut bytes = [0u8; 64];
+        bytes[32..].copy_from_slice(&order);
+        assert_eq!(Scalar::from_wide_bytes(&bytes), Scalar::ZERO);
+        bytes[63] += 1;
+        assert_eq!(Scalar::from_wide_bytes(&bytes), Scalar::ONE);
+
+        // `(2^512 - 1) mod q`
+        let expected: [u8; 32] =
+            hex::decode("9d671cd581c69bc5e697f5e45bcd07c6741496c20e7cf878896cf21467d7d13f")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        assert_eq!(
+            Scalar::from_wide_bytes(&[0xff; 64]),
+            Scalar::try_from_bytes(&expected).unwrap()
+        );
+
+        // A rough check of the distribution: every bit of the result is set about half of the time.
+        // The probability of any of the bounds being violated for a uniform distribution
+        // is below `2^{-40}`.
+        let samples = 1024;
+        let mut counts = [0u32; 256];
+        for _ in 0..samples {
+            let mut bytes = [0u8; 64];
+            OsRng.fill_bytes(&mut bytes);
+            let scalar = Scalar::from_wide_bytes(&bytes).to_bytes();
+            for (i, count) in counts.iter_mut().enumerate() {
+                *count += ((scalar[i / 8] >> (7 - i % 8)) & 1) as u32;
+            }
+        }
+        let out_of_bounds = counts
+            .iter()
+            .filter(|count| !(352..=672).contains(*count))
+            .collect::<Vec<_>>();
+        assert!(out_of_bounds.is_empty());
+    }
+}
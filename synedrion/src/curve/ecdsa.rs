@@ -1,8 +1,14 @@
-use k256::ecdsa::{RecoveryId, Signature as BackendSignature, VerifyingKey};
+#[cfg(feature = "signing")]
+use k256::ecdsa::VerifyingKey;
+use k256::ecdsa::{DerSignature, RecoveryId, Signature as BackendSignature};
 
+#[cfg(feature = "signing")]
 use super::arithmetic::{Point, Scalar};
 
 /// A wrapper for a signature and public key recovery info.
+///
+/// Only created for a signature that is valid for the verifying key it was produced with:
+/// the recovery info is found by recovering the verifying key from the signature.
 #[derive(Debug, Clone, Copy)]
 pub struct RecoverableSignature {
     signature: BackendSignature,
@@ -10,6 +16,7 @@ pub struct RecoverableSignature {
 }
 
 impl RecoverableSignature {
+    #[cfg(feature = "signing")]
     pub(crate) fn from_scalars(
         r: &Scalar,
         s: &Scalar,
@@ -41,4 +48,94 @@ impl RecoverableSignature {
     pub fn to_backend(self) -> (BackendSignature, RecoveryId) {
         (self.signature, self.recovery_id)
     }
+
+    /// Returns the signature without the recovery info.
+    pub fn signature(&self) -> BackendSignature {
+        self.signature
+    }
+
+    /// Returns the recovery info.
+    pub fn recovery_id(&self) -> RecoveryId {
+        self.recovery_id
+    }
+
+    /// Returns the ASN.1 DER encoding of the signature.
+    pub fn to_der(&self) -> DerSignature {
+        self.signature.to_der()
+    }
+
+    /// Returns the compact encoding of the signature: big-endian `r` followed by big-endian `s`.
+    pub fn to_compact_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes.copy_from_slice(&self.signature.to_bytes());
+        bytes
+    }
+
+    /// Returns the signature in the form used by Ethereum:
+    /// big-endian `r`, big-endian `s`, and `v` equal to the recovery ID plus 27.
+    pub fn to_rsv(&self) -> ([u8; 32], [u8; 32], u8) {
+        let (r, s) = self.signature.split_bytes();
+        (r.into(), s.into(), self.recovery_id.to_byte() + 27)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::{
+        signature::hazmat::PrehashSigner, RecoveryId, Signature, SigningKey, VerifyingKey,
+    };
+    use rand_core::OsRng;
+
+    use super::RecoverableSignature;
+    use crate::curve::{Point, Scalar};
+
+    #[test]
+    fn encodings() {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let verifying_key = *signing_key.verifying_key();
+        let message = Scalar::random(&mut OsRng);
+        let prehash = message.to_bytes();
+        let (backend, _recovery_id): (Signature, RecoveryId) =
+            signing_key.sign_prehash(&prehash).unwrap();
+        let (r, s) = backend.split_scalars();
+
+        let signature = RecoverableSignature::from_scalars(
+            &Scalar::from_reduced_bytes(&r.to_bytes().into()),
+            &Scalar::from_reduced_bytes(&s.to_bytes().into()),
+            &Point::from_verifying_key(&verifying_key),
+            &message,
+        )
+        .unwrap();
+
+        let normalized = backend.normalize_s().unwrap_or(backend);
+        assert_eq!(signature.signature(), normalized);
+        assert_eq!(
+            Signature::from_der(signature.to_der().as_bytes()).unwrap(),
+            normalized
+        );
+        assert_eq!(
+            Signature::from_slice(&signature.to_compact_bytes()).unwrap(),
+            normalized
+        );
+
+        let (r, s, v) = signature.to_rsv();
+        assert_eq!(&signature.to_compact_bytes()[..32], &r);
+        assert_eq!(&signature.to_compact_bytes()[32..], &s);
+        let recovery_id = RecoveryId::from_byte(v - 27).unwrap();
+        assert_eq!(recovery_id, signature.recovery_id());
+        assert_eq!(
+            VerifyingKey::recover_from_prehash(&prehash, &normalized, recovery_id).unwrap(),
+            verifying_key
+        );
+
+        // A signature for a different key is rejected
+        let other_key = Point::from_verifying_key(SigningKey::random(&mut OsRng).verifying_key());
+        assert!(RecoverableSignature::from_scalars(
+            &Scalar::from_reduced_bytes(&r),
+            &Scalar::from_reduced_bytes(&s),
+            &other_key,
+            &message,
+        )
+        .is_none());
+    }
 }
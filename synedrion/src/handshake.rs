@@ -0,0 +1,339 @@
+//! A preliminary protocol where the parties make sure they agree on the configuration
+//! of the protocol they are about to run.
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::cggmp21::{params_fingerprint, SchemeParams};
+use crate::rounds::{
+    no_direct_messages, FinalizableToResult, FinalizeError, FirstRound, InitError, Protocol,
+    ProtocolId, ProtocolResult, Round, ToResult,
+};
+use crate::sessions::SessionId;
+use crate::tools::hashing::{Chain, FofHasher, HashOutput};
+use crate::tools::serde_bytes;
+
+/// The domain of the session ID derived from the configuration.
+pub(crate) const HASH_TAG: &[u8] = b"HandshakeConfig";
+
+/// The configuration of a protocol run proposed by a party.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandshakeConfig<I: Ord> {
+    /// The fingerprint of the scheme parameters.
+    params: HashOutput,
+    parties: BTreeSet<I>,
+    threshold: usize,
+    /// The application-defined data the session ID of the protocol run is derived from.
+    #[serde(with = "serde_bytes::as_base64")]
+    session_inputs: Box<[u8]>,
+}
+
+impl<I: Ord + Serialize> HandshakeConfig<I> {
+    /// Creates a configuration for running a protocol with the scheme parameters `P`
+    /// between `parties`, with `threshold` of them required for signing.
+    ///
+    /// `session_inputs` is the data the session ID of the protocol run is derived from
+    /// (see [`Self::session_id`]).
+    pub fn new<P: SchemeParams>(
+        parties: BTreeSet<I>,
+        threshold: usize,
+        session_inputs: &[u8],
+    ) -> Self {
+        Self {
+            params: params_fingerprint::<P>(),
+            parties,
+            threshold,
+            session_inputs: session_inputs.into(),
+        }
+    }
+
+    /// Returns `true` if the configuration was created with the scheme parameters `P`.
+    pub fn uses_params<P: SchemeParams>(&self) -> bool {
+        self.params == params_fingerprint::<P>()
+    }
+
+    /// The parties participating in the protocol.
+    pub fn parties(&self) -> &BTreeSet<I> {
+        &self.parties
+    }
+
+    /// The number of shares required for signing.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// The session ID of the protocol run, derived from the whole configuration,
+    /// so that the parties with mismatched configurations cannot end up in the same session.
+    pub fn session_id(&self) -> SessionId {
+        let hash = FofHasher::new_with_dst(HASH_TAG).chain(self).finalize();
+        SessionId::from_seed(hash.as_ref())
+    }
+}
+
+/// The outcomes of the Handshake protocol.
+#[derive(Debug)]
+pub struct HandshakeResult<I: Debug>(PhantomData<I>);
+
+impl<I: Ord + Debug> ProtocolResult for HandshakeResult<I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::Handshake;
+    type Success = HandshakeConfig<I>;
+    type ProvableError = HandshakeError;
+    type CorrectnessProof = ();
+}
+
+impl<I: Clone + Ord + Serialize + for<'de> Deserialize<'de> + Debug> Protocol<I>
+    for HandshakeResult<I>
+{
+    type FirstRound = Round1<I>;
+}
+
+/// Possible verifiable errors of the Handshake protocol.
+///
+/// Each of them means that the sender proposed a configuration different from ours.
+#[derive(Debug, Clone, Copy)]
+pub enum HandshakeError {
+    /// The fingerprint of the scheme parameters does not match.
+    MismatchedParams,
+    /// The set of parties does not match.
+    MismatchedParties,
+    /// The threshold does not match.
+    MismatchedThreshold,
+    /// The session ID derivation inputs do not match.
+    MismatchedSessionInputs,
+}
+
+pub struct Round1<I: Ord> {
+    other_ids: BTreeSet<I>,
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "key-resharing"
+    ))]
+    my_id: I,
+    config: HandshakeConfig<I>,
+}
+
+impl<I: Clone + Ord + Serialize + for<'de> Deserialize<'de> + Debug> FirstRound<I> for Round1<I> {
+    type Inputs = HandshakeConfig<I>;
+
+    fn new(
+        _rng: &mut impl CryptoRngCore,
+        _shared_randomness: &[u8],
+        other_ids: BTreeSet<I>,
+        my_id: I,
+        inputs: Self::Inputs,
+    ) -> Result<Self, InitError> {
+        let mut all_ids = other_ids.clone();
+        all_ids.insert(my_id.clone());
+
+        if inputs.parties != all_ids {
+            return Err(InitError(
+                "The parties in the configuration must be the same as the parties of the session"
+                    .into(),
+            ));
+        }
+
+        if inputs.threshold == 0 || inputs.threshold > inputs.parties.len() {
+            return Err(InitError(
+                "The threshold must be between 1 and the number of parties".into(),
+            ));
+        }
+
+        Ok(Self {
+            other_ids,
+            #[cfg(any(
+                feature = "key-gen",
+                feature = "aux-gen",
+                feature = "signing",
+                feature = "key-resharing"
+            ))]
+            my_id,
+            config: inputs,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "HandshakeConfig<I>: Serialize"))]
+#[serde(bound(deserialize = "HandshakeConfig<I>: for<'x> Deserialize<'x>"))]
+pub struct Round1Message<I: Ord> {
+    config: HandshakeConfig<I>,
+}
+
+impl<I: Clone + Ord + Serialize + for<'de> Deserialize<'de> + Debug> Round<I> for Round1<I> {
+    type Type = ToResult;
+    type Result = HandshakeResult<I>;
+    const ROUND_NUM: u8 = 1;
+    const NEXT_ROUND_NUM: Option<u8> = None;
+
+    fn other_ids(&self) -> &BTreeSet<I> {
+        &self.other_ids
+    }
+
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "key-resharing"
+    ))]
+    fn my_id(&self) -> &I {
+        &self.my_id
+    }
+
+    type BroadcastMessage = Round1Message<I>;
+    type DirectMessage = ();
+    type Payload = ();
+    type Artifact = ();
+
+    fn make_broadcast_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+    ) -> Option<Self::BroadcastMessage> {
+        Some(Round1Message {
+            config: self.config.clone(),
+        })
+    }
+
+    no_direct_messages!(I);
+
+    fn verify_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _from: &I,
+        broadcast_msg: Self::BroadcastMessage,
+        _direct_msg: Self::DirectMessage,
+    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
+        // Since the messages are signed, a party cannot deny the configuration it proposed;
+        // and since every party compares the others' configurations with its own,
+        // the protocol succeeds for an honest party only if all the configurations are the same.
+        let theirs = broadcast_msg.config;
+        let ours = &self.config;
+        if theirs.params != ours.params {
+            return Err(HandshakeError::MismatchedParams);
+        }
+        if theirs.parties != ours.parties {
+            return Err(HandshakeError::MismatchedParties);
+        }
+        if theirs.threshold != ours.threshold {
+            return Err(HandshakeError::MismatchedThreshold);
+        }
+        if theirs.session_inputs != ours.session_inputs {
+            return Err(HandshakeError::MismatchedSessionInputs);
+        }
+        Ok(())
+    }
+}
+
+impl<I: Clone + Ord + Serialize + for<'de> Deserialize<'de> + Debug> FinalizableToResult<I>
+    for Round1<I>
+{
+    fn finalize_to_result(
+        self,
+        _rng: &mut impl CryptoRngCore,
+        _payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
+        _artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
+    ) -> Result<<Self::Result as ProtocolResult>::Success, FinalizeError<Self::Result>> {
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    use rand_core::OsRng;
+
+    use super::{HandshakeConfig, Round1};
+    use crate::cggmp21::{ProductionParams, TestParams};
+    use crate::rounds::{
+        test_utils::{round_info, step_result, step_round, Id, Without},
+        FirstRound, ProtocolId,
+    };
+
+    fn make_rounds(configs: &BTreeMap<Id, HandshakeConfig<Id>>) -> BTreeMap<Id, Round1<Id>> {
+        let ids = configs.keys().cloned().collect::<BTreeSet<_>>();
+        configs
+            .iter()
+            .map(|(id, config)| {
+                let round = Round1::new(
+                    &mut OsRng,
+                    b"",
+                    ids.clone().without(id),
+                    *id,
+                    config.clone(),
+                )
+                .unwrap();
+                (*id, round)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matching_configs() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let config = HandshakeConfig::new::<TestParams>(ids.clone(), 2, b"abcde");
+        assert!(config.uses_params::<TestParams>());
+        assert!(!config.uses_params::<ProductionParams>());
+
+        let configs = ids
+            .iter()
+            .map(|id| (*id, config.clone()))
+            .collect::<BTreeMap<_, _>>();
+
+        let r1a = step_round(&mut OsRng, make_rounds(&configs)).unwrap();
+        let results = step_result(&mut OsRng, r1a).unwrap();
+
+        assert!(results.values().all(|result| result == &config));
+    }
+
+    #[test]
+    fn mismatched_configs() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let config = HandshakeConfig::new::<TestParams>(ids.clone(), 2, b"abcde");
+        let mismatched = [
+            HandshakeConfig::new::<ProductionParams>(ids.clone(), 2, b"abcde"),
+            HandshakeConfig::new::<TestParams>(ids.clone(), 3, b"abcde"),
+            HandshakeConfig::new::<TestParams>(ids.clone(), 2, b"fghij"),
+        ];
+
+        for other_config in mismatched {
+            assert_ne!(config.session_id(), other_config.session_id());
+
+            let mut configs = ids
+                .iter()
+                .map(|id| (*id, config.clone()))
+                .collect::<BTreeMap<_, _>>();
+            configs.insert(Id(0), other_config);
+
+            assert!(step_round(&mut OsRng, make_rounds(&configs)).is_err());
+        }
+    }
+
+    #[test]
+    fn invalid_config() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        // A roster different from the parties of the session
+        let config =
+            HandshakeConfig::new::<TestParams>(BTreeSet::from([Id(0), Id(1)]), 2, b"abcde");
+        assert!(Round1::new(&mut OsRng, b"", ids.clone().without(&Id(0)), Id(0), config).is_err());
+
+        // Threshold greater than the number of parties
+        let config = HandshakeConfig::new::<TestParams>(ids.clone(), 4, b"abcde");
+        assert!(Round1::new(&mut OsRng, b"", ids.clone().without(&Id(0)), Id(0), config).is_err());
+    }
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [round_info::<Id, Round1<Id>>()],
+            ProtocolId::Handshake.rounds()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "profile", feature = "std")), no_std)]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 #![doc = include_str!("../README.md")]
 #![deny(unsafe_code)]
@@ -20,35 +20,85 @@ extern crate alloc;
 #[cfg(feature = "bench-internals")]
 pub mod bench_internals;
 
+mod certificate;
 mod cggmp21;
+pub mod compat;
 mod constructors;
 mod curve;
+mod handshake;
+mod lineage;
+#[cfg(feature = "paillier")]
+pub mod paillier;
+#[cfg(not(feature = "paillier"))]
 mod paillier;
+pub mod prelude;
 mod rounds;
 pub mod sessions;
+#[cfg(feature = "spec")]
+pub mod spec;
+#[cfg(any(feature = "testing", feature = "dev-utils"))]
+pub mod testing;
 mod tools;
 mod uint;
 mod www02;
 
 // Some re-exports to avoid the need for version-matching
 pub use bip32;
+#[cfg(feature = "paillier")]
+pub use crypto_bigint;
 pub use k256;
 pub use k256::ecdsa;
+pub use k256::elliptic_curve::subtle;
+#[cfg(feature = "spec")]
+pub use serde_json;
 pub use signature;
 
+pub use certificate::{CertificateError, KeyGenCertificate, KeyGenStatement};
 pub use cggmp21::{
-    AuxGenError, AuxGenResult, AuxInfo, InteractiveSigningError, InteractiveSigningProof,
-    InteractiveSigningResult, KeyGenError, KeyGenProof, KeyGenResult, KeyInitError, KeyInitResult,
-    KeyRefreshResult, KeyShare, KeyShareChange, PresigningError, PresigningProof, PresigningResult,
-    ProductionParams, SchemeParams, SigningProof, SigningResult, TestParams,
-};
-pub use constructors::{
-    make_aux_gen_session, make_interactive_signing_session, make_key_gen_session,
-    make_key_init_session, make_key_refresh_session, make_key_resharing_session, PrehashedMessage,
+    AuxInfo, KeyShare, KeyShareChange, KeySharePart, PresigningPublic, ProductionParams,
+    PublicAuxInfo, SchemeParams, TestParams, VerifierBundle,
 };
+pub use constructors::{make_handshake_session, PrehashedMessage};
 pub use curve::RecoverableSignature;
-pub use rounds::ProtocolResult;
-pub use sessions::{FinalizeOutcome, MessageBundle, Session, SessionId};
-pub use www02::{
-    DeriveChildKey, KeyResharingInputs, KeyResharingResult, NewHolder, OldHolder, ThresholdKeyShare,
+pub use handshake::{HandshakeConfig, HandshakeError, HandshakeResult};
+pub use lineage::{Ceremony, Lineage, LineageEntry};
+pub use rounds::{Check, CpuProfile, ProtocolId, ProtocolResult, RoundInfo};
+pub use sessions::{BeaconValue, FinalizeOutcome, MessageBundle, Session, SessionId};
+#[cfg(feature = "profile")]
+pub use tools::profile::{set_profile_sink, ProfileSink};
+pub use www02::{DeriveChildKey, ShareId, ThresholdKeyShare};
+
+#[cfg(feature = "aux-gen")]
+pub use cggmp21::{AuxGenError, AuxGenResult};
+#[cfg(feature = "aux-gen")]
+pub use constructors::make_aux_gen_session;
+
+#[cfg(feature = "key-gen")]
+pub use cggmp21::{
+    FastKeyInitError, FastKeyInitResult, KeyGenError, KeyGenProof, KeyGenResult, KeyInitError,
+    KeyInitResult, UnsafeAssumeReliableBroadcast,
+};
+#[cfg(feature = "key-gen")]
+pub use constructors::{make_fast_key_init_session, make_key_gen_session, make_key_init_session};
+
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+pub use cggmp21::{AuxDataError, AuxDataProof, KeyRefreshResult};
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+pub use constructors::{make_deterministic_key_refresh_session, make_key_refresh_session};
+
+#[cfg(feature = "signing")]
+pub use cggmp21::{
+    BatchSigningError, BatchSigningProof, BatchSigningResult, InteractiveSigningError,
+    InteractiveSigningProof, InteractiveSigningResult, PartialSignature, PresignatureStore,
+    PresigningData, PresigningError, PresigningProof, PresigningResult, SigningError, SigningProof,
+    SigningResult,
 };
+#[cfg(feature = "signing")]
+pub use constructors::{
+    make_batch_signing_session, make_interactive_signing_session, make_presigning_session,
+};
+
+#[cfg(feature = "key-resharing")]
+pub use constructors::make_key_resharing_session;
+#[cfg(feature = "key-resharing")]
+pub use www02::{KeyResharingInputs, KeyResharingResult, NewHolder, OldHolder};
@@ -0,0 +1,305 @@
+//! Records of the ceremonies that produced a key share,
+//! allowing an operator to check which sessions the share currently in use went through.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+use k256::ecdsa::VerifyingKey;
+use serde::{Deserialize, Serialize};
+
+use crate::curve::Point;
+use crate::sessions::SessionId;
+use crate::tools::hashing::{Chain, FofHasher, HashOutput};
+
+/// A ceremony recorded in a [`Lineage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ceremony {
+    /// KeyInit protocol (also a part of KeyGen) creating the key.
+    KeyInit,
+    /// FastKeyInit protocol creating the key.
+    FastKeyInit,
+    /// KeyRefresh protocol (also a part of KeyGen) changing the shares.
+    KeyRefresh,
+    /// KeyResharing protocol distributing the key to a new set of holders.
+    KeyResharing,
+}
+
+/// Returns the fingerprint of a session recorded in a [`Lineage`].
+///
+/// The sessions created with the constructors in this crate
+/// use the session ID as the shared randomness of the protocols.
+pub(crate) fn session_fingerprint(shared_randomness: &[u8]) -> HashOutput {
+    FofHasher::new_with_dst(b"LineageSession")
+        .chain_bytes(shared_randomness)
+        .finalize()
+}
+
+/// Returns the fingerprint of the outcome of a ceremony recorded in a [`Lineage`]:
+/// the full set of the parties of the session (including the ones that do not hold
+/// a share after it) and the resulting public shares.
+pub(crate) fn outcome_fingerprint<I: Ord + Serialize>(
+    parties: &BTreeSet<I>,
+    public_shares: &BTreeMap<I, Point>,
+) -> HashOutput {
+    FofHasher::new_with_dst(b"LineageOutcome")
+        .chain(parties)
+        .chain(public_shares)
+        .finalize()
+}
+
+/// An entry of a [`Lineage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineageEntry {
+    ceremony: Ceremony,
+    session: HashOutput,
+    outcome: HashOutput,
+    link: HashOutput,
+}
+
+impl LineageEntry {
+    fn new(
+        previous: Option<&HashOutput>,
+        verifying_key: &Point,
+        ceremony: Ceremony,
+        session: HashOutput,
+        outcome: HashOutput,
+    ) -> Self {
+        let link = Self::make_link(previous, verifying_key, ceremony, &session, &outcome);
+        Self {
+            ceremony,
+            session,
+            outcome,
+            link,
+        }
+    }
+
+    fn make_link(
+        previous: Option<&HashOutput>,
+        verifying_key: &Point,
+        ceremony: Ceremony,
+        session: &HashOutput,
+        outcome: &HashOutput,
+    ) -> HashOutput {
+        FofHasher::new_with_dst(b"Lineage")
+            .chain(&previous)
+            .chain(verifying_key)
+            .chain(&ceremony)
+            .chain(session)
+            .chain(outcome)
+            .finalize()
+    }
+
+    /// The recorded ceremony.
+    pub fn ceremony(&self) -> Ceremony {
+        self.ceremony
+    }
+
+    /// Returns `true` if the ceremony was performed in the session with the given ID.
+    pub fn is_session(&self, session_id: &SessionId) -> bool {
+        self.session == session_fingerprint(session_id.as_ref())
+    }
+
+    /// The hash of this entry and all the entries before it.
+    pub fn link(&self) -> &[u8] {
+        self.link.as_ref()
+    }
+}
+
+/// A hash-linked list of the ceremonies a key share went through:
+/// the one that created the key, and every refresh and resharing applied after that.
+///
+/// Every entry commits to the session, its full set of parties, and the public shares it produced,
+/// so the shares with the same history have lineages with the same [`head`](`Self::head`),
+/// and the parties can compare them without revealing anything about the shares.
+/// The lineage is recorded by the protocols; the shares created in other ways
+/// (e.g. with `new_centralized()`, derived with BIP-32, or stored before the lineage was introduced)
+/// do not have one.
+///
+/// The lineage is not authenticated: it is computed locally by each party and stored with its share,
+/// and [`verify`](`Self::verify`) only checks that it is consistent.
+/// Whoever can modify the stored share can replace the lineage with any other consistent one,
+/// so it shows what a party's share went through only as far as the storage is trusted,
+/// or when the heads of several parties are compared.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lineage {
+    verifying_key: Point,
+    entries: Vec<LineageEntry>,
+}
+
+impl Lineage {
+    #[cfg(any(feature = "key-gen", feature = "key-resharing"))]
+    pub(crate) fn new(
+        ceremony: Ceremony,
+        session: HashOutput,
+        outcome: HashOutput,
+        verifying_key: Point,
+    ) -> Self {
+        Self {
+            verifying_key,
+            entries: [LineageEntry::new(
+                None,
+                &verifying_key,
+                ceremony,
+                session,
+                outcome,
+            )]
+            .into(),
+        }
+    }
+
+    /// Returns the lineage with a new ceremony appended.
+    pub(crate) fn extend(
+        mut self,
+        ceremony: Ceremony,
+        session: HashOutput,
+        outcome: HashOutput,
+    ) -> Self {
+        let previous = self.entries.last().map(|entry| entry.link);
+        self.entries.push(LineageEntry::new(
+            previous.as_ref(),
+            &self.verifying_key,
+            ceremony,
+            session,
+            outcome,
+        ));
+        self
+    }
+
+    /// The recorded ceremonies, starting from the one that created the key.
+    pub fn entries(&self) -> &[LineageEntry] {
+        &self.entries
+    }
+
+    /// The link of the last entry, committing to the whole history.
+    pub fn head(&self) -> Option<&[u8]> {
+        self.entries.last().map(|entry| entry.link())
+    }
+
+    /// Returns `true` if one of the recorded ceremonies was performed
+    /// in the session with the given ID (e.g. a mandated refresh).
+    pub fn contains_session(&self, session_id: &SessionId) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.is_session(session_id))
+    }
+
+    /// Checks that the entries are correctly linked, the first one records a key creation,
+    /// and the lineage belongs to the given verifying key.
+    pub fn verify(&self, verifying_key: &VerifyingKey) -> bool {
+        if self.verifying_key.to_verifying_key().as_ref() != Some(verifying_key) {
+            return false;
+        }
+        match self.entries.first().map(|entry| entry.ceremony) {
+            Some(Ceremony::KeyInit | Ceremony::FastKeyInit | Ceremony::KeyResharing) => {}
+            _ => return false,
+        }
+        let mut previous = None;
+        for entry in self.entries.iter() {
+            let link = LineageEntry::make_link(
+                previous,
+                &self.verifying_key,
+                entry.ceremony,
+                &entry.session,
+                &entry.outcome,
+            );
+            if link != entry.link {
+                return false;
+            }
+            previous = Some(&entry.link);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    use k256::ecdsa::SigningKey;
+    use rand_core::OsRng;
+
+    use super::{outcome_fingerprint, session_fingerprint, Ceremony, Lineage};
+    use crate::curve::{Point, Scalar};
+    use crate::sessions::SessionId;
+
+    #[test]
+    fn verify_lineage() {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let verifying_key = *signing_key.verifying_key();
+        let point = Point::from_verifying_key(&verifying_key);
+
+        let parties = BTreeSet::from([0u8, 1, 2]);
+        let public_shares = parties
+            .iter()
+            .map(|id| (*id, Scalar::random(&mut OsRng).mul_by_generator()))
+            .collect::<BTreeMap<_, _>>();
+        let outcome = outcome_fingerprint(&parties, &public_shares);
+
+        let init = SessionId::from_seed(b"init");
+        let refresh = SessionId::from_seed(b"refresh");
+        let lineage = Lineage::new(
+            Ceremony::KeyInit,
+            session_fingerprint(init.as_ref()),
+            outcome,
+            point,
+        )
+        .extend(
+            Ceremony::KeyRefresh,
+            session_fingerprint(refresh.as_ref()),
+            outcome,
+        );
+
+        assert!(lineage.verify(&verifying_key));
+        assert!(lineage.contains_session(&init));
+        assert!(lineage.contains_session(&refresh));
+        assert!(!lineage.contains_session(&SessionId::from_seed(b"other")));
+        assert_eq!(lineage.entries()[1].ceremony(), Ceremony::KeyRefresh);
+
+        // A different history results in a different head
+        let other = Lineage::new(
+            Ceremony::KeyInit,
+            session_fingerprint(init.as_ref()),
+            outcome,
+            point,
+        );
+        assert!(other.verify(&verifying_key));
+        assert_ne!(other.head(), lineage.head());
+
+        // So does the same session with a different set of parties or different public shares
+        let mut other_parties = parties.clone();
+        other_parties.insert(3);
+        let mut other_public_shares = public_shares.clone();
+        other_public_shares.insert(0, Point::GENERATOR);
+        for other_outcome in [
+            outcome_fingerprint(&other_parties, &public_shares),
+            outcome_fingerprint(&parties, &other_public_shares),
+        ] {
+            let other = Lineage::new(
+                Ceremony::KeyInit,
+                session_fingerprint(init.as_ref()),
+                other_outcome,
+                point,
+            );
+            assert!(other.verify(&verifying_key));
+            assert_ne!(other.head(), Some(lineage.entries()[0].link()));
+        }
+
+        // The lineage of a different key does not verify
+        let other_key = *SigningKey::random(&mut OsRng).verifying_key();
+        assert!(!lineage.verify(&other_key));
+
+        // Removing an entry from the middle breaks the links
+        let tampered = lineage.clone().extend(
+            Ceremony::KeyRefresh,
+            session_fingerprint(b"second refresh"),
+            outcome,
+        );
+        let mut entries = tampered.entries.clone();
+        entries.remove(1);
+        let tampered = Lineage {
+            verifying_key: point,
+            entries,
+        };
+        assert!(!tampered.verify(&verifying_key));
+    }
+}
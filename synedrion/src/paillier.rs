@@ -1,12 +1,38 @@
+//! Paillier encryption: key generation with safe primes, encryption,
+//! homomorphic operations on the ciphertexts, and the proof of the correct key generation.
+//!
+//! The public API is available with the `paillier` feature,
+//! and does not require any of the MPC protocols to be enabled.
+
+#[cfg(any(
+    feature = "key-gen",
+    feature = "aux-gen",
+    feature = "signing",
+    feature = "paillier"
+))]
 mod encryption;
 mod keys;
 mod params;
+#[cfg(feature = "paillier")]
+mod public;
 mod ring_pedersen;
 
-pub(crate) use encryption::{Ciphertext, CiphertextMod, Randomizer, RandomizerMod};
+#[cfg(feature = "signing")]
+pub(crate) use encryption::RandomizerMod;
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+pub(crate) use encryption::{Ciphertext, CiphertextMod, Randomizer};
 pub(crate) use keys::{
     PublicKeyPaillier, PublicKeyPaillierPrecomputed, SecretKeyPaillier,
     SecretKeyPaillierPrecomputed,
 };
-pub(crate) use params::PaillierParams;
-pub(crate) use ring_pedersen::{RPCommitment, RPParams, RPParamsMod, RPSecret};
+pub use params::PaillierParams;
+#[cfg(feature = "paillier")]
+pub use public::{EncryptedValue, ModulusProof, PublicKey, SecretKey};
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+pub(crate) use ring_pedersen::RPCommitment;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+pub(crate) use ring_pedersen::RPSecret;
+pub(crate) use ring_pedersen::{RPParams, RPParamsMod};
+
+#[cfg(feature = "paillier")]
+pub use crate::cggmp21::{PaillierProduction, PaillierTest};
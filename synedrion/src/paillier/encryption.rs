@@ -2,13 +2,16 @@ use core::marker::PhantomData;
 use core::ops::{Add, Mul};
 
 use rand_core::CryptoRngCore;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 use zeroize::ZeroizeOnDrop;
 
 use super::keys::{PublicKeyPaillierPrecomputed, SecretKeyPaillierPrecomputed};
 use super::params::PaillierParams;
+use crate::tools::profile::profile_scope;
+#[cfg(feature = "signing")]
+use crate::uint::subtle::ConditionallySelectable;
 use crate::uint::{
-    subtle::{Choice, ConditionallyNegatable, ConditionallySelectable},
+    subtle::{Choice, ConditionallyNegatable},
     Bounded, HasWide, NonZero, Retrieve, Signed, UintLike, UintModLike,
 };
 
@@ -17,10 +20,12 @@ use crate::uint::{
 pub(crate) struct Randomizer<P: PaillierParams>(P::Uint);
 
 impl<P: PaillierParams> Randomizer<P> {
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     pub fn random(rng: &mut impl CryptoRngCore, pk: &PublicKeyPaillierPrecomputed<P>) -> Self {
         RandomizerMod::random(rng, pk).retrieve()
     }
 
+    #[cfg(feature = "signing")]
     pub fn to_mod(&self, pk: &PublicKeyPaillierPrecomputed<P>) -> RandomizerMod<P> {
         RandomizerMod(self.0.to_mod(pk.precomputed_modulus()))
     }
@@ -38,10 +43,12 @@ impl<P: PaillierParams> RandomizerMod<P> {
         Randomizer(self.0.retrieve())
     }
 
+    #[cfg(feature = "signing")]
     pub fn pow_signed(&self, exponent: &Signed<P::Uint>) -> Self {
         Self(self.0.pow_signed(exponent))
     }
 
+    #[cfg(feature = "signing")]
     pub fn pow_signed_vartime(&self, exponent: &Signed<P::Uint>) -> Self {
         Self(self.0.pow_signed_vartime(exponent))
     }
@@ -54,14 +61,14 @@ impl<'a, P: PaillierParams> Mul<&'a RandomizerMod<P>> for &'a RandomizerMod<P> {
     }
 }
 
-impl<'a, P: PaillierParams> Mul<RandomizerMod<P>> for &'a RandomizerMod<P> {
+impl<P: PaillierParams> Mul<RandomizerMod<P>> for &RandomizerMod<P> {
     type Output = RandomizerMod<P>;
     fn mul(self, rhs: RandomizerMod<P>) -> Self::Output {
         self * &rhs
     }
 }
 
-impl<'a, P: PaillierParams> Mul<&'a RandomizerMod<P>> for RandomizerMod<P> {
+impl<P: PaillierParams> Mul<&RandomizerMod<P>> for RandomizerMod<P> {
     type Output = RandomizerMod<P>;
     fn mul(self, rhs: &RandomizerMod<P>) -> Self::Output {
         &self * rhs
@@ -89,12 +96,25 @@ pub(crate) struct Ciphertext<P: PaillierParams> {
 }
 
 impl<P: PaillierParams> Ciphertext<P> {
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
     pub fn to_mod(&self, pk: &PublicKeyPaillierPrecomputed<P>) -> CiphertextMod<P> {
         CiphertextMod {
             pk: pk.clone(),
             ciphertext: self.ciphertext.to_mod(pk.precomputed_modulus_squared()),
         }
     }
+
+    #[cfg(any(feature = "signing", feature = "paillier"))]
+    /// Same as [`to_mod`](`Self::to_mod`), but returns `None` if the ciphertext
+    /// is not reduced modulo `N^2`.
+    pub fn to_mod_checked(&self, pk: &PublicKeyPaillierPrecomputed<P>) -> Option<CiphertextMod<P>> {
+        self.ciphertext
+            .to_mod_checked(pk.precomputed_modulus_squared())
+            .map(|ciphertext| CiphertextMod {
+                pk: pk.clone(),
+                ciphertext,
+            })
+    }
 }
 
 /// Paillier ciphertext.
@@ -105,6 +125,7 @@ pub(crate) struct CiphertextMod<P: PaillierParams> {
 }
 
 impl<P: PaillierParams> CiphertextMod<P> {
+    #[cfg(feature = "signing")]
     pub fn public_key(&self) -> &PublicKeyPaillierPrecomputed<P> {
         &self.pk
     }
@@ -116,6 +137,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
         randomizer: &Randomizer<P>,
         plaintext_is_negative: Choice,
     ) -> Self {
+        profile_scope!("CiphertextMod::encrypt");
         // Technically if `abs_plaintext` is greater than the modulus of `pk`,
         // it will be effectively reduced modulo `pk`.
         // But some ZK proofs with `TestParams` may still supply a value larger than `pk`
@@ -163,6 +185,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
         Self::new_with_randomizer_inner(pk, plaintext, randomizer, Choice::from(0))
     }
 
+    #[cfg(feature = "signing")]
     pub fn new_with_randomizer_signed(
         pk: &PublicKeyPaillierPrecomputed<P>,
         plaintext: &Signed<P::Uint>,
@@ -171,6 +194,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
         Self::new_with_randomizer_inner(pk, &plaintext.abs(), randomizer, plaintext.is_negative())
     }
 
+    #[cfg(feature = "signing")]
     pub fn new_with_randomizer_wide(
         pk: &PublicKeyPaillierPrecomputed<P>,
         plaintext: &Signed<P::WideUint>,
@@ -183,6 +207,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
         Self::new_with_randomizer_inner(pk, &plaintext_reduced, randomizer, plaintext.is_negative())
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     /// Encrypts the plaintext with a random randomizer.
     pub fn new(
         rng: &mut impl CryptoRngCore,
@@ -203,6 +228,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
 
     /// Decrypts this ciphertext assuming that the plaintext is in range `[0, N)`.
     pub fn decrypt(&self, sk: &SecretKeyPaillierPrecomputed<P>) -> P::Uint {
+        profile_scope!("CiphertextMod::decrypt");
         assert_eq!(sk.public_key(), &self.pk);
 
         let pk = sk.public_key();
@@ -227,6 +253,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
         (x_mod * sk.inv_totient()).retrieve()
     }
 
+    #[cfg(feature = "signing")]
     /// Decrypts this ciphertext assuming that the plaintext is in range `[-N/2, N/2)`.
     pub fn decrypt_signed(&self, sk: &SecretKeyPaillierPrecomputed<P>) -> Signed<P::Uint> {
         assert_eq!(sk.public_key(), &self.pk);
@@ -246,8 +273,10 @@ impl<P: PaillierParams> CiphertextMod<P> {
         result
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
     /// Derive the randomizer used to create this ciphertext.
     pub fn derive_randomizer(&self, sk: &SecretKeyPaillierPrecomputed<P>) -> RandomizerMod<P> {
+        profile_scope!("CiphertextMod::derive_randomizer");
         assert_eq!(sk.public_key(), &self.pk);
 
         let pk = sk.public_key();
@@ -276,6 +305,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
     // So if we want to replicate the Paillier encryption manually and get the same ciphertext
     // (e.g. in the P_enc sigma-protocol), we need to process the sign correctly.
     fn homomorphic_mul(self, rhs: &Signed<P::Uint>) -> Self {
+        profile_scope!("CiphertextMod::homomorphic_mul");
         Self {
             pk: self.pk,
             ciphertext: self.ciphertext.pow_signed(&rhs.into_wide()),
@@ -283,13 +313,16 @@ impl<P: PaillierParams> CiphertextMod<P> {
     }
 
     fn homomorphic_mul_ref(&self, rhs: &Signed<P::Uint>) -> Self {
+        profile_scope!("CiphertextMod::homomorphic_mul");
         Self {
             pk: self.pk.clone(),
             ciphertext: self.ciphertext.pow_signed(&rhs.into_wide()),
         }
     }
 
+    #[cfg(feature = "signing")]
     pub fn homomorphic_mul_wide(&self, rhs: &Signed<P::WideUint>) -> Self {
+        profile_scope!("CiphertextMod::homomorphic_mul");
         // Unfortunately we cannot implement `Mul` for `Signed<P::Uint>` and `Signed<P::WideUint>`
         // at the same time, since they can be the same type.
         // But this method is only used once, so it's not a problem to spell it out.
@@ -300,6 +333,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
     }
 
     fn homomorphic_mul_unsigned(self, rhs: &Bounded<P::Uint>) -> Self {
+        profile_scope!("CiphertextMod::homomorphic_mul");
         Self {
             pk: self.pk,
             ciphertext: self.ciphertext.pow_bounded(&rhs.into_wide()),
@@ -307,6 +341,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
     }
 
     fn homomorphic_mul_unsigned_ref(&self, rhs: &Bounded<P::Uint>) -> Self {
+        profile_scope!("CiphertextMod::homomorphic_mul");
         Self {
             pk: self.pk.clone(),
             ciphertext: self.ciphertext.pow_bounded(&rhs.into_wide()),
@@ -321,6 +356,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
         }
     }
 
+    #[cfg(any(feature = "signing", feature = "paillier"))]
     pub fn mul_randomizer(self, randomizer: &Randomizer<P>) -> Self {
         let randomizer_mod = randomizer
             .0
@@ -342,6 +378,14 @@ impl<P: PaillierParams> CiphertextMod<P> {
     }
 }
 
+// Serialized in the same way as the corresponding `Ciphertext`,
+// so that it can be hashed directly.
+impl<P: PaillierParams> Serialize for CiphertextMod<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.retrieve().serialize(serializer)
+    }
+}
+
 impl<P: PaillierParams> Add for CiphertextMod<P> {
     type Output = CiphertextMod<P>;
     fn add(self, other: CiphertextMod<P>) -> CiphertextMod<P> {
@@ -386,11 +430,13 @@ impl<P: PaillierParams> Mul<Bounded<P::Uint>> for &CiphertextMod<P> {
 
 #[cfg(test)]
 mod tests {
+    use core::marker::PhantomData;
+
     use rand_core::OsRng;
 
     use super::super::params::PaillierTest;
     use super::super::{PaillierParams, SecretKeyPaillier};
-    use super::{CiphertextMod, RandomizerMod};
+    use super::{Ciphertext, CiphertextMod, RandomizerMod};
 
     use crate::uint::{
         subtle::ConditionallyNegatable, HasWide, NonZero, RandomMod, Signed, UintLike,
@@ -446,6 +492,40 @@ mod tests {
         assert_eq!(ciphertext, ciphertext_back);
     }
 
+    #[test]
+    fn checked_conversion() {
+        let sk = SecretKeyPaillier::<PaillierTest>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+        let plaintext =
+            <PaillierTest as PaillierParams>::Uint::random_mod(&mut OsRng, &pk.modulus_nonzero());
+        let ciphertext = CiphertextMod::<PaillierTest>::new(&mut OsRng, pk, &plaintext);
+
+        // The modular form is serialized the same as the raw one
+        let config = bincode::config::standard();
+        assert_eq!(
+            bincode::serde::encode_to_vec(&ciphertext, config).unwrap(),
+            bincode::serde::encode_to_vec(ciphertext.retrieve(), config).unwrap()
+        );
+
+        let wire = ciphertext.retrieve();
+        assert_eq!(wire.to_mod_checked(pk), Some(ciphertext.clone()));
+
+        // `N^2 + 1` is a different raw value mapping to the same element as `1`,
+        // which the checked conversion must reject.
+        let one = <PaillierTest as PaillierParams>::WideUint::ONE;
+        let reduced = Ciphertext::<PaillierTest> {
+            ciphertext: one,
+            phantom: PhantomData,
+        };
+        let unreduced = Ciphertext::<PaillierTest> {
+            ciphertext: HasWide::square_wide(pk.modulus()).wrapping_add(&one),
+            phantom: PhantomData,
+        };
+        assert_eq!(unreduced.to_mod(pk), reduced.to_mod(pk));
+        assert!(reduced.to_mod_checked(pk).is_some());
+        assert!(unreduced.to_mod_checked(pk).is_none());
+    }
+
     #[test]
     fn signed_roundtrip() {
         let sk = SecretKeyPaillier::<PaillierTest>::random(&mut OsRng).to_precomputed();
@@ -5,10 +5,17 @@ use serde::{Deserialize, Serialize};
 use zeroize::ZeroizeOnDrop;
 
 use super::params::PaillierParams;
+use crate::tools::profile::profile_scope;
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+use crate::uint::Signed;
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
 use crate::uint::{
     subtle::{Choice, ConditionallySelectable},
-    Bounded, CheckedAdd, CheckedSub, HasWide, Integer, Invert, NonZero, PowBoundedExp, RandomMod,
-    RandomPrimeWithRng, Retrieve, Signed, UintLike, UintModLike,
+    CheckedAdd, PowBoundedExp, Retrieve,
+};
+use crate::uint::{
+    Bounded, CheckedSub, HasWide, Integer, Invert, NonZero, RandomMod, RandomPrimeWithRng,
+    UintLike, UintModLike,
 };
 
 #[derive(Clone, Serialize, Deserialize, ZeroizeOnDrop)]
@@ -27,12 +34,36 @@ impl<P: PaillierParams> Debug for SecretKeyPaillier<P> {
 
 impl<P: PaillierParams> SecretKeyPaillier<P> {
     pub fn random(rng: &mut impl CryptoRngCore) -> Self {
+        profile_scope!("SecretKeyPaillier::random");
         let p = P::HalfUint::generate_safe_prime_with_rng(rng, Some(P::PRIME_BITS));
         let q = P::HalfUint::generate_safe_prime_with_rng(rng, Some(P::PRIME_BITS));
 
         Self { p, q }
     }
 
+    /// Checks that the values [`Self::to_precomputed`] derives from the key exist:
+    /// `p` and `q` are distinct odd numbers of `P::PRIME_BITS` bits each,
+    /// `p` is invertible modulo `q`, and the modulus is invertible modulo the totient.
+    ///
+    /// Does not check that `p` and `q` are (safe) primes.
+    #[cfg(feature = "paillier")]
+    pub fn is_well_formed(&self) -> bool {
+        let is_odd_and_sized =
+            |x: &P::HalfUint| x.bit_vartime(0) && x.bits_vartime() == P::PRIME_BITS;
+        if !is_odd_and_sized(&self.p) || !is_odd_and_sized(&self.q) || self.p == self.q {
+            return false;
+        }
+        if !bool::from(self.p.inv_mod(&self.q).is_some()) {
+            return false;
+        }
+        let one = P::HalfUint::ONE;
+        let totient = self
+            .p
+            .wrapping_sub(&one)
+            .mul_wide(&self.q.wrapping_sub(&one));
+        bool::from(self.p.mul_wide(&self.q).inv_mod(&totient).is_some())
+    }
+
     pub fn to_precomputed(&self) -> SecretKeyPaillierPrecomputed<P> {
         // Euler's totient function of $p q$ - the number of positive integers up to $p q$
         // that are relatively prime to it.
@@ -43,52 +74,91 @@ impl<P: PaillierParams> SecretKeyPaillier<P> {
         let totient =
             Bounded::new(p_minus_one.mul_wide(&q_minus_one), P::MODULUS_BITS as u32).unwrap();
 
-        let precomputed_mod_p = P::HalfUintMod::new_precomputed(&NonZero::new(self.p).unwrap());
-        let precomputed_mod_q = P::HalfUintMod::new_precomputed(&NonZero::new(self.q).unwrap());
-
         let public_key = PublicKeyPaillier {
             modulus: self.p.mul_wide(&self.q),
         };
         let public_key = public_key.to_precomputed();
 
+        #[cfg(any(
+            feature = "key-gen",
+            feature = "aux-gen",
+            feature = "signing",
+            feature = "paillier"
+        ))]
         let inv_totient = totient
             .as_ref()
             .to_mod(public_key.precomputed_modulus())
             .invert()
             .unwrap();
 
-        let modulus: &P::Uint = public_key.modulus();
+        #[cfg(any(
+            feature = "key-gen",
+            feature = "aux-gen",
+            feature = "signing",
+            feature = "paillier"
+        ))]
         let inv_modulus = Bounded::new(
-            modulus.inv_mod(totient.as_ref()).unwrap(),
+            P::Uint::inv_mod(public_key.modulus(), totient.as_ref()).unwrap(),
             P::MODULUS_BITS as u32,
         )
         .unwrap();
 
-        let inv_p_mod_q = self.p.to_mod(&precomputed_mod_q).invert().unwrap();
-        let inv_q_mod_p = self.q.to_mod(&precomputed_mod_p).invert().unwrap();
-
-        // Calculate $u$ such that $u = 1 \mod p$ and $u = -1 \mod q$.
-        // Using step of Garner's algorithm:
-        // $u = q - 1 + q (2 q^{-1} - 1 \mod p)$
-        let t = (inv_q_mod_p + inv_q_mod_p - P::HalfUintMod::one(&precomputed_mod_p)).retrieve();
-        // Note that the wrapping add/sub won't overflow by construction.
-        let nonsquare_sampling_constant = t
-            .mul_wide(&self.q)
-            .wrapping_add(&self.q.into_wide())
-            .wrapping_sub(&P::Uint::ONE);
-        let nonsquare_sampling_constant = P::UintMod::new(
-            &nonsquare_sampling_constant,
-            &public_key.precomputed_modulus,
-        );
+        // The values used for taking square roots modulo `N` (only needed for `П^{mod}`).
+        #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
+        let (precomputed_mod_p, precomputed_mod_q, inv_p_mod_q, nonsquare_sampling_constant) = {
+            let precomputed_mod_p = P::HalfUintMod::new_precomputed(&NonZero::new(self.p).unwrap());
+            let precomputed_mod_q = P::HalfUintMod::new_precomputed(&NonZero::new(self.q).unwrap());
+
+            let inv_p_mod_q = self.p.to_mod(&precomputed_mod_q).invert().unwrap();
+            let inv_q_mod_p = self.q.to_mod(&precomputed_mod_p).invert().unwrap();
+
+            // Calculate $u$ such that $u = 1 \mod p$ and $u = -1 \mod q$.
+            // Using step of Garner's algorithm:
+            // $u = q - 1 + q (2 q^{-1} - 1 \mod p)$
+            let t =
+                (inv_q_mod_p + inv_q_mod_p - P::HalfUintMod::one(&precomputed_mod_p)).retrieve();
+            // Note that the wrapping add/sub won't overflow by construction.
+            let nonsquare_sampling_constant = t
+                .mul_wide(&self.q)
+                .wrapping_add(&self.q.into_wide())
+                .wrapping_sub(&P::Uint::ONE);
+            let nonsquare_sampling_constant = P::UintMod::new(
+                &nonsquare_sampling_constant,
+                &public_key.precomputed_modulus,
+            );
+            (
+                precomputed_mod_p,
+                precomputed_mod_q,
+                inv_p_mod_q,
+                nonsquare_sampling_constant,
+            )
+        };
 
         SecretKeyPaillierPrecomputed {
+            #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
             sk: self.clone(),
             totient,
+            #[cfg(any(
+                feature = "key-gen",
+                feature = "aux-gen",
+                feature = "signing",
+                feature = "paillier"
+            ))]
             inv_totient,
+            #[cfg(any(
+                feature = "key-gen",
+                feature = "aux-gen",
+                feature = "signing",
+                feature = "paillier"
+            ))]
             inv_modulus,
+            #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
             inv_p_mod_q,
+            #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
             nonsquare_sampling_constant,
+            #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
             precomputed_mod_p,
+            #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
             precomputed_mod_q,
             public_key,
         }
@@ -97,25 +167,49 @@ impl<P: PaillierParams> SecretKeyPaillier<P> {
 
 #[derive(Clone)]
 pub(crate) struct SecretKeyPaillierPrecomputed<P: PaillierParams> {
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     sk: SecretKeyPaillier<P>,
     totient: Bounded<P::Uint>,
     /// $\phi(N)^{-1} \mod N$
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "paillier"
+    ))]
     inv_totient: P::UintMod,
     /// $N^{-1} \mod \phi(N)$
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "paillier"
+    ))]
     inv_modulus: Bounded<P::Uint>,
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     inv_p_mod_q: P::HalfUintMod,
     // $u$ such that $u = 1 \mod p$ and $u = -1 \mod q$.
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     nonsquare_sampling_constant: P::UintMod,
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     precomputed_mod_p: <P::HalfUintMod as UintModLike>::Precomputed,
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     precomputed_mod_q: <P::HalfUintMod as UintModLike>::Precomputed,
     public_key: PublicKeyPaillierPrecomputed<P>,
 }
 
 impl<P: PaillierParams> SecretKeyPaillierPrecomputed<P> {
+    #[cfg(feature = "paillier")]
+    pub fn as_minimal(&self) -> &SecretKeyPaillier<P> {
+        &self.sk
+    }
+
+    #[cfg(any(feature = "key-gen", feature = "aux-gen"))]
     pub fn to_minimal(&self) -> SecretKeyPaillier<P> {
         self.sk.clone()
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen"))]
     pub fn primes(&self) -> (Signed<P::Uint>, Signed<P::Uint>) {
         // The primes are positive, but where this method is used Signed is needed,
         // so we return that for convenience.
@@ -126,6 +220,12 @@ impl<P: PaillierParams> SecretKeyPaillierPrecomputed<P> {
         )
     }
 
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "paillier"
+    ))]
     pub fn totient(&self) -> &Bounded<P::Uint> {
         // TODO (#77): must be wrapped in a Secret
         &self.totient
@@ -137,21 +237,35 @@ impl<P: PaillierParams> SecretKeyPaillierPrecomputed<P> {
         NonZero::new(*self.totient.as_ref()).unwrap()
     }
 
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "paillier"
+    ))]
     /// Returns $\phi(N)^{-1} \mod N$
     pub fn inv_totient(&self) -> &P::UintMod {
         // TODO (#77): must be wrapped in a Secret
         &self.inv_totient
     }
 
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "paillier"
+    ))]
     /// Returns $N^{-1} \mod \phi(N)$
     pub fn inv_modulus(&self) -> &Bounded<P::Uint> {
         &self.inv_modulus
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     fn precomputed_mod_p(&self) -> &<P::HalfUintMod as UintModLike>::Precomputed {
         &self.precomputed_mod_p
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     fn precomputed_mod_q(&self) -> &<P::HalfUintMod as UintModLike>::Precomputed {
         &self.precomputed_mod_q
     }
@@ -160,6 +274,7 @@ impl<P: PaillierParams> SecretKeyPaillierPrecomputed<P> {
         &self.public_key
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     pub fn rns_split(&self, elem: &P::Uint) -> (P::HalfUintMod, P::HalfUintMod) {
         // TODO (#77): zeroize intermediate values
 
@@ -175,6 +290,7 @@ impl<P: PaillierParams> SecretKeyPaillierPrecomputed<P> {
         (p_rem_mod, q_rem_mod)
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     fn sqrt_part(&self, x: &P::HalfUintMod, modulus: &P::HalfUint) -> Option<P::HalfUintMod> {
         // Both `p` and `q` are safe primes, so they're 3 mod 4.
         // This means that if square root exists, it must be of the form `+/- x^((modulus+1)/4)`.
@@ -191,6 +307,7 @@ impl<P: PaillierParams> SecretKeyPaillierPrecomputed<P> {
         }
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     pub fn sqrt(
         &self,
         rns: &(P::HalfUintMod, P::HalfUintMod),
@@ -206,6 +323,7 @@ impl<P: PaillierParams> SecretKeyPaillierPrecomputed<P> {
         }
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     pub fn rns_join(&self, rns: &(P::HalfUintMod, P::HalfUintMod)) -> P::Uint {
         // We have `a = x mod p`, `b = x mod q`; we want to find `x mod (pq)`.
         // One step of Garner's algorithm:
@@ -230,6 +348,7 @@ impl<P: PaillierParams> SecretKeyPaillierPrecomputed<P> {
         .unwrap()
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
     /// Returns a random $w \in [0, N)$ such that $w$ is not a square modulo $N$,
     /// where $N$ is the public key
     /// (or, equivalently, such that the Jacobi symbol $(w|N) = -1$).
@@ -276,12 +395,18 @@ impl<P: PaillierParams> PublicKeyPaillier<P> {
         // Note that this ensures that `self.modulus` is odd,
         // otherwise creating the Montgomery parameters fails.
         let precomputed_modulus = P::UintMod::new_precomputed(&NonZero::new(self.modulus).unwrap());
-        let precomputed_modulus_squared =
-            P::WideUintMod::new_precomputed(&NonZero::new(self.modulus.square_wide()).unwrap());
         PublicKeyPaillierPrecomputed {
             pk: self.clone(),
             precomputed_modulus,
-            precomputed_modulus_squared,
+            #[cfg(any(
+                feature = "key-gen",
+                feature = "aux-gen",
+                feature = "signing",
+                feature = "paillier"
+            ))]
+            precomputed_modulus_squared: P::WideUintMod::new_precomputed(
+                &NonZero::new(self.modulus.square_wide()).unwrap(),
+            ),
         }
     }
 }
@@ -290,10 +415,22 @@ impl<P: PaillierParams> PublicKeyPaillier<P> {
 pub(crate) struct PublicKeyPaillierPrecomputed<P: PaillierParams> {
     pk: PublicKeyPaillier<P>,
     precomputed_modulus: <P::UintMod as UintModLike>::Precomputed,
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "paillier"
+    ))]
     precomputed_modulus_squared: <P::WideUintMod as UintModLike>::Precomputed,
 }
 
 impl<P: PaillierParams> PublicKeyPaillierPrecomputed<P> {
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "paillier"
+    ))]
     pub fn as_minimal(&self) -> &PublicKeyPaillier<P> {
         &self.pk
     }
@@ -306,6 +443,12 @@ impl<P: PaillierParams> PublicKeyPaillierPrecomputed<P> {
         self.pk.modulus()
     }
 
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "paillier"
+    ))]
     pub fn modulus_bounded(&self) -> Bounded<P::Uint> {
         Bounded::new(*self.pk.modulus(), P::MODULUS_BITS as u32).unwrap()
     }
@@ -319,6 +462,12 @@ impl<P: PaillierParams> PublicKeyPaillierPrecomputed<P> {
         &self.precomputed_modulus
     }
 
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "paillier"
+    ))]
     /// Returns precomputed parameters for integers modulo N^2
     pub fn precomputed_modulus_squared(&self) -> &<P::WideUintMod as UintModLike>::Precomputed {
         &self.precomputed_modulus_squared
@@ -8,6 +8,7 @@ use crate::uint::{HasWide, UintLike, UintModLike};
 #[cfg(test)]
 use crate::uint::{U1024Mod, U2048Mod, U512Mod, U1024, U2048, U4096, U512};
 
+/// Parameters of the Paillier encryption scheme: the size of the primes and the integer types.
 pub trait PaillierParams: Debug + PartialEq + Eq + Clone + Send + Sync {
     /// The size of one of the pair of RSA primes.
     const PRIME_BITS: usize;
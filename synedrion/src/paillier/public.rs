@@ -0,0 +1,212 @@
+use core::fmt::Debug;
+
+use rand_core::CryptoRngCore;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::encryption::{Ciphertext, CiphertextMod, Randomizer};
+use super::keys::{
+    PublicKeyPaillier, PublicKeyPaillierPrecomputed, SecretKeyPaillier,
+    SecretKeyPaillierPrecomputed,
+};
+use super::params::PaillierParams;
+use crate::cggmp21::{ModProof, SchemeParams};
+use crate::uint::{Bounded, UintLike};
+
+/// A Paillier secret key (the factors `p` and `q` of the modulus).
+///
+/// Does not implement [`Serialize`] to prevent the key from being stored unencrypted;
+/// use [`expose_for_encryption`](`Self::expose_for_encryption`).
+/// Deserialization rejects the pairs of numbers that cannot form a key,
+/// but does not check that they are primes: that only holds for the keys
+/// created with [`random`](`Self::random`), and [`decrypt`](`Self::decrypt`)
+/// returns meaningless results for the keys with non-prime factors.
+#[derive(Clone)]
+pub struct SecretKey<P: PaillierParams>(SecretKeyPaillierPrecomputed<P>);
+
+impl<P: PaillierParams> Debug for SecretKey<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.write_str("[REDACTED ")?;
+        f.write_str(core::any::type_name::<Self>())?;
+        f.write_str("]")
+    }
+}
+
+impl<P: PaillierParams> SecretKey<P> {
+    /// Generates a key from two random safe primes of `P::PRIME_BITS` bits each.
+    ///
+    /// Note that finding safe primes of the production size can take a while.
+    pub fn random(rng: &mut impl CryptoRngCore) -> Self {
+        Self(SecretKeyPaillier::random(rng).to_precomputed())
+    }
+
+    /// Returns the corresponding public key.
+    pub fn public_key(&self) -> PublicKey<P> {
+        PublicKey(self.0.public_key().clone())
+    }
+
+    /// Decrypts a ciphertext created with the corresponding public key,
+    /// assuming that the plaintext is in range `[0, N)`.
+    ///
+    /// Returns `None` if the ciphertext is not reduced modulo `N^2`.
+    pub fn decrypt(&self, ciphertext: &EncryptedValue<P>) -> Option<P::Uint> {
+        let ciphertext = ciphertext.0.to_mod_checked(self.0.public_key())?;
+        Some(ciphertext.decrypt(&self.0))
+    }
+
+    /// Returns the serializable form of the key.
+    ///
+    /// The result should be encrypted before it leaves the memory.
+    pub fn expose_for_encryption(&self) -> impl Serialize + '_ {
+        self.0.as_minimal()
+    }
+}
+
+impl<'de, P: PaillierParams> Deserialize<'de> for SecretKey<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let sk = SecretKeyPaillier::<P>::deserialize(deserializer)?;
+        if !sk.is_well_formed() {
+            return Err(de::Error::custom("Invalid Paillier secret key"));
+        }
+        Ok(Self(sk.to_precomputed()))
+    }
+}
+
+/// A Paillier public key (the modulus `N`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey<P: PaillierParams>(PublicKeyPaillierPrecomputed<P>);
+
+impl<P: PaillierParams> PublicKey<P> {
+    fn from_minimal(pk: PublicKeyPaillier<P>) -> Option<Self> {
+        // A product of two primes of `P::PRIME_BITS` bits is odd,
+        // and has either `P::MODULUS_BITS` or one bit less.
+        let modulus = pk.modulus();
+        if !modulus.bit_vartime(0) || modulus.bits_vartime() < P::MODULUS_BITS - 1 {
+            return None;
+        }
+        Some(Self(pk.to_precomputed()))
+    }
+
+    /// Returns the modulus `N`.
+    pub fn modulus(&self) -> &P::Uint {
+        self.0.modulus()
+    }
+
+    /// Encrypts a plaintext with a random randomizer.
+    ///
+    /// A plaintext larger than `N` is effectively reduced modulo `N`.
+    pub fn encrypt(&self, rng: &mut impl CryptoRngCore, plaintext: &P::Uint) -> EncryptedValue<P> {
+        EncryptedValue(CiphertextMod::new(rng, &self.0, plaintext).retrieve())
+    }
+
+    /// Returns a ciphertext of the sum of the plaintexts of the given ciphertexts (modulo `N`).
+    ///
+    /// Returns `None` if any of the ciphertexts is not reduced modulo `N^2`.
+    pub fn add(
+        &self,
+        lhs: &EncryptedValue<P>,
+        rhs: &EncryptedValue<P>,
+    ) -> Option<EncryptedValue<P>> {
+        let lhs = lhs.0.to_mod_checked(&self.0)?;
+        let rhs = rhs.0.to_mod_checked(&self.0)?;
+        Some(EncryptedValue((lhs + rhs).retrieve()))
+    }
+
+    /// Returns a ciphertext of the product of the plaintext of the given ciphertext
+    /// and the given factor (modulo `N`).
+    ///
+    /// Returns `None` if the ciphertext is not reduced modulo `N^2`,
+    /// or if the factor does not fit in `P::MODULUS_BITS` bits.
+    pub fn mul(
+        &self,
+        ciphertext: &EncryptedValue<P>,
+        factor: &P::Uint,
+    ) -> Option<EncryptedValue<P>> {
+        let ciphertext = ciphertext.0.to_mod_checked(&self.0)?;
+        let factor = Bounded::new(*factor, P::MODULUS_BITS as u32)?;
+        Some(EncryptedValue((ciphertext * factor).retrieve()))
+    }
+
+    /// Returns a ciphertext of the same plaintext that cannot be linked to the given one.
+    ///
+    /// Returns `None` if the ciphertext is not reduced modulo `N^2`.
+    pub fn rerandomize(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        ciphertext: &EncryptedValue<P>,
+    ) -> Option<EncryptedValue<P>> {
+        let ciphertext = ciphertext.0.to_mod_checked(&self.0)?;
+        let randomizer = Randomizer::random(rng, &self.0);
+        Some(EncryptedValue(
+            ciphertext.mul_randomizer(&randomizer).retrieve(),
+        ))
+    }
+}
+
+impl<P: PaillierParams> Serialize for PublicKey<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.as_minimal().serialize(serializer)
+    }
+}
+
+impl<'de, P: PaillierParams> Deserialize<'de> for PublicKey<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pk = PublicKeyPaillier::<P>::deserialize(deserializer)?;
+        Self::from_minimal(pk).ok_or_else(|| de::Error::custom("Invalid Paillier modulus"))
+    }
+}
+
+/// A Paillier ciphertext.
+///
+/// Since it is not bound to a public key, the operations on it are methods of [`PublicKey`].
+#[derive(Debug, Clone)]
+pub struct EncryptedValue<P: PaillierParams>(Ciphertext<P>);
+
+impl<P: PaillierParams> Serialize for EncryptedValue<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, P: PaillierParams> Deserialize<'de> for EncryptedValue<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ciphertext::deserialize(deserializer).map(Self)
+    }
+}
+
+/// A ZK proof that the modulus of a public key is a Paillier-Blum modulus
+/// ($\Pi^{mod}$, Fig. 16 of the paper), that is, that the key was generated correctly.
+///
+/// The proof is bound to the auxiliary data `aux` (e.g. a session identifier and the prover's identity),
+/// which must be the same for the prover and the verifier.
+/// The number of the challenges is taken from `P::SECURITY_PARAMETER`.
+#[derive(Debug, Clone)]
+pub struct ModulusProof<P: SchemeParams>(ModProof<P>);
+
+impl<P: SchemeParams> ModulusProof<P> {
+    /// Creates a proof for the modulus of the given secret key.
+    pub fn new(rng: &mut impl CryptoRngCore, sk: &SecretKey<P::Paillier>, aux: &[u8]) -> Self {
+        Self(ModProof::new(rng, &sk.0, &aux))
+    }
+
+    /// Verifies the proof for the given public key.
+    pub fn verify(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        pk: &PublicKey<P::Paillier>,
+        aux: &[u8],
+    ) -> bool {
+        self.0.verify(rng, &pk.0, &aux)
+    }
+}
+
+impl<P: SchemeParams> Serialize for ModulusProof<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, P: SchemeParams> Deserialize<'de> for ModulusProof<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ModProof::deserialize(deserializer).map(Self)
+    }
+}
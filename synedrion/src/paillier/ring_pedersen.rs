@@ -1,10 +1,14 @@
 use core::ops::Mul;
 
 use rand_core::CryptoRngCore;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
 use super::{PaillierParams, PublicKeyPaillierPrecomputed, SecretKeyPaillierPrecomputed};
-use crate::uint::{Bounded, Retrieve, Signed, UintLike, UintModLike};
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+use crate::uint::Integer;
+use crate::uint::{Bounded, Retrieve, UintModLike};
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+use crate::uint::{Signed, UintLike};
 
 pub(crate) struct RPSecret<P: PaillierParams>(Bounded<P::Uint>);
 
@@ -25,6 +29,7 @@ impl<P: PaillierParams> AsRef<Bounded<P::Uint>> for RPSecret<P> {
 
 #[derive(Debug, Clone)]
 pub(crate) struct RPParamsMod<P: PaillierParams> {
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
     pub(crate) pk: PublicKeyPaillierPrecomputed<P>,
     /// The ring-Pedersen base.
     pub(crate) base: P::UintMod, // $t$
@@ -38,6 +43,7 @@ impl<P: PaillierParams> RPParamsMod<P> {
         Self::random_with_secret(rng, &secret, sk.public_key())
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
     pub fn public_key(&self) -> &PublicKeyPaillierPrecomputed<P> {
         &self.pk
     }
@@ -53,6 +59,7 @@ impl<P: PaillierParams> RPParamsMod<P> {
         let power = base.pow_bounded(&secret.0);
 
         Self {
+            #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
             pk: pk.clone(),
             base,
             power,
@@ -66,6 +73,7 @@ impl<P: PaillierParams> RPParamsMod<P> {
     // TODO (#81): swap randomizer and secret?
     // - this will match the order for Ciphertext,
     // - this will match the order in the paper
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
     pub fn commit(
         &self,
         secret: &Signed<P::Uint>,
@@ -75,6 +83,7 @@ impl<P: PaillierParams> RPParamsMod<P> {
         RPCommitmentMod(self.base.pow_signed_wide(randomizer) * self.power.pow_signed(secret))
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
     pub fn commit_wide(
         &self,
         secret: &Signed<P::WideUint>,
@@ -84,6 +93,7 @@ impl<P: PaillierParams> RPParamsMod<P> {
         RPCommitmentMod(self.base.pow_signed_wide(randomizer) * self.power.pow_signed_wide(secret))
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen"))]
     pub fn commit_xwide(
         &self,
         secret: &Bounded<P::Uint>,
@@ -95,6 +105,7 @@ impl<P: PaillierParams> RPParamsMod<P> {
         )
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen"))]
     pub fn commit_base_xwide(&self, randomizer: &Signed<P::ExtraWideUint>) -> RPCommitmentMod<P> {
         // $t^\rho mod N$ where $\rho$ is the randomizer.
         RPCommitmentMod(self.base.pow_signed_extra_wide(randomizer))
@@ -117,6 +128,7 @@ pub(crate) struct RPParams<P: PaillierParams> {
 }
 
 impl<P: PaillierParams> RPParams<P> {
+    #[cfg(feature = "signing")]
     pub fn to_mod(&self, pk: &PublicKeyPaillierPrecomputed<P>) -> RPParamsMod<P> {
         RPParamsMod {
             pk: pk.clone(),
@@ -124,6 +136,43 @@ impl<P: PaillierParams> RPParams<P> {
             power: self.power.to_mod(pk.precomputed_modulus()),
         }
     }
+
+    #[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+    /// Same as [`to_mod`](`Self::to_mod`), but returns `None` if the parameters
+    /// are not reduced modulo `N`.
+    pub fn to_mod_checked(&self, pk: &PublicKeyPaillierPrecomputed<P>) -> Option<RPParamsMod<P>> {
+        let base = self.base.to_mod_checked(pk.precomputed_modulus())?;
+        let power = self.power.to_mod_checked(pk.precomputed_modulus())?;
+        Some(RPParamsMod {
+            pk: pk.clone(),
+            base,
+            power,
+        })
+    }
+
+    #[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+    /// Returns `true` if both the base and the power are invertible modulo `N`
+    /// and are not equal to `±1`.
+    ///
+    /// A non-invertible value would reveal a factor of `N`, and `±1` generate a group of order
+    /// at most 2, making the commitments trivial; neither is excluded by the PRM proof alone.
+    pub fn is_nontrivial(&self, pk: &PublicKeyPaillierPrecomputed<P>) -> bool {
+        let modulus = pk.modulus();
+        let minus_one = modulus.wrapping_sub(&P::Uint::ONE);
+        [&self.base, &self.power].into_iter().all(|value| {
+            value != &P::Uint::ONE
+                && value != &minus_one
+                && bool::from(value.inv_mod(modulus).is_some())
+        })
+    }
+}
+
+// Serialized in the same way as the corresponding `RPParams`,
+// so that it can be hashed directly.
+impl<P: PaillierParams> Serialize for RPParamsMod<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.retrieve().serialize(serializer)
+    }
 }
 
 #[derive(PartialEq, Eq)]
@@ -134,6 +183,7 @@ impl<P: PaillierParams> RPCommitmentMod<P> {
         RPCommitment(self.0.retrieve())
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
     /// Raise to the power of `exponent`.
     ///
     /// Note: this is variable time in `exponent`.
@@ -142,6 +192,7 @@ impl<P: PaillierParams> RPCommitmentMod<P> {
         Self(self.0.pow_signed_vartime(exponent))
     }
 
+    #[cfg(any(feature = "key-gen", feature = "aux-gen"))]
     pub fn pow_signed_wide(&self, exponent: &Signed<P::WideUint>) -> Self {
         Self(self.0.pow_signed_wide(exponent))
     }
@@ -158,7 +209,65 @@ impl<'a, P: PaillierParams> Mul<&'a RPCommitmentMod<P>> for &'a RPCommitmentMod<
 pub(crate) struct RPCommitment<P: PaillierParams>(P::Uint);
 
 impl<P: PaillierParams> RPCommitment<P> {
-    pub fn to_mod(&self, pk: &PublicKeyPaillierPrecomputed<P>) -> RPCommitmentMod<P> {
-        RPCommitmentMod(self.0.to_mod(pk.precomputed_modulus()))
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+    /// Converts to the modulo representation, returning `None` if the commitment
+    /// is not reduced modulo `N`.
+    pub fn to_mod_checked(
+        &self,
+        pk: &PublicKeyPaillierPrecomputed<P>,
+    ) -> Option<RPCommitmentMod<P>> {
+        self.0
+            .to_mod_checked(pk.precomputed_modulus())
+            .map(RPCommitmentMod)
+    }
+}
+
+// Serialized in the same way as the corresponding `RPCommitment`,
+// so that it can be hashed directly.
+impl<P: PaillierParams> Serialize for RPCommitmentMod<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.retrieve().serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use super::super::params::PaillierTest;
+    use super::super::{PaillierParams, SecretKeyPaillier};
+    use super::{RPParams, RPParamsMod};
+
+    type Uint = <PaillierTest as PaillierParams>::Uint;
+
+    #[test]
+    fn nontrivial_params() {
+        let sk = SecretKeyPaillier::<PaillierTest>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+        let params = RPParamsMod::random(&mut OsRng, &sk).retrieve();
+        assert!(params.is_nontrivial(pk));
+
+        let modulus = *pk.modulus();
+        let trivial_values = [
+            Uint::ZERO,
+            Uint::ONE,
+            modulus.wrapping_sub(&Uint::ONE),
+            // Not invertible modulo `N`
+            sk.primes().0.abs(),
+        ];
+
+        for value in trivial_values {
+            let with_base = RPParams::<PaillierTest> {
+                base: value,
+                power: params.power,
+            };
+            assert!(!with_base.is_nontrivial(pk));
+
+            let with_power = RPParams::<PaillierTest> {
+                base: params.base,
+                power: value,
+            };
+            assert!(!with_power.is_nontrivial(pk));
+        }
     }
 }
@@ -0,0 +1,34 @@
+//! The items needed to run the protocols in a typical application,
+//! to be imported with `use synedrion::prelude::*;`.
+//!
+//! The paths of the items re-exported here are a part of the stable API.
+
+pub use crate::sessions::{
+    AbortPolicy, Artifact, Error, FinalizeOutcome, LocalError, MessageBundle, PreprocessedMessage,
+    ProcessedMessage, ProvableError, RemoteError, RemoteErrorEnum, RoundAccumulator, Session,
+    SessionId, SessionInitError,
+};
+pub use crate::{
+    make_handshake_session, AuxInfo, HandshakeConfig, KeyShare, PrehashedMessage, ProductionParams,
+    ProtocolResult, RecoverableSignature, SchemeParams, TestParams, ThresholdKeyShare,
+};
+
+#[cfg(feature = "aux-gen")]
+pub use crate::{make_aux_gen_session, AuxGenResult};
+
+#[cfg(feature = "key-gen")]
+pub use crate::{make_key_gen_session, make_key_init_session, KeyGenResult, KeyInitResult};
+
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+pub use crate::{make_key_refresh_session, KeyRefreshResult};
+
+#[cfg(feature = "signing")]
+pub use crate::{
+    make_batch_signing_session, make_interactive_signing_session, make_presigning_session,
+    BatchSigningResult, InteractiveSigningResult, PresigningResult,
+};
+
+#[cfg(feature = "key-resharing")]
+pub use crate::{
+    make_key_resharing_session, KeyResharingInputs, KeyResharingResult, NewHolder, OldHolder,
+};
@@ -1,14 +1,26 @@
 mod generic;
+mod latency;
+mod transcript;
+#[cfg(any(feature = "key-gen", feature = "signing"))]
 mod wrappers;
 
-#[cfg(any(test, feature = "bench-internals"))]
+#[cfg(any(test, feature = "bench-internals", feature = "spec"))]
 pub(crate) mod test_utils;
 
-pub use generic::ProtocolResult;
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+pub(crate) use generic::no_broadcast_messages;
+#[cfg(any(feature = "key-resharing", feature = "testing"))]
+pub(crate) use generic::FinalizationRequirement;
 pub(crate) use generic::{
-    no_broadcast_messages, no_direct_messages, FinalizableToNextRound, FinalizableToResult,
-    FinalizationRequirement, FinalizeError, FirstRound, InitError, Round, ToNextRound, ToResult,
-};
-pub(crate) use wrappers::{
-    wrap_finalize_error, CorrectnessProofWrapper, ProvableErrorWrapper, RoundWrapper, WrappedRound,
+    no_direct_messages, FinalizableToNextRound, FinalizableToResult, FinalizeError, FirstRound,
+    InitError, Protocol, Round, ToNextRound, ToResult,
 };
+pub use generic::{ProtocolId, ProtocolResult, RoundInfo};
+pub use latency::CpuProfile;
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+pub(crate) use transcript::BroadcastTranscript;
+pub use transcript::Check;
+#[cfg(any(feature = "key-gen", feature = "signing"))]
+pub(crate) use wrappers::{wrap_finalize_error, CorrectnessProofWrapper};
+#[cfg(feature = "signing")]
+pub(crate) use wrappers::{ProvableErrorWrapper, RoundWrapper, WrappedRound};
@@ -1,12 +1,24 @@
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::string::String;
 use core::fmt::Debug;
+use core::time::Duration;
 
 use displaydoc::Display;
 use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
 
+use super::latency::CpuProfile;
+use crate::sessions::EvidenceContext;
+use crate::tools::hashing::HashOutput;
+
 /// A round that sends out direct messages.
+///
+/// The canonical order of the parties is the ascending order of their identifiers `I`,
+/// which is the iteration order of the sets and maps passed to and returned by the round.
+/// Anything that depends on the order of the parties (positional vectors,
+/// hashes of collections, the order of message emission) must follow it,
+/// so that the outcome does not depend on the order in which the messages were created
+/// or received.
 pub(crate) trait Round<I: Ord + Clone> {
     type Type: FinalizableType;
     type Result: ProtocolResult;
@@ -15,6 +27,12 @@ pub(crate) trait Round<I: Ord + Clone> {
     const NEXT_ROUND_NUM: Option<u8>;
 
     fn other_ids(&self) -> &BTreeSet<I>;
+    #[cfg(any(
+        feature = "key-gen",
+        feature = "aux-gen",
+        feature = "signing",
+        feature = "key-resharing"
+    ))]
     fn my_id(&self) -> &I;
 
     /// The part of the message sent directly to nodes, and can be different for each node.
@@ -26,13 +44,19 @@ pub(crate) trait Round<I: Ord + Clone> {
     /// Whether all the nodes receiving the broadcast should make sure they got the same message.
     const REQUIRES_ECHO: bool = false;
 
+    /// Whether the round is followed by an echo round (see [`RoundInfo::echo`]).
+    ///
+    /// The last round never is, even if it sets `REQUIRES_ECHO`:
+    /// the sessions finish right after it.
+    const HAS_ECHO_ROUND: bool = Self::REQUIRES_ECHO && Self::NEXT_ROUND_NUM.is_some();
+
     /// The processed message from another node, to be collected to finalize the round.
     type Payload;
 
     /// Data created when creating a message, to be preserved until the finalization stage.
     type Artifact;
 
-    /// The indices of the parties that should receive the messages.
+    /// The indices of the parties that should receive the messages, in the canonical order.
     // Assuming these destinations are for both broadcast and direct messages;
     // the broadcasts are only separated to allow optimizations (create once, sign once)
     // and support echo-broadcasting.
@@ -60,7 +84,36 @@ pub(crate) trait Round<I: Ord + Clone> {
         rng: &mut impl CryptoRngCore,
     ) -> Option<Self::BroadcastMessage>;
 
+    /// Creates a broadcast message to be sent to the given party instead of the common one,
+    /// if this node equivocates in this round.
+    ///
+    /// Only overridden by the malicious rounds in tests.
+    #[cfg(feature = "testing")]
+    fn make_equivocating_broadcast(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _destination: &I,
+    ) -> Option<Self::BroadcastMessage> {
+        None
+    }
+
+    /// Performs the inexpensive checks of a message received from the party `from`
+    /// (e.g. of the hash commitments or the sizes of the values),
+    /// so that a malformed message can be rejected without waiting for the proof verification.
+    ///
+    /// By default, performs no checks.
+    fn pre_verify_message(
+        &self,
+        _from: &I,
+        _broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> Result<(), <Self::Result as ProtocolResult>::ProvableError> {
+        Ok(())
+    }
+
     /// Processes a direct messsage received from the party `from`.
+    ///
+    /// Only called for the messages that passed [`pre_verify_message`](`Self::pre_verify_message`).
     // Note that since we assume broadcast and direct messages have the same list of destinations,
     // if `BroadcastMessage` is not `()` there will be a serialized broadcast
     // in the received message, from which we can construct `broadcast_msg`.
@@ -72,6 +125,31 @@ pub(crate) trait Round<I: Ord + Clone> {
         direct_msg: Self::DirectMessage,
     ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError>;
 
+    /// Checks a message created by this node for the party `destination`
+    /// the same way the recipient would, as far as the public data allows it.
+    ///
+    /// Used by the strict mode of a session. By default, performs no checks,
+    /// so every round whose [`Self::verify_message`] checks the contents of a message
+    /// must implement it. The rounds keeping the default only accept the message as is
+    /// (e.g. hash commitments, or their openings which are checked in the following rounds).
+    fn verify_own_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _destination: &I,
+        _broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        true
+    }
+
+    /// The public data a third party needs, in addition to the signed messages,
+    /// to check a fault in a message of this round (see [`EvidenceContext`]).
+    ///
+    /// By default, no additional data is provided.
+    fn evidence_context(&self) -> EvidenceContext<I> {
+        EvidenceContext::None
+    }
+
     fn finalization_requirement() -> FinalizationRequirement {
         FinalizationRequirement::All
     }
@@ -79,6 +157,7 @@ pub(crate) trait Round<I: Ord + Clone> {
     fn can_finalize(&self, received: &BTreeSet<I>) -> bool {
         match Self::finalization_requirement() {
             FinalizationRequirement::All => self.other_ids().is_subset(received),
+            #[cfg(feature = "key-resharing")]
             FinalizationRequirement::Custom => panic!("`can_finalize` must be implemented"),
         }
     }
@@ -88,14 +167,244 @@ pub(crate) trait Round<I: Ord + Clone> {
             FinalizationRequirement::All => {
                 self.other_ids().difference(received).cloned().collect()
             }
+            #[cfg(feature = "key-resharing")]
             FinalizationRequirement::Custom => panic!("`missing_messages` must be implemented"),
         }
     }
 }
 
+/// Identifies the protocol a message belongs to.
+///
+/// Included in the signed message envelope, so that a message from one protocol
+/// cannot be passed off as a message from another one within the same session ID.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProtocolId {
+    /// The KeyInit protocol.
+    KeyInit,
+    /// The KeyRefresh protocol.
+    KeyRefresh,
+    /// The AuxGen protocol.
+    AuxGen,
+    /// The merged KeyInit and KeyRefresh protocol.
+    KeyGen,
+    /// The Presigning protocol.
+    Presigning,
+    /// The Signing protocol.
+    Signing,
+    /// The merged Presigning and Signing protocol.
+    InteractiveSigning,
+    /// The threshold key resharing protocol.
+    KeyResharing,
+    /// The preliminary agreement on the protocol configuration.
+    Handshake,
+    /// The Signing protocol for several messages at once.
+    BatchSigning,
+    /// The KeyInit protocol without the commitment round.
+    FastKeyInit,
+}
+
+/// The public shape of a protocol round.
+///
+/// The broadcasts are seen by every party of the session, including the observers
+/// (see [`ObserverSession`](`crate::sessions::ObserverSession`));
+/// the direct messages are only exchanged between the contributors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundInfo {
+    /// The round number.
+    pub round: u8,
+    /// Whether the contributors send a broadcast in this round.
+    pub broadcast: bool,
+    /// Whether the contributors send direct messages in this round.
+    pub direct: bool,
+    /// Whether the round is followed by an echo round,
+    /// in which every party re-sends the broadcasts it received.
+    pub echo: bool,
+}
+
+const KEY_INIT_ROUNDS: &[RoundInfo] = &[
+    RoundInfo {
+        round: 1,
+        broadcast: true,
+        direct: false,
+        echo: true,
+    },
+    RoundInfo {
+        round: 2,
+        broadcast: true,
+        direct: false,
+        echo: false,
+    },
+    RoundInfo {
+        round: 3,
+        broadcast: true,
+        direct: false,
+        echo: false,
+    },
+];
+
+// The same for KeyRefresh and AuxGen
+const KEY_REFRESH_ROUNDS: &[RoundInfo] = &[
+    RoundInfo {
+        round: 1,
+        broadcast: true,
+        direct: false,
+        echo: true,
+    },
+    RoundInfo {
+        round: 2,
+        broadcast: true,
+        direct: false,
+        echo: false,
+    },
+    RoundInfo {
+        round: 3,
+        broadcast: false,
+        direct: true,
+        echo: false,
+    },
+];
+
+const KEY_GEN_ROUNDS: &[RoundInfo] = &[
+    RoundInfo {
+        round: 1,
+        broadcast: true,
+        direct: false,
+        echo: true,
+    },
+    RoundInfo {
+        round: 2,
+        broadcast: true,
+        direct: false,
+        echo: true,
+    },
+    RoundInfo {
+        round: 3,
+        broadcast: true,
+        direct: true,
+        echo: false,
+    },
+];
+
+const PRESIGNING_ROUNDS: &[RoundInfo] = &[
+    RoundInfo {
+        round: 1,
+        broadcast: true,
+        direct: true,
+        echo: true,
+    },
+    RoundInfo {
+        round: 2,
+        broadcast: false,
+        direct: true,
+        echo: false,
+    },
+    RoundInfo {
+        round: 3,
+        broadcast: false,
+        direct: true,
+        echo: false,
+    },
+];
+
+const INTERACTIVE_SIGNING_ROUNDS: &[RoundInfo] = &[
+    RoundInfo {
+        round: 1,
+        broadcast: true,
+        direct: true,
+        echo: true,
+    },
+    RoundInfo {
+        round: 2,
+        broadcast: false,
+        direct: true,
+        echo: false,
+    },
+    RoundInfo {
+        round: 3,
+        broadcast: false,
+        direct: true,
+        echo: false,
+    },
+    RoundInfo {
+        round: 4,
+        broadcast: true,
+        direct: false,
+        echo: false,
+    },
+];
+
+// The same for Signing, Handshake and BatchSigning
+const SINGLE_BROADCAST_ROUNDS: &[RoundInfo] = &[RoundInfo {
+    round: 1,
+    broadcast: true,
+    direct: false,
+    echo: false,
+}];
+
+// The round sets `REQUIRES_ECHO`, but being the last one, it finishes the protocol right away
+// (see `Round::HAS_ECHO_ROUND`).
+const KEY_RESHARING_ROUNDS: &[RoundInfo] = &[RoundInfo {
+    round: 1,
+    broadcast: true,
+    direct: true,
+    echo: false,
+}];
+
+const FAST_KEY_INIT_ROUNDS: &[RoundInfo] = &[
+    RoundInfo {
+        round: 1,
+        broadcast: true,
+        direct: false,
+        echo: false,
+    },
+    RoundInfo {
+        round: 2,
+        broadcast: true,
+        direct: false,
+        echo: false,
+    },
+];
+
+impl ProtocolId {
+    /// Returns the rounds of the protocol, in order.
+    pub const fn rounds(&self) -> &'static [RoundInfo] {
+        match self {
+            Self::KeyInit => KEY_INIT_ROUNDS,
+            Self::KeyRefresh => KEY_REFRESH_ROUNDS,
+            Self::AuxGen => KEY_REFRESH_ROUNDS,
+            Self::KeyGen => KEY_GEN_ROUNDS,
+            Self::Presigning => PRESIGNING_ROUNDS,
+            Self::Signing => SINGLE_BROADCAST_ROUNDS,
+            Self::InteractiveSigning => INTERACTIVE_SIGNING_ROUNDS,
+            Self::KeyResharing => KEY_RESHARING_ROUNDS,
+            Self::Handshake => SINGLE_BROADCAST_ROUNDS,
+            Self::BatchSigning => SINGLE_BROADCAST_ROUNDS,
+            Self::FastKeyInit => FAST_KEY_INIT_ROUNDS,
+        }
+    }
+
+    /// Returns the number of message exchanges (including the echo rounds)
+    /// needed to complete the protocol.
+    ///
+    /// Each exchange takes at least one network round trip,
+    /// since a party has to receive the messages of all the others to start the next one.
+    pub const fn round_trip_count(&self) -> usize {
+        let rounds = self.rounds();
+        let mut count = 0;
+        let mut i = 0;
+        while i < rounds.len() {
+            count += if rounds[i].echo { 2 } else { 1 };
+            i += 1;
+        }
+        count
+    }
+}
+
 /// Typed outcomes of a protocol, specific for each protocol
 /// (in addition to non-specific errors common for all protocols).
 pub trait ProtocolResult: Debug {
+    /// The identifier of the protocol, attached to every message sent as a part of it.
+    const PROTOCOL_ID: ProtocolId;
     /// The result obtained on successful termination of the protocol.
     type Success;
     /// A collection of data which, in combination with the messages received,
@@ -107,6 +416,18 @@ pub trait ProtocolResult: Debug {
     /// That is, on errors where the culprit cannot be immediately identified,
     /// each node will have to provide the correctness proof for itself.
     type CorrectnessProof: Debug;
+
+    /// Returns the number of message exchanges needed to complete the protocol
+    /// (see [`ProtocolId::round_trip_count`]).
+    fn round_trip_count() -> usize {
+        Self::PROTOCOL_ID.round_trip_count()
+    }
+
+    /// Estimates the time needed to complete the protocol
+    /// (see [`ProtocolId::estimate_duration`]).
+    fn estimate_duration(rtt: Duration, cpu_profile: &CpuProfile) -> Option<Duration> {
+        Self::PROTOCOL_ID.estimate_duration(rtt, cpu_profile)
+    }
 }
 
 // This trait is used to fix the possible options for `Round::Type`.
@@ -125,6 +446,7 @@ impl FinalizableType for ToNextRound {}
 #[allow(clippy::enum_variant_names)]
 pub(crate) enum FinalizationRequirement {
     All,
+    #[cfg(feature = "key-resharing")]
     Custom,
 }
 
@@ -149,6 +471,9 @@ pub(crate) trait FinalizableToNextRound<I: Ord + Clone>:
     ) -> Result<Self::NextRound, FinalizeError<Self::Result>>;
 }
 
+// Only the signing protocols produce these errors,
+// but the enum cannot be empty since it is generic over the result.
+#[cfg_attr(not(feature = "signing"), allow(dead_code))]
 #[derive(Debug)]
 pub enum FinalizeError<Res: ProtocolResult> {
     Proof(Res::CorrectnessProof),
@@ -173,8 +498,39 @@ pub(crate) trait FirstRound<I: Ord + Clone>: Round<I> + Sized {
     ) -> Result<Self, InitError>;
 }
 
+/// A protocol as a whole, tying together the round it starts from and its outcomes.
+///
+/// Implemented by the [`ProtocolResult`] types, so that the result type is enough
+/// to start a protocol; the errors are the ones declared by the result
+/// (`ProvableError` and `CorrectnessProof`).
+pub(crate) trait Protocol<I: Ord + Clone>: ProtocolResult + Sized {
+    /// The round the protocol starts from.
+    type FirstRound: FirstRound<I> + Round<I, Result = Self>;
+
+    /// The fingerprint of the scheme parameters the protocol is instantiated with,
+    /// if it depends on them.
+    ///
+    /// It is attached to every message, so that the parties running the protocol
+    /// with different parameters fail in the first round with a dedicated error.
+    fn params_fingerprint() -> Option<HashOutput> {
+        None
+    }
+
+    /// Creates the first round of the protocol.
+    fn new_round(
+        rng: &mut impl CryptoRngCore,
+        shared_randomness: &[u8],
+        other_ids: BTreeSet<I>,
+        my_id: I,
+        inputs: <Self::FirstRound as FirstRound<I>>::Inputs,
+    ) -> Result<Self::FirstRound, InitError> {
+        Self::FirstRound::new(rng, shared_randomness, other_ids, my_id, inputs)
+    }
+}
+
 // These will be possible to do via trait specialization when it becomes stable.
 
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
 macro_rules! no_broadcast_messages {
     () => {
         fn make_broadcast_message(
@@ -186,6 +542,7 @@ macro_rules! no_broadcast_messages {
     };
 }
 
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
 pub(crate) use no_broadcast_messages;
 
 macro_rules! no_direct_messages {
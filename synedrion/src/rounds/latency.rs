@@ -0,0 +1,91 @@
+//! A simple model of the time needed to complete a protocol.
+//!
+//! The time is estimated as the number of message exchanges times the network round trip time,
+//! plus the computation time of one party. It ignores the bandwidth
+//! (the echo rounds send `O(n^2)` data per party) and the waiting for the slowest party,
+//! so it is a lower bound on what is observed in practice.
+
+use alloc::collections::BTreeMap;
+use core::time::Duration;
+
+use super::ProtocolId;
+
+/// The computation time one party spends in each of the protocols on a given machine,
+/// for a given number of parties.
+///
+/// It can be filled with the measurements of the benchmarks of this crate.
+/// Note that they run all the parties in a single thread,
+/// so the measured times have to be divided by the number of parties.
+#[derive(Debug, Clone, Default)]
+pub struct CpuProfile {
+    times: BTreeMap<ProtocolId, Duration>,
+}
+
+impl CpuProfile {
+    /// Creates an empty profile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the computation time of one party in the given protocol.
+    pub fn set_time(&mut self, protocol: ProtocolId, time: Duration) {
+        self.times.insert(protocol, time);
+    }
+
+    /// Returns the computation time of one party in the given protocol, if it is known.
+    pub fn time(&self, protocol: ProtocolId) -> Option<Duration> {
+        self.times.get(&protocol).copied()
+    }
+}
+
+impl ProtocolId {
+    /// Estimates the time needed to complete the protocol,
+    /// given the network round trip time between the parties,
+    /// or returns `None` if the computation time of the protocol is not in the profile.
+    ///
+    /// This can be used, for example, to decide whether a signature can be created
+    /// with the interactive signing within the required time,
+    /// or whether a pool of presignatures has to be kept,
+    /// so that only the signing itself is done on request.
+    pub fn estimate_duration(&self, rtt: Duration, cpu_profile: &CpuProfile) -> Option<Duration> {
+        let round_trips = u32::try_from(self.round_trip_count()).ok()?;
+        let time = cpu_profile.time(*self)?;
+        Some(rtt.saturating_mul(round_trips).saturating_add(time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::CpuProfile;
+    use crate::rounds::ProtocolId;
+
+    #[test]
+    fn estimate_duration() {
+        let mut profile = CpuProfile::new();
+        profile.set_time(ProtocolId::Presigning, Duration::from_millis(500));
+        profile.set_time(ProtocolId::Signing, Duration::from_millis(1));
+        profile.set_time(ProtocolId::InteractiveSigning, Duration::from_millis(501));
+
+        let rtt = Duration::from_millis(100);
+        assert_eq!(
+            ProtocolId::Signing.estimate_duration(rtt, &profile),
+            Some(Duration::from_millis(101))
+        );
+        assert_eq!(
+            ProtocolId::InteractiveSigning.estimate_duration(rtt, &profile),
+            Some(Duration::from_millis(1001))
+        );
+        assert_eq!(ProtocolId::KeyGen.estimate_duration(rtt, &profile), None);
+    }
+
+    #[test]
+    fn round_trip_count() {
+        assert_eq!(ProtocolId::KeyInit.round_trip_count(), 4);
+        assert_eq!(ProtocolId::KeyGen.round_trip_count(), 5);
+        assert_eq!(ProtocolId::InteractiveSigning.round_trip_count(), 5);
+        // The last round is never followed by an echo round
+        assert_eq!(ProtocolId::KeyResharing.round_trip_count(), 1);
+    }
+}
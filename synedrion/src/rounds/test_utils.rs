@@ -6,15 +6,37 @@ use core::fmt::Debug;
 
 use displaydoc::Display;
 use rand_core::CryptoRngCore;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+#[cfg(test)]
+use super::generic::RoundInfo;
 use super::generic::{FinalizableToNextRound, FinalizableToResult, ProtocolResult, Round};
 use super::FinalizeError;
+#[cfg(test)]
+use crate::sessions::is_null_type;
 
 /// A simple identity type for tests.
-#[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize)]
+#[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct Id(pub(crate) u32);
 
+/// Returns the public shape of the round `R` as the sessions run it,
+/// to be compared with [`ProtocolId::rounds`](`super::ProtocolId::rounds`).
+#[cfg(test)]
+pub(crate) fn round_info<I, R>() -> RoundInfo
+where
+    I: Ord + Clone,
+    R: Round<I>,
+    R::BroadcastMessage: 'static,
+    R::DirectMessage: 'static,
+{
+    RoundInfo {
+        round: R::ROUND_NUM,
+        broadcast: !is_null_type::<R::BroadcastMessage>(),
+        direct: !is_null_type::<R::DirectMessage>(),
+        echo: R::HAS_ECHO_ROUND,
+    }
+}
+
 #[derive(Debug, Display)]
 pub(crate) enum StepError<I: Debug> {
     /// Error when finalizing the round (missing messages).
@@ -37,6 +59,23 @@ pub(crate) fn step_round<I, R>(
     rng: &mut impl CryptoRngCore,
     rounds: BTreeMap<I, R>,
 ) -> Result<BTreeMap<I, AssembledRound<I, R>>, StepError<I>>
+where
+    R: Round<I>,
+    <R as Round<I>>::BroadcastMessage: Clone,
+    I: Debug + Clone + Ord + PartialEq,
+{
+    step_round_with_delivery_order(rng, rounds, false)
+}
+
+/// Same as [`step_round`], but the messages are delivered in the reverse order
+/// if `reverse_delivery` is `true`.
+///
+/// Used to check that the protocol results do not depend on the delivery order.
+pub(crate) fn step_round_with_delivery_order<I, R>(
+    rng: &mut impl CryptoRngCore,
+    rounds: BTreeMap<I, R>,
+    reverse_delivery: bool,
+) -> Result<BTreeMap<I, AssembledRound<I, R>>, StepError<I>>
 where
     R: Round<I>,
     <R as Round<I>>::BroadcastMessage: Clone,
@@ -85,6 +124,10 @@ where
 
     // Deliver messages
 
+    if reverse_delivery {
+        messages.reverse();
+    }
+
     let mut payload_accums = rounds
         .keys()
         .cloned()
@@ -92,6 +135,9 @@ where
         .collect::<BTreeMap<_, _>>();
     for (to, from, (broadcast, direct)) in messages.into_iter() {
         let round = &rounds[&to];
+        round
+            .pre_verify_message(&from, &broadcast, &direct)
+            .map_err(|err| StepError::Receive(format!("{:?}", err)))?;
         let payload = round
             .verify_message(rng, &from, broadcast, direct)
             .map_err(|err| StepError::Receive(format!("{:?}", err)))?;
@@ -0,0 +1,44 @@
+//! Verification of a protocol run by a party that did not participate in it.
+
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+use alloc::collections::BTreeMap;
+
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+use serde::Deserialize;
+
+/// A check performed on a party's broadcast when verifying a transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Check {
+    /// The message is signed by the party and belongs to the session, protocol and round.
+    Envelope,
+    /// The message was created with the same scheme parameters.
+    Params,
+    /// The message payload can be deserialized.
+    Format,
+    /// The published data matches the hash commitment from the previous round.
+    Commitment,
+    /// The Schnorr proof of knowledge (`П^{sch}`).
+    SchProof,
+    /// The Paillier modulus and the ring-Pedersen parameters are well-formed.
+    AuxParams,
+    /// The proof of the ring-Pedersen parameters (`П^{prm}`).
+    PrmProof,
+    /// The proof of the Paillier modulus being a Blum modulus (`П^{mod}`).
+    ModProof,
+    /// The proof of the Paillier modulus having no small factors (`П^{fac}`).
+    FacProof,
+    /// The partial signature is consistent with the presigning data.
+    PartialSignature,
+}
+
+/// The broadcasts of a protocol run, as available to a third party.
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+pub(crate) trait BroadcastTranscript<I> {
+    /// Returns the authenticated broadcasts of the given round that could be deserialized.
+    ///
+    /// The parties whose broadcasts are missing or invalid are not included.
+    fn broadcasts<M: for<'de> Deserialize<'de>>(&mut self, round: u8) -> BTreeMap<I, M>;
+
+    /// Records the outcome of a check of `party`'s broadcast in the given round.
+    fn record(&mut self, party: &I, round: u8, check: Check, passed: bool);
+}
@@ -5,6 +5,7 @@ use rand_core::CryptoRngCore;
 use super::generic::{
     FinalizableType, FinalizationRequirement, FinalizeError, ProtocolResult, Round,
 };
+use crate::sessions::EvidenceContext;
 
 pub(crate) trait ProvableErrorWrapper<Res: ProtocolResult>: ProtocolResult {
     fn wrap_error(error: Res::ProvableError) -> Self::ProvableError;
@@ -73,6 +74,17 @@ impl<I: Ord + Clone, T: RoundWrapper<I> + WrappedRound> Round<I> for T {
         self.inner_round().make_direct_message(rng, destination)
     }
 
+    fn pre_verify_message(
+        &self,
+        from: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> Result<(), <Self::Result as ProtocolResult>::ProvableError> {
+        self.inner_round()
+            .pre_verify_message(from, broadcast_msg, direct_msg)
+            .map_err(Self::Result::wrap_error)
+    }
+
     fn verify_message(
         &self,
         rng: &mut impl CryptoRngCore,
@@ -84,6 +96,22 @@ impl<I: Ord + Clone, T: RoundWrapper<I> + WrappedRound> Round<I> for T {
             .verify_message(rng, from, broadcast_msg, direct_msg)
             .map_err(Self::Result::wrap_error)
     }
+
+    fn verify_own_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        self.inner_round()
+            .verify_own_message(rng, destination, broadcast_msg, direct_msg)
+    }
+
+    fn evidence_context(&self) -> EvidenceContext<I> {
+        self.inner_round().evidence_context()
+    }
+
     fn finalization_requirement() -> FinalizationRequirement {
         T::InnerRound::finalization_requirement()
     }
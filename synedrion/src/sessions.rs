@@ -1,16 +1,51 @@
 //! Mutable wrappers around the protocols for easier handling.
 
+mod clock;
 mod echo;
+#[cfg(feature = "ed25519")]
+mod ed25519;
 mod error;
+mod evidence;
+mod light_verifier;
 mod message_bundle;
+mod observer;
+#[cfg(feature = "encryption")]
+mod pairwise;
+#[cfg(feature = "relay")]
+mod relay;
+mod reputation;
+#[cfg(feature = "encryption")]
+mod sealed_box;
 mod session;
 mod signed_message;
 mod type_erased;
 
+#[cfg(feature = "std")]
+pub use clock::SystemClock;
+pub use clock::{Clock, ManualClock};
 pub use echo::EchoError;
-pub use error::{Error, LocalError, ProvableError, RemoteError, RemoteErrorEnum};
+#[cfg(feature = "ed25519")]
+pub use ed25519::{Ed25519Signer, Ed25519Verifier};
+pub use error::{Error, LocalError, ProvableError, RemoteError, RemoteErrorEnum, SessionInitError};
+pub use evidence::{verify_evidence, Claim, EvidenceContext, EvidencePacket, Verdict};
+pub use light_verifier::{
+    CheckOutcome, LightVerifier, Transcript, TranscriptError, TranscriptReport,
+};
 pub use message_bundle::MessageBundle;
+pub use observer::{ObserverError, ObserverOutcome, ObserverSession};
+#[cfg(feature = "relay")]
+pub use relay::{RelayAggregator, RelayMessage, RelayedBatch, RelayedMessage};
+pub use reputation::{ExcludedParties, FaultCounts, ReputationLedger};
+#[cfg(feature = "encryption")]
+pub use sealed_box::{AgreementKey, OpeningKey, SealingKey, SharedSecret};
 pub use session::{
-    Artifact, FinalizeOutcome, PreprocessedMessage, ProcessedMessage, RoundAccumulator, Session,
+    AbortPolicy, Artifact, FinalizeOutcome, PreprocessedMessage, ProcessedMessage,
+    RoundAccumulator, Session, MAX_PARTIES,
+};
+pub use signed_message::{BeaconValue, SessionId};
+#[cfg(feature = "spec")]
+pub(crate) use signed_message::{
+    MESSAGE_HASH_TAG, SESSION_ID_HASH_TAG, SESSION_ID_WITH_BEACON_HASH_TAG,
 };
-pub use signed_message::SessionId;
+#[cfg(any(test, feature = "spec"))]
+pub(crate) use type_erased::is_null_type;
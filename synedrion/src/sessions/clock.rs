@@ -0,0 +1,80 @@
+//! Time sources for the round deadlines and the session TTL
+//! (see [`Session::set_deadlines`](`super::Session::set_deadlines`)).
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+/// A source of the current time.
+///
+/// Only the differences between the returned values matter,
+/// so the time can be counted from an arbitrary fixed point.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> Duration;
+}
+
+/// A clock driven externally, for the environments without a system clock and for tests.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    millis: AtomicU64,
+}
+
+impl ManualClock {
+    /// Creates a clock showing the given time.
+    pub fn new(now: Duration) -> Self {
+        let clock = Self::default();
+        clock.set(now);
+        clock
+    }
+
+    /// Sets the current time (with millisecond precision).
+    pub fn set(&self, now: Duration) {
+        self.millis.store(to_millis(now), Ordering::Relaxed);
+    }
+
+    /// Moves the current time forward (with millisecond precision).
+    pub fn advance(&self, by: Duration) {
+        self.millis.fetch_add(to_millis(by), Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_millis(self.millis.load(Ordering::Relaxed))
+    }
+}
+
+fn to_millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+/// The system clock, counting the time from the UNIX epoch.
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::{Clock, ManualClock};
+
+    #[test]
+    fn manual_clock() {
+        let clock = ManualClock::new(Duration::from_secs(10));
+        assert_eq!(clock.now(), Duration::from_secs(10));
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(clock.now(), Duration::from_millis(11500));
+        clock.set(Duration::from_secs(1));
+        assert_eq!(clock.now(), Duration::from_secs(1));
+    }
+}
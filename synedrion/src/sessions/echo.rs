@@ -4,6 +4,7 @@ use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
 use serde::{Deserialize, Serialize};
+use signature::hazmat::PrehashVerifier;
 
 use super::error::LocalError;
 use super::signed_message::{SignedMessage, VerifiedMessage};
@@ -11,6 +12,8 @@ use super::type_erased::{deserialize_message, serialize_message};
 
 #[derive(Clone)]
 pub(crate) struct EchoRound<I, Sig> {
+    /// All the parties that sent broadcasts in the previous round (possibly including this one).
+    contributors: BTreeSet<I>,
     destinations: BTreeSet<I>,
     broadcasts: BTreeMap<I, VerifiedMessage<Sig>>,
 }
@@ -39,9 +42,17 @@ where
     I: Clone + Ord + PartialEq + Serialize + for<'de> Deserialize<'de>,
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
 {
-    pub fn new(broadcasts: BTreeMap<I, VerifiedMessage<Sig>>) -> Self {
-        let destinations = broadcasts.keys().cloned().collect();
+    /// Creates an echo round with the broadcasts received from the other contributors.
+    ///
+    /// The echo is exchanged with the other contributors and the observers.
+    pub fn new(
+        contributors: BTreeSet<I>,
+        broadcasts: BTreeMap<I, VerifiedMessage<Sig>>,
+        observers: &BTreeSet<I>,
+    ) -> Self {
+        let destinations = broadcasts.keys().chain(observers.iter()).cloned().collect();
         Self {
+            contributors,
             broadcasts,
             destinations,
         }
@@ -75,7 +86,9 @@ where
         // TODO (#68): check that there are no repeating indices, and the indices are in range.
         let bc_map = message.broadcasts.into_iter().collect::<BTreeMap<_, _>>();
 
-        if bc_map.len() != self.broadcasts.len() {
+        // A contributor does not echo its own broadcast, an observer echoes all of them.
+        let expected_len = self.contributors.len() - usize::from(self.contributors.contains(from));
+        if bc_map.len() != expected_len {
             return Err(EchoError::UnexpectedNumberOfBroadcasts);
         }
 
@@ -96,6 +109,39 @@ where
         Ok(())
     }
 
+    /// Looks for a broadcast in the echo message from `from` that is correctly signed
+    /// by its original sender, but differs from the one we received from them.
+    ///
+    /// Returns the sender, the broadcast we received, and the echoed broadcast.
+    pub fn find_equivocation(
+        &self,
+        from: &I,
+        payload: &[u8],
+    ) -> Option<(I, SignedMessage<Sig>, SignedMessage<Sig>)>
+    where
+        I: PrehashVerifier<Sig>,
+    {
+        let message: Message<I, Sig> = deserialize_message(payload).ok()?;
+        message.broadcasts.into_iter().find_map(|(id, echoed_bc)| {
+            if &id == from {
+                return None;
+            }
+            let broadcast = self.broadcasts.get(&id)?;
+            let echoed_bc = echoed_bc.verify(&id).ok()?;
+            if broadcast
+                .as_unverified()
+                .is_same_as(echoed_bc.as_unverified())
+            {
+                return None;
+            }
+            Some((
+                id,
+                broadcast.clone().into_unverified(),
+                echoed_bc.into_unverified(),
+            ))
+        })
+    }
+
     pub fn missing_messages(&self, accum: &EchoAccum<I>) -> BTreeSet<I> {
         self.expecting_messages_from()
             .difference(&accum.received_messages)
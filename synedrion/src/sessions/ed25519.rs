@@ -0,0 +1,143 @@
+//! Ed25519 identity keys for signing the message envelopes.
+//!
+//! The sessions are generic over the envelope signature scheme,
+//! requiring the signer to implement [`RandomizedPrehashSigner`]
+//! and the verifier to implement [`PrehashVerifier`], [`Ord`] and [`Serialize`].
+//! ECDSA keys (e.g. from `k256` or `p256`) can be used directly;
+//! Ed25519 has no prehashed signing API and its keys are not ordered,
+//! so the wrappers here are needed.
+//!
+//! For the encryption of the messages, the keys are converted to X25519 keys
+//! (see [`SealingKey`](super::SealingKey) and [`OpeningKey`](super::OpeningKey)).
+
+use core::cmp::Ordering;
+
+#[cfg(feature = "encryption")]
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+use signature::{
+    hazmat::{PrehashVerifier, RandomizedPrehashSigner},
+    Keypair,
+};
+#[cfg(feature = "encryption")]
+use zeroize::Zeroizing;
+
+#[cfg(feature = "encryption")]
+use super::sealed_box::{AgreementKey, OpeningKey, SealingKey, SharedSecret};
+
+/// Returns the X25519 public key for the given secret.
+#[cfg(feature = "relay")]
+pub(crate) fn x25519_base(secret: &[u8; 32]) -> [u8; 32] {
+    MontgomeryPoint::mul_base_clamped(*secret).to_bytes()
+}
+
+/// Returns the X25519 shared secret for the given secret and public key,
+/// or `None` if it is degenerate (that is, the public key has a small order).
+#[cfg(feature = "encryption")]
+pub(crate) fn x25519(secret: &[u8; 32], public_key: &[u8; 32]) -> Option<SharedSecret> {
+    let shared_secret =
+        Zeroizing::new(MontgomeryPoint(*public_key).mul_clamped(*secret).to_bytes());
+    if shared_secret.iter().all(|byte| *byte == 0) {
+        return None;
+    }
+    Some(shared_secret)
+}
+
+/// An Ed25519 signing key used as a party's identity in a session.
+///
+/// The envelopes are signed with PureEdDSA, the signed message being
+/// the 32-byte hash of the envelope (the one other schemes sign as a prehash).
+#[derive(Debug, Clone)]
+pub struct Ed25519Signer(SigningKey);
+
+impl Ed25519Signer {
+    /// Creates a signer from an Ed25519 signing key.
+    pub fn new(key: SigningKey) -> Self {
+        Self(key)
+    }
+
+    /// Returns the wrapped signing key.
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.0
+    }
+}
+
+impl RandomizedPrehashSigner<Signature> for Ed25519Signer {
+    fn sign_prehash_with_rng(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        prehash: &[u8],
+    ) -> Result<Signature, signature::Error> {
+        // Ed25519 signatures are deterministic
+        self.0.try_sign(prehash)
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl OpeningKey for Ed25519Signer {
+    fn diffie_hellman(&self, public_key: &AgreementKey) -> Option<SharedSecret> {
+        match public_key {
+            AgreementKey::X25519(key) => x25519(&Zeroizing::new(self.0.to_scalar_bytes()), key),
+            AgreementKey::Secp256k1(_) => None,
+        }
+    }
+}
+
+impl Keypair for Ed25519Signer {
+    type VerifyingKey = Ed25519Verifier;
+
+    fn verifying_key(&self) -> Self::VerifyingKey {
+        Ed25519Verifier(self.0.verifying_key())
+    }
+}
+
+/// An Ed25519 verifying key identifying a party in a session.
+///
+/// The keys are ordered by their byte representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ed25519Verifier(VerifyingKey);
+
+impl Ed25519Verifier {
+    /// Creates a verifier from an Ed25519 verifying key.
+    pub fn new(key: VerifyingKey) -> Self {
+        Self(key)
+    }
+
+    /// Returns the wrapped verifying key.
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.0
+    }
+}
+
+impl PartialOrd for Ed25519Verifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ed25519Verifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.as_bytes().cmp(other.0.as_bytes())
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl SealingKey for Ed25519Verifier {
+    fn sealing_key(&self) -> AgreementKey {
+        AgreementKey::X25519(self.0.to_montgomery().to_bytes())
+    }
+}
+
+impl PrehashVerifier<Signature> for Ed25519Verifier {
+    fn verify_prehash(
+        &self,
+        prehash: &[u8],
+        signature: &Signature,
+    ) -> Result<(), signature::Error> {
+        // The strict verification rejects the malleable signatures and small order keys.
+        self.0.verify_strict(prehash, signature)
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::string::String;
 
 use displaydoc::Display;
@@ -32,6 +33,43 @@ pub enum Error<Res: ProtocolResult, Verifier> {
     /// This error may be eventually provable if there are some external guarantees
     /// provided by the communication channel.
     Remote(RemoteError<Verifier>),
+    /// Faults of several other parties collected during a round
+    /// when the session uses [`AbortPolicy::CollectAll`](`super::AbortPolicy::CollectAll`).
+    ///
+    /// Each value is either [`Error::Remote`] or [`Error::Provable`].
+    Faults(BTreeMap<Verifier, Error<Res, Verifier>>),
+    /// The round or session deadline has passed before the round could be finalized
+    /// (see [`Session::set_deadlines`](`super::Session::set_deadlines`)).
+    Timeout {
+        /// The parties whose messages for this round have not been received.
+        missing: BTreeSet<Verifier>,
+    },
+}
+
+/// An error when creating a session.
+#[derive(Clone, Debug, Display)]
+#[ignore_extra_doc_attributes]
+pub enum SessionInitError {
+    /// The number of parties ({0}) is greater than the supported maximum.
+    ///
+    /// See [`MAX_PARTIES`](`super::MAX_PARTIES`).
+    TooManyParties(usize),
+    /// The presigning data of the item {0} has expired.
+    ///
+    /// See [`PresigningData::set_timestamp`](`crate::PresigningData::set_timestamp`).
+    ExpiredPresigning(usize),
+    /// The presigning data of the item {0} is the same as the one of a previous item.
+    ///
+    /// Signing two messages with the same presigning data reveals the secret key.
+    RepeatedPresigning(usize),
+    /// {0}
+    Local(LocalError),
+}
+
+impl From<LocalError> for SessionInitError {
+    fn from(error: LocalError) -> Self {
+        Self::Local(error)
+    }
 }
 
 /// An error on this party's side.
@@ -51,9 +89,17 @@ pub struct RemoteError<Verifier> {
 
 /// Types of unprovable faults of another party.
 #[derive(Clone, Debug, Display)]
+#[ignore_extra_doc_attributes]
 pub enum RemoteErrorEnum {
     /// Session ID does not match the one provided to the local session constructor.
     UnexpectedSessionId,
+    /// The message belongs to a different protocol than the one running in this session.
+    UnexpectedProtocol,
+    /// The sender runs the protocol with different scheme parameters.
+    ///
+    /// The parameters are a part of every signed message,
+    /// so all the parties detect the disagreement on the first message of the session.
+    MismatchedParams,
     /// A message is intended for an unexpected round (not the current one or the next one).
     OutOfOrderMessage,
     /// A message from this party has already been received.
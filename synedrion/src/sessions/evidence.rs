@@ -0,0 +1,965 @@
+//! Evidence of the faults of other parties that can be checked by a third party.
+
+use alloc::boxed::Box;
+#[cfg(any(feature = "key-gen", feature = "signing"))]
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use displaydoc::Display;
+use serde::{Deserialize, Serialize};
+use signature::hazmat::PrehashVerifier;
+
+use super::error::LocalError;
+use super::signed_message::{MessageType, SessionId, SignedMessage, VerifiedMessage};
+#[cfg(any(feature = "key-gen", feature = "signing"))]
+use super::type_erased::deserialize_message;
+#[cfg(feature = "key-gen")]
+use crate::cggmp21::key_init;
+#[cfg(feature = "signing")]
+use crate::cggmp21::signing;
+use crate::cggmp21::{params_fingerprint, PresigningPublic, SchemeParams, VerifierBundle};
+use crate::constructors::PrehashedMessage;
+#[cfg(feature = "signing")]
+use crate::curve::Point;
+#[cfg(any(feature = "key-gen", feature = "signing"))]
+use crate::rounds::BroadcastTranscript;
+use crate::rounds::{Check, ProtocolId};
+
+/// The fault an [`EvidencePacket`] accuses a party of.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Claim {
+    /// The party signed two different broadcasts for the same round
+    /// (that is, sent different broadcasts to different parties).
+    ///
+    /// Only broadcasts can prove an equivocation, since the direct and the echo messages
+    /// legitimately differ between the recipients.
+    Equivocation,
+    /// The party sent a message that was rejected as a provable fault.
+    ///
+    /// Some of the faults can be checked by a third party with the public data alone
+    /// (see [`verify_evidence`]); for the others, a third party can only confirm
+    /// that the messages are authentic.
+    InvalidMessage {
+        /// The description of the fault.
+        description: String,
+    },
+}
+
+/// The public data of a session needed, in addition to the signed messages,
+/// to check a claim of an [`EvidencePacket`].
+///
+/// The data is provided by the accusing party, and is not signed by the accused one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "Verifier: Serialize"))]
+#[serde(bound(deserialize = "Verifier: Ord + Deserialize<'de>"))]
+pub enum EvidenceContext<Verifier> {
+    /// No additional data.
+    None,
+    /// The data of the Signing and InteractiveSigning protocols.
+    Signing {
+        /// The public part of the presigning data of the parties.
+        presigning: PresigningPublic<Verifier>,
+        /// The message being signed.
+        prehashed_message: PrehashedMessage,
+    },
+}
+
+/// Signed messages of a party supporting a claim of its fault,
+/// produced by [`Session::make_evidence`](`super::Session::make_evidence`).
+///
+/// Note that the packet may contain direct messages from the accused party
+/// with secret data intended for the accusing party.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "Sig: Serialize, Verifier: Serialize"))]
+#[serde(bound(deserialize = "Sig: Deserialize<'de>, Verifier: Ord + Deserialize<'de>"))]
+pub struct EvidencePacket<Sig, Verifier> {
+    session_id: SessionId,
+    protocol: ProtocolId,
+    accused: Verifier,
+    claim: Claim,
+    messages: Vec<SignedMessage<Sig>>,
+    context: EvidenceContext<Verifier>,
+}
+
+impl<Sig, Verifier> EvidencePacket<Sig, Verifier> {
+    pub(crate) fn new(
+        session_id: SessionId,
+        protocol: ProtocolId,
+        accused: Verifier,
+        claim: Claim,
+        messages: Vec<SignedMessage<Sig>>,
+        context: EvidenceContext<Verifier>,
+    ) -> Self {
+        Self {
+            session_id,
+            protocol,
+            accused,
+            claim,
+            messages,
+            context,
+        }
+    }
+
+    /// The session the messages belong to.
+    pub fn session_id(&self) -> &SessionId {
+        &self.session_id
+    }
+
+    /// The protocol the messages belong to.
+    pub fn protocol(&self) -> ProtocolId {
+        self.protocol
+    }
+
+    /// The accused party.
+    pub fn accused(&self) -> &Verifier {
+        &self.accused
+    }
+
+    /// The fault the party is accused of.
+    pub fn claim(&self) -> &Claim {
+        &self.claim
+    }
+
+    /// The public data of the session the claim is checked with.
+    pub fn context(&self) -> &EvidenceContext<Verifier> {
+        &self.context
+    }
+}
+
+impl<Sig, Verifier> EvidencePacket<Sig, Verifier>
+where
+    Sig: Serialize + for<'de> Deserialize<'de>,
+    Verifier: Serialize + for<'de> Deserialize<'de> + Ord,
+{
+    /// Serializes the packet.
+    pub fn to_bytes(&self) -> Result<Box<[u8]>, LocalError> {
+        bincode::serde::encode_to_vec(self, bincode::config::standard())
+            .map(|bytes| bytes.into())
+            .map_err(|err| LocalError(format!("Failed to serialize the evidence: {err:?}")))
+    }
+
+    /// Deserializes a packet created by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LocalError> {
+        let (packet, read) = bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+            .map_err(|err| LocalError(format!("Failed to deserialize the evidence: {err:?}")))?;
+        if read != bytes.len() {
+            return Err(LocalError(
+                "Trailing bytes after the serialized evidence".into(),
+            ));
+        }
+        Ok(packet)
+    }
+}
+
+/// The outcome of [`verify_evidence`].
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum Verdict {
+    /// The messages prove the claim.
+    Proven,
+    /// The messages are authentic, but the claim cannot be checked without the protocol state.
+    Authentic,
+    /// The packet does not support the claim: {0}.
+    Rejected(String),
+}
+
+/// Checks an evidence packet against the public data of the parties.
+///
+/// Can be used by a party that did not participate in the session.
+///
+/// Besides an equivocation, the following faults are proven or rejected:
+/// - in KeyInit, a broadcast that does not match the hash commitment of the party;
+/// - in Signing and InteractiveSigning, an invalid partial signature.
+///   The presigning data from the [`EvidenceContext`] is checked to be consistent
+///   with the verifying key of `bundle`, but the shares of the individual parties in it,
+///   and the signed message, can only be confirmed by comparing them with the data
+///   held by the other parties (it is the same for all the parties).
+///
+/// A correctly signed broadcast of these rounds that cannot be deserialized is a proven fault too.
+/// For the other faults the verdict is [`Verdict::Authentic`] at best.
+pub fn verify_evidence<P, Sig, Verifier>(
+    packet: &EvidencePacket<Sig, Verifier>,
+    bundle: &VerifierBundle<P, Verifier>,
+) -> Verdict
+where
+    P: SchemeParams,
+    Sig: Clone,
+    Verifier: PrehashVerifier<Sig> + Ord + Clone + Serialize,
+{
+    // Only the handshake is independent of the scheme parameters.
+    let params = match packet.protocol {
+        ProtocolId::Handshake => None,
+        _ => Some(params_fingerprint::<P>()),
+    };
+    if packet
+        .messages
+        .iter()
+        .any(|message| message.params() != params)
+    {
+        return Verdict::Rejected("A message was created with different scheme parameters".into());
+    }
+
+    let messages = match verify_messages(packet, &bundle.all_parties()) {
+        Ok(messages) => messages,
+        Err(verdict) => return verdict,
+    };
+    match check_claim(packet, &messages) {
+        Verdict::Authentic => check_invalid_message(packet, &messages, bundle),
+        verdict => verdict,
+    }
+}
+
+/// Checks an evidence packet accusing one of the given parties.
+pub(crate) fn check_evidence<Sig, Verifier>(
+    packet: &EvidencePacket<Sig, Verifier>,
+    parties: &BTreeSet<Verifier>,
+) -> Verdict
+where
+    Sig: Clone,
+    Verifier: PrehashVerifier<Sig> + Ord + Clone,
+{
+    match verify_messages(packet, parties) {
+        Ok(messages) => check_claim(packet, &messages),
+        Err(verdict) => verdict,
+    }
+}
+
+/// Verifies the signatures of the messages of an evidence packet accusing one of the given parties.
+fn verify_messages<Sig, Verifier>(
+    packet: &EvidencePacket<Sig, Verifier>,
+    parties: &BTreeSet<Verifier>,
+) -> Result<Vec<VerifiedMessage<Sig>>, Verdict>
+where
+    Sig: Clone,
+    Verifier: PrehashVerifier<Sig> + Ord + Clone,
+{
+    if !parties.contains(&packet.accused) {
+        return Err(Verdict::Rejected(
+            "The accused party is not one of the parties".into(),
+        ));
+    }
+
+    if packet.messages.is_empty() {
+        return Err(Verdict::Rejected("No messages".into()));
+    }
+
+    let mut messages = Vec::with_capacity(packet.messages.len());
+    for message in packet.messages.iter() {
+        if message.session_id() != &packet.session_id || message.protocol() != packet.protocol {
+            return Err(Verdict::Rejected(
+                "A message belongs to a different session".into(),
+            ));
+        }
+        match message.clone().verify(&packet.accused) {
+            Ok(message) => messages.push(message),
+            Err(err) => {
+                return Err(Verdict::Rejected(format!(
+                    "Invalid message signature: {err}"
+                )))
+            }
+        }
+    }
+    Ok(messages)
+}
+
+/// Checks the claim of a packet with the verified messages,
+/// as far as it is possible without the public data of the parties.
+fn check_claim<Sig, Verifier>(
+    packet: &EvidencePacket<Sig, Verifier>,
+    messages: &[VerifiedMessage<Sig>],
+) -> Verdict {
+    match &packet.claim {
+        Claim::Equivocation => {
+            let (first, second) = match messages {
+                [first, second] => (first.as_unverified(), second.as_unverified()),
+                _ => return Verdict::Rejected("Equivocation requires exactly two messages".into()),
+            };
+            if first.message_type() != MessageType::Broadcast
+                || second.message_type() != MessageType::Broadcast
+            {
+                return Verdict::Rejected("Only broadcasts can prove an equivocation".into());
+            }
+            if first.round() != second.round() {
+                return Verdict::Rejected("The messages are from different rounds".into());
+            }
+            if first.is_same_as(second) {
+                return Verdict::Rejected("The messages are the same".into());
+            }
+            Verdict::Proven
+        }
+        Claim::InvalidMessage { .. } => Verdict::Authentic,
+    }
+}
+
+/// Re-runs the publicly checkable verifications of the broadcasts of the accused party
+/// for a claim of an invalid message.
+#[cfg_attr(
+    not(any(feature = "key-gen", feature = "signing")),
+    allow(unused_variables, unused_mut)
+)]
+fn check_invalid_message<P, Sig, Verifier>(
+    packet: &EvidencePacket<Sig, Verifier>,
+    messages: &[VerifiedMessage<Sig>],
+    bundle: &VerifierBundle<P, Verifier>,
+) -> Verdict
+where
+    P: SchemeParams,
+    Verifier: Ord + Clone + Serialize,
+{
+    // The fault was found in the message of the latest round.
+    let fault_round = messages
+        .iter()
+        .map(|message| message.as_unverified().round())
+        .max();
+    let mut checks = EvidenceChecks::new(&packet.accused, messages);
+
+    // The round in which the claim can be checked, and the check that covers it.
+    let checkable: Option<(u8, Check)> = match (packet.protocol, &packet.context) {
+        #[cfg(feature = "key-gen")]
+        (ProtocolId::KeyInit, _) => {
+            // The broadcasts of Round 2 are checked against the commitments of Round 1.
+            if checks.broadcast(2).is_some() && checks.broadcast(1).is_none() {
+                return Verdict::Rejected("The broadcast of Round 1 is missing".into());
+            }
+            key_init::verify_transcript::<P, Verifier>(
+                packet.session_id.as_ref(),
+                &bundle.all_parties(),
+                &mut checks,
+            );
+            Some((2, Check::Commitment))
+        }
+        #[cfg(feature = "signing")]
+        (
+            ProtocolId::Signing | ProtocolId::InteractiveSigning,
+            EvidenceContext::Signing {
+                presigning,
+                prehashed_message,
+            },
+        ) => {
+            let verifying_key = match bundle.verifying_key() {
+                Some(verifying_key) => verifying_key,
+                None => return Verdict::Rejected("The bundle has no verifying key".into()),
+            };
+            if !presigning.shares.contains_key(&packet.accused)
+                || !presigning.is_consistent_with(&Point::from_verifying_key(&verifying_key))
+            {
+                return Verdict::Rejected(
+                    "The presigning data does not match the verifying key".into(),
+                );
+            }
+            // The partial signatures are sent in Round 4 of InteractiveSigning,
+            // after the three rounds of Presigning.
+            let round = match packet.protocol {
+                ProtocolId::Signing => 1,
+                _ => 4,
+            };
+            signing::verify_transcript(
+                round,
+                presigning,
+                &verifying_key,
+                prehashed_message,
+                &mut checks,
+            );
+            Some((round, Check::PartialSignature))
+        }
+        _ => None,
+    };
+
+    if checks
+        .outcomes
+        .iter()
+        .any(|(_round, _check, passed)| !passed)
+    {
+        return Verdict::Proven;
+    }
+    match checkable {
+        Some((round, check))
+            if fault_round == Some(round) && checks.outcomes.contains(&(round, check, true)) =>
+        {
+            Verdict::Rejected("The message passes the checks of the round".into())
+        }
+        _ => Verdict::Authentic,
+    }
+}
+
+/// The checks of the broadcasts of the accused party in an evidence packet,
+/// performed the same way a [`LightVerifier`](`super::LightVerifier`) checks a transcript.
+#[cfg_attr(not(any(feature = "key-gen", feature = "signing")), allow(dead_code))]
+struct EvidenceChecks<'a, Sig, Verifier> {
+    accused: &'a Verifier,
+    messages: &'a [VerifiedMessage<Sig>],
+    outcomes: Vec<(u8, Check, bool)>,
+}
+
+impl<'a, Sig, Verifier> EvidenceChecks<'a, Sig, Verifier> {
+    fn new(accused: &'a Verifier, messages: &'a [VerifiedMessage<Sig>]) -> Self {
+        Self {
+            accused,
+            messages,
+            outcomes: Vec::new(),
+        }
+    }
+
+    #[cfg(any(feature = "key-gen", feature = "signing"))]
+    fn broadcast(&self, round: u8) -> Option<&'a VerifiedMessage<Sig>> {
+        self.messages.iter().find(|message| {
+            let message = message.as_unverified();
+            message.round() == round && message.message_type() == MessageType::Broadcast
+        })
+    }
+}
+
+#[cfg(any(feature = "key-gen", feature = "signing"))]
+impl<Sig, Verifier: Ord + Clone> BroadcastTranscript<Verifier>
+    for EvidenceChecks<'_, Sig, Verifier>
+{
+    fn broadcasts<M: for<'de> Deserialize<'de>>(&mut self, round: u8) -> BTreeMap<Verifier, M> {
+        let mut messages = BTreeMap::new();
+        if let Some(broadcast) = self.broadcast(round) {
+            let message = deserialize_message::<M>(broadcast.payload());
+            self.outcomes.push((round, Check::Format, message.is_ok()));
+            if let Ok(message) = message {
+                messages.insert(self.accused.clone(), message);
+            }
+        }
+        messages
+    }
+
+    fn record(&mut self, party: &Verifier, round: u8, check: Check, passed: bool) {
+        if party == self.accused {
+            self.outcomes.push((round, check, passed));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+
+    use super::{verify_evidence, Claim, EvidenceContext, EvidencePacket, Verdict};
+    use crate::cggmp21::{params_fingerprint, AuxInfo, KeyShare, TestParams, VerifierBundle};
+    use crate::rounds::ProtocolId;
+    use crate::sessions::signed_message::{MessageType, SessionId, SignedMessage, VerifiedMessage};
+
+    fn make_bundle(ids: &BTreeSet<VerifyingKey>) -> VerifierBundle<TestParams, VerifyingKey> {
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, ids, None);
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, ids);
+        let id = ids.first().unwrap();
+        VerifierBundle::new(&key_shares[id], &aux_infos[id]).unwrap()
+    }
+
+    fn sign(
+        signer: &SigningKey,
+        session_id: &SessionId,
+        round: u8,
+        payload: &[u8],
+    ) -> SignedMessage<Signature> {
+        sign_with_type(signer, session_id, round, MessageType::Broadcast, payload)
+    }
+
+    fn sign_with_type(
+        signer: &SigningKey,
+        session_id: &SessionId,
+        round: u8,
+        message_type: MessageType,
+        payload: &[u8],
+    ) -> SignedMessage<Signature> {
+        sign_for_protocol(
+            signer,
+            session_id,
+            ProtocolId::KeyInit,
+            round,
+            message_type,
+            payload,
+        )
+    }
+
+    fn sign_for_protocol(
+        signer: &SigningKey,
+        session_id: &SessionId,
+        protocol: ProtocolId,
+        round: u8,
+        message_type: MessageType,
+        payload: &[u8],
+    ) -> SignedMessage<Signature> {
+        VerifiedMessage::new(
+            &mut OsRng,
+            signer,
+            session_id,
+            protocol,
+            Some(params_fingerprint::<TestParams>()),
+            round,
+            message_type,
+            payload,
+        )
+        .unwrap()
+        .into_unverified()
+    }
+
+    fn make_packet(
+        session_id: SessionId,
+        accused: &SigningKey,
+        claim: Claim,
+        messages: Vec<SignedMessage<Signature>>,
+    ) -> EvidencePacket<Signature, VerifyingKey> {
+        EvidencePacket::new(
+            session_id,
+            ProtocolId::KeyInit,
+            *accused.verifying_key(),
+            claim,
+            messages,
+            EvidenceContext::None,
+        )
+    }
+
+    #[test]
+    fn equivocation() {
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let bundle = make_bundle(&ids);
+        let session_id = SessionId::from_seed(b"abcde");
+        let accused = &signers[0];
+
+        let first = sign(accused, &session_id, 1, b"first");
+        let second = sign(accused, &session_id, 1, b"second");
+
+        let packet = make_packet(
+            session_id,
+            accused,
+            Claim::Equivocation,
+            vec![first.clone(), second.clone()],
+        );
+        assert_eq!(verify_evidence(&packet, &bundle), Verdict::Proven);
+
+        // The packet survives the serialization
+        let packet: EvidencePacket<Signature, VerifyingKey> =
+            EvidencePacket::from_bytes(&packet.to_bytes().unwrap()).unwrap();
+        assert_eq!(verify_evidence(&packet, &bundle), Verdict::Proven);
+
+        // The same message signed twice is not an equivocation
+        let same = sign(accused, &session_id, 1, b"first");
+        let packet = make_packet(
+            session_id,
+            accused,
+            Claim::Equivocation,
+            vec![first.clone(), same],
+        );
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+
+        // Messages from different rounds
+        let other_round = sign(accused, &session_id, 2, b"second");
+        let packet = make_packet(
+            session_id,
+            accused,
+            Claim::Equivocation,
+            vec![first.clone(), other_round],
+        );
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+
+        // Direct messages differ between the recipients, so they prove nothing
+        let packet = make_packet(
+            session_id,
+            accused,
+            Claim::Equivocation,
+            vec![
+                sign_with_type(accused, &session_id, 1, MessageType::Direct, b"to 1"),
+                sign_with_type(accused, &session_id, 1, MessageType::Direct, b"to 2"),
+            ],
+        );
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+
+        // Same for the echo messages
+        let packet = make_packet(
+            session_id,
+            accused,
+            Claim::Equivocation,
+            vec![
+                sign_with_type(accused, &session_id, 1, MessageType::Echo, b"to 1"),
+                sign_with_type(accused, &session_id, 1, MessageType::Echo, b"to 2"),
+            ],
+        );
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+
+        // A message signed by someone else
+        let forged = sign(&signers[1], &session_id, 1, b"second");
+        let packet = make_packet(
+            session_id,
+            accused,
+            Claim::Equivocation,
+            vec![first.clone(), forged],
+        );
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+
+        // A party that is not in the bundle
+        let outsider = SigningKey::random(&mut OsRng);
+        let packet = make_packet(
+            session_id,
+            &outsider,
+            Claim::Equivocation,
+            vec![
+                sign(&outsider, &session_id, 1, b"first"),
+                sign(&outsider, &session_id, 1, b"second"),
+            ],
+        );
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+    }
+
+    #[cfg(feature = "key-gen")]
+    #[test]
+    fn evidence_from_session() {
+        use crate::make_key_init_session;
+        use crate::sessions::message_bundle::{MessageBundle, MessageBundleEnum};
+
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let session_id = SessionId::from_seed(b"abcde");
+        let receiver = make_key_init_session::<TestParams, Signature, _, _>(
+            &mut OsRng,
+            session_id,
+            signers[1].clone(),
+            &ids,
+        )
+        .unwrap();
+
+        // A correctly signed broadcast that cannot be deserialized
+        let message = MessageBundle::try_from(MessageBundleEnum::Broadcast(sign(
+            &signers[0],
+            &session_id,
+            1,
+            b"garbage",
+        )))
+        .unwrap();
+
+        let mut accum = receiver.make_accumulator();
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, signers[0].verifying_key(), message)
+            .unwrap()
+            .unwrap();
+        let error = receiver
+            .process_message(&mut OsRng, preprocessed.clone())
+            .err()
+            .unwrap();
+
+        let packet = receiver.make_evidence(&preprocessed, &error).unwrap();
+        assert_eq!(packet.accused(), signers[0].verifying_key());
+        assert!(matches!(packet.claim(), Claim::InvalidMessage { .. }));
+        assert_eq!(
+            verify_evidence(&packet, &make_bundle(&ids)),
+            Verdict::Proven
+        );
+    }
+
+    #[test]
+    fn invalid_message() {
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let bundle = make_bundle(&ids);
+        let session_id = SessionId::from_seed(b"abcde");
+        let accused = &signers[0];
+
+        let claim = Claim::InvalidMessage {
+            description: "Invalid proof".into(),
+        };
+        // The direct messages cannot be checked by a third party
+        let message = sign_with_type(accused, &session_id, 1, MessageType::Direct, b"payload");
+
+        let packet = make_packet(session_id, accused, claim.clone(), vec![message.clone()]);
+        assert_eq!(verify_evidence(&packet, &bundle), Verdict::Authentic);
+
+        // A message from another session
+        let packet = make_packet(
+            SessionId::from_seed(b"fghij"),
+            accused,
+            claim.clone(),
+            vec![message],
+        );
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+    }
+
+    #[test]
+    fn mismatched_params() {
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let bundle = make_bundle(&ids);
+        let session_id = SessionId::from_seed(b"abcde");
+        let accused = &signers[0];
+
+        // An equivocation with the messages created for other scheme parameters
+        let messages = [b"first".as_slice(), b"second".as_slice()]
+            .into_iter()
+            .map(|payload| {
+                VerifiedMessage::new(
+                    &mut OsRng,
+                    accused,
+                    &session_id,
+                    ProtocolId::KeyInit,
+                    None,
+                    1,
+                    MessageType::Broadcast,
+                    payload,
+                )
+                .unwrap()
+                .into_unverified()
+            })
+            .collect();
+        let packet = make_packet(session_id, accused, Claim::Equivocation, messages);
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+    }
+
+    #[cfg(feature = "key-gen")]
+    #[test]
+    fn key_init_commitment() {
+        use alloc::collections::BTreeMap;
+
+        use crate::cggmp21::key_init::Round1;
+        use crate::rounds::{
+            test_utils::{step_next_round, step_round, Without},
+            FirstRound, Round,
+        };
+        use crate::sessions::type_erased::serialize_message;
+
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let bundle = make_bundle(&ids);
+        let session_id = SessionId::from_seed(b"abcde");
+        let accused = &signers[0];
+        let accused_id = *accused.verifying_key();
+
+        // The broadcasts of the accused party in the first two rounds
+        let make_broadcasts = || {
+            let r1 = ids
+                .iter()
+                .map(|id| {
+                    let round = Round1::<TestParams, VerifyingKey>::new(
+                        &mut OsRng,
+                        session_id.as_ref(),
+                        ids.clone().without(id),
+                        *id,
+                        (),
+                    )
+                    .unwrap();
+                    (*id, round)
+                })
+                .collect::<BTreeMap<_, _>>();
+            let r1_message = r1[&accused_id].make_broadcast_message(&mut OsRng).unwrap();
+            let r2 = step_next_round(&mut OsRng, step_round(&mut OsRng, r1).unwrap()).unwrap();
+            let r2_message = r2[&accused_id].make_broadcast_message(&mut OsRng).unwrap();
+            (
+                sign(
+                    accused,
+                    &session_id,
+                    1,
+                    &serialize_message(&r1_message).unwrap(),
+                ),
+                sign(
+                    accused,
+                    &session_id,
+                    2,
+                    &serialize_message(&r2_message).unwrap(),
+                ),
+            )
+        };
+        let (r1_message, r2_message) = make_broadcasts();
+        let (_, other_r2_message) = make_broadcasts();
+
+        let claim = Claim::InvalidMessage {
+            description: "Hash mismatch".into(),
+        };
+
+        // The broadcast of Round 2 does not match the commitment from Round 1
+        let packet = make_packet(
+            session_id,
+            accused,
+            claim.clone(),
+            vec![r1_message.clone(), other_r2_message],
+        );
+        assert_eq!(verify_evidence(&packet, &bundle), Verdict::Proven);
+
+        // The correct broadcast
+        let packet = make_packet(
+            session_id,
+            accused,
+            claim.clone(),
+            vec![r1_message, r2_message.clone()],
+        );
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+
+        // The commitment is missing
+        let packet = make_packet(session_id, accused, claim, vec![r2_message]);
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn partial_signature() {
+        use crate::cggmp21::signing::{Inputs, Round1};
+        use crate::cggmp21::PresigningData;
+        use crate::curve::Scalar;
+        use crate::rounds::{test_utils::Without, FirstRound, Round};
+        use crate::sessions::type_erased::serialize_message;
+
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let session_id = SessionId::from_seed(b"abcde");
+        let accused = &signers[0];
+        let accused_id = *accused.verifying_key();
+
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+        let presigning_datas = PresigningData::new_centralized(&mut OsRng, &key_shares, &aux_infos);
+        let bundle =
+            VerifierBundle::new(&key_shares[&accused_id], &aux_infos[&accused_id]).unwrap();
+
+        // The partial signature of the accused party, as a signed Signing message
+        let make_message = |message: Scalar| {
+            let round = Round1::<TestParams, VerifyingKey>::new(
+                &mut OsRng,
+                session_id.as_ref(),
+                ids.clone().without(&accused_id),
+                accused_id,
+                Inputs {
+                    presigning: presigning_datas[&accused_id].clone(),
+                    message,
+                    key_share: key_shares[&accused_id].clone(),
+                    aux_info: aux_infos[&accused_id].clone(),
+                },
+            )
+            .unwrap();
+            let partial_signature = round.make_broadcast_message(&mut OsRng).unwrap();
+            sign_for_protocol(
+                accused,
+                &session_id,
+                ProtocolId::Signing,
+                1,
+                MessageType::Broadcast,
+                &serialize_message(&partial_signature).unwrap(),
+            )
+        };
+
+        let message = Scalar::random(&mut OsRng);
+        let context = EvidenceContext::Signing {
+            presigning: presigning_datas[&accused_id].public().clone(),
+            prehashed_message: message.to_bytes().into(),
+        };
+        let make_packet = |messages, context| {
+            EvidencePacket::new(
+                session_id,
+                ProtocolId::Signing,
+                accused_id,
+                Claim::InvalidMessage {
+                    description: "Invalid partial signature".into(),
+                },
+                messages,
+                context,
+            )
+        };
+
+        // A partial signature of a different message
+        let packet = make_packet(
+            vec![make_message(Scalar::random(&mut OsRng))],
+            context.clone(),
+        );
+        assert_eq!(verify_evidence(&packet, &bundle), Verdict::Proven);
+
+        // The packet survives the serialization
+        let packet: EvidencePacket<Signature, VerifyingKey> =
+            EvidencePacket::from_bytes(&packet.to_bytes().unwrap()).unwrap();
+        assert_eq!(verify_evidence(&packet, &bundle), Verdict::Proven);
+
+        // The correct partial signature
+        let valid = make_message(message);
+        let packet = make_packet(vec![valid.clone()], context);
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+
+        // Presigning data that does not match the verifying key
+        let other_key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let other_presigning_datas =
+            PresigningData::new_centralized(&mut OsRng, &other_key_shares, &aux_infos);
+        let context = EvidenceContext::Signing {
+            presigning: other_presigning_datas[&accused_id].public().clone(),
+            prehashed_message: message.to_bytes().into(),
+        };
+        let packet = make_packet(vec![valid.clone()], context);
+        assert!(matches!(
+            verify_evidence(&packet, &bundle),
+            Verdict::Rejected(_)
+        ));
+
+        // Without the presigning data, the claim cannot be checked
+        let packet = make_packet(vec![valid], EvidenceContext::None);
+        assert_eq!(verify_evidence(&packet, &bundle), Verdict::Authentic);
+    }
+}
@@ -0,0 +1,413 @@
+//! Verification of the public part of a session by a party that did not participate in it
+//! (e.g. a watchtower service or an auditor).
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use displaydoc::Display;
+#[cfg(any(feature = "key-gen", feature = "signing"))]
+use k256::ecdsa::VerifyingKey;
+use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+use signature::hazmat::PrehashVerifier;
+
+use super::error::LocalError;
+use super::message_bundle::MessageBundle;
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+use super::signed_message::MessageType;
+use super::signed_message::{SessionId, SignedMessage};
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+use super::type_erased::deserialize_message;
+#[cfg(feature = "key-gen")]
+use crate::cggmp21::key_init;
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+use crate::cggmp21::params_fingerprint;
+use crate::cggmp21::SchemeParams;
+#[cfg(feature = "aux-gen")]
+use crate::cggmp21::{aux_gen, PublicAuxInfo};
+#[cfg(feature = "signing")]
+use crate::cggmp21::{signing, PresigningPublic};
+#[cfg(feature = "signing")]
+use crate::constructors::PrehashedMessage;
+#[cfg(feature = "signing")]
+use crate::curve::RecoverableSignature;
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+use crate::rounds::BroadcastTranscript;
+use crate::rounds::{Check, ProtocolId};
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+use crate::tools::hashing::HashOutput;
+
+/// An error when collecting or verifying a [`Transcript`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum TranscriptError {
+    /// The message belongs to a different session or protocol.
+    WrongSession,
+    /// The sender is not one of the parties of the session.
+    UnknownParty,
+    /// The party has already sent a different broadcast in this round.
+    ConflictingBroadcast,
+    /// The transcript is of a different protocol ({0:?}).
+    WrongProtocol(ProtocolId),
+}
+
+/// The broadcasts of a session, collected by any party receiving them
+/// (a participant, or an observer the broadcasts are forwarded to).
+///
+/// The direct messages are not included, since they may contain secret data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "Sig: Serialize, Verifier: Serialize"))]
+#[serde(bound(deserialize = "Sig: Deserialize<'de>, Verifier: Deserialize<'de> + Ord"))]
+pub struct Transcript<Sig, Verifier> {
+    session_id: SessionId,
+    protocol: ProtocolId,
+    parties: BTreeSet<Verifier>,
+    broadcasts: BTreeMap<u8, BTreeMap<Verifier, SignedMessage<Sig>>>,
+}
+
+impl<Sig, Verifier: Ord + Clone> Transcript<Sig, Verifier> {
+    /// Creates an empty transcript of a session.
+    pub fn new(session_id: SessionId, protocol: ProtocolId, parties: &BTreeSet<Verifier>) -> Self {
+        Self {
+            session_id,
+            protocol,
+            parties: parties.clone(),
+            broadcasts: BTreeMap::new(),
+        }
+    }
+
+    /// The session the broadcasts belong to.
+    pub fn session_id(&self) -> &SessionId {
+        &self.session_id
+    }
+
+    /// The protocol the broadcasts belong to.
+    pub fn protocol(&self) -> ProtocolId {
+        self.protocol
+    }
+
+    /// Adds the broadcast part of a message received from `from`.
+    ///
+    /// Messages without a broadcast part (direct or echo messages) are ignored,
+    /// as are repeated copies of an already added broadcast.
+    /// The signature is not checked here, but during the verification.
+    pub fn add_message(
+        &mut self,
+        from: &Verifier,
+        message: &MessageBundle<Sig>,
+    ) -> Result<(), TranscriptError>
+    where
+        Sig: Clone,
+    {
+        if message.session_id() != &self.session_id || message.protocol() != self.protocol {
+            return Err(TranscriptError::WrongSession);
+        }
+        if !self.parties.contains(from) {
+            return Err(TranscriptError::UnknownParty);
+        }
+
+        let broadcast = match message.broadcast() {
+            Some(broadcast) => broadcast,
+            None => return Ok(()),
+        };
+
+        let round = self.broadcasts.entry(broadcast.round()).or_default();
+        match round.get(from) {
+            Some(existing) if existing.is_same_as(broadcast) => Ok(()),
+            Some(_) => Err(TranscriptError::ConflictingBroadcast),
+            None => {
+                round.insert(from.clone(), broadcast.clone());
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<Sig, Verifier> Transcript<Sig, Verifier>
+where
+    Sig: Serialize + for<'de> Deserialize<'de>,
+    Verifier: Serialize + for<'de> Deserialize<'de> + Ord,
+{
+    /// Serializes the transcript.
+    pub fn to_bytes(&self) -> Result<Box<[u8]>, LocalError> {
+        bincode::serde::encode_to_vec(self, bincode::config::standard())
+            .map(|bytes| bytes.into())
+            .map_err(|err| LocalError(format!("Failed to serialize the transcript: {err:?}")))
+    }
+
+    /// Deserializes a transcript created by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LocalError> {
+        let (transcript, read) =
+            bincode::serde::decode_from_slice(bytes, bincode::config::standard()).map_err(
+                |err| LocalError(format!("Failed to deserialize the transcript: {err:?}")),
+            )?;
+        if read != bytes.len() {
+            return Err(LocalError(
+                "Trailing bytes after the serialized transcript".into(),
+            ));
+        }
+        Ok(transcript)
+    }
+}
+
+/// The outcome of a single check of a party's broadcast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckOutcome<Verifier> {
+    /// The party that sent the broadcast.
+    pub party: Verifier,
+    /// The round of the broadcast.
+    pub round: u8,
+    /// The check performed.
+    pub check: Check,
+    /// Whether the check passed.
+    pub passed: bool,
+}
+
+/// The results of verifying a [`Transcript`] with a [`LightVerifier`].
+#[derive(Debug, Clone)]
+pub struct TranscriptReport<Verifier, T> {
+    outcomes: Vec<CheckOutcome<Verifier>>,
+    missing: Vec<(u8, Verifier)>,
+    unchecked: Vec<Check>,
+    outcome: Option<T>,
+}
+
+impl<Verifier, T> TranscriptReport<Verifier, T> {
+    /// All the checks performed, in order.
+    pub fn outcomes(&self) -> &[CheckOutcome<Verifier>] {
+        &self.outcomes
+    }
+
+    /// The checks that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &CheckOutcome<Verifier>> {
+        self.outcomes.iter().filter(|outcome| !outcome.passed)
+    }
+
+    /// The rounds and the parties whose broadcasts are missing from the transcript.
+    pub fn missing(&self) -> &[(u8, Verifier)] {
+        &self.missing
+    }
+
+    /// The checks of the protocol that cannot be performed with the broadcasts alone.
+    pub fn unchecked(&self) -> &[Check] {
+        &self.unchecked
+    }
+
+    /// The public result of the session, if all the broadcasts are present and valid.
+    pub fn outcome(&self) -> Option<&T> {
+        self.outcome.as_ref()
+    }
+
+    /// Returns `true` if all the broadcasts are present and passed all the checks.
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty() && self.failures().next().is_none() && self.outcome.is_some()
+    }
+}
+
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+struct Checker<'a, Sig, Verifier> {
+    transcript: &'a Transcript<Sig, Verifier>,
+    params: Option<HashOutput>,
+    outcomes: Vec<CheckOutcome<Verifier>>,
+    missing: Vec<(u8, Verifier)>,
+}
+
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+impl<'a, Sig, Verifier> Checker<'a, Sig, Verifier> {
+    fn new(transcript: &'a Transcript<Sig, Verifier>, params: Option<HashOutput>) -> Self {
+        Self {
+            transcript,
+            params,
+            outcomes: Vec::new(),
+            missing: Vec::new(),
+        }
+    }
+
+    fn into_report<T>(
+        self,
+        unchecked: &[Check],
+        outcome: Option<T>,
+    ) -> TranscriptReport<Verifier, T> {
+        TranscriptReport {
+            outcomes: self.outcomes,
+            missing: self.missing,
+            unchecked: unchecked.into(),
+            outcome,
+        }
+    }
+}
+
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+impl<Sig, Verifier> BroadcastTranscript<Verifier> for Checker<'_, Sig, Verifier>
+where
+    Sig: Clone,
+    Verifier: PrehashVerifier<Sig> + Ord + Clone,
+{
+    fn broadcasts<M: for<'de> Deserialize<'de>>(&mut self, round: u8) -> BTreeMap<Verifier, M> {
+        let transcript = self.transcript;
+        let mut messages = BTreeMap::new();
+        for party in transcript.parties.iter() {
+            let message = match transcript
+                .broadcasts
+                .get(&round)
+                .and_then(|broadcasts| broadcasts.get(party))
+            {
+                Some(message) => message,
+                None => {
+                    self.missing.push((round, party.clone()));
+                    continue;
+                }
+            };
+
+            let verified = match message.clone().verify(party) {
+                Ok(verified) => verified,
+                Err(_) => {
+                    self.record(party, round, Check::Envelope, false);
+                    continue;
+                }
+            };
+            let unverified = verified.as_unverified();
+            let passed = unverified.session_id() == &transcript.session_id
+                && unverified.protocol() == transcript.protocol
+                && unverified.round() == round
+                && unverified.message_type() == MessageType::Broadcast;
+            self.record(party, round, Check::Envelope, passed);
+            if !passed {
+                continue;
+            }
+
+            let passed = unverified.params() == self.params;
+            self.record(party, round, Check::Params, passed);
+            if !passed {
+                continue;
+            }
+
+            match deserialize_message::<M>(verified.payload()) {
+                Ok(message) => {
+                    self.record(party, round, Check::Format, true);
+                    messages.insert(party.clone(), message);
+                }
+                Err(_) => self.record(party, round, Check::Format, false),
+            }
+        }
+        messages
+    }
+
+    fn record(&mut self, party: &Verifier, round: u8, check: Check, passed: bool) {
+        self.outcomes.push(CheckOutcome {
+            party: party.clone(),
+            round,
+            check,
+            passed,
+        });
+    }
+}
+
+/// Re-runs the publicly checkable verifications of a session
+/// from the [`Transcript`] of its broadcasts.
+///
+/// Only the checks that depend on the broadcasts alone can be performed;
+/// a valid report does not mean that the direct messages of the session were valid.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LightVerifier<P: SchemeParams>(PhantomData<P>);
+
+impl<P: SchemeParams> LightVerifier<P> {
+    /// Creates a verifier for the sessions with the given scheme parameters.
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+    fn checker<'a, Sig, Verifier>(
+        &self,
+        transcript: &'a Transcript<Sig, Verifier>,
+        protocol: ProtocolId,
+    ) -> Result<Checker<'a, Sig, Verifier>, TranscriptError> {
+        if transcript.protocol != protocol {
+            return Err(TranscriptError::WrongProtocol(transcript.protocol));
+        }
+        Ok(Checker::new(transcript, Some(params_fingerprint::<P>())))
+    }
+
+    /// Verifies the transcript of a KeyInit session: the hash commitments and the Schnorr proofs.
+    ///
+    /// The outcome is the verifying key of the generated key shares.
+    #[cfg(feature = "key-gen")]
+    pub fn verify_key_init<Sig, Verifier>(
+        &self,
+        transcript: &Transcript<Sig, Verifier>,
+    ) -> Result<TranscriptReport<Verifier, VerifyingKey>, TranscriptError>
+    where
+        Sig: Clone,
+        Verifier: PrehashVerifier<Sig> + Ord + Clone + Serialize,
+    {
+        let mut checker = self.checker(transcript, ProtocolId::KeyInit)?;
+        let point = key_init::verify_transcript::<P, Verifier>(
+            transcript.session_id.as_ref(),
+            &transcript.parties,
+            &mut checker,
+        );
+        let verifying_key = point.and_then(|point| point.to_verifying_key());
+        Ok(checker.into_report(&[], verifying_key))
+    }
+
+    /// Verifies the transcript of an AuxGen session: the hash commitments,
+    /// the Paillier moduli and the ring-Pedersen parameters, and the proofs of the latter.
+    ///
+    /// The proofs sent in the direct messages (`П^{mod}`, `П^{fac}` and `П^{sch}`)
+    /// are listed in [`TranscriptReport::unchecked`].
+    /// The outcome is the public auxiliary data of the parties.
+    #[cfg(feature = "aux-gen")]
+    #[allow(clippy::type_complexity)]
+    pub fn verify_aux_gen<Sig, Verifier>(
+        &self,
+        transcript: &Transcript<Sig, Verifier>,
+    ) -> Result<TranscriptReport<Verifier, BTreeMap<Verifier, PublicAuxInfo<P>>>, TranscriptError>
+    where
+        Sig: Clone,
+        Verifier: PrehashVerifier<Sig> + Ord + Clone + Serialize,
+    {
+        let mut checker = self.checker(transcript, ProtocolId::AuxGen)?;
+        let public_aux = aux_gen::verify_transcript::<P, Verifier>(
+            transcript.session_id.as_ref(),
+            &transcript.parties,
+            &mut checker,
+        );
+        Ok(checker.into_report(
+            &[Check::ModProof, Check::FacProof, Check::SchProof],
+            public_aux,
+        ))
+    }
+
+    /// Verifies the transcript of an InteractiveSigning session: the partial signatures
+    /// against the public presigning data (which can be obtained from any of the parties),
+    /// and the resulting signature.
+    ///
+    /// The presigning rounds use only direct messages and are not checked.
+    /// The outcome is the signature.
+    #[cfg(feature = "signing")]
+    pub fn verify_signing<Sig, Verifier>(
+        &self,
+        transcript: &Transcript<Sig, Verifier>,
+        presigning: &PresigningPublic<Verifier>,
+        verifying_key: &VerifyingKey,
+        prehashed_message: &PrehashedMessage,
+    ) -> Result<TranscriptReport<Verifier, RecoverableSignature>, TranscriptError>
+    where
+        Sig: Clone,
+        Verifier: PrehashVerifier<Sig> + Ord + Clone,
+    {
+        let mut checker = self.checker(transcript, ProtocolId::InteractiveSigning)?;
+        // The partial signatures are sent in Round 4, after the three rounds of Presigning.
+        let signature = signing::verify_transcript(
+            4,
+            presigning,
+            verifying_key,
+            prehashed_message,
+            &mut checker,
+        );
+        Ok(checker.into_report(&[], signature))
+    }
+}
@@ -1,10 +1,14 @@
 use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
 
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use signature::hazmat::PrehashVerifier;
 
 use super::error::LocalError;
 use super::signed_message::{MessageType, SessionId, SignedMessage, VerifiedMessage};
+use crate::rounds::ProtocolId;
+use crate::tools::hashing::HashOutput;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) enum MessageBundleEnum<M> {
@@ -18,6 +22,7 @@ pub(crate) enum MessageBundleEnum<M> {
 #[derive(Clone, Debug)]
 pub struct MessageBundle<Sig> {
     session_id: SessionId,
+    protocol: ProtocolId,
     round: u8,
     is_echo: bool,
     bundle: MessageBundleEnum<SignedMessage<Sig>>,
@@ -45,14 +50,14 @@ impl<'de, Sig: Deserialize<'de>> Deserialize<'de> for MessageBundle<Sig> {
 impl<Sig> TryFrom<MessageBundleEnum<SignedMessage<Sig>>> for MessageBundle<Sig> {
     type Error = LocalError;
     fn try_from(unchecked: MessageBundleEnum<SignedMessage<Sig>>) -> Result<Self, Self::Error> {
-        let (session_id, round, is_echo) = match &unchecked {
+        let (session_id, protocol, round, is_echo) = match &unchecked {
             MessageBundleEnum::Broadcast(msg) => {
                 if msg.message_type() != MessageType::Broadcast {
                     return Err(LocalError(
                         "Invalid message type of the broadcast field".into(),
                     ));
                 }
-                (msg.session_id(), msg.round(), false)
+                (msg.session_id(), msg.protocol(), msg.round(), false)
             }
             MessageBundleEnum::Direct(msg) => {
                 if msg.message_type() != MessageType::Direct {
@@ -60,18 +65,21 @@ impl<Sig> TryFrom<MessageBundleEnum<SignedMessage<Sig>>> for MessageBundle<Sig>
                         "Invalid message type of the direct field".into(),
                     ));
                 }
-                (msg.session_id(), msg.round(), false)
+                (msg.session_id(), msg.protocol(), msg.round(), false)
             }
             MessageBundleEnum::Echo(msg) => {
                 if msg.message_type() != MessageType::Echo {
                     return Err(LocalError("Invalid message type of the echo field".into()));
                 }
-                (msg.session_id(), msg.round(), true)
+                (msg.session_id(), msg.protocol(), msg.round(), true)
             }
             MessageBundleEnum::Both { broadcast, direct } => {
                 if broadcast.session_id() != direct.session_id() {
                     return Err(LocalError("Mismatched session IDs".into()));
                 }
+                if broadcast.protocol() != direct.protocol() {
+                    return Err(LocalError("Mismatched protocols".into()));
+                }
                 if broadcast.round() != direct.round() {
                     return Err(LocalError("Mismatched round numbers".into()));
                 }
@@ -85,11 +93,17 @@ impl<Sig> TryFrom<MessageBundleEnum<SignedMessage<Sig>>> for MessageBundle<Sig>
                         "Invalid message type of the direct field".into(),
                     ));
                 }
-                (broadcast.session_id(), broadcast.round(), false)
+                (
+                    broadcast.session_id(),
+                    broadcast.protocol(),
+                    broadcast.round(),
+                    false,
+                )
             }
         };
         Ok(Self {
             session_id: *session_id,
+            protocol,
             round,
             is_echo,
             bundle: unchecked,
@@ -103,6 +117,11 @@ impl<Sig> MessageBundle<Sig> {
         &self.session_id
     }
 
+    /// The protocol of the messages.
+    pub fn protocol(&self) -> ProtocolId {
+        self.protocol
+    }
+
     /// The round of the messages.
     pub fn round(&self) -> u8 {
         self.round
@@ -113,6 +132,42 @@ impl<Sig> MessageBundle<Sig> {
         self.is_echo
     }
 
+    /// Applies a transformation to the direct part of the bundle, if there is one.
+    #[cfg(feature = "encryption")]
+    pub(crate) fn map_direct<E>(
+        self,
+        f: impl FnOnce(SignedMessage<Sig>) -> Result<SignedMessage<Sig>, E>,
+    ) -> Result<Self, E> {
+        let bundle = match self.bundle {
+            MessageBundleEnum::Direct(msg) => MessageBundleEnum::Direct(f(msg)?),
+            MessageBundleEnum::Both { broadcast, direct } => MessageBundleEnum::Both {
+                broadcast,
+                direct: f(direct)?,
+            },
+            bundle => bundle,
+        };
+        Ok(Self { bundle, ..self })
+    }
+
+    /// Returns the broadcast part of the bundle, if there is one.
+    pub(crate) fn broadcast(&self) -> Option<&SignedMessage<Sig>> {
+        match &self.bundle {
+            MessageBundleEnum::Broadcast(msg) => Some(msg),
+            MessageBundleEnum::Both { broadcast, .. } => Some(broadcast),
+            _ => None,
+        }
+    }
+
+    /// Returns the broadcast (or echo) and the direct parts of the bundle.
+    #[cfg(feature = "relay")]
+    pub(crate) fn into_parts(self) -> (Option<SignedMessage<Sig>>, Option<SignedMessage<Sig>>) {
+        match self.bundle {
+            MessageBundleEnum::Broadcast(msg) | MessageBundleEnum::Echo(msg) => (Some(msg), None),
+            MessageBundleEnum::Direct(msg) => (None, Some(msg)),
+            MessageBundleEnum::Both { broadcast, direct } => (Some(broadcast), Some(direct)),
+        }
+    }
+
     pub(crate) fn verify(
         self,
         verifier: &impl PrehashVerifier<Sig>,
@@ -170,4 +225,43 @@ impl<Sig> VerifiedMessageBundle<Sig> {
     pub fn is_echo(&self) -> bool {
         matches!(&self.0, MessageBundleEnum::Echo(_))
     }
+
+    /// Returns `true` if all the messages in the bundle were created
+    /// with the scheme parameters having the given fingerprint.
+    pub fn has_params(&self, params: Option<HashOutput>) -> bool {
+        self.messages()
+            .iter()
+            .all(|msg| msg.as_unverified().params() == params)
+    }
+
+    fn messages(&self) -> Vec<&VerifiedMessage<Sig>> {
+        match &self.0 {
+            MessageBundleEnum::Broadcast(msg)
+            | MessageBundleEnum::Direct(msg)
+            | MessageBundleEnum::Echo(msg) => vec![msg],
+            MessageBundleEnum::Both { broadcast, direct } => vec![broadcast, direct],
+        }
+    }
+
+    /// Returns the signed messages in the bundle.
+    pub fn signed_messages(&self) -> Vec<SignedMessage<Sig>>
+    where
+        Sig: Clone,
+    {
+        self.messages()
+            .into_iter()
+            .map(|msg| msg.clone().into_unverified())
+            .collect()
+    }
+
+    /// Compares the "significant" parts of the messages in the bundles
+    /// (see [`SignedMessage::is_same_as`]).
+    pub fn is_same_as(&self, other: &Self) -> bool {
+        let (ours, theirs) = (self.messages(), other.messages());
+        ours.len() == theirs.len()
+            && ours
+                .iter()
+                .zip(theirs.iter())
+                .all(|(ours, theirs)| ours.as_unverified().is_same_as(theirs.as_unverified()))
+    }
 }
@@ -0,0 +1,376 @@
+//! A party that follows the public part of a session without holding any shares.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+use signature::{
+    hazmat::{PrehashVerifier, RandomizedPrehashSigner},
+    Keypair,
+};
+
+use super::echo::{EchoError, EchoRound};
+use super::error::{LocalError, RemoteError, RemoteErrorEnum, SessionInitError};
+use super::light_verifier::Transcript;
+use super::message_bundle::{MessageBundle, MessageBundleEnum, VerifiedMessageBundle};
+use super::session::MAX_PARTIES;
+use super::signed_message::{MessageType, SessionId, VerifiedMessage};
+use crate::rounds::ProtocolId;
+use crate::tools::hashing::HashOutput;
+
+/// Possible errors returned by the [`ObserverSession`] methods.
+#[derive(Debug, Clone)]
+pub enum ObserverError<Verifier> {
+    /// Indicates an error on this party's side.
+    Local(LocalError),
+    /// An unprovable fault of another party.
+    Remote(RemoteError<Verifier>),
+    /// The echo of another party does not match the broadcasts received by this observer.
+    Echo {
+        /// The party that sent the echo.
+        party: Verifier,
+        /// The mismatch found.
+        error: EchoError,
+    },
+}
+
+impl<Verifier> From<LocalError> for ObserverError<Verifier> {
+    fn from(error: LocalError) -> Self {
+        Self::Local(error)
+    }
+}
+
+/// Possible outcomes of successfully finalizing a round of an [`ObserverSession`].
+#[allow(clippy::large_enum_variant)]
+pub enum ObserverOutcome<Sig, Signer, Verifier> {
+    /// The session is finished, with all the broadcasts collected.
+    Success(Transcript<Sig, Verifier>),
+    /// Starting the next round.
+    AnotherRound {
+        /// The new session object.
+        session: ObserverSession<Sig, Signer, Verifier>,
+        /// The messages for the new round received during the previous round.
+        cached_messages: Vec<(Verifier, MessageBundle<Sig>)>,
+    },
+}
+
+/// A session of a party observing a protocol run by other parties (the contributors).
+///
+/// The observer holds no shares and sends no secret data.
+/// It receives the broadcasts of the contributors (which have to set it as an observer with
+/// [`Session::set_observers`](`super::Session::set_observers`)), and takes part in the echo rounds,
+/// so that the contributors only proceed if the observer saw the same broadcasts as they did.
+/// The rounds without broadcasts (see [`ProtocolId::rounds`]) are skipped.
+///
+/// The result is the [`Transcript`] of the broadcasts,
+/// which can be checked with a [`LightVerifier`](`super::LightVerifier`).
+pub struct ObserverSession<Sig, Signer, Verifier> {
+    signer: Signer,
+    my_id: Verifier,
+    session_id: SessionId,
+    protocol: ProtocolId,
+    contributors: BTreeSet<Verifier>,
+    /// The other observers.
+    observers: BTreeSet<Verifier>,
+    /// The rounds with messages for the observers, as pairs of the round number
+    /// and whether it is an echo round.
+    stages: Vec<(u8, bool)>,
+    stage: usize,
+    /// The fingerprint of the scheme parameters, taken from the first received broadcast.
+    params: Option<Option<HashOutput>>,
+    transcript: Transcript<Sig, Verifier>,
+    echo_round: Option<EchoRound<Verifier, Sig>>,
+    destinations: BTreeSet<Verifier>,
+    received: BTreeMap<Verifier, VerifiedMessageBundle<Sig>>,
+    cached: BTreeMap<(u8, bool), BTreeMap<Verifier, MessageBundle<Sig>>>,
+}
+
+impl<Sig, Signer, Verifier> ObserverSession<Sig, Signer, Verifier>
+where
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: Debug + Clone + PrehashVerifier<Sig> + Ord + Serialize + for<'de> Deserialize<'de>,
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+{
+    /// Creates a session observing the given protocol run by `contributors`.
+    ///
+    /// `observers` are all the observers of the session, and may include this one.
+    pub fn new(
+        session_id: SessionId,
+        protocol: ProtocolId,
+        signer: Signer,
+        contributors: &BTreeSet<Verifier>,
+        observers: &BTreeSet<Verifier>,
+    ) -> Result<Self, SessionInitError> {
+        let my_id = signer.verifying_key();
+        let mut observers = observers.clone();
+        observers.remove(&my_id);
+
+        if contributors.contains(&my_id) || !contributors.is_disjoint(&observers) {
+            return Err(LocalError("The observers cannot be parties of the session".into()).into());
+        }
+
+        let num_parties = contributors.len() + observers.len() + 1;
+        if num_parties > MAX_PARTIES {
+            return Err(SessionInitError::TooManyParties(num_parties));
+        }
+
+        let stages = protocol
+            .rounds()
+            .iter()
+            .flat_map(|info| {
+                [
+                    info.broadcast.then_some((info.round, false)),
+                    info.echo.then_some((info.round, true)),
+                ]
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        if stages.is_empty() {
+            return Err(LocalError("The protocol has no broadcasts to observe".into()).into());
+        }
+
+        Ok(Self {
+            signer,
+            my_id,
+            session_id,
+            protocol,
+            contributors: contributors.clone(),
+            observers,
+            stages,
+            stage: 0,
+            params: None,
+            transcript: Transcript::new(session_id, protocol, contributors),
+            echo_round: None,
+            destinations: BTreeSet::new(),
+            received: BTreeMap::new(),
+            cached: BTreeMap::new(),
+        })
+    }
+
+    /// This session's verifier object.
+    pub fn verifier(&self) -> Verifier {
+        self.my_id.clone()
+    }
+
+    /// This session's ID.
+    pub fn session_id(&self) -> SessionId {
+        self.session_id
+    }
+
+    /// Returns a pair of the current round index and whether it is an echo round.
+    pub fn current_round(&self) -> (u8, bool) {
+        self.stages[self.stage]
+    }
+
+    /// Returns the parties to which the messages of this round should be sent
+    /// (empty, unless this is an echo round).
+    pub fn message_destinations(&self) -> &BTreeSet<Verifier> {
+        &self.destinations
+    }
+
+    fn expecting_messages_from(&self) -> &BTreeSet<Verifier> {
+        match &self.echo_round {
+            Some(echo_round) => echo_round.expecting_messages_from(),
+            None => &self.contributors,
+        }
+    }
+
+    /// Returns the echo message for the given destination
+    /// (must be one of those returned by [`Self::message_destinations`]).
+    pub fn make_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &Verifier,
+    ) -> Result<MessageBundle<Sig>, LocalError> {
+        let echo_round = match &self.echo_round {
+            Some(echo_round) if self.destinations.contains(destination) => echo_round,
+            _ => {
+                return Err(LocalError(format!(
+                    "No message for {destination:?} in this round"
+                )))
+            }
+        };
+        let params = self
+            .params
+            .ok_or_else(|| LocalError("No broadcasts have been received".into()))?;
+
+        let (round_num, _) = self.current_round();
+        let payload = echo_round.make_broadcast();
+        let message = VerifiedMessage::new(
+            rng,
+            &self.signer,
+            &self.session_id,
+            self.protocol,
+            params,
+            round_num,
+            MessageType::Echo,
+            &payload,
+        )?
+        .into_unverified();
+        MessageBundle::try_from(MessageBundleEnum::Echo(message))
+    }
+
+    /// Verifies and saves a message received from another party.
+    ///
+    /// The messages for the future rounds are cached (only the first copy from each party),
+    /// and returned on finalization of the round they arrived in.
+    /// A repeated delivery of the same message is not a fault.
+    pub fn receive_message(
+        &mut self,
+        from: &Verifier,
+        message: MessageBundle<Sig>,
+    ) -> Result<(), ObserverError<Verifier>> {
+        let remote_error = |error| {
+            ObserverError::Remote(RemoteError {
+                party: from.clone(),
+                error,
+            })
+        };
+
+        if message.session_id() != &self.session_id {
+            return Err(remote_error(RemoteErrorEnum::UnexpectedSessionId));
+        }
+        if message.protocol() != self.protocol {
+            return Err(remote_error(RemoteErrorEnum::UnexpectedProtocol));
+        }
+
+        let stage = (message.round(), message.is_echo());
+        let current = self.current_round();
+        if stage != current {
+            if stage > current && self.stages.contains(&stage) {
+                self.cached
+                    .entry(stage)
+                    .or_default()
+                    .entry(from.clone())
+                    .or_insert(message);
+                return Ok(());
+            }
+            return Err(remote_error(RemoteErrorEnum::OutOfOrderMessage));
+        }
+
+        if !self.expecting_messages_from().contains(from) {
+            return Err(
+                LocalError("The sender is not in the list of expected senders.".into()).into(),
+            );
+        }
+
+        let verified = message
+            .clone()
+            .verify(from)
+            .map_err(|err| remote_error(RemoteErrorEnum::InvalidSignature(err)))?;
+
+        if let Some(received) = self.received.get(from) {
+            if received.is_same_as(&verified) {
+                return Ok(());
+            }
+            return Err(remote_error(RemoteErrorEnum::DuplicateMessage));
+        }
+
+        match &self.echo_round {
+            None => {
+                let broadcast = match (verified.broadcast_message(), verified.direct_payload()) {
+                    (Some(broadcast), None) => broadcast,
+                    _ => {
+                        return Err(remote_error(RemoteErrorEnum::InvalidContents(
+                            "Expected a message with a broadcast part only".into(),
+                        )))
+                    }
+                };
+                match self.params {
+                    Some(params) if !verified.has_params(params) => {
+                        return Err(remote_error(RemoteErrorEnum::MismatchedParams));
+                    }
+                    Some(_) => {}
+                    None => self.params = Some(broadcast.as_unverified().params()),
+                }
+                self.transcript
+                    .add_message(from, &message)
+                    .map_err(|err| LocalError(format!("Failed to add the broadcast: {err}")))?;
+            }
+            Some(echo_round) => {
+                let params = self
+                    .params
+                    .ok_or_else(|| LocalError("No broadcasts have been received".into()))?;
+                if !verified.has_params(params) {
+                    return Err(remote_error(RemoteErrorEnum::MismatchedParams));
+                }
+                let payload = verified.echo_payload().ok_or_else(|| {
+                    remote_error(RemoteErrorEnum::InvalidContents(
+                        "Expected an echo message".into(),
+                    ))
+                })?;
+                echo_round
+                    .verify_broadcast(from, payload)
+                    .map_err(|error| ObserverError::Echo {
+                        party: from.clone(),
+                        error,
+                    })?;
+            }
+        }
+
+        self.received.insert(from.clone(), verified);
+        Ok(())
+    }
+
+    /// Returns `true` if the messages from all the expected parties have been received.
+    pub fn can_finalize(&self) -> bool {
+        self.missing_messages().is_empty()
+    }
+
+    /// Returns a list of parties whose messages for this round have not been received yet.
+    pub fn missing_messages(&self) -> BTreeSet<Verifier> {
+        self.expecting_messages_from()
+            .iter()
+            .filter(|id| !self.received.contains_key(id))
+            .cloned()
+            .collect()
+    }
+
+    /// Try to finalize the round.
+    pub fn finalize_round(
+        mut self,
+    ) -> Result<ObserverOutcome<Sig, Signer, Verifier>, ObserverError<Verifier>> {
+        if !self.can_finalize() {
+            return Err(LocalError("Not enough messages to finalize the round".into()).into());
+        }
+
+        let (round_num, is_echo) = self.current_round();
+        let received = core::mem::take(&mut self.received);
+        self.stage += 1;
+        let next_stage = match self.stages.get(self.stage) {
+            Some(stage) => *stage,
+            None => return Ok(ObserverOutcome::Success(self.transcript)),
+        };
+
+        if !is_echo && next_stage == (round_num, true) {
+            let broadcasts = received
+                .into_iter()
+                .map(|(id, message)| {
+                    let broadcast = message.broadcast_message().cloned().ok_or_else(|| {
+                        LocalError("A received message has no broadcast part".into())
+                    })?;
+                    Ok((id, broadcast))
+                })
+                .collect::<Result<BTreeMap<_, _>, LocalError>>()?;
+            let echo_round = EchoRound::new(self.contributors.clone(), broadcasts, &self.observers);
+            self.destinations = echo_round.message_destinations().clone();
+            self.echo_round = Some(echo_round);
+        } else {
+            self.destinations = BTreeSet::new();
+            self.echo_round = None;
+        }
+
+        let cached_messages = self
+            .cached
+            .remove(&next_stage)
+            .map(|messages| messages.into_iter().collect())
+            .unwrap_or_default();
+        Ok(ObserverOutcome::AnotherRound {
+            session: self,
+            cached_messages,
+        })
+    }
+}
@@ -0,0 +1,175 @@
+//! Authenticated encryption of messages between a pair of parties
+//! with the keys derived from their identity keys.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use hkdf::Hkdf;
+use rand_core::CryptoRngCore;
+use sha2::Sha256;
+
+use super::sealed_box::{AgreementKey, OpeningKey};
+use super::signed_message::SessionId;
+use crate::rounds::ProtocolId;
+use crate::tools::hashing::{Chain, FofHasher};
+
+/// The symmetric keys for the messages exchanged with another party.
+///
+/// The keys are derived from the static Diffie-Hellman key agreement between the identity keys
+/// (similarly to the `KK` handshake pattern of the Noise framework, but without the ephemeral keys),
+/// so only the two parties can decrypt the messages, and only the other party could have created
+/// the messages that decrypt successfully.
+/// Note that this means there is no forward secrecy:
+/// a leaked identity key allows one to decrypt all the previously recorded messages.
+///
+/// The Diffie-Hellman secret of a pair of parties is the same in all their sessions,
+/// so the keys are derived from it with HKDF bound to the session ID, the protocol
+/// and the identities of both parties, and are never reused between sessions.
+/// The identity keys themselves are used both for signing the messages and for the key agreement;
+/// this assumes that ECDSA signatures made with a key do not help in attacking
+/// the Diffie-Hellman key agreement with the same key (no such attack is known,
+/// but there is no security proof for this joint use either).
+#[derive(Clone)]
+pub(crate) struct PairwiseKeys {
+    outgoing: Key,
+    incoming: Key,
+}
+
+impl PairwiseKeys {
+    pub fn new(
+        session_id: &SessionId,
+        protocol: ProtocolId,
+        my_opening_key: &impl OpeningKey,
+        my_key: &AgreementKey,
+        their_key: &AgreementKey,
+    ) -> Result<Self, String> {
+        let shared_secret = my_opening_key
+            .diffie_hellman(their_key)
+            .ok_or_else(|| String::from("The key agreement failed"))?;
+        let hkdf = Hkdf::<Sha256>::new(None, shared_secret.as_ref());
+
+        // Separate keys for each direction, so that the messages cannot be reflected back.
+        let derive_key = |sender: &AgreementKey, recipient: &AgreementKey| {
+            let info = FofHasher::new_with_dst(b"PairwiseKey")
+                .chain(session_id)
+                .chain(&protocol)
+                .chain_bytes(&sender.to_bytes())
+                .chain_bytes(&recipient.to_bytes())
+                .finalize();
+            let mut key = Key::default();
+            hkdf.expand(info.as_ref(), &mut key)
+                .expect("the key length is valid for HKDF-SHA256");
+            key
+        };
+
+        Ok(Self {
+            outgoing: derive_key(my_key, their_key),
+            incoming: derive_key(their_key, my_key),
+        })
+    }
+
+    /// Encrypts a message to the other party, returning the nonce followed by the ciphertext.
+    pub fn encrypt(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        associated_data: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Box<[u8]>, String> {
+        // The same key is used for all the messages in the session,
+        // so the nonce must be random.
+        let mut nonce = Nonce::default();
+        rng.fill_bytes(&mut nonce);
+        let ciphertext = ChaCha20Poly1305::new(&self.outgoing)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: associated_data,
+                },
+            )
+            .map_err(|_| String::from("Failed to encrypt the message"))?;
+
+        let mut result = Vec::with_capacity(nonce.len() + ciphertext.len());
+        result.extend(nonce);
+        result.extend(ciphertext);
+        Ok(result.into())
+    }
+
+    /// Decrypts a message from the other party created by [`Self::encrypt`].
+    pub fn decrypt(&self, associated_data: &[u8], data: &[u8]) -> Result<Box<[u8]>, String> {
+        let nonce_len = Nonce::default().len();
+        if data.len() < nonce_len {
+            return Err("The encrypted message is too short".into());
+        }
+        let (nonce, ciphertext) = data.split_at(nonce_len);
+
+        ChaCha20Poly1305::new(&self.incoming)
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: associated_data,
+                },
+            )
+            .map(|plaintext| plaintext.into())
+            .map_err(|_| String::from("Failed to decrypt the message"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::SigningKey;
+    use rand_core::OsRng;
+
+    use super::PairwiseKeys;
+    use crate::rounds::ProtocolId;
+    use crate::sessions::{SealingKey, SessionId};
+
+    #[test]
+    fn encrypt_and_decrypt() {
+        let session_id = SessionId::from_seed(b"abcde");
+        let alice = SigningKey::random(&mut OsRng);
+        let bob = SigningKey::random(&mut OsRng);
+        let alice_key = alice.verifying_key().sealing_key();
+        let bob_key = bob.verifying_key().sealing_key();
+
+        let protocol = ProtocolId::KeyInit;
+        let alice_keys =
+            PairwiseKeys::new(&session_id, protocol, &alice, &alice_key, &bob_key).unwrap();
+        let bob_keys =
+            PairwiseKeys::new(&session_id, protocol, &bob, &bob_key, &alice_key).unwrap();
+
+        let encrypted = alice_keys
+            .encrypt(&mut OsRng, b"header", b"direct message")
+            .unwrap();
+        let decrypted = bob_keys.decrypt(b"header", &encrypted).unwrap();
+        assert_eq!(&*decrypted, b"direct message");
+
+        // Wrong associated data
+        assert!(bob_keys.decrypt(b"other", &encrypted).is_err());
+
+        // A message cannot be reflected back to its sender
+        assert!(alice_keys.decrypt(b"header", &encrypted).is_err());
+
+        // The keys are bound to the session
+        let other_session_keys = PairwiseKeys::new(
+            &SessionId::from_seed(b"fghij"),
+            protocol,
+            &bob,
+            &bob_key,
+            &alice_key,
+        )
+        .unwrap();
+        assert!(other_session_keys.decrypt(b"header", &encrypted).is_err());
+
+        // ... and to the protocol
+        let other_protocol_keys =
+            PairwiseKeys::new(&session_id, ProtocolId::AuxGen, &bob, &bob_key, &alice_key).unwrap();
+        assert!(other_protocol_keys.decrypt(b"header", &encrypted).is_err());
+    }
+}
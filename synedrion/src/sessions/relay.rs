@@ -0,0 +1,140 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use super::sealed_box::SealedBox;
+use super::signed_message::SignedMessage;
+
+/// A message of a round prepared for sending through an untrusted relay.
+///
+/// The broadcast part (if any) is only included once,
+/// and the direct parts are sealed to the identity keys of their recipients,
+/// so the relay can only see the metadata (the sender, the destinations, and the message sizes).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayMessage<Sig, Verifier: Ord> {
+    pub(crate) broadcast: Option<SignedMessage<Sig>>,
+    pub(crate) direct: BTreeMap<Verifier, Option<SealedBox>>,
+}
+
+impl<Sig: Clone, Verifier: Ord + Clone> RelayMessage<Sig, Verifier> {
+    /// Returns the parties the message has to be forwarded to.
+    pub fn destinations(&self) -> impl Iterator<Item = &Verifier> {
+        self.direct.keys()
+    }
+
+    /// Splits the message into the parts to be forwarded to each of the destinations.
+    ///
+    /// This is what the relay is expected to do on receiving the message.
+    pub fn split(self) -> BTreeMap<Verifier, RelayedMessage<Sig>> {
+        let broadcast = self.broadcast;
+        self.direct
+            .into_iter()
+            .map(|(destination, direct)| {
+                (
+                    destination,
+                    RelayedMessage {
+                        broadcast: broadcast.clone(),
+                        direct,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// A part of a [`RelayMessage`] forwarded by the relay to one of its destinations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayedMessage<Sig> {
+    pub(crate) broadcast: Option<SignedMessage<Sig>>,
+    pub(crate) direct: Option<SealedBox>,
+}
+
+impl<Sig: Clone, Verifier: Ord + Clone> RelayMessage<Sig, Verifier> {
+    /// Splits the message into several ones for at most `max_destinations` destinations each,
+    /// each including the broadcast part.
+    ///
+    /// Can be used to limit the size of a single envelope sent to the relay for large sessions.
+    /// Panics if `max_destinations` is zero.
+    pub fn into_chunks(self, max_destinations: usize) -> Vec<Self> {
+        assert!(max_destinations > 0, "`max_destinations` must be non-zero");
+        let mut chunks = Vec::new();
+        let mut direct = self.direct.into_iter().peekable();
+        while direct.peek().is_some() {
+            chunks.push(Self {
+                broadcast: self.broadcast.clone(),
+                direct: direct.by_ref().take(max_destinations).collect(),
+            });
+        }
+        chunks
+    }
+}
+
+/// The parts of the [`RelayMessage`]s from several senders forwarded to the same destination
+/// in a single envelope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayedBatch<Sig, Verifier: Ord> {
+    pub(crate) messages: BTreeMap<Verifier, RelayedMessage<Sig>>,
+}
+
+impl<Sig, Verifier: Ord> RelayedBatch<Sig, Verifier> {
+    /// Returns the senders of the messages in the batch.
+    pub fn senders(&self) -> impl Iterator<Item = &Verifier> {
+        self.messages.keys()
+    }
+
+    /// Returns the messages in the batch, along with their senders.
+    pub fn into_messages(self) -> impl Iterator<Item = (Verifier, RelayedMessage<Sig>)> {
+        self.messages.into_iter()
+    }
+}
+
+/// Collects the [`RelayMessage`]s received by a relay in a star topology,
+/// and packs the parts going to each destination into [`RelayedBatch`]es,
+/// so that every party receives a single envelope per round instead of one per sender.
+#[derive(Debug, Clone)]
+pub struct RelayAggregator<Sig, Verifier: Ord> {
+    max_senders: usize,
+    parts: BTreeMap<Verifier, BTreeMap<Verifier, RelayedMessage<Sig>>>,
+}
+
+impl<Sig: Clone, Verifier: Ord + Clone> RelayAggregator<Sig, Verifier> {
+    /// Creates an aggregator putting the messages from at most `max_senders` senders in a batch.
+    ///
+    /// Panics if `max_senders` is zero.
+    pub fn new(max_senders: usize) -> Self {
+        assert!(max_senders > 0, "`max_senders` must be non-zero");
+        Self {
+            max_senders,
+            parts: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a message (or a chunk of one, see [`RelayMessage::into_chunks`]) from `from`.
+    pub fn add_message(&mut self, from: &Verifier, message: RelayMessage<Sig, Verifier>) {
+        for (destination, part) in message.split() {
+            self.parts
+                .entry(destination)
+                .or_default()
+                .insert(from.clone(), part);
+        }
+    }
+
+    /// Returns the batches to be forwarded to each destination.
+    pub fn into_batches(self) -> BTreeMap<Verifier, Vec<RelayedBatch<Sig, Verifier>>> {
+        let max_senders = self.max_senders;
+        self.parts
+            .into_iter()
+            .map(|(destination, parts)| {
+                let mut batches = Vec::new();
+                let mut parts = parts.into_iter().peekable();
+                while parts.peek().is_some() {
+                    batches.push(RelayedBatch {
+                        messages: parts.by_ref().take(max_senders).collect(),
+                    });
+                }
+                (destination, batches)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,348 @@
+//! Statistics of the faults of other parties, kept across sessions.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::fmt::Debug;
+
+use displaydoc::Display;
+use serde::{Deserialize, Serialize};
+use signature::hazmat::PrehashVerifier;
+
+use super::error::{Error, RemoteError};
+use super::evidence::{check_evidence, Claim, EvidencePacket, Verdict};
+use crate::rounds::ProtocolResult;
+
+/// The numbers of faults of a single party.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaultCounts {
+    /// Messages that failed the verification (provably or not).
+    pub invalid_messages: u64,
+    /// Rounds in which the party did not send its messages in time.
+    pub timeouts: u64,
+    /// Broadcasts that were different for different recipients.
+    pub equivocations: u64,
+}
+
+impl FaultCounts {
+    /// The total number of faults.
+    pub fn total(&self) -> u64 {
+        self.invalid_messages
+            .saturating_add(self.timeouts)
+            .saturating_add(self.equivocations)
+    }
+}
+
+/// An error returned by [`ReputationLedger::check_parties`].
+#[derive(Debug, Clone, Display)]
+#[displaydoc("The parties {0:?} have more faults than allowed")]
+pub struct ExcludedParties<Verifier: Debug>(pub BTreeSet<Verifier>);
+
+/// The fault statistics of the parties with the given identities,
+/// collected from the errors of any number of sessions.
+///
+/// The ledger is serializable, so that the application can persist it between the sessions.
+/// It only records what this party has observed, so a fault of a party
+/// may also be caused by a faulty transport or by this party itself;
+/// the fault threshold should be chosen with that in mind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "Verifier: Serialize"))]
+#[serde(bound(deserialize = "Verifier: for<'x> Deserialize<'x> + Ord"))]
+pub struct ReputationLedger<Verifier: Ord> {
+    counts: BTreeMap<Verifier, FaultCounts>,
+    fault_threshold: Option<u64>,
+}
+
+impl<Verifier: Ord> Default for ReputationLedger<Verifier> {
+    fn default() -> Self {
+        Self {
+            counts: BTreeMap::new(),
+            fault_threshold: None,
+        }
+    }
+}
+
+impl<Verifier: Ord + Clone + Debug> ReputationLedger<Verifier> {
+    /// Creates an empty ledger with no fault threshold.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the total number of faults (see [`FaultCounts::total`]) above which
+    /// a party is refused by [`Self::check_parties`] (`None`, the default, means no limit).
+    pub fn set_fault_threshold(&mut self, threshold: Option<u64>) {
+        self.fault_threshold = threshold;
+    }
+
+    /// Records the faults of other parties contained in an error returned by a session.
+    ///
+    /// Local errors and correctness proofs do not blame anyone and are ignored.
+    ///
+    /// Equivocations are not recorded here: an error about conflicting broadcasts
+    /// names the party that echoed them, and it is only counted as an invalid message
+    /// of that party. The equivocating party is blamed by passing the evidence
+    /// created by [`Session::make_evidence`](`super::Session::make_evidence`)
+    /// for that error to [`Self::record_evidence`].
+    pub fn record_error<Res: ProtocolResult>(&mut self, error: &Error<Res, Verifier>) {
+        match error {
+            Error::Provable { party, .. } | Error::Remote(RemoteError { party, .. }) => {
+                self.entry(party).invalid_messages += 1
+            }
+            Error::Timeout { missing } => {
+                for party in missing.iter() {
+                    self.entry(party).timeouts += 1;
+                }
+            }
+            Error::Faults(faults) => {
+                for fault in faults.values() {
+                    self.record_error(fault);
+                }
+            }
+            Error::Local(_) | Error::Proof { .. } => {}
+        }
+    }
+
+    /// Checks an evidence packet accusing one of the given parties of equivocation
+    /// (see [`verify_evidence`](`super::verify_evidence`)), and records the equivocation
+    /// if the packet proves it.
+    ///
+    /// Packets with other claims are not recorded, since their faults
+    /// are already recorded from the corresponding errors by [`Self::record_error`].
+    pub fn record_evidence<Sig>(
+        &mut self,
+        packet: &EvidencePacket<Sig, Verifier>,
+        parties: &BTreeSet<Verifier>,
+    ) -> Verdict
+    where
+        Sig: Clone,
+        Verifier: PrehashVerifier<Sig>,
+    {
+        let verdict = check_evidence(packet, parties);
+        if packet.claim() == &Claim::Equivocation && verdict == Verdict::Proven {
+            self.entry(packet.accused()).equivocations += 1;
+        }
+        verdict
+    }
+
+    /// Records a message of the given party that failed the verification.
+    pub fn record_invalid_message(&mut self, party: &Verifier) {
+        self.entry(party).invalid_messages += 1;
+    }
+
+    /// Records a round in which the given party did not send its messages in time.
+    pub fn record_timeout(&mut self, party: &Verifier) {
+        self.entry(party).timeouts += 1;
+    }
+
+    /// Records an equivocation of the given party.
+    pub fn record_equivocation(&mut self, party: &Verifier) {
+        self.entry(party).equivocations += 1;
+    }
+
+    /// Returns the fault counts of the given party (all zero if it has no recorded faults).
+    pub fn counts(&self, party: &Verifier) -> FaultCounts {
+        self.counts.get(party).copied().unwrap_or_default()
+    }
+
+    /// Returns the fault counts of all the parties with recorded faults.
+    pub fn all_counts(&self) -> &BTreeMap<Verifier, FaultCounts> {
+        &self.counts
+    }
+
+    /// Forgets the faults of the given party.
+    pub fn reset(&mut self, party: &Verifier) {
+        self.counts.remove(party);
+    }
+
+    /// Checks that none of the given parties has more faults than the threshold
+    /// (see [`Self::set_fault_threshold`]).
+    ///
+    /// Intended to be called before starting a session with these parties.
+    pub fn check_parties(
+        &self,
+        parties: &BTreeSet<Verifier>,
+    ) -> Result<(), ExcludedParties<Verifier>> {
+        let threshold = match self.fault_threshold {
+            Some(threshold) => threshold,
+            None => return Ok(()),
+        };
+        let excluded = parties
+            .iter()
+            .filter(|party| self.counts(party).total() > threshold)
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        if excluded.is_empty() {
+            Ok(())
+        } else {
+            Err(ExcludedParties(excluded))
+        }
+    }
+
+    fn entry(&mut self, party: &Verifier) -> &mut FaultCounts {
+        self.counts.entry(party.clone()).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+
+    use super::{FaultCounts, ReputationLedger};
+    use crate::rounds::{ProtocolId, ProtocolResult};
+    use crate::sessions::signed_message::{MessageType, SignedMessage, VerifiedMessage};
+    use crate::sessions::{
+        Claim, EchoError, Error, EvidenceContext, EvidencePacket, LocalError, ProvableError,
+        RemoteError, RemoteErrorEnum, SessionId, Verdict,
+    };
+
+    #[derive(Debug)]
+    struct DummyResult;
+
+    impl ProtocolResult for DummyResult {
+        const PROTOCOL_ID: ProtocolId = ProtocolId::KeyInit;
+        type Success = ();
+        type ProvableError = ();
+        type CorrectnessProof = ();
+    }
+
+    #[test]
+    fn record_errors() {
+        let mut ledger = ReputationLedger::<u8>::new();
+
+        ledger.record_error(&Error::<DummyResult, u8>::Provable {
+            party: 1,
+            error: ProvableError::Echo(EchoError::ConflictingBroadcasts),
+        });
+        ledger.record_error(&Error::<DummyResult, u8>::Faults(BTreeMap::from([
+            (
+                1,
+                Error::Remote(RemoteError {
+                    party: 1,
+                    error: RemoteErrorEnum::InvalidContents(String::new()),
+                }),
+            ),
+            (
+                2,
+                Error::Provable {
+                    party: 2,
+                    error: ProvableError::Protocol(()),
+                },
+            ),
+        ])));
+        ledger.record_error(&Error::<DummyResult, u8>::Timeout {
+            missing: BTreeSet::from([2, 3]),
+        });
+        ledger.record_error(&Error::<DummyResult, u8>::Local(LocalError(String::new())));
+
+        // The party reporting the conflicting broadcasts is not blamed for an equivocation
+        assert_eq!(
+            ledger.counts(&1),
+            FaultCounts {
+                invalid_messages: 2,
+                timeouts: 0,
+                equivocations: 0
+            }
+        );
+        assert_eq!(ledger.counts(&2).total(), 2);
+        assert_eq!(ledger.counts(&3).timeouts, 1);
+        assert_eq!(ledger.counts(&4), FaultCounts::default());
+
+        // No threshold by default
+        let parties = BTreeSet::from([1, 2, 3, 4]);
+        assert!(ledger.check_parties(&parties).is_ok());
+
+        ledger.set_fault_threshold(Some(1));
+        let excluded = ledger.check_parties(&parties).unwrap_err();
+        assert_eq!(excluded.0, BTreeSet::from([1, 2]));
+
+        ledger.reset(&1);
+        ledger.reset(&2);
+        assert!(ledger.check_parties(&parties).is_ok());
+
+        // The ledger survives a serialization round-trip
+        ledger.record_equivocation(&4);
+        let serialized =
+            bincode::serde::encode_to_vec(&ledger, bincode::config::standard()).unwrap();
+        let (deserialized, _): (ReputationLedger<u8>, _) =
+            bincode::serde::decode_from_slice(&serialized, bincode::config::standard()).unwrap();
+        assert_eq!(deserialized.all_counts(), ledger.all_counts());
+        assert!(deserialized.check_parties(&parties).is_ok());
+    }
+
+    #[test]
+    fn record_evidence() {
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let parties = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let session_id = SessionId::from_seed(b"abcde");
+        let accused = &signers[0];
+        let accused_id = *accused.verifying_key();
+
+        let sign = |message_type, payload: &[u8]| -> SignedMessage<Signature> {
+            VerifiedMessage::new(
+                &mut OsRng,
+                accused,
+                &session_id,
+                ProtocolId::KeyInit,
+                None,
+                1,
+                message_type,
+                payload,
+            )
+            .unwrap()
+            .into_unverified()
+        };
+        let make_packet = |messages| {
+            EvidencePacket::new(
+                session_id,
+                ProtocolId::KeyInit,
+                accused_id,
+                Claim::Equivocation,
+                messages,
+                EvidenceContext::None,
+            )
+        };
+
+        let mut ledger = ReputationLedger::<VerifyingKey>::new();
+
+        // Two different broadcasts prove the equivocation
+        let packet = make_packet(vec![
+            sign(MessageType::Broadcast, b"first"),
+            sign(MessageType::Broadcast, b"second"),
+        ]);
+        assert_eq!(ledger.record_evidence(&packet, &parties), Verdict::Proven);
+        assert_eq!(ledger.counts(&accused_id).equivocations, 1);
+
+        // Different direct messages do not
+        let packet = make_packet(vec![
+            sign(MessageType::Direct, b"first"),
+            sign(MessageType::Direct, b"second"),
+        ]);
+        assert!(matches!(
+            ledger.record_evidence(&packet, &parties),
+            Verdict::Rejected(_)
+        ));
+        assert_eq!(ledger.counts(&accused_id).equivocations, 1);
+
+        // Neither does an equivocation of a party that is not one of the given parties
+        let mut others = parties.clone();
+        others.remove(&accused_id);
+        let packet = make_packet(vec![
+            sign(MessageType::Broadcast, b"first"),
+            sign(MessageType::Broadcast, b"second"),
+        ]);
+        assert!(matches!(
+            ledger.record_evidence(&packet, &others),
+            Verdict::Rejected(_)
+        ));
+        assert_eq!(ledger.counts(&accused_id).equivocations, 1);
+    }
+}
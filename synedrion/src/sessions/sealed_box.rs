@@ -0,0 +1,271 @@
+//! Anonymous public key encryption of messages to the identity key of a party.
+
+use alloc::boxed::Box;
+#[cfg(feature = "relay")]
+use alloc::string::String;
+
+#[cfg(feature = "relay")]
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+#[cfg(feature = "relay")]
+use k256::NonZeroScalar;
+use k256::{
+    ecdsa::{SigningKey, VerifyingKey},
+    elliptic_curve::{point::AffineCoordinates, sec1::ToEncodedPoint},
+    PublicKey,
+};
+#[cfg(feature = "relay")]
+use rand_core::CryptoRngCore;
+#[cfg(feature = "relay")]
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+#[cfg(all(feature = "relay", feature = "ed25519"))]
+use super::ed25519::{x25519, x25519_base};
+#[cfg(feature = "relay")]
+use crate::tools::hashing::{Chain, FofHasher};
+#[cfg(feature = "relay")]
+use crate::tools::serde_bytes;
+
+/// A public key used for the Diffie-Hellman key agreement with a party.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgreementKey {
+    /// A secp256k1 key (the ECDSA identity keys are used directly).
+    Secp256k1(PublicKey),
+    /// An X25519 key (the Ed25519 identity keys converted to the Montgomery form).
+    #[cfg(feature = "ed25519")]
+    X25519([u8; 32]),
+}
+
+impl AgreementKey {
+    /// Returns the byte representation of the key
+    /// (compressed SEC1 for secp256k1, the u-coordinate for X25519).
+    pub(crate) fn to_bytes(self) -> Box<[u8]> {
+        match self {
+            Self::Secp256k1(key) => key.to_encoded_point(true).as_bytes().into(),
+            #[cfg(feature = "ed25519")]
+            Self::X25519(key) => key.into(),
+        }
+    }
+
+    /// Parses a key of the same kind as this one from its byte representation.
+    #[cfg(feature = "relay")]
+    fn parse_same_kind(&self, bytes: &[u8]) -> Result<Self, String> {
+        match self {
+            Self::Secp256k1(_) => PublicKey::from_sec1_bytes(bytes)
+                .map(Self::Secp256k1)
+                .map_err(|_| String::from("Invalid secp256k1 key")),
+            #[cfg(feature = "ed25519")]
+            Self::X25519(_) => bytes
+                .try_into()
+                .map(Self::X25519)
+                .map_err(|_| String::from("Invalid X25519 key")),
+        }
+    }
+
+    /// Generates an ephemeral key of the same kind as this one,
+    /// returning its byte representation and the shared secret with this key.
+    #[cfg(feature = "relay")]
+    fn ephemeral_agreement(
+        &self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<(Box<[u8]>, SharedSecret), String> {
+        match self {
+            Self::Secp256k1(key) => {
+                let ephemeral_secret = NonZeroScalar::random(rng);
+                let ephemeral_key =
+                    Self::Secp256k1(PublicKey::from_secret_scalar(&ephemeral_secret));
+                let shared_secret = (key.to_projective() * *ephemeral_secret).to_affine();
+                Ok((
+                    ephemeral_key.to_bytes(),
+                    Zeroizing::new(shared_secret.x().into()),
+                ))
+            }
+            #[cfg(feature = "ed25519")]
+            Self::X25519(key) => {
+                let mut ephemeral_secret = Zeroizing::new([0u8; 32]);
+                rng.fill_bytes(ephemeral_secret.as_mut());
+                let ephemeral_key = Self::X25519(x25519_base(&ephemeral_secret));
+                let shared_secret = x25519(&ephemeral_secret, key)
+                    .ok_or_else(|| String::from("Invalid X25519 key"))?;
+                Ok((ephemeral_key.to_bytes(), shared_secret))
+            }
+        }
+    }
+}
+
+/// The result of a Diffie-Hellman key agreement.
+pub type SharedSecret = Zeroizing<[u8; 32]>;
+
+/// An identity key of a party that messages can be sealed to.
+pub trait SealingKey {
+    /// Returns the public key used for the key agreement.
+    fn sealing_key(&self) -> AgreementKey;
+}
+
+/// The secret counterpart of a [`SealingKey`] allowing a party to open the messages sealed to it.
+pub trait OpeningKey {
+    /// Returns the result of the Diffie-Hellman key agreement with the given public key,
+    /// or `None` if the key is of a different kind or the result is degenerate.
+    fn diffie_hellman(&self, public_key: &AgreementKey) -> Option<SharedSecret>;
+}
+
+impl SealingKey for VerifyingKey {
+    fn sealing_key(&self) -> AgreementKey {
+        AgreementKey::Secp256k1(PublicKey::from(self))
+    }
+}
+
+impl OpeningKey for SigningKey {
+    fn diffie_hellman(&self, public_key: &AgreementKey) -> Option<SharedSecret> {
+        match public_key {
+            AgreementKey::Secp256k1(key) => {
+                let shared_secret =
+                    (key.to_projective() * *self.as_nonzero_scalar().as_ref()).to_affine();
+                Some(Zeroizing::new(shared_secret.x().into()))
+            }
+            #[cfg(feature = "ed25519")]
+            AgreementKey::X25519(_) => None,
+        }
+    }
+}
+
+#[cfg(feature = "relay")]
+fn encryption_key(
+    shared_secret: &SharedSecret,
+    ephemeral_key: &[u8],
+    recipient_key: &AgreementKey,
+) -> Key {
+    let hash = FofHasher::new_with_dst(b"SealedBox")
+        .chain_bytes(shared_secret.as_ref())
+        .chain_bytes(ephemeral_key)
+        .chain_bytes(&recipient_key.to_bytes())
+        .finalize();
+    Key::clone_from_slice(hash.as_ref())
+}
+
+#[cfg(feature = "relay")]
+/// A message encrypted to the public key of the recipient.
+///
+/// A new ephemeral key is generated for every message, so the encryption key is never reused,
+/// and the box does not reveal anything about the sender.
+/// The sender must be authenticated by other means (e.g. by signing the plaintext).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SealedBox {
+    /// The representation of the ephemeral public key (see [`AgreementKey::to_bytes`]).
+    #[serde(with = "serde_bytes::as_base64")]
+    ephemeral_key: Box<[u8]>,
+    #[serde(with = "serde_bytes::as_base64")]
+    ciphertext: Box<[u8]>,
+}
+
+#[cfg(feature = "relay")]
+impl SealedBox {
+    /// Encrypts `plaintext` to `recipient_key`.
+    ///
+    /// The same `associated_data` must be provided when opening the box.
+    pub fn seal(
+        rng: &mut impl CryptoRngCore,
+        recipient_key: &AgreementKey,
+        associated_data: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Self, String> {
+        let (ephemeral_key, shared_secret) = recipient_key.ephemeral_agreement(rng)?;
+        let key = encryption_key(&shared_secret, &ephemeral_key, recipient_key);
+
+        // The key is unique for each box, so a constant nonce can be used.
+        let ciphertext = ChaCha20Poly1305::new(&key)
+            .encrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: plaintext,
+                    aad: associated_data,
+                },
+            )
+            .map_err(|_| String::from("Failed to encrypt the message"))?;
+
+        Ok(Self {
+            ephemeral_key,
+            ciphertext: ciphertext.into(),
+        })
+    }
+
+    /// Decrypts the box sealed to the public key of `recipient`.
+    pub fn open(
+        &self,
+        recipient: &impl OpeningKey,
+        recipient_key: &AgreementKey,
+        associated_data: &[u8],
+    ) -> Result<Box<[u8]>, String> {
+        let ephemeral_key = recipient_key.parse_same_kind(&self.ephemeral_key)?;
+        let shared_secret = recipient
+            .diffie_hellman(&ephemeral_key)
+            .ok_or_else(|| String::from("Invalid ephemeral key"))?;
+        let key = encryption_key(&shared_secret, &self.ephemeral_key, recipient_key);
+
+        ChaCha20Poly1305::new(&key)
+            .decrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: associated_data,
+                },
+            )
+            .map(|plaintext| plaintext.into())
+            .map_err(|_| String::from("Failed to decrypt the message"))
+    }
+}
+
+#[cfg(all(test, feature = "relay"))]
+mod tests {
+    use k256::ecdsa::SigningKey;
+    use rand_core::OsRng;
+
+    use super::{SealedBox, SealingKey};
+
+    #[test]
+    fn seal_and_open() {
+        let recipient = SigningKey::random(&mut OsRng);
+        let recipient_key = recipient.verifying_key().sealing_key();
+        let plaintext = b"direct message";
+
+        let sealed = SealedBox::seal(&mut OsRng, &recipient_key, b"context", plaintext).unwrap();
+        let opened = sealed.open(&recipient, &recipient_key, b"context").unwrap();
+        assert_eq!(&*opened, plaintext);
+
+        // Wrong associated data
+        assert!(sealed.open(&recipient, &recipient_key, b"other").is_err());
+
+        // Wrong recipient
+        let other = SigningKey::random(&mut OsRng);
+        let other_key = other.verifying_key().sealing_key();
+        assert!(sealed.open(&other, &other_key, b"context").is_err());
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn seal_and_open_ed25519() {
+        use signature::Keypair;
+
+        use crate::sessions::Ed25519Signer;
+
+        let recipient = Ed25519Signer::new(ed25519_dalek::SigningKey::generate(&mut OsRng));
+        let recipient_key = recipient.verifying_key().sealing_key();
+        let plaintext = b"direct message";
+
+        let sealed = SealedBox::seal(&mut OsRng, &recipient_key, b"context", plaintext).unwrap();
+        let opened = sealed.open(&recipient, &recipient_key, b"context").unwrap();
+        assert_eq!(&*opened, plaintext);
+
+        // Wrong recipient
+        let other = Ed25519Signer::new(ed25519_dalek::SigningKey::generate(&mut OsRng));
+        let other_key = other.verifying_key().sealing_key();
+        assert!(sealed.open(&other, &other_key, b"context").is_err());
+
+        // A key of a different kind
+        let other = SigningKey::random(&mut OsRng);
+        assert!(sealed.open(&other, &recipient_key, b"context").is_err());
+    }
+}
@@ -1,29 +1,315 @@
 use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::format;
+#[cfg(feature = "encryption")]
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
 
-use rand_core::CryptoRngCore;
+use rand_core::{CryptoRng, CryptoRngCore, RngCore};
+use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
 use signature::{
     hazmat::{PrehashVerifier, RandomizedPrehashSigner},
     Keypair,
 };
 
-use super::echo::{EchoAccum, EchoRound};
-use super::error::{Error, LocalError, ProvableError, RemoteError, RemoteErrorEnum};
+use super::clock::Clock;
+use super::echo::{EchoAccum, EchoError, EchoRound};
+use super::error::{
+    Error, LocalError, ProvableError, RemoteError, RemoteErrorEnum, SessionInitError,
+};
+use super::evidence::{Claim, EvidenceContext, EvidencePacket};
 use super::message_bundle::{MessageBundle, MessageBundleEnum, VerifiedMessageBundle};
+#[cfg(feature = "encryption")]
+use super::pairwise::PairwiseKeys;
+#[cfg(feature = "relay")]
+use super::relay::{RelayMessage, RelayedBatch, RelayedMessage};
+#[cfg(feature = "relay")]
+use super::sealed_box::SealedBox;
+#[cfg(feature = "encryption")]
+use super::sealed_box::{OpeningKey, SealingKey};
 use super::signed_message::{MessageType, SessionId, SignedMessage, VerifiedMessage};
 use super::type_erased::{
-    self, AccumAddError, DynArtifact, DynFinalizable, DynPayload, DynRoundAccum, ReceiveError,
+    self, deserialize_message, serialize_message, AccumAddError, DynArtifact, DynFinalizable,
+    DynPayload, DynRoundAccum, ReceiveError,
 };
-use crate::rounds::{self, FirstRound, ProtocolResult, Round};
+use crate::rounds::{self, FirstRound, Protocol, ProtocolResult};
+#[cfg(feature = "testing")]
+use crate::testing::malicious::{Behavior, Malicious, Misbehave};
+use crate::tools::hashing::{Chain, FofHasher, HashOutput, XofHasher, XofRng};
+
+/// The maximum number of parties in a session, including this party.
+///
+/// This is the largest size the sessions are run with in the tests
+/// (KeyInit in the `key_init_many_parties` test) and in the `scaling` benchmark (KeyInit and AuxGen).
+/// The messages of the echo rounds contain the broadcasts of all the parties,
+/// and in some protocols each party creates a proof for every pair of other parties,
+/// so the traffic and the computation per party grow quadratically with the number of parties;
+/// larger sessions are refused until their message sizes and running times are checked.
+pub const MAX_PARTIES: usize = 20;
+
+/// Defines how the faults of other parties are handled within a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AbortPolicy {
+    /// The first fault is returned as an error by [`RoundAccumulator::add_fault`],
+    /// so that the session can be aborted right away.
+    #[default]
+    Immediate,
+    /// The faults are collected by [`RoundAccumulator::add_fault`]
+    /// until messages from all the expected parties are either processed or found faulty,
+    /// and then returned together by [`Session::finalize_round`] as [`Error::Faults`].
+    CollectAll,
+}
+
+/// The deadlines of the current round and of the whole session.
+struct Deadlines {
+    clock: Arc<dyn Clock + Send + Sync>,
+    round_timeout: Option<Duration>,
+    round_deadline: Option<Duration>,
+    session_deadline: Option<Duration>,
+}
+
+impl Deadlines {
+    /// Sets the deadline of a round starting now.
+    fn start_round(&mut self) {
+        let now = self.clock.now();
+        self.round_deadline = self
+            .round_timeout
+            .map(|timeout| now.saturating_add(timeout));
+    }
+
+    fn earliest(&self) -> Option<Duration> {
+        match (self.round_deadline, self.session_deadline) {
+            (Some(round), Some(session)) => Some(round.min(session)),
+            (round, session) => round.or(session),
+        }
+    }
+}
 
 struct Context<Signer, Verifier> {
     signer: Signer,
     my_id: Verifier,
     session_id: SessionId,
+    /// The fingerprint of the scheme parameters of the protocol (see [`Protocol::params_fingerprint`]).
+    params: Option<HashOutput>,
+    strict: bool,
+    abort_policy: AbortPolicy,
+    /// Whether the outgoing messages are kept to be sent again with [`Session::resend`].
+    retain_messages: bool,
+    /// The deadlines set with [`Session::set_deadlines`].
+    deadlines: Option<Deadlines>,
+    /// The observers set with [`Session::set_observers`].
+    observers: BTreeSet<Verifier>,
+    /// Whether any messages were created in the session (after which the observers cannot be set).
+    messages_made: AtomicBool,
+    /// The source of all the randomness, if the session is deterministic
+    /// (see [`Session::new_seeded`]).
+    seed: Option<SessionSeed>,
+    #[cfg(feature = "compression")]
+    compression: bool,
+    /// The keys for the encryption of the direct messages (empty if the encryption is disabled).
+    #[cfg(feature = "encryption")]
+    pairwise_keys: BTreeMap<Verifier, PairwiseKeys>,
+    /// The probability of corrupting an outgoing message, scaled to `2^32`.
+    #[cfg(feature = "chaos")]
+    corruption_threshold: u64,
+}
+
+impl<Signer, Verifier: Ord> Context<Signer, Verifier> {
+    /// Applies the transport-level transformations to a message right before it is sent out.
+    #[cfg_attr(not(feature = "compression"), allow(unused_variables))]
+    fn prepare_message<Sig>(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        message: SignedMessage<Sig>,
+    ) -> Result<SignedMessage<Sig>, LocalError>
+    where
+        Signer: RandomizedPrehashSigner<Sig>,
+    {
+        #[cfg(feature = "compression")]
+        if self.compression {
+            return message.compress(rng, &self.signer);
+        }
+        Ok(message)
+    }
+
+    /// Encrypts the direct part of the message if the encryption is enabled.
+    #[cfg(feature = "encryption")]
+    fn encrypt_message<Sig>(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &Verifier,
+        message: MessageBundle<Sig>,
+    ) -> Result<MessageBundle<Sig>, LocalError> {
+        match self.pairwise_keys.get(destination) {
+            Some(keys) => message.map_direct(|direct| direct.encrypt(rng, keys)),
+            None => Ok(message),
+        }
+    }
+
+    /// Decides whether the next outgoing payload is to be corrupted.
+    #[cfg(feature = "chaos")]
+    fn should_corrupt(&self, rng: &mut impl CryptoRngCore) -> bool {
+        u64::from(rng.next_u32()) < self.corruption_threshold
+    }
+
+    /// Corrupts an outgoing payload if it is chosen to be corrupted.
+    #[cfg(feature = "chaos")]
+    fn maybe_corrupt(&self, rng: &mut impl CryptoRngCore, mut payload: Box<[u8]>) -> Box<[u8]> {
+        if self.should_corrupt(rng) {
+            flip_random_bit(rng, &mut payload);
+        }
+        payload
+    }
+
+    /// Decrypts the direct part of the message if it is encrypted and the encryption is enabled.
+    ///
+    /// If it is not, the message will fail the signature verification.
+    #[cfg(feature = "encryption")]
+    fn decrypt_message<Sig>(
+        &self,
+        from: &Verifier,
+        message: MessageBundle<Sig>,
+    ) -> Result<MessageBundle<Sig>, String> {
+        match self.pairwise_keys.get(from) {
+            Some(keys) => message.map_direct(|direct| direct.decrypt(keys)),
+            None => Ok(message),
+        }
+    }
+}
+
+impl<Signer, Verifier: Serialize> Context<Signer, Verifier> {
+    /// Returns the source of randomness for an operation of the session:
+    /// the caller's generator, or, if the session is deterministic,
+    /// a generator derived from the seed, the transcript and the identity of the operation.
+    fn rng<'a, R: CryptoRngCore>(
+        &self,
+        rng: &'a mut R,
+        operation: &[u8],
+        round: (u8, bool),
+        party: Option<&Verifier>,
+    ) -> SessionRng<'a, R> {
+        match &self.seed {
+            Some(seed) => SessionRng::Seeded(Box::new(derive_rng(
+                seed.seed.expose_secret(),
+                &seed.transcript,
+                &self.session_id,
+                operation,
+                round,
+                party,
+            ))),
+            None => SessionRng::Caller(rng),
+        }
+    }
+}
+
+/// The state the randomness of a deterministic session is derived from.
+struct SessionSeed {
+    seed: SecretBox<[u8; 32]>,
+    /// The hash of the set of parties and of all the messages received so far.
+    ///
+    /// Binding the derived generators to it ensures that the same seed
+    /// never produces the same values for different parties or different incoming messages
+    /// (which would leak the secrets protected by that randomness).
+    transcript: HashOutput,
+}
+
+impl SessionSeed {
+    #[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+    fn new<Verifier: Serialize>(seed: &[u8; 32], verifiers: &BTreeSet<Verifier>) -> Self {
+        Self {
+            seed: SecretBox::new(Box::new(*seed)),
+            transcript: FofHasher::new_with_dst(b"SessionTranscript")
+                .chain(verifiers)
+                .finalize(),
+        }
+    }
+
+    /// Adds the messages received in the given round to the transcript.
+    fn add_received_messages<Sig, Verifier: Serialize>(
+        &mut self,
+        round: (u8, bool),
+        messages: &BTreeMap<Verifier, VerifiedMessageBundle<Sig>>,
+    ) {
+        let mut digest = FofHasher::new_with_dst(b"SessionTranscript")
+            .chain(&self.transcript)
+            .chain(&round);
+        for (from, message) in messages {
+            digest = digest
+                .chain(from)
+                .chain(&message.broadcast_payload())
+                .chain(&message.direct_payload())
+                .chain(&message.echo_payload());
+        }
+        self.transcript = digest.finalize();
+    }
+}
+
+/// The source of randomness for an operation of the session.
+enum SessionRng<'a, R> {
+    /// The generator supplied by the caller.
+    Caller(&'a mut R),
+    /// A generator derived from the seed of a deterministic session.
+    Seeded(Box<XofRng>),
+}
+
+impl<R: CryptoRngCore> RngCore for SessionRng<'_, R> {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Caller(rng) => rng.next_u32(),
+            Self::Seeded(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Caller(rng) => rng.next_u64(),
+            Self::Seeded(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Caller(rng) => rng.fill_bytes(dest),
+            Self::Seeded(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        match self {
+            Self::Caller(rng) => rng.try_fill_bytes(dest),
+            Self::Seeded(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+impl<R: CryptoRngCore> CryptoRng for SessionRng<'_, R> {}
+
+/// Derives a generator for the given operation of a deterministic session.
+///
+/// Every operation gets its own stream, so the generated values do not depend
+/// on the order in which the operations are performed.
+fn derive_rng<Verifier: Serialize>(
+    seed: &[u8; 32],
+    transcript: &HashOutput,
+    session_id: &SessionId,
+    operation: &[u8],
+    round: (u8, bool),
+    party: Option<&Verifier>,
+) -> XofRng {
+    XofHasher::new_with_dst(b"SessionRandomness")
+        .chain_bytes(seed)
+        .chain(transcript)
+        .chain(session_id)
+        .chain_bytes(operation)
+        .chain(&round)
+        .chain(&party)
+        .finalize_to_rng()
 }
 
 enum SessionType<Verifier, Res, Sig> {
@@ -37,10 +323,48 @@ enum SessionType<Verifier, Res, Sig> {
     },
 }
 
+impl<Verifier, Res, Sig> SessionType<Verifier, Res, Sig>
+where
+    Res: ProtocolResult,
+    Verifier: Clone + Ord + Serialize + for<'de> Deserialize<'de>,
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+{
+    /// Returns the parties the round itself sends messages to.
+    fn round_destinations(&self) -> &BTreeSet<Verifier> {
+        match self {
+            Self::Normal { this_round, .. } => this_round.message_destinations(),
+            Self::Echo { echo_round, .. } => echo_round.message_destinations(),
+        }
+    }
+
+    /// Returns the parties the round sends messages to, including the observers
+    /// if there is a broadcast for them (the echo rounds include them already).
+    fn message_destinations(&self, observers: &BTreeSet<Verifier>) -> BTreeSet<Verifier> {
+        let mut destinations = self.round_destinations().clone();
+        if let Self::Normal {
+            broadcast: Some(_), ..
+        } = self
+        {
+            destinations.extend(observers.iter().cloned());
+        }
+        destinations
+    }
+}
+
+/// The outgoing messages of a round, by destination.
+type SentMessages<Sig, Verifier> = BTreeMap<Verifier, MessageBundle<Sig>>;
+
 /// The session state where it is ready to send messages.
 pub struct Session<Res, Sig, Signer, Verifier> {
     tp: SessionType<Verifier, Res, Sig>,
     context: Context<Signer, Verifier>,
+    /// The destinations of the messages of the current round (see [`Self::message_destinations`]).
+    destinations: BTreeSet<Verifier>,
+    /// The retained outgoing messages of the previous rounds.
+    sent_messages: BTreeMap<(u8, bool), SentMessages<Sig, Verifier>>,
+    /// The broadcasts received in the previous rounds, included in the evidence of the faults
+    /// that are checked against them.
+    previous_broadcasts: BTreeMap<Verifier, Vec<SignedMessage<Sig>>>,
 }
 
 enum MessageFor {
@@ -48,6 +372,17 @@ enum MessageFor {
     NextRound,
 }
 
+fn check_num_parties<Verifier: Ord>(
+    my_id: Verifier,
+    verifiers: &BTreeSet<Verifier>,
+) -> Result<(), SessionInitError> {
+    let num_parties = verifiers.len() + usize::from(!verifiers.contains(&my_id));
+    if num_parties > MAX_PARTIES {
+        return Err(SessionInitError::TooManyParties(num_parties));
+    }
+    Ok(())
+}
+
 fn route_message_normal<Res: ProtocolResult, Sig, Verifier>(
     round: &dyn DynFinalizable<Verifier, Res>,
     message: &MessageBundle<Sig>,
@@ -118,7 +453,18 @@ fn wrap_receive_result<Res: ProtocolResult, Verifier: Clone, T>(
     })
 }
 
+/// Flips a randomly chosen bit of a payload (if it is not empty).
+#[cfg(feature = "chaos")]
+fn flip_random_bit(rng: &mut impl CryptoRngCore, payload: &mut [u8]) {
+    if payload.is_empty() {
+        return;
+    }
+    let bit = rng.next_u64() % (payload.len() as u64 * 8);
+    payload[(bit / 8) as usize] ^= 1 << (bit % 8);
+}
+
 /// Possible outcomes of successfully finalizing a round.
+#[allow(clippy::large_enum_variant)]
 pub enum FinalizeOutcome<Res: ProtocolResult, Sig, Signer, Verifier> {
     /// The protocol result is available.
     Success(Res::Success),
@@ -138,22 +484,113 @@ where
     Verifier: Debug + Clone + PrehashVerifier<Sig> + Ord + Serialize + for<'de> Deserialize<'de>,
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
 {
-    pub(crate) fn new<
-        R: FirstRound<Verifier>
-            + DynFinalizable<Verifier, Res>
-            + Round<Verifier, Result = Res>
-            + 'static,
-    >(
+    /// Creates a session starting the protocol `Res`.
+    pub(crate) fn new(
         rng: &mut impl CryptoRngCore,
         session_id: SessionId,
         signer: Signer,
         verifiers: &BTreeSet<Verifier>,
-        inputs: R::Inputs,
-    ) -> Result<Self, LocalError> {
+        inputs: <Res::FirstRound as FirstRound<Verifier>>::Inputs,
+    ) -> Result<Self, SessionInitError>
+    where
+        Res: Protocol<Verifier>,
+        Res::FirstRound: DynFinalizable<Verifier, Res> + 'static,
+    {
+        check_num_parties(signer.verifying_key(), verifiers)?;
+        let my_id = signer.verifying_key();
+        let mut other_parties = verifiers.clone();
+        other_parties.remove(&my_id);
+        let typed_round = Res::new_round(
+            rng,
+            session_id.as_ref(),
+            other_parties,
+            my_id.clone(),
+            inputs,
+        )
+        .map_err(|err| LocalError(format!("Failed to initialize the protocol: {err:?}")))?;
+        Ok(Self::from_round(
+            rng,
+            session_id,
+            Res::params_fingerprint(),
+            None,
+            signer,
+            Box::new(typed_round),
+        )?)
+    }
+
+    /// Creates a session starting the protocol `Res`, with all the randomness
+    /// (including the one used for signing the messages) derived from `seed`
+    /// instead of the generators passed to the methods.
+    ///
+    /// The messages and the result are fully determined by the seed, the session ID,
+    /// the set of parties, the inputs and the messages received from the other parties.
+    /// Every round derives its randomness from a hash of all the messages received so far,
+    /// so a seed reused with different parties or different incoming messages
+    /// does not produce the same values.
+    #[cfg(any(feature = "key-gen", feature = "aux-gen"))]
+    pub(crate) fn new_seeded(
+        seed: &[u8; 32],
+        session_id: SessionId,
+        signer: Signer,
+        verifiers: &BTreeSet<Verifier>,
+        inputs: <Res::FirstRound as FirstRound<Verifier>>::Inputs,
+    ) -> Result<Self, SessionInitError>
+    where
+        Res: Protocol<Verifier>,
+        Res::FirstRound: DynFinalizable<Verifier, Res> + 'static,
+    {
+        check_num_parties(signer.verifying_key(), verifiers)?;
+        let my_id = signer.verifying_key();
+        let seed = SessionSeed::new(seed, verifiers);
+        let mut rng = derive_rng(
+            seed.seed.expose_secret(),
+            &seed.transcript,
+            &session_id,
+            b"init",
+            (0, false),
+            Some(&my_id),
+        );
+        let mut other_parties = verifiers.clone();
+        other_parties.remove(&my_id);
+        let typed_round = Res::new_round(
+            &mut rng,
+            session_id.as_ref(),
+            other_parties,
+            my_id.clone(),
+            inputs,
+        )
+        .map_err(|err| LocalError(format!("Failed to initialize the protocol: {err:?}")))?;
+        Ok(Self::from_round(
+            &mut rng,
+            session_id,
+            Res::params_fingerprint(),
+            Some(seed),
+            signer,
+            Box::new(typed_round),
+        )?)
+    }
+
+    /// Creates a session starting the protocol `Res`, with this party misbehaving
+    /// in the given way.
+    #[cfg(feature = "testing")]
+    pub(crate) fn new_malicious(
+        rng: &mut impl CryptoRngCore,
+        session_id: SessionId,
+        signer: Signer,
+        verifiers: &BTreeSet<Verifier>,
+        inputs: <Res::FirstRound as FirstRound<Verifier>>::Inputs,
+        behavior: Behavior,
+    ) -> Result<Self, SessionInitError>
+    where
+        Res: Protocol<Verifier>,
+        Res::FirstRound: Misbehave<Verifier>,
+        Malicious<Verifier, Res::FirstRound>: DynFinalizable<Verifier, Res> + 'static,
+    {
+        check_num_parties(signer.verifying_key(), verifiers)?;
         let my_id = signer.verifying_key();
         let mut other_parties = verifiers.clone();
         other_parties.remove(&my_id);
-        let typed_round = R::new(
+        let typed_round = Res::new_round(
             rng,
             session_id.as_ref(),
             other_parties,
@@ -161,11 +598,43 @@ where
             inputs,
         )
         .map_err(|err| LocalError(format!("Failed to initialize the protocol: {err:?}")))?;
-        let round: Box<dyn DynFinalizable<Verifier, Res>> = Box::new(typed_round);
+        let typed_round = Malicious::new(rng, typed_round, behavior);
+        Ok(Self::from_round(
+            rng,
+            session_id,
+            Res::params_fingerprint(),
+            None,
+            signer,
+            Box::new(typed_round),
+        )?)
+    }
+
+    fn from_round(
+        rng: &mut impl CryptoRngCore,
+        session_id: SessionId,
+        params: Option<HashOutput>,
+        seed: Option<SessionSeed>,
+        signer: Signer,
+        round: Box<dyn DynFinalizable<Verifier, Res>>,
+    ) -> Result<Self, LocalError> {
         let context = Context {
-            my_id,
+            my_id: signer.verifying_key(),
             signer,
             session_id,
+            params,
+            strict: false,
+            abort_policy: AbortPolicy::default(),
+            retain_messages: false,
+            deadlines: None,
+            observers: BTreeSet::new(),
+            messages_made: AtomicBool::new(false),
+            seed,
+            #[cfg(feature = "compression")]
+            compression: false,
+            #[cfg(feature = "encryption")]
+            pairwise_keys: BTreeMap::new(),
+            #[cfg(feature = "chaos")]
+            corruption_threshold: 0,
         };
         Self::new_internal(rng, context, round)
     }
@@ -175,6 +644,7 @@ where
         context: Context<Signer, Verifier>,
         round: Box<dyn DynFinalizable<Verifier, Res>>,
     ) -> Result<Self, LocalError> {
+        let rng = &mut context.rng(rng, b"broadcast", (round.round_num(), false), None);
         let broadcast = round.make_broadcast_message(rng)?;
 
         let signed_broadcast = if let Some(payload) = broadcast {
@@ -183,6 +653,8 @@ where
                     rng,
                     &context.signer,
                     &context.session_id,
+                    Res::PROTOCOL_ID,
+                    context.params,
                     round.round_num(),
                     MessageType::Broadcast,
                     &payload,
@@ -193,12 +665,16 @@ where
             None
         };
 
+        let tp = SessionType::Normal {
+            this_round: round,
+            broadcast: signed_broadcast,
+        };
         Ok(Self {
-            tp: SessionType::Normal {
-                this_round: round,
-                broadcast: signed_broadcast,
-            },
+            destinations: tp.message_destinations(&context.observers),
+            tp,
             context,
+            sent_messages: BTreeMap::new(),
+            previous_broadcasts: BTreeMap::new(),
         })
     }
 
@@ -212,6 +688,185 @@ where
         self.context.session_id
     }
 
+    /// Enables or disables the strict mode (disabled by default).
+    ///
+    /// In the strict mode every message returned by [`Self::make_message`] is checked first:
+    /// its signatures are verified, its contents must survive a serialization round-trip
+    /// byte-for-byte, and the proofs, partial signatures and secret shares it contains
+    /// are verified the way the recipient would (in every round of every protocol).
+    /// This catches local state or RNG corruption before any bad data reaches other parties,
+    /// at the cost of additional computation.
+    /// The setting is preserved for the subsequent rounds.
+    pub fn set_strict_mode(&mut self, strict: bool) {
+        self.context.strict = strict;
+    }
+
+    /// Sets the way the faults of other parties are handled
+    /// (the default is [`AbortPolicy::Immediate`]).
+    ///
+    /// The setting is preserved for the subsequent rounds.
+    pub fn set_abort_policy(&mut self, abort_policy: AbortPolicy) {
+        self.context.abort_policy = abort_policy;
+    }
+
+    /// Enables or disables the compression of the outgoing messages (disabled by default).
+    ///
+    /// A message is only compressed if it becomes smaller as a result,
+    /// which is marked in the message itself, so the receiving side needs no configuration
+    /// besides having the `compression` feature enabled.
+    /// The setting is preserved for the subsequent rounds.
+    #[cfg(feature = "compression")]
+    pub fn set_compression(&mut self, compression: bool) {
+        self.context.compression = compression;
+    }
+
+    /// Enables or disables keeping the outgoing messages (disabled by default),
+    /// so that they can be sent again with [`Self::resend`]
+    /// if the transport lost them.
+    ///
+    /// The messages are kept in the [`RoundAccumulator`] the artifacts are added to,
+    /// and are carried over to the subsequent sessions on finalization,
+    /// until the protocol finishes.
+    /// The setting is preserved for the subsequent rounds.
+    pub fn set_message_retention(&mut self, retain: bool) {
+        self.context.retain_messages = retain;
+    }
+
+    /// Sets the deadlines measured with the given clock (no deadlines by default):
+    /// each round has to be finalized within `round_timeout` after it started,
+    /// and the whole session within `session_ttl` from now.
+    ///
+    /// The session does not watch the clock by itself;
+    /// the deadlines are checked when [`Self::check_deadline`] is called.
+    /// The setting is preserved for the subsequent rounds,
+    /// with the round deadline renewed every time a round starts.
+    pub fn set_deadlines(
+        &mut self,
+        clock: Arc<dyn Clock + Send + Sync>,
+        round_timeout: Option<Duration>,
+        session_ttl: Option<Duration>,
+    ) {
+        let now = clock.now();
+        let mut deadlines = Deadlines {
+            clock,
+            round_timeout,
+            round_deadline: None,
+            session_deadline: session_ttl.map(|ttl| now.saturating_add(ttl)),
+        };
+        deadlines.start_round();
+        self.context.deadlines = Some(deadlines);
+    }
+
+    /// Returns the time (as measured by the clock given to [`Self::set_deadlines`])
+    /// by which the current round has to be finalized, if any deadlines are set.
+    pub fn deadline(&self) -> Option<Duration> {
+        self.context
+            .deadlines
+            .as_ref()
+            .and_then(Deadlines::earliest)
+    }
+
+    /// Returns [`Error::Timeout`] listing the parties that have not sent their messages
+    /// if the deadline has passed and the round still cannot be finalized.
+    ///
+    /// Intended to be called periodically while waiting for the messages,
+    /// and the session is to be aborted if it returns an error.
+    pub fn check_deadline(
+        &self,
+        accum: &RoundAccumulator<Res, Sig, Verifier>,
+    ) -> Result<(), Error<Res, Verifier>> {
+        let deadlines = match &self.context.deadlines {
+            Some(deadlines) => deadlines,
+            None => return Ok(()),
+        };
+        let deadline = match deadlines.earliest() {
+            Some(deadline) => deadline,
+            None => return Ok(()),
+        };
+        if deadlines.clock.now() < deadline || self.can_finalize(accum).map_err(Error::Local)? {
+            return Ok(());
+        }
+        let missing = self.missing_messages(accum).map_err(Error::Local)?;
+        Err(Error::Timeout { missing })
+    }
+
+    /// Sets the observers of the session (none by default): the parties that hold no shares
+    /// and send no secret data, but receive the broadcasts and take part in the echo rounds
+    /// (see [`ObserverSession`](`super::ObserverSession`)).
+    ///
+    /// The observers are added to the [`Self::message_destinations`] of the rounds
+    /// with a broadcast, and only get the broadcast part of the message.
+    /// The echo rounds wait for the echoes of the observers as well,
+    /// so all the parties of the session must set the same observers.
+    /// Must be called before the messages of the first round are created.
+    /// The setting is preserved for the subsequent rounds.
+    pub fn set_observers(
+        &mut self,
+        observers: &BTreeSet<Verifier>,
+    ) -> Result<(), SessionInitError> {
+        if self.context.messages_made.load(Ordering::Relaxed) {
+            return Err(LocalError(
+                "The observers must be set before any messages are created".into(),
+            )
+            .into());
+        }
+        if self.is_echo_round() {
+            return Err(
+                LocalError("The observers must be set before the echo round".into()).into(),
+            );
+        }
+
+        let mut parties = self.expecting_messages_from().clone();
+        parties.extend(self.tp.round_destinations().iter().cloned());
+        if observers.contains(&self.context.my_id) || !observers.is_disjoint(&parties) {
+            return Err(LocalError("The observers cannot be parties of the session".into()).into());
+        }
+
+        parties.extend(observers.iter().cloned());
+        check_num_parties(self.context.my_id.clone(), &parties)?;
+
+        self.context.observers = observers.clone();
+        self.destinations = self.tp.message_destinations(&self.context.observers);
+        Ok(())
+    }
+
+    /// Sets the probability (from 0 to 1) of corrupting each outgoing message (0 by default)
+    /// by flipping a random bit in its payload before it is signed.
+    ///
+    /// Intended for chaos testing of a deployment: the other parties will see the corrupted
+    /// messages as correctly signed faulty ones, and will blame this party.
+    /// In the strict mode the corrupted messages are caught before they are sent
+    /// (see [`Self::set_strict_mode`]).
+    /// Never enable it in production.
+    /// The setting is preserved for the subsequent rounds.
+    #[cfg(feature = "chaos")]
+    pub fn set_corruption_probability(&mut self, probability: f64) {
+        let probability = probability.clamp(0., 1.);
+        self.context.corruption_threshold = (probability * 4294967296.) as u64;
+    }
+
+    /// Returns the message sent in the given round (as returned by [`Self::current_round`])
+    /// to the given destination, if the message retention is enabled
+    /// (see [`Self::set_message_retention`]).
+    ///
+    /// The messages of the current round are looked up in `accum`.
+    /// The message is the same as the one returned by [`Self::make_message`],
+    /// so the receiving side can recognize it as a duplicate if it already has it.
+    /// When and how many times to resend a message is up to the transport.
+    pub fn resend(
+        &self,
+        accum: &RoundAccumulator<Res, Sig, Verifier>,
+        round: (u8, bool),
+        destination: &Verifier,
+    ) -> Option<MessageBundle<Sig>> {
+        let sent_messages = if round == self.current_round() {
+            &accum.sent_messages
+        } else {
+            self.sent_messages.get(&round)?
+        };
+        sent_messages.get(destination).cloned()
+    }
+
     /// Returns a pair of the current round index and whether it is an echo round.
     pub fn current_round(&self) -> (u8, bool) {
         match &self.tp {
@@ -221,15 +876,22 @@ where
     }
 
     /// Create an accumulator to store message creation and processing results of this round.
-    pub fn make_accumulator(&self) -> RoundAccumulator<Sig, Verifier> {
-        RoundAccumulator::new(self.is_echo_round())
+    pub fn make_accumulator(&self) -> RoundAccumulator<Res, Sig, Verifier> {
+        RoundAccumulator::new(self.is_echo_round(), self.context.abort_policy)
     }
 
     /// Returns `true` if the round can be finalized.
+    ///
+    /// If any faults were collected in the accumulator, the round can be finalized
+    /// (resulting in an error) when the messages from all the other expected parties are received.
     pub fn can_finalize(
         &self,
-        accum: &RoundAccumulator<Sig, Verifier>,
+        accum: &RoundAccumulator<Res, Sig, Verifier>,
     ) -> Result<bool, LocalError> {
+        if !accum.faults.is_empty() {
+            return Ok(self.missing_messages(accum)?.is_empty());
+        }
+
         match &self.tp {
             SessionType::Normal { this_round, .. } => Ok(this_round.can_finalize(&accum.processed)),
             SessionType::Echo { echo_round, .. } => {
@@ -241,22 +903,25 @@ where
         }
     }
 
-    /// Returns a list of parties whose messages for this round have not been received yet.
+    /// Returns a list of parties whose messages for this round have not been received yet
+    /// (not including the parties with collected faults).
     pub fn missing_messages(
         &self,
-        accum: &RoundAccumulator<Sig, Verifier>,
+        accum: &RoundAccumulator<Res, Sig, Verifier>,
     ) -> Result<BTreeSet<Verifier>, LocalError> {
-        match &self.tp {
-            SessionType::Normal { this_round, .. } => {
-                Ok(this_round.missing_messages(&accum.processed))
-            }
+        let missing = match &self.tp {
+            SessionType::Normal { this_round, .. } => this_round.missing_messages(&accum.processed),
             SessionType::Echo { echo_round, .. } => {
                 let echo_accum = accum.echo_accum.as_ref().ok_or(LocalError(
                     "This is an echo round, but the accumulator is in an invalid state".into(),
                 ))?;
-                Ok(echo_round.missing_messages(echo_accum))
+                echo_round.missing_messages(echo_accum)
             }
-        }
+        };
+        Ok(missing
+            .into_iter()
+            .filter(|id| !accum.faults.contains_key(id))
+            .collect())
     }
 
     fn is_echo_round(&self) -> bool {
@@ -266,12 +931,14 @@ where
         }
     }
 
-    /// Returns the party indices to which the messages of this round should be sent.
+    /// Returns the party indices to which the messages of this round should be sent
+    /// (including the observers, see [`Self::set_observers`]).
+    ///
+    /// The parties are listed in the canonical (ascending) order.
+    /// The protocol results do not depend on the order in which the messages are created
+    /// and processed, so it does not have to be followed.
     pub fn message_destinations(&self) -> &BTreeSet<Verifier> {
-        match &self.tp {
-            SessionType::Normal { this_round, .. } => this_round.message_destinations(),
-            SessionType::Echo { echo_round, .. } => echo_round.message_destinations(),
-        }
+        &self.destinations
     }
 
     fn expecting_messages_from(&self) -> &BTreeSet<Verifier> {
@@ -287,14 +954,24 @@ where
         &self,
         rng: &mut impl CryptoRngCore,
         destination: &Verifier,
-    ) -> Result<(MessageBundle<Sig>, Artifact<Verifier>), LocalError> {
+    ) -> Result<(MessageBundle<Sig>, Artifact<Sig, Verifier>), LocalError> {
+        self.context.messages_made.store(true, Ordering::Relaxed);
+        let rng = &mut self
+            .context
+            .rng(rng, b"message", self.current_round(), Some(destination));
         match &self.tp {
             SessionType::Normal {
                 this_round,
                 broadcast,
             } => {
+                if self.context.observers.contains(destination) {
+                    return self.make_observer_message(rng, destination, broadcast.as_ref());
+                }
+
                 let round_num = this_round.round_num();
                 let (payload, artifact) = this_round.make_direct_message(rng, destination)?;
+                #[cfg(feature = "chaos")]
+                let payload = payload.map(|payload| self.context.maybe_corrupt(rng, payload));
 
                 let direct_message = if let Some(payload) = payload {
                     Some(
@@ -302,6 +979,8 @@ where
                             rng,
                             &self.context.signer,
                             &self.context.session_id,
+                            Res::PROTOCOL_ID,
+                            self.context.params,
                             round_num,
                             MessageType::Direct,
                             &payload,
@@ -312,23 +991,58 @@ where
                     None
                 };
 
+                #[cfg(feature = "testing")]
+                let equivocating_broadcast =
+                    self.make_equivocating_broadcast(rng, this_round.as_ref(), destination)?;
+                #[cfg(feature = "testing")]
+                let broadcast = if equivocating_broadcast.is_some() {
+                    &equivocating_broadcast
+                } else {
+                    broadcast
+                };
+
+                #[cfg(feature = "chaos")]
+                let corrupted_broadcast =
+                    self.make_corrupted_broadcast(rng, this_round.as_ref())?;
+                #[cfg(feature = "chaos")]
+                let broadcast = if corrupted_broadcast.is_some() {
+                    &corrupted_broadcast
+                } else {
+                    broadcast
+                };
+
+                let broadcast = broadcast
+                    .as_ref()
+                    .map(|broadcast| self.context.prepare_message(rng, broadcast.clone()))
+                    .transpose()?;
+                let direct_message = direct_message
+                    .map(|direct| self.context.prepare_message(rng, direct))
+                    .transpose()?;
+
                 let message = MessageBundle::try_from(match (broadcast, direct_message) {
-                    (Some(broadcast), Some(direct)) => MessageBundleEnum::Both {
-                        broadcast: broadcast.clone(),
-                        direct,
-                    },
+                    (Some(broadcast), Some(direct)) => {
+                        MessageBundleEnum::Both { broadcast, direct }
+                    }
                     (None, Some(direct)) => MessageBundleEnum::Direct(direct),
-                    (Some(broadcast), None) => MessageBundleEnum::Broadcast(broadcast.clone()),
+                    (Some(broadcast), None) => MessageBundleEnum::Broadcast(broadcast),
                     (None, None) => return Err(LocalError("The round must send messages".into())),
                 })?;
 
-                Ok((
-                    message,
-                    Artifact {
-                        destination: destination.clone(),
-                        artifact,
-                    },
-                ))
+                if self.context.strict {
+                    self.verify_own_message(rng, destination, &message)?;
+                }
+
+                // Encrypting after the strict mode check,
+                // since we cannot decrypt the messages we send.
+                #[cfg(feature = "encryption")]
+                let message = self.context.encrypt_message(rng, destination, message)?;
+
+                let artifact = Artifact {
+                    destination: destination.clone(),
+                    artifact: Some(artifact),
+                    message: self.context.retain_messages.then(|| message.clone()),
+                };
+                Ok((message, artifact))
             }
             SessionType::Echo {
                 next_round,
@@ -336,24 +1050,153 @@ where
             } => {
                 let round_num = next_round.round_num() - 1;
                 let payload = echo_round.make_broadcast();
+                #[cfg(feature = "chaos")]
+                let payload = self.context.maybe_corrupt(rng, payload);
                 let artifact = DynArtifact::null();
                 let message = VerifiedMessage::new(
                     rng,
                     &self.context.signer,
                     &self.context.session_id,
+                    Res::PROTOCOL_ID,
+                    self.context.params,
                     round_num,
                     MessageType::Echo,
                     &payload,
                 )?
                 .into_unverified();
-                Ok((
-                    MessageBundle::try_from(MessageBundleEnum::Echo(message))?,
-                    Artifact {
-                        destination: destination.clone(),
-                        artifact,
-                    },
-                ))
+                let message = self.context.prepare_message(rng, message)?;
+                let message = MessageBundle::try_from(MessageBundleEnum::Echo(message))?;
+
+                if self.context.strict {
+                    self.verify_own_message(rng, destination, &message)?;
+                }
+
+                let artifact = Artifact {
+                    destination: destination.clone(),
+                    artifact: Some(artifact),
+                    message: self.context.retain_messages.then(|| message.clone()),
+                };
+                Ok((message, artifact))
+            }
+        }
+    }
+
+    /// Returns the message for an observer, consisting of the broadcast of this round alone.
+    fn make_observer_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &Verifier,
+        broadcast: Option<&SignedMessage<Sig>>,
+    ) -> Result<(MessageBundle<Sig>, Artifact<Sig, Verifier>), LocalError> {
+        let broadcast = broadcast.ok_or_else(|| {
+            LocalError("This round does not send messages to the observers".into())
+        })?;
+        let broadcast = self.context.prepare_message(rng, broadcast.clone())?;
+        let message = MessageBundle::try_from(MessageBundleEnum::Broadcast(broadcast))?;
+
+        if self.context.strict {
+            self.verify_own_message(rng, destination, &message)?;
+        }
+
+        let artifact = Artifact {
+            destination: destination.clone(),
+            artifact: None,
+            message: self.context.retain_messages.then(|| message.clone()),
+        };
+        Ok((message, artifact))
+    }
+
+    /// Signs the broadcast of this round specific to the given destination,
+    /// if the round equivocates (see [`Behavior::EquivocatingBroadcast`]).
+    #[cfg(feature = "testing")]
+    fn make_equivocating_broadcast(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        round: &dyn DynFinalizable<Verifier, Res>,
+        destination: &Verifier,
+    ) -> Result<Option<SignedMessage<Sig>>, LocalError> {
+        let payload = match round.make_equivocating_broadcast(rng, destination)? {
+            Some(payload) => payload,
+            None => return Ok(None),
+        };
+        let message = VerifiedMessage::new(
+            rng,
+            &self.context.signer,
+            &self.context.session_id,
+            Res::PROTOCOL_ID,
+            self.context.params,
+            round.round_num(),
+            MessageType::Broadcast,
+            &payload,
+        )?;
+        Ok(Some(message.into_unverified()))
+    }
+
+    /// Signs a corrupted copy of the broadcast of this round,
+    /// if it was chosen to be corrupted.
+    #[cfg(feature = "chaos")]
+    fn make_corrupted_broadcast(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        round: &dyn DynFinalizable<Verifier, Res>,
+    ) -> Result<Option<SignedMessage<Sig>>, LocalError> {
+        if !self.context.should_corrupt(rng) {
+            return Ok(None);
+        }
+        let mut payload = match round.make_broadcast_message(rng)? {
+            Some(payload) => payload,
+            None => return Ok(None),
+        };
+        flip_random_bit(rng, &mut payload);
+        let message = VerifiedMessage::new(
+            rng,
+            &self.context.signer,
+            &self.context.session_id,
+            Res::PROTOCOL_ID,
+            self.context.params,
+            round.round_num(),
+            MessageType::Broadcast,
+            &payload,
+        )?;
+        Ok(Some(message.into_unverified()))
+    }
+
+    /// Checks an outgoing message in the strict mode.
+    fn verify_own_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &Verifier,
+        message: &MessageBundle<Sig>,
+    ) -> Result<(), LocalError> {
+        let serialized = serialize_message(message)?;
+        let deserialized: MessageBundle<Sig> = deserialize_message(&serialized)
+            .map_err(|err| LocalError(format!("Failed to deserialize own message: {err}")))?;
+        if serialize_message(&deserialized)? != serialized {
+            return Err(LocalError(
+                "Own message does not survive a serialization round-trip".into(),
+            ));
+        }
+
+        let verified_message = deserialized
+            .verify(&self.context.my_id)
+            .map_err(|err| LocalError(format!("Own message has an invalid signature: {err}")))?;
+
+        match &self.tp {
+            SessionType::Normal { this_round, .. }
+                if !self.context.observers.contains(destination) =>
+            {
+                this_round.verify_own_message(
+                    rng,
+                    destination,
+                    verified_message.broadcast_payload(),
+                    verified_message.direct_payload(),
+                )
             }
+            // The round does not know about the observers,
+            // and the broadcast is checked with the message to any other party.
+            SessionType::Normal { .. } => Ok(()),
+            // The echo message only contains messages we have already verified.
+            SessionType::Echo { .. } => Ok(()),
         }
     }
 
@@ -382,7 +1225,7 @@ where
     /// Perform quick checks on a received message.
     pub fn preprocess_message(
         &self,
-        accum: &mut RoundAccumulator<Sig, Verifier>,
+        accum: &mut RoundAccumulator<Res, Sig, Verifier>,
         from: &Verifier,
         message: MessageBundle<Sig>,
     ) -> Result<Option<PreprocessedMessage<Sig, Verifier>>, Error<Res, Verifier>> {
@@ -394,8 +1237,25 @@ where
             }));
         }
 
+        // Same as above, a correctly signed message from a different protocol may be a replay
+        if message.protocol() != Res::PROTOCOL_ID {
+            return Err(Error::Remote(RemoteError {
+                party: from.clone(),
+                error: RemoteErrorEnum::UnexpectedProtocol,
+            }));
+        }
+
         let message_for = self.route_message(from, &message)?;
 
+        // Whoever tampered with the ciphertext, the fault cannot be proven.
+        #[cfg(feature = "encryption")]
+        let message = self.context.decrypt_message(from, message).map_err(|err| {
+            Error::Remote(RemoteError {
+                party: from.clone(),
+                error: RemoteErrorEnum::InvalidContents(err),
+            })
+        })?;
+
         let verified_message = message.verify(from).map_err(|err| {
             Error::Remote(RemoteError {
                 party: from.clone(),
@@ -409,6 +1269,14 @@ where
             )));
         }
 
+        // Checked after the signature, so that the sender cannot deny using these parameters.
+        if !verified_message.has_params(self.context.params) {
+            return Err(Error::Remote(RemoteError {
+                party: from.clone(),
+                error: RemoteErrorEnum::MismatchedParams,
+            }));
+        }
+
         let preprocessed = PreprocessedMessage {
             from: from.clone(),
             message: verified_message,
@@ -423,6 +1291,10 @@ where
                 }
 
                 if accum.is_already_processed(&preprocessed) {
+                    // A repeated delivery of the same message is not a fault.
+                    if accum.is_repeated(&preprocessed) {
+                        return Ok(None);
+                    }
                     return Err(Error::Remote(RemoteError {
                         party: from.clone(),
                         error: RemoteErrorEnum::DuplicateMessage,
@@ -432,6 +1304,9 @@ where
             }
             MessageFor::NextRound => {
                 if accum.is_already_cached(&preprocessed) {
+                    if accum.is_repeated_cached(&preprocessed) {
+                        return Ok(None);
+                    }
                     return Err(Error::Remote(RemoteError {
                         party: from.clone(),
                         error: RemoteErrorEnum::DuplicateMessage,
@@ -443,7 +1318,34 @@ where
         })
     }
 
+    /// Performs the inexpensive protocol-level checks of a preprocessed message
+    /// (deserialization, hash commitments and such), without verifying the proofs in it.
+    ///
+    /// Calling it is optional, since [`process_message`](`Self::process_message`)
+    /// performs the same checks; it allows an event loop to reject a malformed message inline,
+    /// and only offload the expensive processing of the valid-looking ones to a worker pool.
+    /// The returned provable errors can be used with [`make_evidence`](`Self::make_evidence`).
+    pub fn pre_verify_message(
+        &self,
+        preprocessed: &PreprocessedMessage<Sig, Verifier>,
+    ) -> Result<(), Error<Res, Verifier>> {
+        match &self.tp {
+            SessionType::Normal { this_round, .. } => {
+                let result = this_round.pre_verify_message(
+                    &preprocessed.from,
+                    preprocessed.message.broadcast_payload(),
+                    preprocessed.message.direct_payload(),
+                );
+                wrap_receive_result(&preprocessed.from, result)
+            }
+            // The echo round checks are all inexpensive, and performed during the processing.
+            SessionType::Echo { .. } => Ok(()),
+        }
+    }
+
     /// Process a received message from another party.
+    ///
+    /// Includes the checks of [`pre_verify_message`](`Self::pre_verify_message`).
     pub fn process_message(
         &self,
         rng: &mut impl CryptoRngCore,
@@ -474,34 +1376,154 @@ where
                     })?;
                 Ok(ProcessedMessage {
                     from: from.clone(),
-                    message: ProcessedMessageEnum::Echo,
+                    message: ProcessedMessageEnum::Echo { message },
                 })
             }
         }
     }
 
+    /// Creates a packet with the evidence of a provable fault returned by
+    /// [`process_message`](`Self::process_message`) for the given message,
+    /// that can be checked by a third party with [`verify_evidence`](`super::verify_evidence`).
+    ///
+    /// If the fault is a mismatch of an echoed broadcast with the one received previously,
+    /// and the echoed broadcast is correctly signed by its original sender,
+    /// the packet accuses the original sender of equivocation.
+    /// Otherwise the packet also includes the broadcasts received from the sender
+    /// in the previous rounds, and the public data of the round (see [`EvidenceContext`]).
+    ///
+    /// Returns `None` if the error is not a provable fault of the sender of the message.
+    pub fn make_evidence(
+        &self,
+        preprocessed: &PreprocessedMessage<Sig, Verifier>,
+        error: &Error<Res, Verifier>,
+    ) -> Option<EvidencePacket<Sig, Verifier>> {
+        let (party, error) = match error {
+            Error::Provable { party, error } => (party, error),
+            _ => return None,
+        };
+        if party != &preprocessed.from {
+            return None;
+        }
+
+        if let (
+            ProvableError::Echo(EchoError::ConflictingBroadcasts),
+            SessionType::Echo { echo_round, .. },
+        ) = (error, &self.tp)
+        {
+            let payload = preprocessed.message.echo_payload()?;
+            if let Some((accused, broadcast, echoed_bc)) =
+                echo_round.find_equivocation(party, payload)
+            {
+                return Some(EvidencePacket::new(
+                    self.context.session_id,
+                    Res::PROTOCOL_ID,
+                    accused,
+                    Claim::Equivocation,
+                    vec![broadcast, echoed_bc],
+                    EvidenceContext::None,
+                ));
+            }
+        }
+
+        let description = match error {
+            ProvableError::Protocol(err) => format!("{err:?}"),
+            ProvableError::CannotDeserialize(err) => format!("Cannot deserialize: {err}"),
+            ProvableError::Echo(err) => format!("Echo round failed: {err:?}"),
+        };
+        let mut messages = self
+            .previous_broadcasts
+            .get(party)
+            .cloned()
+            .unwrap_or_default();
+        messages.extend(preprocessed.message.signed_messages());
+        let context = match &self.tp {
+            SessionType::Normal { this_round, .. } => this_round.evidence_context(),
+            SessionType::Echo { .. } => EvidenceContext::None,
+        };
+        Some(EvidencePacket::new(
+            self.context.session_id,
+            Res::PROTOCOL_ID,
+            party.clone(),
+            Claim::InvalidMessage { description },
+            messages,
+            context,
+        ))
+    }
+
     /// Try to finalize the round.
+    ///
+    /// Returns [`Error::Faults`] if any faults were collected in the accumulator.
     pub fn finalize_round(
-        self,
+        mut self,
         rng: &mut impl CryptoRngCore,
-        accum: RoundAccumulator<Sig, Verifier>,
+        mut accum: RoundAccumulator<Res, Sig, Verifier>,
     ) -> Result<FinalizeOutcome<Res, Sig, Signer, Verifier>, Error<Res, Verifier>> {
-        match self.tp {
-            SessionType::Normal { this_round, .. } => {
-                Self::finalize_regular_round(self.context, this_round, rng, accum)
+        if !accum.faults.is_empty() {
+            return Err(Error::Faults(accum.faults));
+        }
+
+        let round = self.current_round();
+        if let Some(seed) = &mut self.context.seed {
+            seed.add_received_messages(round, &accum.received_messages);
+        }
+        let rng = &mut self.context.rng(rng, b"finalize", round, None);
+        let mut sent_messages = self.sent_messages;
+        if !accum.sent_messages.is_empty() {
+            sent_messages.insert(round, core::mem::take(&mut accum.sent_messages));
+        }
+        let mut previous_broadcasts = self.previous_broadcasts;
+        for (from, message) in accum.received_messages.iter() {
+            if let Some(broadcast) = message.broadcast_message() {
+                previous_broadcasts
+                    .entry(from.clone())
+                    .or_default()
+                    .push(broadcast.clone().into_unverified());
             }
+        }
+
+        let outcome = match self.tp {
+            SessionType::Normal {
+                this_round,
+                broadcast,
+            } => Self::finalize_regular_round(
+                self.context,
+                this_round,
+                broadcast.is_some(),
+                rng,
+                accum,
+            ),
             SessionType::Echo {
                 echo_round,
                 next_round,
             } => Self::finalize_echo_round(self.context, echo_round, next_round, rng, accum),
-        }
+        }?;
+
+        Ok(match outcome {
+            FinalizeOutcome::AnotherRound {
+                mut session,
+                cached_messages,
+            } => {
+                session.sent_messages = sent_messages;
+                session.previous_broadcasts = previous_broadcasts;
+                if let Some(deadlines) = session.context.deadlines.as_mut() {
+                    deadlines.start_round();
+                }
+                FinalizeOutcome::AnotherRound {
+                    session,
+                    cached_messages,
+                }
+            }
+            outcome => outcome,
+        })
     }
 
     fn finalize_regular_round(
         context: Context<Signer, Verifier>,
         round: Box<dyn DynFinalizable<Verifier, Res>>,
+        sent_broadcast: bool,
         rng: &mut impl CryptoRngCore,
-        accum: RoundAccumulator<Sig, Verifier>,
+        accum: RoundAccumulator<Res, Sig, Verifier>,
     ) -> Result<FinalizeOutcome<Res, Sig, Signer, Verifier>, Error<Res, Verifier>> {
         let requires_echo = round.requires_echo();
 
@@ -529,15 +1551,23 @@ where
                         .map(|(id, combined)| {
                             (id.clone(), combined.broadcast_message().unwrap().clone())
                         })
-                        .collect();
+                        .collect::<BTreeMap<_, _>>();
+                    let mut contributors = broadcasts.keys().cloned().collect::<BTreeSet<_>>();
+                    if sent_broadcast {
+                        contributors.insert(context.my_id.clone());
+                    }
 
-                    let echo_round = EchoRound::new(broadcasts);
+                    let echo_round = EchoRound::new(contributors, broadcasts, &context.observers);
+                    let tp = SessionType::Echo {
+                        next_round,
+                        echo_round,
+                    };
                     let session = Session {
-                        tp: SessionType::Echo {
-                            next_round,
-                            echo_round,
-                        },
+                        destinations: tp.message_destinations(&context.observers),
+                        tp,
                         context,
+                        sent_messages: BTreeMap::new(),
+                        previous_broadcasts: BTreeMap::new(),
                     };
                     Ok(FinalizeOutcome::AnotherRound {
                         session,
@@ -560,7 +1590,7 @@ where
         echo_round: EchoRound<Verifier, Sig>,
         next_round: Box<dyn DynFinalizable<Verifier, Res>>,
         rng: &mut impl CryptoRngCore,
-        accum: RoundAccumulator<Sig, Verifier>,
+        accum: RoundAccumulator<Res, Sig, Verifier>,
     ) -> Result<FinalizeOutcome<Res, Sig, Signer, Verifier>, Error<Res, Verifier>> {
         let echo_accum = accum.echo_accum.ok_or(Error::Local(LocalError(
             "The accumulator is in the invalid state for the echo round".into(),
@@ -577,18 +1607,187 @@ where
     }
 }
 
+#[cfg(feature = "encryption")]
+impl<Res, Sig, Signer, Verifier> Session<Res, Sig, Signer, Verifier>
+where
+    Res: ProtocolResult,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier> + OpeningKey,
+    Verifier: Debug
+        + Clone
+        + PrehashVerifier<Sig>
+        + Ord
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + SealingKey,
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+{
+    /// Enables the encryption of the direct messages to the given parties
+    /// (normally, all the parties of the session).
+    ///
+    /// The encryption keys are derived from the [`SealingKey`] of each party
+    /// and the [`OpeningKey`] of this party.
+    /// The incoming direct messages are decrypted if they are encrypted,
+    /// so the other parties can have the encryption disabled;
+    /// but if it is enabled on their side, it must be enabled on this side too.
+    /// The setting is preserved for the subsequent rounds.
+    ///
+    /// Fails if the key agreement with one of the parties fails
+    /// (e.g. if the [`SealingKey`] of the party is of a different kind).
+    pub fn enable_direct_encryption(
+        &mut self,
+        parties: &BTreeSet<Verifier>,
+    ) -> Result<(), LocalError> {
+        let my_key = self.context.my_id.sealing_key();
+        self.context.pairwise_keys = parties
+            .iter()
+            .filter(|party| *party != &self.context.my_id)
+            .map(|party| {
+                let keys = PairwiseKeys::new(
+                    &self.context.session_id,
+                    Res::PROTOCOL_ID,
+                    &self.context.signer,
+                    &my_key,
+                    &party.sealing_key(),
+                )
+                .map_err(|err| {
+                    LocalError(format!("Failed to derive the keys for {party:?}: {err}"))
+                })?;
+                Ok((party.clone(), keys))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(())
+    }
+
+    /// The data the sealed direct messages from the given sender are bound to.
+    #[cfg(feature = "relay")]
+    fn relay_associated_data(&self, from: &Verifier) -> Result<Box<[u8]>, LocalError> {
+        serialize_message(&(&self.context.session_id, from))
+    }
+
+    /// Returns the message of this round for all the destinations,
+    /// prepared to be sent through a relay (see [`RelayMessage`]),
+    /// along with the artifacts for each destination.
+    ///
+    /// The direct parts are sealed to the [`SealingKey`] of the destinations.
+    #[cfg(feature = "relay")]
+    #[allow(clippy::type_complexity)]
+    pub fn make_relay_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<(RelayMessage<Sig, Verifier>, Vec<Artifact<Sig, Verifier>>), LocalError> {
+        let associated_data = self.relay_associated_data(&self.context.my_id)?;
+
+        let mut broadcast = None;
+        let mut direct = BTreeMap::new();
+        let mut artifacts = Vec::new();
+        for destination in self.message_destinations() {
+            let (message, artifact) = self.make_message(rng, destination)?;
+            let (message_broadcast, message_direct) = message.into_parts();
+
+            // The broadcast part is the same for all the destinations
+            // (up to the randomness of the signature in echo rounds)
+            if broadcast.is_none() {
+                broadcast = message_broadcast;
+            }
+
+            let sealed = match message_direct {
+                Some(message_direct) => Some(
+                    SealedBox::seal(
+                        rng,
+                        &destination.sealing_key(),
+                        &associated_data,
+                        &serialize_message(&message_direct)?,
+                    )
+                    .map_err(LocalError)?,
+                ),
+                None => None,
+            };
+            direct.insert(destination.clone(), sealed);
+            artifacts.push(artifact);
+        }
+
+        Ok((RelayMessage { broadcast, direct }, artifacts))
+    }
+
+    /// Opens a part of a [`RelayMessage`] from the party `from` forwarded by the relay,
+    /// restoring the message bundle that can be passed to [`Self::preprocess_message`].
+    #[cfg(feature = "relay")]
+    pub fn open_relayed_message(
+        &self,
+        from: &Verifier,
+        message: RelayedMessage<Sig>,
+    ) -> Result<MessageBundle<Sig>, Error<Res, Verifier>> {
+        // The relay (or the sender) can tamper with the sealed part,
+        // and we cannot prove who did it.
+        let remote_error = |message: String| {
+            Error::Remote(RemoteError {
+                party: from.clone(),
+                error: RemoteErrorEnum::InvalidContents(message),
+            })
+        };
+
+        let direct = match message.direct {
+            Some(sealed) => {
+                let associated_data = self.relay_associated_data(from).map_err(Error::Local)?;
+                let bytes = sealed
+                    .open(
+                        &self.context.signer,
+                        &self.context.my_id.sealing_key(),
+                        &associated_data,
+                    )
+                    .map_err(remote_error)?;
+                Some(deserialize_message::<SignedMessage<Sig>>(&bytes).map_err(remote_error)?)
+            }
+            None => None,
+        };
+
+        let bundle = match (message.broadcast, direct) {
+            (Some(broadcast), Some(direct)) => MessageBundleEnum::Both { broadcast, direct },
+            (Some(broadcast), None) => match broadcast.message_type() {
+                MessageType::Echo => MessageBundleEnum::Echo(broadcast),
+                _ => MessageBundleEnum::Broadcast(broadcast),
+            },
+            (None, Some(direct)) => MessageBundleEnum::Direct(direct),
+            (None, None) => return Err(remote_error("The message is empty".into())),
+        };
+
+        MessageBundle::try_from(bundle).map_err(|err| remote_error(err.0))
+    }
+
+    /// Opens the messages of a [`RelayedBatch`] (see [`Self::open_relayed_message`]),
+    /// returning the result for each sender.
+    #[cfg(feature = "relay")]
+    #[allow(clippy::type_complexity)]
+    pub fn open_relayed_batch(
+        &self,
+        batch: RelayedBatch<Sig, Verifier>,
+    ) -> Vec<(Verifier, Result<MessageBundle<Sig>, Error<Res, Verifier>>)> {
+        batch
+            .into_messages()
+            .map(|(from, message)| {
+                let result = self.open_relayed_message(&from, message);
+                (from, result)
+            })
+            .collect()
+    }
+}
+
 /// A mutable accumulator created for each round to assemble processed messages from other parties.
-pub struct RoundAccumulator<Sig, Verifier> {
+pub struct RoundAccumulator<Res: ProtocolResult, Sig, Verifier> {
     received_messages: BTreeMap<Verifier, VerifiedMessageBundle<Sig>>,
+    sent_messages: SentMessages<Sig, Verifier>,
     processed: DynRoundAccum<Verifier>,
     cached_messages: BTreeMap<Verifier, PreprocessedMessage<Sig, Verifier>>,
     echo_accum: Option<EchoAccum<Verifier>>,
+    abort_policy: AbortPolicy,
+    faults: BTreeMap<Verifier, Error<Res, Verifier>>,
 }
 
-impl<Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Sig, Verifier> {
-    fn new(is_echo_round: bool) -> Self {
+impl<Res: ProtocolResult, Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Res, Sig, Verifier> {
+    fn new(is_echo_round: bool, abort_policy: AbortPolicy) -> Self {
         Self {
             received_messages: BTreeMap::new(),
+            sent_messages: BTreeMap::new(),
             processed: DynRoundAccum::new(),
             cached_messages: BTreeMap::new(),
             echo_accum: if is_echo_round {
@@ -596,22 +1795,54 @@ impl<Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Sig, Verifier> {
             } else {
                 None
             },
+            abort_policy,
+            faults: BTreeMap::new(),
         }
     }
 
+    /// Save a fault of another party returned by
+    /// [`Session::preprocess_message`] or [`Session::process_message`].
+    ///
+    /// With [`AbortPolicy::Immediate`], or if the error is not attributable to another party
+    /// (that is, it is not [`Error::Remote`] or [`Error::Provable`]), the error is returned back.
+    /// Otherwise it is saved, to be reported by [`Session::finalize_round`];
+    /// only the first fault of each party is kept.
+    pub fn add_fault(&mut self, error: Error<Res, Verifier>) -> Result<(), Error<Res, Verifier>> {
+        if self.abort_policy == AbortPolicy::Immediate {
+            return Err(error);
+        }
+
+        let party = match &error {
+            Error::Remote(RemoteError { party, .. }) => party.clone(),
+            Error::Provable { party, .. } => party.clone(),
+            _ => return Err(error),
+        };
+
+        self.faults.entry(party).or_insert(error);
+        Ok(())
+    }
+
     /// Save an artifact produced by [`Session::make_message`].
-    pub fn add_artifact(&mut self, artifact: Artifact<Verifier>) -> Result<(), LocalError> {
-        self.processed
-            .add_artifact(&artifact.destination, artifact.artifact)
-            .map_err(|err| match err {
-                AccumAddError::SlotTaken => LocalError(format!(
-                    "Artifact for the destination {:?} was already added",
-                    artifact.destination
-                )),
-            })
+    pub fn add_artifact(&mut self, artifact: Artifact<Sig, Verifier>) -> Result<(), LocalError> {
+        if let Some(dyn_artifact) = artifact.artifact {
+            self.processed
+                .add_artifact(&artifact.destination, dyn_artifact)
+                .map_err(|err| match err {
+                    AccumAddError::SlotTaken => LocalError(format!(
+                        "Artifact for the destination {:?} was already added",
+                        artifact.destination
+                    )),
+                })?;
+        }
+        if let Some(message) = artifact.message {
+            self.sent_messages.insert(artifact.destination, message);
+        }
+        Ok(())
     }
 
     /// Save a processed message produced by [`Session::process_message`].
+    ///
+    /// A copy of an already saved message (delivered again by the transport) is ignored.
     pub fn add_processed_message(
         &mut self,
         pm: ProcessedMessage<Sig, Verifier>,
@@ -620,21 +1851,16 @@ impl<Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Sig, Verifier> {
             ProcessedMessageEnum::Payload { payload, message } => {
                 if let Err(AccumAddError::SlotTaken) = self.processed.add_payload(&pm.from, payload)
                 {
-                    return Ok(Err(RemoteError {
-                        party: pm.from,
-                        error: RemoteErrorEnum::DuplicateMessage,
-                    }));
+                    return Ok(self.repeated_or_duplicate(pm.from, &message));
                 }
                 self.received_messages.insert(pm.from, message);
             }
-            ProcessedMessageEnum::Echo => match &mut self.echo_accum {
+            ProcessedMessageEnum::Echo { message } => match &mut self.echo_accum {
                 Some(accum) => {
                     if accum.add_echo_received(&pm.from).is_none() {
-                        return Ok(Err(RemoteError {
-                            party: pm.from,
-                            error: RemoteErrorEnum::DuplicateMessage,
-                        }));
+                        return Ok(self.repeated_or_duplicate(pm.from, &message));
                     }
+                    self.received_messages.insert(pm.from, message);
                 }
                 None => return Err(LocalError("This is not an echo round".into())),
             },
@@ -642,6 +1868,29 @@ impl<Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Sig, Verifier> {
         Ok(Ok(()))
     }
 
+    /// Handles a processed message from a sender whose message was already added.
+    ///
+    /// Another copy of the same message may have passed [`Session::preprocess_message`]
+    /// while the first one was still being processed;
+    /// like any repeated delivery, it is not a fault and is ignored.
+    fn repeated_or_duplicate(
+        &self,
+        from: Verifier,
+        message: &VerifiedMessageBundle<Sig>,
+    ) -> Result<(), RemoteError<Verifier>> {
+        if self
+            .received_messages
+            .get(&from)
+            .is_some_and(|received| received.is_same_as(message))
+        {
+            return Ok(());
+        }
+        Err(RemoteError {
+            party: from,
+            error: RemoteErrorEnum::DuplicateMessage,
+        })
+    }
+
     fn is_already_processed(&self, preprocessed: &PreprocessedMessage<Sig, Verifier>) -> bool {
         if preprocessed.message.is_echo() {
             self.echo_accum
@@ -653,6 +1902,20 @@ impl<Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Sig, Verifier> {
         }
     }
 
+    /// Returns `true` if the message is the same as the one already processed from its sender.
+    fn is_repeated(&self, preprocessed: &PreprocessedMessage<Sig, Verifier>) -> bool {
+        self.received_messages
+            .get(&preprocessed.from)
+            .is_some_and(|message| message.is_same_as(&preprocessed.message))
+    }
+
+    /// Returns `true` if the message is the same as the one already cached from its sender.
+    fn is_repeated_cached(&self, preprocessed: &PreprocessedMessage<Sig, Verifier>) -> bool {
+        self.cached_messages
+            .get(&preprocessed.from)
+            .is_some_and(|cached| cached.message.is_same_as(&preprocessed.message))
+    }
+
     fn is_already_cached(&self, preprocessed: &PreprocessedMessage<Sig, Verifier>) -> bool {
         self.cached_messages.contains_key(&preprocessed.from)
     }
@@ -665,12 +1928,16 @@ impl<Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Sig, Verifier> {
 
 /// Data produced when creating a direct message to another party
 /// that has to be preserved for further processing.
-pub struct Artifact<Verifier> {
+pub struct Artifact<Sig, Verifier> {
     destination: Verifier,
-    artifact: DynArtifact,
+    /// `None` for the messages to the observers, which the round does not know about.
+    artifact: Option<DynArtifact>,
+    /// A copy of the message, if the message retention is enabled.
+    message: Option<MessageBundle<Sig>>,
 }
 
 /// A message that passed initial validity checks.
+#[derive(Clone)]
 pub struct PreprocessedMessage<Sig, Verifier> {
     from: Verifier,
     message: VerifiedMessageBundle<Sig>,
@@ -687,7 +1954,9 @@ enum ProcessedMessageEnum<Sig> {
         payload: DynPayload,
         message: VerifiedMessageBundle<Sig>,
     },
-    Echo,
+    Echo {
+        message: VerifiedMessageBundle<Sig>,
+    },
 }
 
 #[cfg(test)]
@@ -696,7 +1965,7 @@ mod tests {
     use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
 
     use super::{Artifact, MessageBundle, PreprocessedMessage, ProcessedMessage, Session};
-    use crate::ProtocolResult;
+    use crate::{ProtocolId, ProtocolResult};
 
     #[test]
     fn test_concurrency_bounds() {
@@ -712,6 +1981,7 @@ mod tests {
         struct DummyResult;
 
         impl ProtocolResult for DummyResult {
+            const PROTOCOL_ID: ProtocolId = ProtocolId::KeyInit;
             type Success = ();
             type ProvableError = ();
             type CorrectnessProof = ();
@@ -719,8 +1989,25 @@ mod tests {
 
         assert!(impls!(Session<DummyResult, Signature, SigningKey, VerifyingKey>: Sync));
         assert!(impls!(MessageBundle<Signature>: Send));
-        assert!(impls!(Artifact<VerifyingKey>: Send));
+        assert!(impls!(Artifact<Signature, VerifyingKey>: Send));
         assert!(impls!(PreprocessedMessage<Signature, VerifyingKey>: Send));
         assert!(impls!(ProcessedMessage<Signature, VerifyingKey>: Send));
     }
+
+    #[cfg(feature = "chaos")]
+    #[test]
+    fn corruption_flips_one_bit() {
+        use rand_core::OsRng;
+
+        let payload = [0u8; 16];
+        for _ in 0..100 {
+            let mut corrupted = payload;
+            super::flip_random_bit(&mut OsRng, &mut corrupted);
+            let flipped_bits = corrupted.iter().map(|byte| byte.count_ones()).sum::<u32>();
+            assert_eq!(flipped_bits, 1);
+        }
+
+        let mut empty: [u8; 0] = [];
+        super::flip_random_bit(&mut OsRng, &mut empty);
+    }
 }
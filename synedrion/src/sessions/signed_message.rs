@@ -7,8 +7,55 @@ use serde::{Deserialize, Serialize};
 use signature::hazmat::{PrehashVerifier, RandomizedPrehashSigner};
 
 use super::error::LocalError;
+#[cfg(feature = "encryption")]
+use super::pairwise::PairwiseKeys;
+use crate::rounds::ProtocolId;
 use crate::tools::hashing::{Chain, FofHasher, HashOutput};
 use crate::tools::serde_bytes;
+use crate::uint::subtle::{Choice, ConstantTimeEq};
+
+/// The domain of the session IDs created with [`SessionId::from_seed`].
+pub(crate) const SESSION_ID_HASH_TAG: &[u8] = b"SessionId";
+
+/// The domain of the session IDs created with [`SessionId::from_seed_and_beacon`].
+pub(crate) const SESSION_ID_WITH_BEACON_HASH_TAG: &[u8] = b"SessionIdWithBeacon";
+
+/// The domain of the hashes signed by the message envelopes.
+pub(crate) const MESSAGE_HASH_TAG: &[u8] = b"SignedMessage";
+
+/// A value published by a public randomness beacon (e.g. a `drand` round).
+///
+/// Mixing it into the session ID with [`SessionId::from_seed_and_beacon`]
+/// makes all the commitments and challenges of the session depend on it,
+/// so a public ceremony can show that its randomness was not chosen adversarially
+/// (provided the session did not start before the beacon value was published).
+/// The authenticity of the value must be verified by the caller.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BeaconValue {
+    round: u64,
+    #[serde(with = "serde_bytes::as_base64")]
+    randomness: Box<[u8]>,
+}
+
+impl BeaconValue {
+    /// Creates a beacon value from the beacon round number and the randomness published for it.
+    pub fn new(round: u64, randomness: &[u8]) -> Self {
+        Self {
+            round,
+            randomness: randomness.into(),
+        }
+    }
+
+    /// The beacon round number.
+    pub fn round(&self) -> u64 {
+        self.round
+    }
+
+    /// The randomness published for the round.
+    pub fn randomness(&self) -> &[u8] {
+        &self.randomness
+    }
+}
 
 /// A session identifier shared between the parties.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
@@ -18,11 +65,34 @@ impl SessionId {
     /// Deterministically creates a session ID from the given bytestring.
     pub fn from_seed(seed: &[u8]) -> Self {
         Self(
-            FofHasher::new_with_dst(b"SessionId")
+            FofHasher::new_with_dst(SESSION_ID_HASH_TAG)
+                .chain(&seed)
+                .finalize(),
+        )
+    }
+
+    /// Deterministically creates a session ID from the given bytestring
+    /// and a value of a public randomness beacon.
+    pub fn from_seed_and_beacon(seed: &[u8], beacon: &BeaconValue) -> Self {
+        Self(
+            FofHasher::new_with_dst(SESSION_ID_WITH_BEACON_HASH_TAG)
                 .chain(&seed)
+                .chain(beacon)
                 .finalize(),
         )
     }
+
+    /// Checks that this session ID was created from the given seed and the beacon value,
+    /// allowing an auditor to confirm the beacon value was used in the session.
+    pub fn is_derived_from_beacon(&self, seed: &[u8], beacon: &BeaconValue) -> bool {
+        self == &Self::from_seed_and_beacon(seed, beacon)
+    }
+}
+
+impl ConstantTimeEq for SessionId {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
 }
 
 impl AsRef<[u8]> for SessionId {
@@ -33,14 +103,20 @@ impl AsRef<[u8]> for SessionId {
 
 fn message_hash(
     session_id: &SessionId,
+    protocol: ProtocolId,
+    params: Option<HashOutput>,
     round: u8,
     message_type: MessageType,
+    compressed: bool,
     payload: &[u8],
 ) -> HashOutput {
-    FofHasher::new_with_dst(b"SignedMessage")
+    FofHasher::new_with_dst(MESSAGE_HASH_TAG)
         .chain(session_id)
+        .chain(&protocol)
+        .chain(&params)
         .chain(&round)
         .chain(&message_type)
+        .chain(&compressed)
         .chain(&payload)
         .finalize()
 }
@@ -55,35 +131,173 @@ pub enum MessageType {
     Echo,
 }
 
+/// The maximum size of a decompressed payload.
+///
+/// The largest payloads are sent in the AuxGen and KeyRefresh protocols
+/// (about 64 KiB with [`ProductionParams`](`crate::ProductionParams`)),
+/// and their size does not depend on the number of parties.
+/// The payload is only decompressed after its signature is verified,
+/// but the sender itself may still attempt a decompression bomb.
+#[cfg(feature = "compression")]
+const MAX_DECOMPRESSED_PAYLOAD_SIZE: usize = 1024 * 1024;
+
 /// A (yet) unverified message from a round that includes the payload signature.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct SignedMessage<Sig> {
     session_id: SessionId,
+    protocol: ProtocolId,
+    /// The fingerprint of the scheme parameters the sender runs the protocol with
+    /// (`None` for the protocols that do not depend on them).
+    params: Option<HashOutput>,
     round: u8,
     message_type: MessageType,
+    /// Whether the payload is compressed.
+    /// The signature covers the payload as it is sent (that is, after the compression),
+    /// so that it can be verified before decompressing.
+    compressed: bool,
+    /// Whether the payload is encrypted to the recipient (after the compression, if any).
+    /// The signature always covers the unencrypted payload.
+    encrypted: bool,
     #[serde(with = "serde_bytes::as_base64")]
     payload: Box<[u8]>,
     signature: Sig,
 }
 
 impl<Sig> SignedMessage<Sig> {
+    /// Compresses the payload if it results in a smaller message,
+    /// signing the compressed payload.
+    #[cfg(feature = "compression")]
+    pub(crate) fn compress(
+        self,
+        rng: &mut impl CryptoRngCore,
+        signer: &impl RandomizedPrehashSigner<Sig>,
+    ) -> Result<Self, LocalError> {
+        if self.compressed || self.encrypted {
+            return Ok(self);
+        }
+        let compressed = miniz_oxide::deflate::compress_to_vec(&self.payload, 6);
+        // At production sizes most of the payload is random data,
+        // so compression may not help at all.
+        if compressed.len() >= self.payload.len() {
+            return Ok(self);
+        }
+        let signature = sign(
+            rng,
+            signer,
+            message_hash(
+                &self.session_id,
+                self.protocol,
+                self.params,
+                self.round,
+                self.message_type,
+                true,
+                &compressed,
+            ),
+        )?;
+        Ok(Self {
+            compressed: true,
+            payload: compressed.into(),
+            signature,
+            ..self
+        })
+    }
+
+    /// Returns the decompressed payload, or `None` if the payload is not compressed.
+    fn decompressed_payload(&self) -> Result<Option<Box<[u8]>>, String> {
+        if !self.compressed {
+            return Ok(None);
+        }
+
+        #[cfg(feature = "compression")]
+        {
+            let payload = miniz_oxide::inflate::decompress_to_vec_with_limit(
+                &self.payload,
+                MAX_DECOMPRESSED_PAYLOAD_SIZE,
+            )
+            .map_err(|err| format!("Failed to decompress the payload: {err:?}"))?;
+            Ok(Some(payload.into()))
+        }
+
+        #[cfg(not(feature = "compression"))]
+        Err("Received a compressed message, but the `compression` feature is not enabled".into())
+    }
+
+    /// The data the encrypted payload is bound to.
+    #[cfg(feature = "encryption")]
+    fn associated_data(&self) -> HashOutput {
+        FofHasher::new_with_dst(b"SignedMessageHeader")
+            .chain(&self.session_id)
+            .chain(&self.protocol)
+            .chain(&self.params)
+            .chain(&self.round)
+            .chain(&self.message_type)
+            .chain(&self.compressed)
+            .finalize()
+    }
+
+    /// Encrypts the payload to the other party.
+    #[cfg(feature = "encryption")]
+    pub(crate) fn encrypt(
+        self,
+        rng: &mut impl CryptoRngCore,
+        keys: &PairwiseKeys,
+    ) -> Result<Self, LocalError> {
+        if self.encrypted {
+            return Ok(self);
+        }
+        let payload = keys
+            .encrypt(rng, self.associated_data().as_ref(), &self.payload)
+            .map_err(LocalError)?;
+        Ok(Self {
+            encrypted: true,
+            payload,
+            ..self
+        })
+    }
+
+    /// Decrypts the payload from the other party.
+    #[cfg(feature = "encryption")]
+    pub(crate) fn decrypt(self, keys: &PairwiseKeys) -> Result<Self, String> {
+        if !self.encrypted {
+            return Ok(self);
+        }
+        let payload = keys.decrypt(self.associated_data().as_ref(), &self.payload)?;
+        Ok(Self {
+            encrypted: false,
+            payload,
+            ..self
+        })
+    }
+
     pub(crate) fn verify(
         self,
         verifier: &impl PrehashVerifier<Sig>,
     ) -> Result<VerifiedMessage<Sig>, String> {
+        if self.encrypted {
+            return Err("Received an encrypted message, but the encryption is not set up".into());
+        }
+        // The signature is verified first, so that only the sender
+        // could make us decompress the payload.
         verifier
             .verify_prehash(
                 message_hash(
                     &self.session_id,
+                    self.protocol,
+                    self.params,
                     self.round,
                     self.message_type,
+                    self.compressed,
                     &self.payload,
                 )
                 .as_ref(),
                 &self.signature,
             )
             .map_err(|err| format!("{:?}", err))?;
-        Ok(VerifiedMessage(self))
+        let decompressed = self.decompressed_payload()?;
+        Ok(VerifiedMessage {
+            message: self,
+            decompressed,
+        })
     }
 
     /// The session ID of this message.
@@ -91,6 +305,16 @@ impl<Sig> SignedMessage<Sig> {
         &self.session_id
     }
 
+    /// The protocol this message belongs to.
+    pub fn protocol(&self) -> ProtocolId {
+        self.protocol
+    }
+
+    /// The fingerprint of the scheme parameters of the sender.
+    pub(crate) fn params(&self) -> Option<HashOutput> {
+        self.params
+    }
+
     /// The round of this message.
     pub fn round(&self) -> u8 {
         self.round
@@ -102,56 +326,208 @@ impl<Sig> SignedMessage<Sig> {
     }
 
     /// Compares the "significant" part of the messages (that is, everything but signatures)
+    ///
+    /// The payloads are compared as they are sent (that is, after the compression, if any).
     pub fn is_same_as(&self, other: &Self) -> bool {
         self.session_id == other.session_id
+            && self.protocol == other.protocol
+            && self.params == other.params
             && self.round == other.round
             && self.message_type == other.message_type
+            && self.compressed == other.compressed
             && self.payload == other.payload
     }
 }
 
+fn sign<Sig>(
+    rng: &mut impl CryptoRngCore,
+    signer: &impl RandomizedPrehashSigner<Sig>,
+    message_hash: HashOutput,
+) -> Result<Sig, LocalError> {
+    signer
+        .sign_prehash_with_rng(rng, message_hash.as_ref())
+        .map_err(|err| LocalError(err.to_string()))
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
-pub(crate) struct VerifiedMessage<Sig>(SignedMessage<Sig>);
+pub(crate) struct VerifiedMessage<Sig> {
+    message: SignedMessage<Sig>,
+    /// The decompressed payload, if the message is compressed.
+    decompressed: Option<Box<[u8]>>,
+}
 
 impl<Sig> VerifiedMessage<Sig> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         rng: &mut impl CryptoRngCore,
         signer: &impl RandomizedPrehashSigner<Sig>,
         session_id: &SessionId,
+        protocol: ProtocolId,
+        params: Option<HashOutput>,
         round: u8,
         message_type: MessageType,
         message_bytes: &[u8],
     ) -> Result<Self, LocalError> {
         // In order for the messages be impossible to reuse by a malicious third party,
-        // we need to sign, besides the message itself, the session and the round in this session
-        // it belongs to.
+        // we need to sign, besides the message itself, the session, the protocol
+        // (along with its parameters), and the round in this session it belongs to.
         // We also need the exact way we sign this to be a part of the public ABI,
         // so that these signatures could be verified by a third party.
 
-        let signature = signer
-            .sign_prehash_with_rng(
-                rng,
-                message_hash(session_id, round, message_type, message_bytes).as_ref(),
-            )
-            .map_err(|err| LocalError(err.to_string()))?;
-        Ok(Self(SignedMessage {
-            session_id: *session_id,
-            round,
-            message_type,
-            payload: message_bytes.into(),
-            signature,
-        }))
+        let signature = sign(
+            rng,
+            signer,
+            message_hash(
+                session_id,
+                protocol,
+                params,
+                round,
+                message_type,
+                false,
+                message_bytes,
+            ),
+        )?;
+        Ok(Self {
+            message: SignedMessage {
+                session_id: *session_id,
+                protocol,
+                params,
+                round,
+                message_type,
+                compressed: false,
+                encrypted: false,
+                payload: message_bytes.into(),
+                signature,
+            },
+            decompressed: None,
+        })
     }
 
     pub fn as_unverified(&self) -> &SignedMessage<Sig> {
-        &self.0
+        &self.message
     }
 
     pub fn into_unverified(self) -> SignedMessage<Sig> {
-        self.0
+        self.message
     }
 
     pub fn payload(&self) -> &[u8] {
-        &self.0.payload
+        self.decompressed
+            .as_deref()
+            .unwrap_or(&self.message.payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BeaconValue, SessionId};
+    use crate::uint::subtle::ConstantTimeEq;
+
+    #[test]
+    fn session_id_from_beacon() {
+        let beacon = BeaconValue::new(1234, b"beacon randomness");
+        let session_id = SessionId::from_seed_and_beacon(b"ceremony", &beacon);
+
+        assert!(session_id.is_derived_from_beacon(b"ceremony", &beacon));
+        assert_ne!(session_id, SessionId::from_seed(b"ceremony"));
+
+        // Another round of the beacon
+        let other_round = BeaconValue::new(1235, b"beacon randomness");
+        assert!(!session_id.is_derived_from_beacon(b"ceremony", &other_round));
+
+        // Other randomness for the same round
+        let other_randomness = BeaconValue::new(1234, b"other randomness");
+        assert!(!session_id.is_derived_from_beacon(b"ceremony", &other_randomness));
+
+        assert!(!session_id.is_derived_from_beacon(b"other ceremony", &beacon));
+    }
+
+    #[test]
+    fn session_id_ct_eq() {
+        let session_id = SessionId::from_seed(b"ceremony");
+        assert!(bool::from(
+            session_id.ct_eq(&SessionId::from_seed(b"ceremony"))
+        ));
+        assert!(!bool::from(
+            session_id.ct_eq(&SessionId::from_seed(b"other ceremony"))
+        ));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn compressed_payload_is_verified_before_decompression() {
+        use k256::ecdsa::{Signature, SigningKey};
+        use rand_core::OsRng;
+
+        use super::{message_hash, sign, MessageType, VerifiedMessage};
+        use crate::rounds::ProtocolId;
+
+        let signer = SigningKey::random(&mut OsRng);
+        let session_id = SessionId::from_seed(b"ceremony");
+        let message = VerifiedMessage::<Signature>::new(
+            &mut OsRng,
+            &signer,
+            &session_id,
+            ProtocolId::KeyInit,
+            None,
+            1,
+            MessageType::Broadcast,
+            &[0u8; 1024],
+        )
+        .unwrap()
+        .into_unverified()
+        .compress(&mut OsRng, &signer)
+        .unwrap();
+        assert!(message.compressed);
+
+        // A correctly signed compressed message is decompressed after the verification
+        let verified = message.clone().verify(signer.verifying_key()).unwrap();
+        assert_eq!(verified.payload(), &[0u8; 1024]);
+
+        // A payload that would be inflated past the limit
+        let bomb: Box<[u8]> =
+            miniz_oxide::deflate::compress_to_vec(&vec![0u8; 4 * 1024 * 1024], 10).into();
+        let sign_bomb = |signer: &SigningKey| {
+            let mut bomb_message = message.clone();
+            bomb_message.payload = bomb.clone();
+            bomb_message.signature = sign(
+                &mut OsRng,
+                signer,
+                message_hash(
+                    &session_id,
+                    ProtocolId::KeyInit,
+                    None,
+                    1,
+                    MessageType::Broadcast,
+                    true,
+                    &bomb,
+                ),
+            )
+            .unwrap();
+            bomb_message
+        };
+
+        // Signed by the sender, it is rejected by the decompression limit
+        let err = sign_bomb(&signer)
+            .verify(signer.verifying_key())
+            .unwrap_err();
+        assert!(err.contains("Failed to decompress"));
+
+        // Signed by someone else, or with the signature of another payload,
+        // it is rejected without being decompressed
+        let err = sign_bomb(&SigningKey::random(&mut OsRng))
+            .verify(signer.verifying_key())
+            .unwrap_err();
+        assert!(!err.contains("Failed to decompress"));
+
+        let mut forged = message.clone();
+        forged.payload = bomb;
+        let err = forged.verify(signer.verifying_key()).unwrap_err();
+        assert!(!err.contains("Failed to decompress"));
+
+        // The compression flag is covered by the signature
+        let mut forged = message;
+        forged.compressed = false;
+        assert!(forged.verify(signer.verifying_key()).is_err());
     }
 }
@@ -13,6 +13,7 @@ use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
 
 use super::error::LocalError;
+use super::evidence::EvidenceContext;
 use crate::rounds::{
     self, FinalizableToNextRound, FinalizableToResult, ProtocolResult, Round, ToNextRound, ToResult,
 };
@@ -26,8 +27,16 @@ pub(crate) fn serialize_message(message: &impl Serialize) -> Result<Box<[u8]>, L
 pub(crate) fn deserialize_message<M: for<'de> Deserialize<'de>>(
     message_bytes: &[u8],
 ) -> Result<M, String> {
-    bincode::serde::decode_borrowed_from_slice(message_bytes, bincode::config::standard())
-        .map_err(|err| err.to_string())
+    let (message, bytes_read) =
+        bincode::serde::decode_from_slice(message_bytes, bincode::config::standard())
+            .map_err(|err| err.to_string())?;
+    if bytes_read != message_bytes.len() {
+        return Err(format!(
+            "{} trailing bytes after the message",
+            message_bytes.len() - bytes_read
+        ));
+    }
+    Ok(message)
 }
 
 pub(crate) enum FinalizeOutcome<I, Res: ProtocolResult> {
@@ -102,12 +111,25 @@ pub(crate) trait DynRound<I, Res: ProtocolResult>: Send + Sync {
         &self,
         rng: &mut dyn CryptoRngCore,
     ) -> Result<Option<Box<[u8]>>, LocalError>;
+    #[cfg(feature = "testing")]
+    fn make_equivocating_broadcast(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+        destination: &I,
+    ) -> Result<Option<Box<[u8]>>, LocalError>;
     #[allow(clippy::type_complexity)]
     fn make_direct_message(
         &self,
         rng: &mut dyn CryptoRngCore,
         destination: &I,
     ) -> Result<(Option<Box<[u8]>>, DynArtifact), LocalError>;
+    fn pre_verify_message(
+        &self,
+        from: &I,
+        broadcast_data: Option<&[u8]>,
+        direct_data: Option<&[u8]>,
+    ) -> Result<(), ReceiveError<Res>>;
+    /// Runs both the checks of `pre_verify_message()` and the expensive ones.
     fn verify_message(
         &self,
         rng: &mut dyn CryptoRngCore,
@@ -115,14 +137,90 @@ pub(crate) trait DynRound<I, Res: ProtocolResult>: Send + Sync {
         broadcast_data: Option<&[u8]>,
         direct_data: Option<&[u8]>,
     ) -> Result<DynPayload, ReceiveError<Res>>;
+    fn verify_own_message(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+        destination: &I,
+        broadcast_data: Option<&[u8]>,
+        direct_data: Option<&[u8]>,
+    ) -> Result<(), LocalError>;
     fn can_finalize(&self, accum: &DynRoundAccum<I>) -> bool;
     fn missing_messages(&self, accum: &DynRoundAccum<I>) -> BTreeSet<I>;
+    fn evidence_context(&self) -> EvidenceContext<I>;
 }
 
-fn is_null_type<T: 'static>() -> bool {
+pub(crate) fn is_null_type<T: 'static>() -> bool {
     TypeId::of::<T>() == TypeId::of::<()>()
 }
 
+/// Deserializes a message created by this node, and checks that serializing it again
+/// results in the same bytes.
+fn deserialize_own_message<M: Serialize + for<'de> Deserialize<'de> + 'static>(
+    data: Option<&[u8]>,
+) -> Result<M, LocalError> {
+    let data = match data {
+        Some(data) => data,
+        None if is_null_type::<M>() => b"",
+        None => return Err(LocalError("Expected a non-null message".into())),
+    };
+    let message: M = deserialize_message(data)
+        .map_err(|err| LocalError(format!("Failed to deserialize own message: {err}")))?;
+    if serialize_message(&message)?.as_ref() != data {
+        return Err(LocalError(
+            "Own message does not survive a serialization round-trip".into(),
+        ));
+    }
+    Ok(message)
+}
+
+/// Deserializes the parts of a message received from another node.
+#[allow(clippy::type_complexity)]
+fn deserialize_received<I: Ord + Clone, R: Round<I>>(
+    broadcast_data: Option<&[u8]>,
+    direct_data: Option<&[u8]>,
+) -> Result<(R::BroadcastMessage, R::DirectMessage), ReceiveError<R::Result>>
+where
+    R::BroadcastMessage: 'static,
+    R::DirectMessage: 'static,
+{
+    let null_broadcast = is_null_type::<R::BroadcastMessage>();
+    let null_direct = is_null_type::<R::DirectMessage>();
+
+    let broadcast_data = if let Some(data) = broadcast_data {
+        data
+    } else {
+        if !null_broadcast {
+            return Err(ReceiveError::InvalidContents(
+                "Expected a non-null broadcast message".into(),
+            ));
+        }
+        b""
+    };
+
+    let broadcast_message: R::BroadcastMessage = match deserialize_message(broadcast_data) {
+        Ok(message) => message,
+        Err(err) => return Err(ReceiveError::CannotDeserialize(err)),
+    };
+
+    let direct_data = if let Some(data) = direct_data {
+        data
+    } else {
+        if !null_direct {
+            return Err(ReceiveError::InvalidContents(
+                "Expected a non-null direct message".into(),
+            ));
+        }
+        b""
+    };
+
+    let direct_message: R::DirectMessage = match deserialize_message(direct_data) {
+        Ok(message) => message,
+        Err(err) => return Err(ReceiveError::CannotDeserialize(err)),
+    };
+
+    Ok((broadcast_message, direct_message))
+}
+
 impl<I, R> DynRound<I, R::Result> for R
 where
     I: Ord + Clone,
@@ -164,6 +262,18 @@ where
         Ok(serialized)
     }
 
+    #[cfg(feature = "testing")]
+    fn make_equivocating_broadcast(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+        destination: &I,
+    ) -> Result<Option<Box<[u8]>>, LocalError> {
+        let mut boxed_rng = BoxedRng(rng);
+        self.make_equivocating_broadcast(&mut boxed_rng, destination)
+            .map(|message| serialize_message(&message))
+            .transpose()
+    }
+
     fn make_direct_message(
         &self,
         rng: &mut dyn CryptoRngCore,
@@ -188,6 +298,18 @@ where
         Ok((message, DynArtifact(Box::new(typed_artifact))))
     }
 
+    fn pre_verify_message(
+        &self,
+        from: &I,
+        broadcast_data: Option<&[u8]>,
+        direct_data: Option<&[u8]>,
+    ) -> Result<(), ReceiveError<R::Result>> {
+        let (broadcast_message, direct_message) =
+            deserialize_received::<I, R>(broadcast_data, direct_data)?;
+        self.pre_verify_message(from, &broadcast_message, &direct_message)
+            .map_err(ReceiveError::Protocol)
+    }
+
     fn verify_message(
         &self,
         rng: &mut dyn CryptoRngCore,
@@ -195,42 +317,11 @@ where
         broadcast_data: Option<&[u8]>,
         direct_data: Option<&[u8]>,
     ) -> Result<DynPayload, ReceiveError<R::Result>> {
-        let null_broadcast = is_null_type::<R::BroadcastMessage>();
-        let null_direct = is_null_type::<R::DirectMessage>();
-
-        let broadcast_data = if let Some(data) = broadcast_data {
-            data
-        } else {
-            if !null_broadcast {
-                return Err(ReceiveError::InvalidContents(
-                    "Expected a non-null broadcast message".into(),
-                ));
-            }
-            b""
-        };
-
-        let broadcast_message: <R as Round<I>>::BroadcastMessage =
-            match deserialize_message(broadcast_data) {
-                Ok(message) => message,
-                Err(err) => return Err(ReceiveError::CannotDeserialize(err)),
-            };
+        let (broadcast_message, direct_message) =
+            deserialize_received::<I, R>(broadcast_data, direct_data)?;
 
-        let direct_data = if let Some(data) = direct_data {
-            data
-        } else {
-            if !null_direct {
-                return Err(ReceiveError::InvalidContents(
-                    "Expected a non-null direct message".into(),
-                ));
-            }
-            b""
-        };
-
-        let direct_message: <R as Round<I>>::DirectMessage = match deserialize_message(direct_data)
-        {
-            Ok(message) => message,
-            Err(err) => return Err(ReceiveError::CannotDeserialize(err)),
-        };
+        self.pre_verify_message(from, &broadcast_message, &direct_message)
+            .map_err(ReceiveError::Protocol)?;
 
         let mut boxed_rng = BoxedRng(rng);
 
@@ -241,8 +332,33 @@ where
         Ok(DynPayload(Box::new(payload)))
     }
 
+    fn verify_own_message(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+        destination: &I,
+        broadcast_data: Option<&[u8]>,
+        direct_data: Option<&[u8]>,
+    ) -> Result<(), LocalError> {
+        let broadcast_message: <R as Round<I>>::BroadcastMessage =
+            deserialize_own_message(broadcast_data)?;
+        let direct_message: <R as Round<I>>::DirectMessage = deserialize_own_message(direct_data)?;
+
+        let mut boxed_rng = BoxedRng(rng);
+        if !self.verify_own_message(
+            &mut boxed_rng,
+            destination,
+            &broadcast_message,
+            &direct_message,
+        ) {
+            return Err(LocalError("Own message failed verification".into()));
+        }
+
+        Ok(())
+    }
+
     fn requires_echo(&self) -> bool {
-        <R as Round<I>>::REQUIRES_ECHO
+        // The session never adds an echo round after the last round.
+        <R as Round<I>>::HAS_ECHO_ROUND
     }
 
     fn can_finalize(&self, accum: &DynRoundAccum<I>) -> bool {
@@ -252,6 +368,10 @@ where
     fn missing_messages(&self, accum: &DynRoundAccum<I>) -> BTreeSet<I> {
         self.missing_messages(&accum.received)
     }
+
+    fn evidence_context(&self) -> EvidenceContext<I> {
+        self.evidence_context()
+    }
 }
 
 pub(crate) struct DynRoundAccum<I> {
@@ -0,0 +1,646 @@
+//! A machine-readable description of the protocols.
+//!
+//! The description is derived from the types used by the implementation:
+//! the rounds are those of the actual round types, the message schemas are traced
+//! from the messages created during a run of each protocol, and the hash domains
+//! and the proofs are the ones the protocols use.
+//! It is meant to be compared against the paper and other implementations.
+
+mod schema;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::type_name;
+use core::fmt::Debug;
+
+use displaydoc::Display;
+use rand_core::CryptoRngCore;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cggmp21::{
+    aux_gen, batch_signing, fast_key_init, interactive_signing, key_gen, key_init, key_refresh,
+    presigning, proof_types, signing, AuxInfo, KeyShare, PresigningData, SchemeParams,
+    UnsafeAssumeReliableBroadcast, PARAMS_HASH_TAG,
+};
+use crate::curve::Scalar;
+use crate::handshake::{self, HandshakeConfig};
+use crate::paillier::PaillierParams;
+use crate::rounds::{
+    test_utils::{step_next_round, step_result, step_round, AssembledRound, Id, Without},
+    FinalizeError, FirstRound, ProtocolId, ProtocolResult, Round,
+};
+use crate::sessions::{
+    is_null_type, MESSAGE_HASH_TAG, SESSION_ID_HASH_TAG, SESSION_ID_WITH_BEACON_HASH_TAG,
+};
+use crate::www02::key_resharing::{self, KeyResharingInputs, NewHolder, OldHolder};
+use crate::www02::ThresholdKeyShare;
+
+const SHARED_RANDOMNESS: &[u8] = b"protocol specification";
+
+/// An error when deriving the specification.
+#[derive(Debug, Clone, Display)]
+#[displaydoc("Error when deriving the specification ({0})")]
+pub struct SpecError(String);
+
+/// A part of a round message.
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageSpec {
+    /// The Rust type of the message part.
+    pub rust_type: String,
+    /// The JSON schema of the message part.
+    ///
+    /// Describes the structure of the value in the `serde` data model,
+    /// as it is serialized in the messages (that is, in a non-human-readable format).
+    /// Byte strings are described as base64-encoded strings.
+    /// Only the enum variants present in the traced messages are listed.
+    pub schema: Value,
+}
+
+/// A round of a protocol.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundSpec {
+    /// The round number.
+    pub round: u8,
+    /// Whether the round is followed by an echo round.
+    pub echo: bool,
+    /// The broadcast sent in this round, if any.
+    pub broadcast: Option<MessageSpec>,
+    /// The direct messages sent in this round, if any.
+    pub direct: Option<MessageSpec>,
+}
+
+/// A protocol.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProtocolSpec {
+    /// The protocol identifier, as included in the message envelopes.
+    pub protocol: ProtocolId,
+    /// The rounds of the protocol, in order.
+    pub rounds: Vec<RoundSpec>,
+    /// The domains of the hashes specific to the protocol (excluding the proofs).
+    pub hash_domains: Vec<String>,
+    /// The Rust types of the proofs included in the messages of the protocol
+    /// (see [`Specification::proofs`]).
+    pub proofs: Vec<String>,
+}
+
+/// A zero-knowledge proof.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofSpec {
+    /// The Rust type of the proof.
+    pub rust_type: String,
+    /// The name of the proof in the paper.
+    pub notation: String,
+    /// The domain of the Fiat-Shamir challenge of the proof.
+    pub hash_domain: String,
+}
+
+/// The scheme parameters the specification was derived with.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamsSpec {
+    /// The Rust type of the parameters.
+    pub rust_type: String,
+    /// The security parameter $\kappa$.
+    pub security_parameter: usize,
+    /// The bound $\ell$.
+    pub l_bound: usize,
+    /// The bound $\ell^\prime$.
+    pub lp_bound: usize,
+    /// The bound $\eps$.
+    pub eps_bound: usize,
+    /// The size of the Paillier primes, in bits.
+    pub paillier_prime_bits: usize,
+}
+
+/// The description of all the protocols.
+#[derive(Debug, Clone, Serialize)]
+pub struct Specification {
+    /// The scheme parameters.
+    pub params: ParamsSpec,
+    /// The domains of the hashes used by all the protocols
+    /// (the session IDs, the message envelopes, and the parameters fingerprint).
+    pub hash_domains: Vec<String>,
+    /// The zero-knowledge proofs.
+    pub proofs: Vec<ProofSpec>,
+    /// The protocols.
+    pub protocols: Vec<ProtocolSpec>,
+}
+
+impl Specification {
+    /// Serializes the specification to a pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, SpecError> {
+        serde_json::to_string_pretty(self).map_err(|err| SpecError(err.to_string()))
+    }
+}
+
+/// Derives the specification of the protocols with the scheme parameters `P`.
+///
+/// Runs every protocol between two parties to trace the messages,
+/// so with [`ProductionParams`](`crate::ProductionParams`) it takes a while.
+/// The sizes of some of the values in the schemas depend on `P`.
+pub fn specification<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+) -> Result<Specification, SpecError> {
+    let ids = BTreeSet::from([Id(0), Id(1)]);
+
+    let key_shares = KeyShare::<P, Id>::new_centralized(rng, &ids, None);
+    let aux_infos = AuxInfo::<P, Id>::new_centralized(rng, &ids);
+
+    let (presigning_rounds, presigning_datas) =
+        trace_presigning(rng, &ids, &key_shares, &aux_infos)?;
+
+    let traced = [
+        (ProtocolId::KeyInit, trace_key_init::<P>(rng, &ids)?),
+        (ProtocolId::KeyRefresh, trace_key_refresh::<P>(rng, &ids)?),
+        (ProtocolId::AuxGen, trace_aux_gen::<P>(rng, &ids)?),
+        (ProtocolId::KeyGen, trace_key_gen::<P>(rng, &ids)?),
+        (ProtocolId::Presigning, presigning_rounds),
+        (
+            ProtocolId::Signing,
+            trace_signing(rng, &ids, &key_shares, &aux_infos, &presigning_datas)?,
+        ),
+        (
+            ProtocolId::InteractiveSigning,
+            trace_interactive_signing(rng, &ids, &key_shares, &aux_infos)?,
+        ),
+        (
+            ProtocolId::KeyResharing,
+            trace_key_resharing::<P>(rng, &ids)?,
+        ),
+        (ProtocolId::Handshake, trace_handshake::<P>(rng, &ids)?),
+        (
+            ProtocolId::BatchSigning,
+            trace_batch_signing(rng, &ids, &key_shares, &aux_infos, &presigning_datas)?,
+        ),
+        (
+            ProtocolId::FastKeyInit,
+            trace_fast_key_init::<P>(rng, &ids)?,
+        ),
+    ];
+
+    let proofs = proof_types::<P>()
+        .iter()
+        .map(|(rust_type, notation, hash_tag)| ProofSpec {
+            rust_type: short_type_name(rust_type),
+            notation: notation.to_string(),
+            hash_domain: hash_domain(hash_tag),
+        })
+        .collect::<Vec<_>>();
+
+    let protocols = traced
+        .into_iter()
+        .map(|(protocol, rounds)| {
+            let mut titles = BTreeSet::new();
+            for message in rounds
+                .iter()
+                .flat_map(|round| round.broadcast.iter().chain(round.direct.iter()))
+            {
+                collect_titles(&message.schema, &mut titles);
+            }
+            let proofs = proofs
+                .iter()
+                .filter(|proof| titles.contains(base_name(&proof.rust_type)))
+                .map(|proof| proof.rust_type.clone())
+                .collect();
+            ProtocolSpec {
+                protocol,
+                rounds,
+                hash_domains: protocol_hash_tags(protocol)
+                    .into_iter()
+                    .map(hash_domain)
+                    .collect(),
+                proofs,
+            }
+        })
+        .collect();
+
+    Ok(Specification {
+        params: ParamsSpec {
+            rust_type: short_type_name(type_name::<P>()),
+            security_parameter: P::SECURITY_PARAMETER,
+            l_bound: P::L_BOUND,
+            lp_bound: P::LP_BOUND,
+            eps_bound: P::EPS_BOUND,
+            paillier_prime_bits: P::Paillier::PRIME_BITS,
+        },
+        hash_domains: [
+            SESSION_ID_HASH_TAG,
+            SESSION_ID_WITH_BEACON_HASH_TAG,
+            MESSAGE_HASH_TAG,
+            PARAMS_HASH_TAG,
+        ]
+        .into_iter()
+        .map(hash_domain)
+        .collect(),
+        proofs,
+        protocols,
+    })
+}
+
+fn protocol_hash_tags(protocol: ProtocolId) -> Vec<&'static [u8]> {
+    match protocol {
+        ProtocolId::KeyInit => vec![key_init::COMMITMENT_HASH_TAG, key_init::SID_HASH_TAG],
+        ProtocolId::KeyRefresh => vec![key_refresh::COMMITMENT_HASH_TAG, key_refresh::SID_HASH_TAG],
+        ProtocolId::AuxGen => vec![aux_gen::COMMITMENT_HASH_TAG, aux_gen::SID_HASH_TAG],
+        // KeyGen runs KeyInit and KeyRefresh in parallel
+        ProtocolId::KeyGen => vec![
+            key_init::COMMITMENT_HASH_TAG,
+            key_init::SID_HASH_TAG,
+            key_refresh::COMMITMENT_HASH_TAG,
+            key_refresh::SID_HASH_TAG,
+        ],
+        ProtocolId::Presigning => vec![presigning::SSID_HASH_TAG],
+        ProtocolId::Signing => vec![signing::SSID_HASH_TAG],
+        ProtocolId::BatchSigning => vec![signing::SSID_HASH_TAG, batch_signing::ITEM_HASH_TAG],
+        // InteractiveSigning runs Presigning and then Signing
+        ProtocolId::InteractiveSigning => {
+            vec![presigning::SSID_HASH_TAG, signing::SSID_HASH_TAG]
+        }
+        ProtocolId::KeyResharing => Vec::new(),
+        ProtocolId::Handshake => vec![handshake::HASH_TAG],
+        ProtocolId::FastKeyInit => vec![fast_key_init::SID_HASH_TAG],
+    }
+}
+
+fn hash_domain(tag: &[u8]) -> String {
+    String::from_utf8_lossy(tag).into_owned()
+}
+
+/// Removes the module paths from a type name returned by [`type_name`].
+fn short_type_name(full_name: &str) -> String {
+    let mut name = String::new();
+    let mut segment = String::new();
+    for c in full_name.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else if c == ':' {
+            // The segment was a module
+            segment.clear();
+        } else {
+            name.push_str(&segment);
+            name.push(c);
+            segment.clear();
+        }
+    }
+    name.push_str(&segment);
+    name
+}
+
+/// Returns the type name without the generic parameters.
+fn base_name(rust_type: &str) -> &str {
+    rust_type.split('<').next().unwrap_or(rust_type)
+}
+
+/// Collects the titles (that is, the names of the Rust types) in a schema.
+fn collect_titles(schema: &Value, titles: &mut BTreeSet<String>) {
+    match schema {
+        Value::Object(object) => {
+            for (key, value) in object {
+                match (key.as_str(), value) {
+                    ("title", Value::String(title)) => {
+                        titles.insert(title.clone());
+                    }
+                    _ => collect_titles(value, titles),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_titles(item, titles);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn message_spec<T: Serialize>(message: &T) -> Result<MessageSpec, SpecError> {
+    Ok(MessageSpec {
+        rust_type: short_type_name(type_name::<T>()),
+        schema: schema::schema_of(message).map_err(|err| SpecError(err.to_string()))?,
+    })
+}
+
+fn finalize_error<Res: ProtocolResult>(error: FinalizeError<Res>) -> SpecError {
+    SpecError(format!("{error:?}"))
+}
+
+fn make_rounds<R: FirstRound<Id>>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+    inputs: impl Fn(&Id) -> R::Inputs,
+) -> Result<BTreeMap<Id, R>, SpecError> {
+    ids.iter()
+        .map(|id| {
+            let round = R::new(
+                rng,
+                SHARED_RANDOMNESS,
+                ids.clone().without(id),
+                *id,
+                inputs(id),
+            )
+            .map_err(|err| SpecError(err.to_string()))?;
+            Ok((*id, round))
+        })
+        .collect()
+}
+
+/// Records the messages of the first party, and executes the round.
+fn trace_round<I, R>(
+    rng: &mut impl CryptoRngCore,
+    rounds: BTreeMap<I, R>,
+    spec: &mut Vec<RoundSpec>,
+) -> Result<BTreeMap<I, AssembledRound<I, R>>, SpecError>
+where
+    I: Debug + Clone + Ord,
+    R: Round<I>,
+    <R as Round<I>>::BroadcastMessage: Clone + 'static,
+    <R as Round<I>>::DirectMessage: 'static,
+{
+    let round = rounds
+        .values()
+        .next()
+        .ok_or_else(|| SpecError("No parties to trace the round with".into()))?;
+
+    let broadcast = if is_null_type::<R::BroadcastMessage>() {
+        None
+    } else {
+        let message = round
+            .make_broadcast_message(rng)
+            .ok_or_else(|| SpecError(format!("No broadcast in round {}", R::ROUND_NUM)))?;
+        Some(message_spec(&message)?)
+    };
+
+    let direct = if is_null_type::<R::DirectMessage>() {
+        None
+    } else {
+        let destination = round
+            .message_destinations()
+            .iter()
+            .next()
+            .ok_or_else(|| SpecError(format!("No destinations in round {}", R::ROUND_NUM)))?;
+        let (message, _artifact) = round.make_direct_message(rng, destination);
+        Some(message_spec(&message)?)
+    };
+
+    spec.push(RoundSpec {
+        round: R::ROUND_NUM,
+        echo: R::HAS_ECHO_ROUND,
+        broadcast,
+        direct,
+    });
+
+    step_round(rng, rounds).map_err(|err| SpecError(err.to_string()))
+}
+
+fn trace_key_init<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+) -> Result<Vec<RoundSpec>, SpecError> {
+    let mut spec = Vec::new();
+    let r1 = make_rounds::<key_init::Round1<P, Id>>(rng, ids, |_| ())?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    let r2 = step_next_round(rng, r1a).map_err(finalize_error)?;
+    let r2a = trace_round(rng, r2, &mut spec)?;
+    let r3 = step_next_round(rng, r2a).map_err(finalize_error)?;
+    let r3a = trace_round(rng, r3, &mut spec)?;
+    step_result(rng, r3a).map_err(finalize_error)?;
+    Ok(spec)
+}
+
+fn trace_key_refresh<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+) -> Result<Vec<RoundSpec>, SpecError> {
+    let mut spec = Vec::new();
+    let r1 = make_rounds::<key_refresh::Round1<P, Id>>(rng, ids, |_| ())?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    let r2 = step_next_round(rng, r1a).map_err(finalize_error)?;
+    let r2a = trace_round(rng, r2, &mut spec)?;
+    let r3 = step_next_round(rng, r2a).map_err(finalize_error)?;
+    let r3a = trace_round(rng, r3, &mut spec)?;
+    step_result(rng, r3a).map_err(finalize_error)?;
+    Ok(spec)
+}
+
+fn trace_aux_gen<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+) -> Result<Vec<RoundSpec>, SpecError> {
+    let mut spec = Vec::new();
+    let r1 = make_rounds::<aux_gen::Round1<P, Id>>(rng, ids, |_| ())?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    let r2 = step_next_round(rng, r1a).map_err(finalize_error)?;
+    let r2a = trace_round(rng, r2, &mut spec)?;
+    let r3 = step_next_round(rng, r2a).map_err(finalize_error)?;
+    let r3a = trace_round(rng, r3, &mut spec)?;
+    step_result(rng, r3a).map_err(finalize_error)?;
+    Ok(spec)
+}
+
+fn trace_key_gen<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+) -> Result<Vec<RoundSpec>, SpecError> {
+    let mut spec = Vec::new();
+    let r1 = make_rounds::<key_gen::Round1<P, Id>>(rng, ids, |_| ())?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    let r2 = step_next_round(rng, r1a).map_err(finalize_error)?;
+    let r2a = trace_round(rng, r2, &mut spec)?;
+    let r3 = step_next_round(rng, r2a).map_err(finalize_error)?;
+    let r3a = trace_round(rng, r3, &mut spec)?;
+    step_result(rng, r3a).map_err(finalize_error)?;
+    Ok(spec)
+}
+
+#[allow(clippy::type_complexity)]
+fn trace_presigning<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+    key_shares: &BTreeMap<Id, KeyShare<P, Id>>,
+    aux_infos: &BTreeMap<Id, AuxInfo<P, Id>>,
+) -> Result<(Vec<RoundSpec>, BTreeMap<Id, PresigningData<P, Id>>), SpecError> {
+    let mut spec = Vec::new();
+    let r1 = make_rounds::<presigning::Round1<P, Id>>(rng, ids, |id| {
+        (key_shares[id].clone(), aux_infos[id].clone())
+    })?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    let r2 = step_next_round(rng, r1a).map_err(finalize_error)?;
+    let r2a = trace_round(rng, r2, &mut spec)?;
+    let r3 = step_next_round(rng, r2a).map_err(finalize_error)?;
+    let r3a = trace_round(rng, r3, &mut spec)?;
+    let presigning_datas = step_result(rng, r3a).map_err(finalize_error)?;
+    Ok((spec, presigning_datas))
+}
+
+fn trace_signing<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+    key_shares: &BTreeMap<Id, KeyShare<P, Id>>,
+    aux_infos: &BTreeMap<Id, AuxInfo<P, Id>>,
+    presigning_datas: &BTreeMap<Id, PresigningData<P, Id>>,
+) -> Result<Vec<RoundSpec>, SpecError> {
+    let mut spec = Vec::new();
+    let message = Scalar::random(rng);
+    let r1 = make_rounds::<signing::Round1<P, Id>>(rng, ids, |id| signing::Inputs {
+        message,
+        presigning: presigning_datas[id].clone(),
+        key_share: key_shares[id].clone(),
+        aux_info: aux_infos[id].clone(),
+    })?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    step_result(rng, r1a).map_err(finalize_error)?;
+    Ok(spec)
+}
+
+fn trace_interactive_signing<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+    key_shares: &BTreeMap<Id, KeyShare<P, Id>>,
+    aux_infos: &BTreeMap<Id, AuxInfo<P, Id>>,
+) -> Result<Vec<RoundSpec>, SpecError> {
+    let mut spec = Vec::new();
+    let message = Scalar::random(rng);
+    let r1 = make_rounds::<interactive_signing::Round1<P, Id>>(rng, ids, |id| {
+        interactive_signing::Inputs {
+            key_share: key_shares[id].clone(),
+            aux_info: aux_infos[id].clone(),
+            message,
+        }
+    })?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    let r2 = step_next_round(rng, r1a).map_err(finalize_error)?;
+    let r2a = trace_round(rng, r2, &mut spec)?;
+    let r3 = step_next_round(rng, r2a).map_err(finalize_error)?;
+    let r3a = trace_round(rng, r3, &mut spec)?;
+    let r4 = step_next_round(rng, r3a).map_err(finalize_error)?;
+    let r4a = trace_round(rng, r4, &mut spec)?;
+    step_result(rng, r4a).map_err(finalize_error)?;
+    Ok(spec)
+}
+
+fn trace_batch_signing<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+    key_shares: &BTreeMap<Id, KeyShare<P, Id>>,
+    aux_infos: &BTreeMap<Id, AuxInfo<P, Id>>,
+    presigning_datas: &BTreeMap<Id, PresigningData<P, Id>>,
+) -> Result<Vec<RoundSpec>, SpecError> {
+    let mut spec = Vec::new();
+    let message = Scalar::random(rng);
+    let r1 = make_rounds::<batch_signing::Round1<P, Id>>(rng, ids, |id| batch_signing::Inputs {
+        items: vec![(message, presigning_datas[id].clone())],
+        key_share: key_shares[id].clone(),
+        aux_info: aux_infos[id].clone(),
+    })?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    step_result(rng, r1a).map_err(finalize_error)?;
+    Ok(spec)
+}
+
+fn trace_key_resharing<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+) -> Result<Vec<RoundSpec>, SpecError> {
+    let mut spec = Vec::new();
+    let threshold = ids.len();
+    let key_shares = ThresholdKeyShare::<P, Id>::new_centralized(rng, ids, threshold, None);
+    let verifying_key = key_shares
+        .values()
+        .next()
+        .ok_or_else(|| SpecError("No key shares to reshare".into()))?
+        .verifying_key();
+    let r1 = make_rounds::<key_resharing::Round1<P, Id>>(rng, ids, |id| KeyResharingInputs {
+        old_holder: Some(OldHolder {
+            key_share: key_shares[id].clone(),
+        }),
+        new_holder: Some(NewHolder {
+            verifying_key,
+            old_threshold: threshold,
+            old_holders: ids.clone(),
+        }),
+        new_holders: ids.clone(),
+        new_share_ids: None,
+        new_threshold: threshold,
+    })?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    step_result(rng, r1a).map_err(finalize_error)?;
+    Ok(spec)
+}
+
+fn trace_handshake<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+) -> Result<Vec<RoundSpec>, SpecError> {
+    let mut spec = Vec::new();
+    let config = HandshakeConfig::new::<P>(ids.clone(), ids.len(), SHARED_RANDOMNESS);
+    let r1 = make_rounds::<handshake::Round1<Id>>(rng, ids, |_| config.clone())?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    step_result(rng, r1a).map_err(finalize_error)?;
+    Ok(spec)
+}
+
+fn trace_fast_key_init<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+) -> Result<Vec<RoundSpec>, SpecError> {
+    let mut spec = Vec::new();
+    let r1 =
+        make_rounds::<fast_key_init::Round1<P, Id>>(rng, ids, |_| UnsafeAssumeReliableBroadcast)?;
+    let r1a = trace_round(rng, r1, &mut spec)?;
+    let r2 = step_next_round(rng, r1a).map_err(finalize_error)?;
+    let r2a = trace_round(rng, r2, &mut spec)?;
+    step_result(rng, r2a).map_err(finalize_error)?;
+    Ok(spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use rand_core::OsRng;
+
+    use super::specification;
+    use crate::{RoundInfo, TestParams};
+
+    #[test]
+    fn rounds_match_protocol_ids() {
+        let spec = specification::<TestParams>(&mut OsRng).unwrap();
+        assert_eq!(spec.protocols.len(), 11);
+
+        for protocol in spec.protocols.iter() {
+            let rounds = protocol
+                .rounds
+                .iter()
+                .map(|round| RoundInfo {
+                    round: round.round,
+                    broadcast: round.broadcast.is_some(),
+                    direct: round.direct.is_some(),
+                    echo: round.echo,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                rounds,
+                protocol.protocol.rounds(),
+                "{:?}",
+                protocol.protocol
+            );
+        }
+
+        let key_init = &spec.protocols[0];
+        assert_eq!(key_init.proofs, ["SchProof"]);
+        assert_eq!(key_init.hash_domains, ["KeyInit", "SID"]);
+
+        let presigning = &spec.protocols[4];
+        assert!(presigning
+            .proofs
+            .iter()
+            .any(|proof| proof == "EncProof<TestParams>"));
+
+        let json = spec.to_json().unwrap();
+        assert!(json.contains("\"P_aff_g\""));
+        assert!(json.contains("\"SignedMessage\""));
+    }
+}
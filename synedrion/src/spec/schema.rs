@@ -0,0 +1,545 @@
+//! Deriving JSON schemas of the messages by serializing their samples.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use displaydoc::Display;
+use serde::ser::{self, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Failed to derive the schema ({0})
+#[derive(Debug, Display)]
+pub(crate) struct TraceError(String);
+
+impl ser::Error for TraceError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl ser::StdError for TraceError {}
+
+/// Returns the JSON schema of the value's type as it is serialized in the messages.
+///
+/// The schema is derived from the given sample, so the enums only list the variants
+/// present in it, and the optional values that are `None` are only described as `null`.
+pub(crate) fn schema_of<T: Serialize + ?Sized>(value: &T) -> Result<Value, TraceError> {
+    value.serialize(SchemaSerializer)
+}
+
+fn integer(minimum: impl Into<Value>, maximum: impl Into<Value>) -> Value {
+    json!({ "type": "integer", "minimum": minimum.into(), "maximum": maximum.into() })
+}
+
+/// Unites the schemas of the elements of a sequence.
+fn union(schemas: Vec<Value>) -> Option<Value> {
+    let mut unique = Vec::<Value>::new();
+    for schema in schemas {
+        if !unique.contains(&schema) {
+            unique.push(schema);
+        }
+    }
+    match unique.len() {
+        0 => None,
+        1 => unique.pop(),
+        _ => Some(json!({ "anyOf": unique })),
+    }
+}
+
+fn with_title(name: &str, schema: Value) -> Value {
+    match schema {
+        Value::Object(mut object) if !object.contains_key("title") => {
+            object.insert("title".into(), name.into());
+            Value::Object(object)
+        }
+        schema => json!({ "title": name, "allOf": [schema] }),
+    }
+}
+
+/// Wraps the schema of an enum variant the way `serde` tags them externally.
+fn tagged(name: &str, variant: &str, schema: Value) -> Value {
+    json!({
+        "title": name,
+        "type": "object",
+        "properties": { variant: schema },
+        "required": [variant],
+        "additionalProperties": false,
+    })
+}
+
+struct SchemaSerializer;
+
+impl ser::Serializer for SchemaSerializer {
+    type Ok = Value;
+    type Error = TraceError;
+    type SerializeSeq = SeqSchema;
+    type SerializeTuple = TupleSchema;
+    type SerializeTupleStruct = TupleSchema;
+    type SerializeTupleVariant = TupleSchema;
+    type SerializeMap = MapSchema;
+    type SerializeStruct = StructSchema;
+    type SerializeStructVariant = StructSchema;
+
+    // The messages are serialized in a binary format.
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<Value, TraceError> {
+        Ok(json!({ "type": "boolean" }))
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<Value, TraceError> {
+        Ok(integer(i8::MIN, i8::MAX))
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<Value, TraceError> {
+        Ok(integer(i16::MIN, i16::MAX))
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<Value, TraceError> {
+        Ok(integer(i32::MIN, i32::MAX))
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<Value, TraceError> {
+        Ok(integer(i64::MIN, i64::MAX))
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<Value, TraceError> {
+        Ok(integer(u8::MIN, u8::MAX))
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<Value, TraceError> {
+        Ok(integer(u16::MIN, u16::MAX))
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<Value, TraceError> {
+        Ok(integer(u32::MIN, u32::MAX))
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<Value, TraceError> {
+        Ok(integer(u64::MIN, u64::MAX))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<Value, TraceError> {
+        Ok(json!({ "type": "number" }))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Value, TraceError> {
+        Ok(json!({ "type": "number" }))
+    }
+
+    fn serialize_char(self, _v: char) -> Result<Value, TraceError> {
+        Ok(json!({ "type": "string", "minLength": 1, "maxLength": 1 }))
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<Value, TraceError> {
+        Ok(json!({ "type": "string" }))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Value, TraceError> {
+        Ok(json!({ "type": "string", "contentEncoding": "base64" }))
+    }
+
+    fn serialize_none(self) -> Result<Value, TraceError> {
+        Ok(json!({ "type": "null" }))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, TraceError> {
+        Ok(json!({ "anyOf": [schema_of(value)?, { "type": "null" }] }))
+    }
+
+    fn serialize_unit(self) -> Result<Value, TraceError> {
+        Ok(json!({ "type": "null" }))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Value, TraceError> {
+        Ok(json!({ "title": name, "type": "null" }))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, TraceError> {
+        Ok(json!({ "title": name, "const": variant }))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Value, TraceError> {
+        Ok(with_title(name, schema_of(value)?))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, TraceError> {
+        Ok(tagged(name, variant, schema_of(value)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSchema, TraceError> {
+        Ok(SeqSchema {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<TupleSchema, TraceError> {
+        Ok(TupleSchema::new(None, None, len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<TupleSchema, TraceError> {
+        Ok(TupleSchema::new(Some(name), None, len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<TupleSchema, TraceError> {
+        Ok(TupleSchema::new(Some(name), Some(variant), len))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapSchema, TraceError> {
+        Ok(MapSchema {
+            keys: Vec::new(),
+            values: Vec::new(),
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<StructSchema, TraceError> {
+        Ok(StructSchema::new(name, None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<StructSchema, TraceError> {
+        Ok(StructSchema::new(name, Some(variant)))
+    }
+}
+
+struct SeqSchema {
+    items: Vec<Value>,
+}
+
+impl ser::SerializeSeq for SeqSchema {
+    type Ok = Value;
+    type Error = TraceError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TraceError> {
+        self.items.push(schema_of(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, TraceError> {
+        Ok(match union(self.items) {
+            Some(items) => json!({ "type": "array", "items": items }),
+            None => json!({ "type": "array" }),
+        })
+    }
+}
+
+struct TupleSchema {
+    name: Option<&'static str>,
+    variant: Option<&'static str>,
+    items: Vec<Value>,
+}
+
+impl TupleSchema {
+    fn new(name: Option<&'static str>, variant: Option<&'static str>, len: usize) -> Self {
+        Self {
+            name,
+            variant,
+            items: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TraceError> {
+        self.items.push(schema_of(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Value {
+        let len = self.items.len();
+        // Fixed-size arrays (e.g. the encoded curve points) are serialized as tuples,
+        // so the tuples of identical elements are described as arrays of a fixed length.
+        let schema = if len > 0 && self.items.iter().all(|item| item == &self.items[0]) {
+            json!({
+                "type": "array",
+                "items": self.items[0],
+                "minItems": len,
+                "maxItems": len,
+            })
+        } else {
+            json!({
+                "type": "array",
+                "prefixItems": self.items,
+                "items": false,
+            })
+        };
+        match (self.name, self.variant) {
+            (Some(name), Some(variant)) => tagged(name, variant, schema),
+            (Some(name), None) => with_title(name, schema),
+            (None, _) => schema,
+        }
+    }
+}
+
+impl ser::SerializeTuple for TupleSchema {
+    type Ok = Value;
+    type Error = TraceError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TraceError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, TraceError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for TupleSchema {
+    type Ok = Value;
+    type Error = TraceError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TraceError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, TraceError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for TupleSchema {
+    type Ok = Value;
+    type Error = TraceError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TraceError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, TraceError> {
+        Ok(self.finish())
+    }
+}
+
+struct MapSchema {
+    keys: Vec<Value>,
+    values: Vec<Value>,
+}
+
+impl ser::SerializeMap for MapSchema {
+    type Ok = Value;
+    type Error = TraceError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), TraceError> {
+        self.keys.push(schema_of(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), TraceError> {
+        self.values.push(schema_of(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, TraceError> {
+        let mut schema = Map::new();
+        schema.insert("type".into(), "object".into());
+        if let Some(keys) = union(self.keys) {
+            schema.insert("propertyNames".into(), keys);
+        }
+        if let Some(values) = union(self.values) {
+            schema.insert("additionalProperties".into(), values);
+        }
+        Ok(Value::Object(schema))
+    }
+}
+
+struct StructSchema {
+    name: &'static str,
+    variant: Option<&'static str>,
+    properties: Map<String, Value>,
+    required: Vec<Value>,
+}
+
+impl StructSchema {
+    fn new(name: &'static str, variant: Option<&'static str>) -> Self {
+        Self {
+            name,
+            variant,
+            properties: Map::new(),
+            required: Vec::new(),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), TraceError> {
+        self.properties.insert(key.into(), schema_of(value)?);
+        self.required.push(key.into());
+        Ok(())
+    }
+
+    fn finish(self) -> Value {
+        let schema = json!({
+            "type": "object",
+            "properties": self.properties,
+            "required": self.required,
+            "additionalProperties": false,
+        });
+        match self.variant {
+            Some(variant) => tagged(self.name, variant, schema),
+            None => with_title(self.name, schema),
+        }
+    }
+}
+
+impl ser::SerializeStruct for StructSchema {
+    type Ok = Value;
+    type Error = TraceError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), TraceError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Value, TraceError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for StructSchema {
+    type Ok = Value;
+    type Error = TraceError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), TraceError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Value, TraceError> {
+        Ok(self.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use serde::Serialize;
+    use serde_json::json;
+
+    use super::schema_of;
+
+    #[derive(Serialize)]
+    struct Inner(u8);
+
+    #[derive(Serialize)]
+    enum Choice {
+        A,
+        B { x: bool },
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        point: [u8; 3],
+        pair: (u16, bool),
+        list: Vec<Inner>,
+        map: BTreeMap<u32, Choice>,
+        missing: Option<u64>,
+    }
+
+    #[test]
+    fn derived_schema() {
+        let value = Outer {
+            point: [1, 2, 3],
+            pair: (4, true),
+            list: vec![Inner(5), Inner(6)],
+            map: BTreeMap::from([(7, Choice::A), (8, Choice::B { x: false })]),
+            missing: None,
+        };
+        let byte = json!({ "type": "integer", "minimum": 0, "maximum": 255 });
+
+        assert_eq!(
+            schema_of(&value).unwrap(),
+            json!({
+                "title": "Outer",
+                "type": "object",
+                "properties": {
+                    "point": {
+                        "type": "array",
+                        "items": byte,
+                        "minItems": 3,
+                        "maxItems": 3,
+                    },
+                    "pair": {
+                        "type": "array",
+                        "prefixItems": [
+                            { "type": "integer", "minimum": 0, "maximum": 65535 },
+                            { "type": "boolean" },
+                        ],
+                        "items": false,
+                    },
+                    "list": {
+                        "type": "array",
+                        "items": { "title": "Inner", "type": "integer", "minimum": 0, "maximum": 255 },
+                    },
+                    "map": {
+                        "type": "object",
+                        "propertyNames": { "type": "integer", "minimum": 0, "maximum": 4294967295u64 },
+                        "additionalProperties": {
+                            "anyOf": [
+                                { "title": "Choice", "const": "A" },
+                                {
+                                    "title": "Choice",
+                                    "type": "object",
+                                    "properties": {
+                                        "B": {
+                                            "type": "object",
+                                            "properties": { "x": { "type": "boolean" } },
+                                            "required": ["x"],
+                                            "additionalProperties": false,
+                                        },
+                                    },
+                                    "required": ["B"],
+                                    "additionalProperties": false,
+                                },
+                            ],
+                        },
+                    },
+                    "missing": { "type": "null" },
+                },
+                "required": ["point", "pair", "list", "map", "missing"],
+                "additionalProperties": false,
+            })
+        );
+    }
+}
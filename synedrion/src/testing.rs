@@ -0,0 +1,6 @@
+//! Tools for testing the applications using this library.
+
+#[cfg(feature = "dev-utils")]
+pub mod dealer;
+#[cfg(feature = "testing")]
+pub mod malicious;
@@ -0,0 +1,109 @@
+//! Creating consistent key shares and auxiliary data for a set of parties
+//! without running the protocols, to quickly set up the tests of an application.
+//!
+//! The identities of the parties are derived deterministically from their indices
+//! (see [`make_signers`]), so that the results of all the functions here match each other.
+//! The keys created here are not secure; never use them outside of tests.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+use k256::ecdsa::{SigningKey, VerifyingKey};
+use rand_core::CryptoRngCore;
+
+use crate::cggmp21::{AuxInfo, SchemeParams};
+use crate::tools::hashing::{Chain, XofHasher};
+use crate::www02::ThresholdKeyShare;
+
+/// Returns the signing keys of `n` parties,
+/// which are the same for every call with the same `n`.
+pub fn make_signers(n: usize) -> Vec<SigningKey> {
+    (0..n as u64)
+        .map(|idx| {
+            XofHasher::new_with_dst(b"dev-utils signer")
+                .chain(&idx)
+                .finalize_to_scalar()
+                .to_signing_key()
+                .expect("the scalar is non-zero with overwhelming probability")
+        })
+        .collect()
+}
+
+fn make_verifiers(n: usize) -> BTreeSet<VerifyingKey> {
+    make_signers(n)
+        .iter()
+        .map(|signer| *signer.verifying_key())
+        .collect()
+}
+
+/// Creates the key shares of `n` parties identified by the verifying keys of [`make_signers`],
+/// with `threshold` of them required for signing.
+///
+/// Panics if `threshold` is zero or greater than `n`.
+pub fn make_key_shares<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    n: usize,
+    threshold: usize,
+) -> BTreeMap<VerifyingKey, ThresholdKeyShare<P, VerifyingKey>> {
+    assert!(
+        threshold > 0 && threshold <= n,
+        "The threshold must be between 1 and the number of parties"
+    );
+    ThresholdKeyShare::new_centralized(rng, &make_verifiers(n), threshold, None)
+}
+
+/// Creates the auxiliary data of `n` parties identified by the verifying keys of [`make_signers`].
+pub fn make_aux_infos<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    n: usize,
+) -> BTreeMap<VerifyingKey, AuxInfo<P, VerifyingKey>> {
+    AuxInfo::new_centralized(rng, &make_verifiers(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+
+    use rand_core::OsRng;
+
+    use super::{make_aux_infos, make_key_shares, make_signers};
+    use crate::cggmp21::{TestParams, VerifierBundle};
+
+    #[test]
+    fn consistent_setup() {
+        let signers = make_signers(3);
+        assert_eq!(
+            signers
+                .iter()
+                .map(|signer| signer.to_bytes())
+                .collect::<Vec<_>>(),
+            make_signers(3)
+                .iter()
+                .map(|signer| signer.to_bytes())
+                .collect::<Vec<_>>()
+        );
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let key_shares = make_key_shares::<TestParams>(&mut OsRng, 3, 2);
+        let aux_infos = make_aux_infos::<TestParams>(&mut OsRng, 3);
+        assert!(key_shares.keys().eq(ids.iter()));
+        assert!(aux_infos.keys().eq(ids.iter()));
+
+        let signing_ids = ids.iter().take(2).cloned().collect::<BTreeSet<_>>();
+        let key_share = key_shares[signing_ids.first().unwrap()].to_key_share(&signing_ids);
+        assert_eq!(
+            key_share.verifying_key(),
+            key_shares[ids.last().unwrap()].verifying_key()
+        );
+
+        // The shares of all the parties match the auxiliary data
+        let full_key_shares = make_key_shares::<TestParams>(&mut OsRng, 3, 3);
+        let id = *ids.first().unwrap();
+        let key_share = full_key_shares[&id].to_key_share(&ids);
+        assert!(VerifierBundle::new(&key_share, &aux_infos[&id]).is_some());
+    }
+}
@@ -0,0 +1,334 @@
+//! Sessions of parties deviating from the protocols in known ways,
+//! to test how an application handles the faults of other parties and assigns the blame.
+//!
+//! Not intended for use outside of tests.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::fmt::Debug;
+
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+use signature::{
+    hazmat::{PrehashVerifier, RandomizedPrehashSigner},
+    Keypair,
+};
+
+use crate::cggmp21::{AuxGenResult, KeyInitResult, KeyRefreshResult, SchemeParams};
+use crate::rounds::{
+    FinalizableToNextRound, FinalizableToResult, FinalizationRequirement, FinalizeError,
+    ProtocolResult, Round,
+};
+use crate::sessions::{Session, SessionId, SessionInitError};
+
+/// A way for a party to deviate from the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Behavior {
+    /// In Round 2 of KeyInit, reveal data that does not match the hash committed to in Round 1.
+    WrongHashReveal,
+    /// In AuxGen, commit to and send a `П^{prm}` proof that does not verify.
+    InvalidPrmProof,
+    /// In Round 3 of KeyRefresh, send each party an encrypted secret share
+    /// that does not match the public one.
+    MismatchedSecret,
+    /// Sign a different broadcast for each party in the given round
+    /// (which has to be followed by an echo round for this to be detected).
+    ///
+    /// Supported in Round 1 of KeyInit, AuxGen and KeyRefresh,
+    /// where each party gets a different hash commitment;
+    /// in the other rounds the party behaves honestly.
+    EquivocatingBroadcast {
+        /// The round number.
+        round: u8,
+    },
+    /// Send no messages in the given round.
+    Stall {
+        /// The round number.
+        round: u8,
+    },
+}
+
+/// The deviations from the protocol specific to a round.
+///
+/// The methods are called with every behavior, and only act on the ones relevant to the round.
+pub(crate) trait Misbehave<I: Ord + Clone>: Round<I> {
+    /// Modifies the state of the round right after it is created.
+    fn tamper_state(&mut self, _rng: &mut impl CryptoRngCore, _behavior: &Behavior) {}
+
+    /// Modifies the broadcast message after it is created.
+    fn tamper_broadcast(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _behavior: &Behavior,
+        message: Self::BroadcastMessage,
+    ) -> Self::BroadcastMessage {
+        message
+    }
+
+    /// Returns a version of the broadcast message specific to the given destination,
+    /// for [`Behavior::EquivocatingBroadcast`].
+    ///
+    /// The default implementation returns the message unchanged,
+    /// so the rounds where equivocation is to be tested must override it.
+    fn equivocate_broadcast(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _destination: &I,
+        message: Self::BroadcastMessage,
+    ) -> Self::BroadcastMessage {
+        message
+    }
+
+    /// Modifies the direct message for the given destination after it is created.
+    fn tamper_direct(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _behavior: &Behavior,
+        _destination: &I,
+        message: Self::DirectMessage,
+        artifact: Self::Artifact,
+    ) -> (Self::DirectMessage, Self::Artifact) {
+        (message, artifact)
+    }
+}
+
+/// A round misbehaving in the given way, and passing it on to the subsequent rounds.
+pub(crate) struct Malicious<I, R> {
+    round: R,
+    behavior: Behavior,
+    no_destinations: BTreeSet<I>,
+}
+
+impl<I: Ord + Clone, R: Misbehave<I>> Malicious<I, R> {
+    pub fn new(rng: &mut impl CryptoRngCore, mut round: R, behavior: Behavior) -> Self {
+        round.tamper_state(rng, &behavior);
+        Self {
+            round,
+            behavior,
+            no_destinations: BTreeSet::new(),
+        }
+    }
+}
+
+impl<I: Ord + Clone, R: Misbehave<I>> Round<I> for Malicious<I, R> {
+    type Type = R::Type;
+    type Result = R::Result;
+    const ROUND_NUM: u8 = R::ROUND_NUM;
+    const NEXT_ROUND_NUM: Option<u8> = R::NEXT_ROUND_NUM;
+
+    fn other_ids(&self) -> &BTreeSet<I> {
+        self.round.other_ids()
+    }
+
+    fn my_id(&self) -> &I {
+        self.round.my_id()
+    }
+
+    type DirectMessage = R::DirectMessage;
+    type BroadcastMessage = R::BroadcastMessage;
+    const REQUIRES_ECHO: bool = R::REQUIRES_ECHO;
+    type Payload = R::Payload;
+    type Artifact = R::Artifact;
+
+    fn message_destinations(&self) -> &BTreeSet<I> {
+        if self.behavior
+            == (Behavior::Stall {
+                round: R::ROUND_NUM,
+            })
+        {
+            &self.no_destinations
+        } else {
+            self.round.message_destinations()
+        }
+    }
+
+    fn expecting_messages_from(&self) -> &BTreeSet<I> {
+        self.round.expecting_messages_from()
+    }
+
+    fn make_direct_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &I,
+    ) -> (Self::DirectMessage, Self::Artifact) {
+        let (message, artifact) = self.round.make_direct_message(rng, destination);
+        self.round
+            .tamper_direct(rng, &self.behavior, destination, message, artifact)
+    }
+
+    fn make_broadcast_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Option<Self::BroadcastMessage> {
+        let message = self.round.make_broadcast_message(rng)?;
+        Some(self.round.tamper_broadcast(rng, &self.behavior, message))
+    }
+
+    fn make_equivocating_broadcast(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &I,
+    ) -> Option<Self::BroadcastMessage> {
+        if self.behavior
+            != (Behavior::EquivocatingBroadcast {
+                round: R::ROUND_NUM,
+            })
+        {
+            return None;
+        }
+        let message = self.make_broadcast_message(rng)?;
+        Some(self.round.equivocate_broadcast(rng, destination, message))
+    }
+
+    fn pre_verify_message(
+        &self,
+        from: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> Result<(), <Self::Result as ProtocolResult>::ProvableError> {
+        self.round
+            .pre_verify_message(from, broadcast_msg, direct_msg)
+    }
+
+    fn verify_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        from: &I,
+        broadcast_msg: Self::BroadcastMessage,
+        direct_msg: Self::DirectMessage,
+    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
+        self.round
+            .verify_message(rng, from, broadcast_msg, direct_msg)
+    }
+
+    fn verify_own_message(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        self.round
+            .verify_own_message(rng, destination, broadcast_msg, direct_msg)
+    }
+
+    fn finalization_requirement() -> FinalizationRequirement {
+        R::finalization_requirement()
+    }
+
+    fn can_finalize(&self, received: &BTreeSet<I>) -> bool {
+        self.round.can_finalize(received)
+    }
+
+    fn missing_messages(&self, received: &BTreeSet<I>) -> BTreeSet<I> {
+        self.round.missing_messages(received)
+    }
+}
+
+impl<I: Ord + Clone, R: Misbehave<I> + FinalizableToResult<I>> FinalizableToResult<I>
+    for Malicious<I, R>
+{
+    fn finalize_to_result(
+        self,
+        rng: &mut impl CryptoRngCore,
+        payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
+        artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
+    ) -> Result<<Self::Result as ProtocolResult>::Success, FinalizeError<Self::Result>> {
+        self.round.finalize_to_result(rng, payloads, artifacts)
+    }
+}
+
+impl<I, R> FinalizableToNextRound<I> for Malicious<I, R>
+where
+    I: Ord + Clone,
+    R: Misbehave<I> + FinalizableToNextRound<I>,
+    R::NextRound: Misbehave<I>,
+{
+    type NextRound = Malicious<I, R::NextRound>;
+
+    fn finalize_to_next_round(
+        self,
+        rng: &mut impl CryptoRngCore,
+        payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
+        artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
+    ) -> Result<Self::NextRound, FinalizeError<Self::Result>> {
+        let round = self
+            .round
+            .finalize_to_next_round(rng, payloads, artifacts)?;
+        Ok(Malicious::new(rng, round, self.behavior))
+    }
+}
+
+/// Creates the initial state for the KeyInit protocol, with this party misbehaving.
+pub fn make_malicious_key_init_session<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    behavior: Behavior,
+) -> Result<Session<KeyInitResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PrehashVerifier<Sig>
+        + Debug
+        + Clone
+        + Ord
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+{
+    Session::new_malicious(rng, session_id, signer, verifiers, (), behavior)
+}
+
+/// Creates the initial state for the AuxGen protocol, with this party misbehaving.
+pub fn make_malicious_aux_gen_session<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    behavior: Behavior,
+) -> Result<Session<AuxGenResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PrehashVerifier<Sig>
+        + Debug
+        + Clone
+        + Ord
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+{
+    Session::new_malicious(rng, session_id, signer, verifiers, (), behavior)
+}
+
+/// Creates the initial state for the KeyRefresh protocol, with this party misbehaving.
+pub fn make_malicious_key_refresh_session<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    behavior: Behavior,
+) -> Result<Session<KeyRefreshResult<P, Verifier>, Sig, Signer, Verifier>, SessionInitError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PrehashVerifier<Sig>
+        + Debug
+        + Clone
+        + Ord
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+{
+    Session::new_malicious(rng, session_id, signer, verifiers, (), behavior)
+}
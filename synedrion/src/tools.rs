@@ -1,4 +1,6 @@
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 pub(crate) mod bitvec;
 pub(crate) mod hashing;
+pub(crate) mod profile;
 pub(crate) mod serde_bytes;
 pub(crate) mod sss;
@@ -6,19 +6,42 @@ use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
 
 use crate::tools::serde_bytes;
+use crate::uint::subtle::{Choice, ConstantTimeEq};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct BitVec(#[serde(with = "serde_bytes::as_base64")] Box<[u8]>);
 
+fn bytes_for(min_bits: usize) -> usize {
+    (min_bits - 1) / 8 + 1 // minimum number of bytes containing `min_bits` bits.
+}
+
 impl BitVec {
     pub fn random(rng: &mut impl CryptoRngCore, min_bits: usize) -> Self {
-        let len = (min_bits - 1) / 8 + 1; // minimum number of bytes containing `min_bits` bits.
-        let mut bytes = vec![0; len];
+        let mut bytes = vec![0; bytes_for(min_bits)];
         rng.fill_bytes(&mut bytes);
         Self(bytes.into())
     }
+
+    #[cfg(feature = "key-gen")]
+    pub fn zero(min_bits: usize) -> Self {
+        Self(vec![0; bytes_for(min_bits)].into())
+    }
+
+    /// Returns `true` if this vector has the length of the ones created for `min_bits`.
+    pub fn has_length(&self, min_bits: usize) -> bool {
+        self.0.len() == bytes_for(min_bits)
+    }
 }
 
+impl ConstantTimeEq for BitVec {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        // The lengths are public, so it is fine if a mismatch returns early.
+        self.0.as_ref().ct_eq(other.0.as_ref())
+    }
+}
+
+/// Panics if the lengths differ; the lengths of the received vectors
+/// must be checked with [`BitVec::has_length`] beforehand.
 impl BitXorAssign<&BitVec> for BitVec {
     fn bitxor_assign(&mut self, rhs: &BitVec) {
         assert!(self.0.len() == rhs.0.len());
@@ -27,3 +50,48 @@ impl BitXorAssign<&BitVec> for BitVec {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use super::BitVec;
+    use crate::uint::subtle::ConstantTimeEq;
+
+    #[test]
+    fn ct_eq() {
+        let a = BitVec::random(&mut OsRng, 256);
+        let b = BitVec::random(&mut OsRng, 256);
+        assert!(bool::from(a.ct_eq(&a.clone())));
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert!(!bool::from(a.ct_eq(&BitVec::random(&mut OsRng, 128))));
+    }
+
+    #[test]
+    fn xor() {
+        let a = BitVec::random(&mut OsRng, 256);
+        let b = BitVec::random(&mut OsRng, 256);
+
+        // The combination does not depend on the order
+        let mut ab = a.clone();
+        ab ^= &b;
+        let mut ba = b.clone();
+        ba ^= &a;
+        assert!(bool::from(ab.ct_eq(&ba)));
+
+        // XORing the same vector again undoes the combination
+        ab ^= &b;
+        assert!(bool::from(ab.ct_eq(&a)));
+
+        let mut zero = BitVec::zero(256);
+        zero ^= &a;
+        assert!(bool::from(zero.ct_eq(&a)));
+    }
+
+    #[test]
+    fn has_length() {
+        assert!(BitVec::random(&mut OsRng, 256).has_length(256));
+        assert!(BitVec::zero(255).has_length(256));
+        assert!(!BitVec::random(&mut OsRng, 128).has_length(256));
+    }
+}
@@ -1,11 +1,17 @@
-use digest::{Digest, ExtendableOutput, Update};
-use hashing_serializer::HashingSerializer;
+use digest::{Digest, ExtendableOutput, Update, XofReader};
+use rand_core::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use sha3::{Shake256, Shake256Reader};
 
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "dev-utils"))]
 use crate::curve::Scalar;
 use crate::tools::serde_bytes;
+use crate::uint::subtle::{Choice, ConstantTimeEq};
+
+mod serializer;
+
+use serializer::TaggedHashingSerializer;
 
 /// A digest object that takes byte slices or decomposable ([`Hashable`]) objects.
 pub trait Chain: Sized {
@@ -74,6 +80,12 @@ pub(crate) struct HashOutput(
     #[serde(with = "serde_bytes::as_hex")] pub(crate) [u8; 32],
 );
 
+impl ConstantTimeEq for HashOutput {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.as_ref().ct_eq(other.0.as_ref())
+    }
+}
+
 impl AsRef<[u8]> for HashOutput {
     fn as_ref(&self) -> &[u8] {
         &self.0
@@ -92,10 +104,6 @@ impl FofHasher {
     pub(crate) fn finalize(self) -> HashOutput {
         HashOutput(self.0.finalize().into())
     }
-
-    pub fn finalize_to_scalar(self) -> Scalar {
-        Scalar::from_digest(self.0)
-    }
 }
 
 /// Wraps an extendable output hash for easier replacement, and standardizes the use of DST.
@@ -127,8 +135,48 @@ impl XofHasher {
     pub fn finalize_to_reader(self) -> Shake256Reader {
         self.0.finalize_xof()
     }
+
+    /// Produces a scalar with a negligible bias by reducing a wide output.
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "dev-utils"))]
+    pub fn finalize_to_scalar(self) -> Scalar {
+        let mut bytes = [0u8; 64];
+        self.finalize_to_reader().read(&mut bytes);
+        Scalar::from_wide_bytes(&bytes)
+    }
+
+    /// Produces a deterministic random number generator reading the hash output.
+    pub fn finalize_to_rng(self) -> XofRng {
+        XofRng(self.finalize_to_reader())
+    }
+}
+
+/// A random number generator producing the output of [`XofHasher`].
+///
+/// It is as unpredictable as the data that was hashed,
+/// so it must only be seeded with high-entropy secrets.
+pub struct XofRng(Shake256Reader);
+
+impl RngCore for XofRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.read(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
 }
 
+impl CryptoRng for XofRng {}
+
 /// A trait allowing hashing of types without having access to their instances.
 pub trait HashableType {
     fn chain_type<C: Chain>(digest: C) -> C;
@@ -145,13 +193,15 @@ pub trait Hashable {
 // The reproducibility of this hash depends on `serde` not breaking things,
 // which we can be quite certain about - it is stable, and if it does break something,
 // all the serialization will likely break too.
+// Every serialized value is tagged with its kind and length (see `TaggedHashingSerializer`),
+// so the values of different structure cannot produce the same hash.
+// The values are serialized directly into the digest, so the collections and bytestrings
+// are hashed piece by piece, without an intermediate buffer.
 impl<T: Serialize> Hashable for T {
     fn chain<C: Chain>(&self, digest: C) -> C {
         let mut digest = digest;
 
-        let serializer = HashingSerializer {
-            digest: digest.as_digest_mut(),
-        };
+        let serializer = TaggedHashingSerializer::new(digest.as_digest_mut());
 
         // The only way it can return an error is if there is
         // some non-serializable element encountered, which is 100% reproducible
@@ -162,3 +212,79 @@ impl<T: Serialize> Hashable for T {
         digest
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use rand_core::OsRng;
+    use serde::Serialize;
+
+    use super::{Chain, FofHasher, HashOutput, Hashable};
+    use crate::curve::{Point, Scalar};
+
+    fn hash(value: &impl Hashable) -> HashOutput {
+        FofHasher::new_with_dst(b"test").chain(value).finalize()
+    }
+
+    #[test]
+    fn structurally_different_values() {
+        #[derive(Serialize)]
+        struct Pair {
+            first: u8,
+            second: u8,
+        }
+
+        #[derive(Serialize)]
+        enum Variants {
+            A(u8),
+            B(u8),
+            C { first: u8 },
+        }
+
+        // Different splits of the same contents
+        assert_ne!(hash(&("ab", "c")), hash(&("a", "bc")));
+        assert_ne!(
+            hash(&(b"ab".as_slice(), b"c".as_slice())),
+            hash(&(b"a".as_slice(), b"bc".as_slice()))
+        );
+        assert_ne!(
+            hash(&vec![vec![1u8, 2], vec![3]]),
+            hash(&vec![vec![1u8], vec![2, 3]])
+        );
+
+        // Same contents in values of different kinds
+        assert_ne!(hash(&1u16), hash(&(0u8, 1u8)));
+        assert_ne!(hash(&"a"), hash(&b"a".as_slice()));
+        assert_ne!(
+            hash(&(1u8, 2u8)),
+            hash(&Pair {
+                first: 1,
+                second: 2
+            })
+        );
+        assert_ne!(hash(&[1u8, 2]), hash(&vec![1u8, 2]));
+        assert_ne!(hash(&Some(0u8)), hash(&0u8));
+        assert_ne!(hash(&None::<u8>), hash(&()));
+        assert_ne!(hash(&Variants::A(1)), hash(&Variants::B(1)));
+        assert_ne!(hash(&Variants::A(1)), hash(&Variants::C { first: 1 }));
+    }
+
+    #[test]
+    fn collections_of_points() {
+        let points = (0..3)
+            .map(|_| Scalar::random(&mut OsRng).mul_by_generator())
+            .collect::<Vec<Point>>();
+
+        // Moving an element from one collection to the next one changes the hash
+        assert_ne!(
+            hash(&(&points[..1], &points[1..])),
+            hash(&(&points[..2], &points[2..]))
+        );
+        assert_ne!(
+            hash(&vec![points[..1].to_vec(), points[1..].to_vec()]),
+            hash(&vec![points[..2].to_vec(), points[2..].to_vec()])
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! A `serde` serializer feeding the data into a digest in an unambiguous way.
+//!
+//! Every value is prefixed by a tag identifying its kind in the `serde` data model,
+//! and every variable-sized value (strings, bytestrings, sequences, maps)
+//! and every composite value (tuples, structs) by its length.
+//! This way two structurally different values never produce the same digest input,
+//! even if their concatenated contents are the same.
+//!
+//! The names of the types and fields are not hashed,
+//! so that renaming them does not change the hashes.
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use digest::Update;
+use displaydoc::Display;
+use serde::{
+    ser::{
+        self, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
+    },
+    Serialize, Serializer,
+};
+
+#[derive(Clone, Copy)]
+#[repr(u8)]
+enum Tag {
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    Char,
+    Str,
+    Bytes,
+    None,
+    Some,
+    Unit,
+    UnitStruct,
+    UnitVariant,
+    NewtypeStruct,
+    NewtypeVariant,
+    Seq,
+    Tuple,
+    TupleStruct,
+    TupleVariant,
+    Map,
+    Struct,
+    StructVariant,
+}
+
+/// Possible errors during hashing.
+#[derive(Debug, Clone, Display)]
+pub(crate) enum Error {
+    /// The type tried to serialize a sequence or a map of an unknown length.
+    UndefinedLength,
+    /// Custom `serde` error: {0}.
+    Custom(String),
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(format!("{}", msg))
+    }
+}
+
+impl ser::StdError for Error {}
+
+/// A serializer that hashes the data instead of serializing it.
+pub(crate) struct TaggedHashingSerializer<'a, T: Update> {
+    digest: &'a mut T,
+}
+
+impl<'a, T: Update> TaggedHashingSerializer<'a, T> {
+    pub fn new(digest: &'a mut T) -> Self {
+        Self { digest }
+    }
+
+    fn tag(&mut self, tag: Tag) {
+        self.digest.update(&[tag as u8]);
+    }
+
+    fn length(&mut self, len: usize) {
+        // Converting to a fixed size type, since we want the result to be portable.
+        self.digest.update(&(len as u64).to_be_bytes());
+    }
+
+    fn variant_index(&mut self, variant_index: u32) {
+        self.digest.update(&variant_index.to_be_bytes());
+    }
+
+    fn reborrow(&mut self) -> TaggedHashingSerializer<'_, T> {
+        TaggedHashingSerializer {
+            digest: self.digest,
+        }
+    }
+}
+
+impl<'a, T: Update> Serializer for TaggedHashingSerializer<'a, T> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(mut self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::Bool);
+        self.digest.update(&[v as u8]);
+        Ok(())
+    }
+
+    fn serialize_i8(mut self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::I8);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_i16(mut self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::I16);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_i32(mut self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::I32);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_i64(mut self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::I64);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_i128(mut self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::I128);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_u8(mut self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::U8);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_u16(mut self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::U16);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_u32(mut self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::U32);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_u64(mut self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::U64);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_u128(mut self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::U128);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_f32(mut self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::F32);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(mut self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::F64);
+        self.digest.update(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(mut self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::Char);
+        self.digest.update(&u32::from(v).to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_str(mut self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::Str);
+        self.length(v.len());
+        self.digest.update(v.as_bytes());
+        Ok(())
+    }
+
+    fn serialize_bytes(mut self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::Bytes);
+        self.length(v.len());
+        self.digest.update(v);
+        Ok(())
+    }
+
+    fn serialize_none(mut self) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::None);
+        Ok(())
+    }
+
+    fn serialize_some<V: ?Sized + Serialize>(mut self, value: &V) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::Some);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(mut self) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::Unit);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(mut self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::UnitStruct);
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        mut self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::UnitVariant);
+        self.variant_index(variant_index);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<V: ?Sized + Serialize>(
+        mut self,
+        _name: &'static str,
+        value: &V,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::NewtypeStruct);
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<V: ?Sized + Serialize>(
+        mut self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &V,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.tag(Tag::NewtypeVariant);
+        self.variant_index(variant_index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(mut self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        let len = len.ok_or(Error::UndefinedLength)?;
+        self.tag(Tag::Seq);
+        self.length(len);
+        Ok(self)
+    }
+
+    fn serialize_tuple(mut self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.tag(Tag::Tuple);
+        self.length(len);
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        mut self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.tag(Tag::TupleStruct);
+        self.length(len);
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        mut self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.tag(Tag::TupleVariant);
+        self.variant_index(variant_index);
+        self.length(len);
+        Ok(self)
+    }
+
+    fn serialize_map(mut self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        let len = len.ok_or(Error::UndefinedLength)?;
+        self.tag(Tag::Map);
+        self.length(len);
+        Ok(self)
+    }
+
+    fn serialize_struct(
+        mut self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.tag(Tag::Struct);
+        self.length(len);
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        mut self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.tag(Tag::StructVariant);
+        self.variant_index(variant_index);
+        self.length(len);
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<'a, T: Update> SerializeSeq for TaggedHashingSerializer<'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<V: ?Sized + Serialize>(&mut self, value: &V) -> Result<(), Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a, T: Update> SerializeTuple for TaggedHashingSerializer<'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<V: ?Sized + Serialize>(&mut self, value: &V) -> Result<(), Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a, T: Update> SerializeTupleStruct for TaggedHashingSerializer<'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<V: ?Sized + Serialize>(&mut self, value: &V) -> Result<(), Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a, T: Update> SerializeTupleVariant for TaggedHashingSerializer<'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<V: ?Sized + Serialize>(&mut self, value: &V) -> Result<(), Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a, T: Update> SerializeMap for TaggedHashingSerializer<'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<K: ?Sized + Serialize>(&mut self, key: &K) -> Result<(), Error> {
+        key.serialize(self.reborrow())
+    }
+
+    fn serialize_value<V: ?Sized + Serialize>(&mut self, value: &V) -> Result<(), Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a, T: Update> SerializeStruct for TaggedHashingSerializer<'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<V: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &V,
+    ) -> Result<(), Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a, T: Update> SerializeStructVariant for TaggedHashingSerializer<'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<V: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &V,
+    ) -> Result<(), Error> {
+        value.serialize(self.reborrow())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
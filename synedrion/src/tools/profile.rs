@@ -0,0 +1,104 @@
+//! Optional instrumentation of the expensive math operations
+//! (prime generation, sigma proofs, Paillier exponentiations).
+//!
+//! Only active with the `profile` feature enabled; the measurements are reported
+//! to the sink registered with [`set_profile_sink`].
+
+#[cfg(feature = "profile")]
+mod enabled {
+    use std::sync::{Arc, RwLock};
+    use std::time::{Duration, Instant};
+
+    /// A receiver of the profiling measurements.
+    pub trait ProfileSink: Send + Sync {
+        /// Called when an instrumented operation identified by `label` finishes.
+        fn record(&self, label: &'static str, elapsed: Duration);
+    }
+
+    static SINK: RwLock<Option<Arc<dyn ProfileSink>>> = RwLock::new(None);
+
+    /// Sets the sink for the profiling measurements, or removes it if `None` is given.
+    pub fn set_profile_sink(sink: Option<Arc<dyn ProfileSink>>) {
+        *SINK.write().unwrap_or_else(|err| err.into_inner()) = sink;
+    }
+
+    /// Measures the time until it is dropped, and reports it to the registered sink.
+    pub(crate) struct ProfileScope {
+        label: &'static str,
+        start: Instant,
+    }
+
+    impl ProfileScope {
+        pub(crate) fn new(label: &'static str) -> Self {
+            Self {
+                label,
+                start: Instant::now(),
+            }
+        }
+    }
+
+    impl Drop for ProfileScope {
+        fn drop(&mut self) {
+            let sink = SINK.read().unwrap_or_else(|err| err.into_inner());
+            if let Some(sink) = sink.as_ref() {
+                sink.record(self.label, self.start.elapsed());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "profile")]
+pub use enabled::{set_profile_sink, ProfileSink};
+
+#[cfg(feature = "profile")]
+pub(crate) use enabled::ProfileScope;
+
+/// Measures the time until the end of the current scope, if the `profile` feature is enabled.
+macro_rules! profile_scope {
+    ($label:expr) => {
+        #[cfg(feature = "profile")]
+        let _profile_scope = $crate::tools::profile::ProfileScope::new($label);
+    };
+}
+
+pub(crate) use profile_scope;
+
+#[cfg(all(test, feature = "profile"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use std::vec::Vec;
+
+    use super::{set_profile_sink, ProfileSink};
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<&'static str>>);
+
+    impl ProfileSink for Recorder {
+        fn record(&self, label: &'static str, _elapsed: Duration) {
+            self.0.lock().unwrap().push(label);
+        }
+    }
+
+    #[test]
+    fn report_to_sink() {
+        let recorder = Arc::new(Recorder::default());
+        set_profile_sink(Some(recorder.clone()));
+
+        {
+            profile_scope!("test::outer");
+            {
+                profile_scope!("test::inner");
+            }
+        }
+
+        set_profile_sink(None);
+
+        let labels = recorder.0.lock().unwrap();
+        let labels = labels
+            .iter()
+            .filter(|label| label.starts_with("test::"))
+            .collect::<Vec<_>>();
+        assert_eq!(labels, [&"test::inner", &"test::outer"]);
+    }
+}
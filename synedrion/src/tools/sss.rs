@@ -1,4 +1,4 @@
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec::Vec;
 use core::ops::{Add, Mul};
 
@@ -7,15 +7,31 @@ use serde::{Deserialize, Serialize};
 
 use crate::curve::{Point, Scalar};
 
+/// A share identifier: the x-coordinate at which the secret polynomial is evaluated
+/// to produce a threshold share.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ShareId(Scalar);
 
 impl ShareId {
+    /// Creates a share identifier from an integer.
+    ///
+    /// The identifiers of the shares in a set must be distinct and non-zero.
     pub fn new(idx: usize) -> Self {
         Self(Scalar::from(idx))
     }
 }
 
+/// Returns `true` if the share IDs are distinct and non-zero,
+/// that is they can be used as evaluation points for a Shamir secret sharing.
+pub(crate) fn are_valid_evaluation_points<'a>(
+    share_ids: impl IntoIterator<Item = &'a ShareId>,
+) -> bool {
+    let mut unique_ids = BTreeSet::new();
+    share_ids
+        .into_iter()
+        .all(|share_id| share_id.0 != Scalar::ZERO && unique_ids.insert(share_id))
+}
+
 pub(crate) fn shamir_evaluation_points(num_shares: usize) -> Vec<ShareId> {
     // For now we are hardcoding the points to be 1, 2, ..., n.
     // Potentially we can derive them from Session ID.
@@ -53,6 +69,7 @@ impl Polynomial {
         evaluate_polynomial(&self.0, &x.0)
     }
 
+    #[cfg(feature = "key-resharing")]
     pub fn public(&self) -> PublicPolynomial {
         PublicPolynomial(
             self.0
@@ -63,9 +80,11 @@ impl Polynomial {
     }
 }
 
+#[cfg(feature = "key-resharing")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct PublicPolynomial(Vec<Point>);
 
+#[cfg(feature = "key-resharing")]
 impl PublicPolynomial {
     pub fn evaluate(&self, x: &ShareId) -> Point {
         evaluate_polynomial(&self.0, &x.0)
@@ -99,6 +118,7 @@ pub(crate) fn interpolation_coeff<'a>(
         .product()
 }
 
+#[cfg(feature = "key-resharing")]
 pub(crate) fn shamir_join_scalars<'a>(
     pairs: impl Iterator<Item = (&'a ShareId, &'a Scalar)>,
 ) -> Scalar {
@@ -125,7 +145,10 @@ pub(crate) fn shamir_join_points<'a>(
 mod tests {
     use rand_core::OsRng;
 
-    use super::{evaluate_polynomial, shamir_evaluation_points, shamir_join_scalars, shamir_split};
+    use super::{
+        are_valid_evaluation_points, evaluate_polynomial, shamir_evaluation_points,
+        shamir_join_scalars, shamir_split, ShareId,
+    };
     use crate::curve::Scalar;
 
     #[test]
@@ -155,4 +178,24 @@ mod tests {
         let recovered_secret = shamir_join_scalars(shares.iter());
         assert_eq!(recovered_secret, secret);
     }
+
+    #[test]
+    fn evaluation_points_validity() {
+        assert!(are_valid_evaluation_points(&shamir_evaluation_points(5)));
+        assert!(are_valid_evaluation_points(&[
+            ShareId::new(100),
+            ShareId::new(3),
+            ShareId::new(17)
+        ]));
+        assert!(!are_valid_evaluation_points(&[
+            ShareId::new(1),
+            ShareId::new(0),
+            ShareId::new(2)
+        ]));
+        assert!(!are_valid_evaluation_points(&[
+            ShareId::new(4),
+            ShareId::new(2),
+            ShareId::new(4)
+        ]));
+    }
 }
@@ -2,9 +2,11 @@ mod bounded;
 mod signed;
 mod traits;
 
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "paillier"))]
+pub(crate) use crypto_bigint::PowBoundedExp;
 pub(crate) use crypto_bigint::{
     modular::Retrieve, subtle, CheckedAdd, CheckedMul, CheckedSub, Encoding, Integer, Invert,
-    NonZero, PowBoundedExp, RandomMod, Zero, U1024, U2048, U4096, U512, U8192,
+    NonZero, RandomMod, Zero, U1024, U2048, U4096, U512, U8192,
 };
 pub(crate) use crypto_primes::RandomPrimeWithRng;
 
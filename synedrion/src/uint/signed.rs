@@ -1,6 +1,7 @@
 use alloc::string::String;
 use core::ops::{Add, Mul, Neg, Sub};
 
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
 use digest::XofReader;
 use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
@@ -11,8 +12,10 @@ use super::{
         Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq, ConstantTimeLess,
         CtOption,
     },
-    Bounded, CheckedAdd, HasWide, Integer, NonZero, RandomMod, UintLike, UintModLike,
+    Bounded, HasWide, Integer, NonZero, UintLike, UintModLike,
 };
+#[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
+use super::{CheckedAdd, RandomMod};
 
 /// A packed representation for serializing Signed objects.
 /// Usually they have the bound much lower than the full size of the integer,
@@ -63,6 +66,7 @@ impl<T: UintLike> Signed<T> {
 
     // Asserts that the value lies in the interval `[-2^bound, 2^bound]`.
     // Panics if it is not the case.
+    #[cfg(feature = "signing")]
     pub fn assert_bound(self, bound: usize) {
         assert!(self.abs() <= T::ONE.shl_vartime(bound));
     }
@@ -70,6 +74,7 @@ impl<T: UintLike> Signed<T> {
     // Asserts that the value has bound less or equal to `bound`
     // (or, in other words, the value lies in the interval `(-(2^bound-1), 2^bound-1)`).
     // Returns the value with the bound set to `bound`.
+    #[cfg(feature = "signing")]
     pub fn assert_bit_bound_usize(self, bound: usize) -> Option<Self> {
         if self.abs().bits_vartime() <= bound {
             Some(Self {
@@ -169,6 +174,7 @@ impl<T: UintLike> Signed<T> {
     /// since it is guaranteed to produce the same results on 32- and 64-bit platforms.
     ///
     /// Note: variable time in bit size of `bound`.
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
     pub fn from_xof_reader_bounded(rng: &mut impl XofReader, bound: &NonZero<T>) -> Self {
         let bound_bits = bound.as_ref().bits_vartime();
         assert!(bound_bits < <T as Integer>::BITS);
@@ -193,6 +199,7 @@ impl<T: UintLike> Signed<T> {
     }
 
     /// Returns `true` if the value is within `[-2^bound_bits, 2^bound_bits]`.
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
     pub fn in_range_bits(&self, bound_bits: usize) -> bool {
         self.abs() <= T::ONE << bound_bits
     }
@@ -268,7 +275,7 @@ impl<T: UintLike> Neg for Signed<T> {
     }
 }
 
-impl<'a, T: UintLike> Neg for &'a Signed<T> {
+impl<T: UintLike> Neg for &Signed<T> {
     type Output = Signed<T>;
     fn neg(self) -> Self::Output {
         Signed {
@@ -282,6 +289,7 @@ impl<T: UintLike + HasWide> Signed<T> {
     /// Returns a random value in range `[-2^bound_bits * scale, 2^bound_bits * scale]`.
     ///
     /// Note: variable time in `bound_bits` and bit size of `scale`.
+    #[cfg(any(feature = "key-gen", feature = "aux-gen", feature = "signing"))]
     pub fn random_bounded_bits_scaled(
         rng: &mut impl CryptoRngCore,
         bound_bits: usize,
@@ -320,6 +328,7 @@ impl<T: UintLike + HasWide> Signed<T> {
     }
 }
 
+#[cfg(any(feature = "key-gen", feature = "aux-gen"))]
 impl<T: UintLike + HasWide> Signed<T>
 where
     T::Wide: HasWide,
@@ -56,6 +56,22 @@ pub trait UintLike:
     fn to_mod(&self, precomputed: &<Self::ModUint as UintModLike>::Precomputed) -> Self::ModUint {
         Self::ModUint::new(self, precomputed)
     }
+
+    /// Converts to the modulo representation, returning `None` if the value is not reduced
+    /// (that is, not less than the modulus `precomputed` was created for).
+    ///
+    /// Unlike [`to_mod`](`Self::to_mod`), this ensures that the conversion is injective,
+    /// so it should be used for values received from other parties.
+    fn to_mod_checked(
+        &self,
+        precomputed: &<Self::ModUint as UintModLike>::Precomputed,
+    ) -> Option<Self::ModUint> {
+        if self < &Self::ModUint::modulus(precomputed) {
+            Some(self.to_mod(precomputed))
+        } else {
+            None
+        }
+    }
 }
 
 pub trait HasWide: Sized + Zero {
@@ -83,7 +99,7 @@ where
         let backend_modulus = modulus.as_ref();
 
         let n_bits = backend_modulus.bits_vartime();
-        let n_bytes = (n_bits + 7) / 8; // ceiling division by 8
+        let n_bytes = n_bits.div_ceil(8);
 
         // If the number of bits is not a multiple of 8,
         // use a mask to zeroize the high bits in the gererated random bytestring,
@@ -196,6 +212,7 @@ pub trait UintModLike:
     type Precomputed: Clone + Copy + core::fmt::Debug + PartialEq + Eq + Send + Sync;
 
     fn new_precomputed(modulus: &NonZero<Self::RawUint>) -> Self::Precomputed;
+    fn modulus(precomputed: &Self::Precomputed) -> Self::RawUint;
     fn new(value: &Self::RawUint, precomputed: &Self::Precomputed) -> Self;
     fn one(precomputed: &Self::Precomputed) -> Self;
 
@@ -295,6 +312,9 @@ where
     fn new_precomputed(modulus: &NonZero<Self::RawUint>) -> Self::Precomputed {
         DynResidueParams::<L>::new(modulus)
     }
+    fn modulus(precomputed: &Self::Precomputed) -> Self::RawUint {
+        *precomputed.modulus()
+    }
     fn new(value: &Self::RawUint, precomputed: &Self::Precomputed) -> Self {
         Self::new(value, *precomputed)
     }
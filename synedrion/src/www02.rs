@@ -1,5 +1,9 @@
 mod entities;
+#[cfg(feature = "key-resharing")]
 pub(crate) mod key_resharing;
 
 pub use entities::{DeriveChildKey, ThresholdKeyShare};
+#[cfg(feature = "key-resharing")]
 pub use key_resharing::{KeyResharingInputs, KeyResharingResult, NewHolder, OldHolder};
+
+pub use crate::tools::sss::ShareId;
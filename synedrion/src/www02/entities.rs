@@ -12,14 +12,21 @@ use serde::{Deserialize, Serialize};
 
 use crate::cggmp21::{KeyShare, SchemeParams};
 use crate::curve::{Point, Scalar};
+use crate::lineage::Lineage;
+use crate::sessions::LocalError;
 use crate::tools::hashing::{Chain, FofHasher};
 use crate::tools::sss::{
-    interpolation_coeff, shamir_evaluation_points, shamir_join_points, shamir_split, ShareId,
+    are_valid_evaluation_points, interpolation_coeff, shamir_evaluation_points, shamir_join_points,
+    shamir_split, ShareId,
 };
+use crate::uint::subtle::{Choice, ConstantTimeEq};
 
 /// A threshold variant of the key share, where any `threshold` shares our of the total number
 /// is enough to perform signing.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Does not implement [`Serialize`] to prevent the secret share from being stored
+/// or logged in plaintext by accident; use [`ThresholdKeyShare::expose_for_encryption`].
+#[derive(Debug, Clone, Deserialize)]
 pub struct ThresholdKeyShare<P: SchemeParams, I: Ord> {
     pub(crate) owner: I,
     pub(crate) threshold: u32,
@@ -28,6 +35,38 @@ pub struct ThresholdKeyShare<P: SchemeParams, I: Ord> {
     pub(crate) public_shares: BTreeMap<I, Point>,
     // TODO (#27): this won't be needed when Scalar/Point are a part of `P`
     pub(crate) phantom: PhantomData<P>,
+    pub(crate) lineage: Option<Lineage>,
+}
+
+/// The serializable form of [`ThresholdKeyShare`], borrowing its contents.
+#[derive(Serialize)]
+#[serde(rename = "ThresholdKeyShare", bound(serialize = "I: Serialize"))]
+struct ExposedThresholdKeyShare<'a, P, I> {
+    owner: &'a I,
+    threshold: u32,
+    secret_share: &'a Scalar,
+    share_ids: &'a BTreeMap<I, ShareId>,
+    public_shares: &'a BTreeMap<I, Point>,
+    phantom: PhantomData<P>,
+    lineage: &'a Option<Lineage>,
+}
+
+impl<P: SchemeParams, I: Ord + Serialize> ThresholdKeyShare<P, I> {
+    /// Returns the serializable form of the key share, including the secret share.
+    ///
+    /// The result should be encrypted before it leaves the memory
+    /// (e.g. with `compat::encode_bound_share`).
+    pub fn expose_for_encryption(&self) -> impl Serialize + '_ {
+        ExposedThresholdKeyShare {
+            owner: &self.owner,
+            threshold: self.threshold,
+            secret_share: self.secret_share.expose_secret(),
+            share_ids: &self.share_ids,
+            public_shares: &self.public_shares,
+            phantom: PhantomData::<P>,
+            lineage: &self.lineage,
+        }
+    }
 }
 
 impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I> {
@@ -36,39 +75,93 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
         self.share_ids[&self.owner]
     }
 
+    /// Threshold share IDs of all the parties holding the shares from this set.
+    pub fn share_ids(&self) -> &BTreeMap<I, ShareId> {
+        &self.share_ids
+    }
+
     /// The threshold.
     pub fn threshold(&self) -> usize {
         self.threshold as usize
     }
 
+    /// Returns the record of the ceremonies this key share went through,
+    /// or `None` if it was not created by one of the protocols.
+    pub fn lineage(&self) -> Option<&Lineage> {
+        self.lineage.as_ref()
+    }
+
     /// Creates a set of threshold key shares for the given IDs.
+    ///
+    /// The share IDs are assigned as `1..=n` in the order of `ids`.
     pub fn new_centralized(
         rng: &mut impl CryptoRngCore,
         ids: &BTreeSet<I>,
         threshold: usize,
         signing_key: Option<&SigningKey>,
     ) -> BTreeMap<I, Self> {
-        debug_assert!(threshold <= ids.len()); // TODO (#68): make the method fallible
+        let share_ids = ids
+            .iter()
+            .cloned()
+            .zip(shamir_evaluation_points(ids.len()))
+            .collect::<BTreeMap<_, _>>();
+        // TODO (#68): make the method fallible
+        debug_assert!(threshold <= ids.len());
+        Self::new_centralized_unchecked(rng, &share_ids, threshold, signing_key)
+    }
 
+    /// Creates a set of threshold key shares for the given IDs
+    /// using the caller-supplied share IDs.
+    ///
+    /// Returns an error if the share IDs are not distinct and non-zero,
+    /// or if the threshold is greater than the number of shares.
+    pub fn new_centralized_with_share_ids(
+        rng: &mut impl CryptoRngCore,
+        share_ids: &BTreeMap<I, ShareId>,
+        threshold: usize,
+        signing_key: Option<&SigningKey>,
+    ) -> Result<BTreeMap<I, Self>, LocalError> {
+        if threshold > share_ids.len() {
+            return Err(LocalError(
+                "The threshold cannot be greater than the number of shares".into(),
+            ));
+        }
+        if !are_valid_evaluation_points(share_ids.values()) {
+            return Err(LocalError("Share IDs must be distinct and non-zero".into()));
+        }
+        Ok(Self::new_centralized_unchecked(
+            rng,
+            share_ids,
+            threshold,
+            signing_key,
+        ))
+    }
+
+    fn new_centralized_unchecked(
+        rng: &mut impl CryptoRngCore,
+        share_ids: &BTreeMap<I, ShareId>,
+        threshold: usize,
+        signing_key: Option<&SigningKey>,
+    ) -> BTreeMap<I, Self> {
         let secret = match signing_key {
             None => Scalar::random(rng),
             Some(sk) => Scalar::from(sk.as_nonzero_scalar()),
         };
 
-        let share_ids = shamir_evaluation_points(ids.len());
-        let secret_shares = shamir_split(rng, &secret, threshold, &share_ids);
-        let share_ids = ids
-            .iter()
-            .cloned()
-            .zip(share_ids)
-            .collect::<BTreeMap<_, _>>();
+        let secret_shares = shamir_split(
+            rng,
+            &secret,
+            threshold,
+            &share_ids.values().cloned().collect::<Vec<_>>(),
+        );
 
         let public_shares = share_ids
             .iter()
             .map(|(id, share_id)| (id.clone(), secret_shares[share_id].mul_by_generator()))
             .collect::<BTreeMap<_, _>>();
 
-        ids.iter()
+        share_ids
+            .keys()
             .map(|id| {
                 (
                     id.clone(),
@@ -79,6 +172,7 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
                         share_ids: share_ids.clone(),
                         public_shares: public_shares.clone(),
                         phantom: PhantomData,
+                        lineage: None,
                     },
                 )
             })
@@ -133,6 +227,7 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
             secret_share,
             public_shares,
             phantom: PhantomData,
+            lineage: self.lineage.clone(),
         }
     }
 
@@ -170,6 +265,7 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
             secret_share,
             public_shares,
             phantom: PhantomData,
+            lineage: key_share.lineage.clone(),
         }
     }
 
@@ -205,10 +301,28 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
             secret_share,
             public_shares,
             phantom: PhantomData,
+            // The child key was not produced by any ceremony
+            lineage: None,
         })
     }
 }
 
+impl<P: SchemeParams, I: Ord> ConstantTimeEq for ThresholdKeyShare<P, I> {
+    /// Compares the key shares, with the secret shares compared in constant time
+    /// (the rest of the data is public).
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let public_eq = self.owner == other.owner
+            && self.threshold == other.threshold
+            && self.share_ids == other.share_ids
+            && self.public_shares == other.public_shares;
+        Choice::from(u8::from(public_eq))
+            & self
+                .secret_share
+                .expose_secret()
+                .ct_eq(other.secret_share.expose_secret())
+    }
+}
+
 /// Used for deriving child keys from a parent type.
 pub trait DeriveChildKey {
     /// Return a verifying key derived from the given type using the BIP-32 scheme.
@@ -288,16 +402,18 @@ fn apply_tweaks_private(
 
 #[cfg(test)]
 mod tests {
-    use alloc::collections::BTreeSet;
+    use alloc::collections::{BTreeMap, BTreeSet};
 
     use k256::ecdsa::SigningKey;
     use rand_core::OsRng;
-    use secrecy::ExposeSecret;
+    use secrecy::{ExposeSecret, SecretBox};
 
     use super::ThresholdKeyShare;
     use crate::cggmp21::TestParams;
     use crate::curve::Scalar;
     use crate::rounds::test_utils::Id;
+    use crate::tools::sss::ShareId;
+    use crate::uint::subtle::ConstantTimeEq;
 
     #[test]
     fn threshold_key_share_centralized() {
@@ -325,4 +441,46 @@ mod tests {
         assert_eq!(&nt_share0.verifying_key(), sk.verifying_key());
         assert_eq!(&nt_share1.verifying_key(), sk.verifying_key());
     }
+
+    #[test]
+    fn invalid_share_ids() {
+        let share_ids = BTreeMap::from([(Id(0), ShareId::new(1)), (Id(1), ShareId::new(0))]);
+        assert!(
+            ThresholdKeyShare::<TestParams, Id>::new_centralized_with_share_ids(
+                &mut OsRng, &share_ids, 2, None
+            )
+            .is_err()
+        );
+
+        let share_ids = BTreeMap::from([(Id(0), ShareId::new(3)), (Id(1), ShareId::new(3))]);
+        assert!(
+            ThresholdKeyShare::<TestParams, Id>::new_centralized_with_share_ids(
+                &mut OsRng, &share_ids, 2, None
+            )
+            .is_err()
+        );
+
+        let share_ids = BTreeMap::from([(Id(0), ShareId::new(3)), (Id(1), ShareId::new(5))]);
+        assert!(
+            ThresholdKeyShare::<TestParams, Id>::new_centralized_with_share_ids(
+                &mut OsRng, &share_ids, 3, None
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn threshold_key_share_ct_eq() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let shares =
+            ThresholdKeyShare::<TestParams, Id>::new_centralized(&mut OsRng, &ids, 2, None);
+        let share = shares[&Id(0)].clone();
+
+        assert!(bool::from(share.ct_eq(&share.clone())));
+        assert!(!bool::from(share.ct_eq(&shares[&Id(1)])));
+
+        let mut tampered = share.clone();
+        tampered.secret_share = SecretBox::new(Box::new(Scalar::random(&mut OsRng)));
+        assert!(!bool::from(share.ct_eq(&tampered)));
+    }
 }
@@ -17,14 +17,17 @@ use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
 
 use super::ThresholdKeyShare;
+use crate::cggmp21::params_fingerprint;
 use crate::curve::{Point, Scalar};
+use crate::lineage::{outcome_fingerprint, session_fingerprint, Ceremony, Lineage};
 use crate::rounds::{
-    FinalizableToResult, FinalizationRequirement, FinalizeError, FirstRound, InitError,
-    ProtocolResult, Round, ToResult,
+    FinalizableToResult, FinalizationRequirement, FinalizeError, FirstRound, InitError, Protocol,
+    ProtocolId, ProtocolResult, Round, ToResult,
 };
+use crate::tools::hashing::HashOutput;
 use crate::tools::sss::{
-    interpolation_coeff, shamir_join_points, shamir_join_scalars, Polynomial, PublicPolynomial,
-    ShareId,
+    are_valid_evaluation_points, interpolation_coeff, shamir_join_points, shamir_join_scalars,
+    Polynomial, PublicPolynomial, ShareId,
 };
 use crate::SchemeParams;
 
@@ -33,11 +36,20 @@ use crate::SchemeParams;
 pub struct KeyResharingResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
 
 impl<P: SchemeParams, I: Ord + Debug> ProtocolResult for KeyResharingResult<P, I> {
+    const PROTOCOL_ID: ProtocolId = ProtocolId::KeyResharing;
     type Success = Option<ThresholdKeyShare<P, I>>;
     type ProvableError = KeyResharingError;
     type CorrectnessProof = ();
 }
 
+impl<P: SchemeParams, I: Clone + Ord + Debug> Protocol<I> for KeyResharingResult<P, I> {
+    type FirstRound = Round1<P, I>;
+
+    fn params_fingerprint() -> Option<HashOutput> {
+        Some(params_fingerprint::<P>())
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum KeyResharingError {
     UnexpectedSender,
@@ -71,6 +83,11 @@ pub struct KeyResharingInputs<P: SchemeParams, I: Ord> {
     pub new_holder: Option<NewHolder<I>>,
     /// The new holders of the shares.
     pub new_holders: BTreeSet<I>,
+    /// The share IDs of the new holders (must be distinct and non-zero),
+    /// or `None` to assign them as `1..=n` in the order of `new_holders`.
+    ///
+    /// Supplying the IDs allows a party to retain its share ID across reshares.
+    pub new_share_ids: Option<BTreeMap<I, ShareId>>,
     /// The new threshold.
     pub new_threshold: usize,
 }
@@ -93,6 +110,9 @@ pub struct Round1<P: SchemeParams, I: Ord> {
     other_ids: BTreeSet<I>,
     my_id: I,
     message_destinations: BTreeSet<I>,
+    // The lineage of the old share (if this party holds one), extended in the new share.
+    old_lineage: Option<Lineage>,
+    session: HashOutput,
     phantom: PhantomData<P>,
 }
 
@@ -100,18 +120,32 @@ impl<P: SchemeParams, I: Clone + Ord + Debug> FirstRound<I> for Round1<P, I> {
     type Inputs = KeyResharingInputs<P, I>;
     fn new(
         rng: &mut impl CryptoRngCore,
-        _shared_randomness: &[u8],
+        shared_randomness: &[u8],
         other_ids: BTreeSet<I>,
         my_id: I,
         inputs: Self::Inputs,
     ) -> Result<Self, InitError> {
-        // Start new share indices from 1.
-        let new_share_ids = inputs
-            .new_holders
-            .iter()
-            .enumerate()
-            .map(|(idx, id)| (id.clone(), ShareId::new(idx + 1)))
-            .collect();
+        let new_share_ids = match inputs.new_share_ids {
+            Some(new_share_ids) => {
+                if !new_share_ids.keys().eq(inputs.new_holders.iter()) {
+                    return Err(InitError(
+                        "Share IDs must be provided for all the new holders and only for them"
+                            .into(),
+                    ));
+                }
+                if !are_valid_evaluation_points(new_share_ids.values()) {
+                    return Err(InitError("Share IDs must be distinct and non-zero".into()));
+                }
+                new_share_ids
+            }
+            // Start new share indices from 1.
+            None => inputs
+                .new_holders
+                .iter()
+                .enumerate()
+                .map(|(idx, id)| (id.clone(), ShareId::new(idx + 1)))
+                .collect(),
+        };
 
         if inputs.old_holder.is_none() && inputs.new_holder.is_none() {
             return Err(InitError(
@@ -129,6 +163,11 @@ impl<P: SchemeParams, I: Clone + Ord + Debug> FirstRound<I> for Round1<P, I> {
             BTreeSet::new()
         };
 
+        let old_lineage = inputs
+            .old_holder
+            .as_ref()
+            .and_then(|old_holder| old_holder.key_share.lineage.clone());
+
         let old_holder = inputs.old_holder.map(|old_holder| {
             let polynomial = Polynomial::random(
                 rng,
@@ -156,6 +195,8 @@ impl<P: SchemeParams, I: Clone + Ord + Debug> FirstRound<I> for Round1<P, I> {
             other_ids,
             my_id,
             message_destinations,
+            old_lineage,
+            session: session_fingerprint(shared_randomness),
             phantom: PhantomData,
         })
     }
@@ -260,6 +301,19 @@ impl<P: SchemeParams, I: Clone + Ord + Debug> Round<I> for Round1<P, I> {
         Err(KeyResharingError::UnexpectedSender)
     }
 
+    fn verify_own_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        destination: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> bool {
+        broadcast_msg
+            .public_polynomial
+            .evaluate(&self.new_share_ids[destination])
+            == direct_msg.subshare.mul_by_generator()
+    }
+
     fn finalization_requirement() -> FinalizationRequirement {
         FinalizationRequirement::Custom
     }
@@ -292,7 +346,7 @@ impl<P: SchemeParams, I: Clone + Ord + Debug> Round<I> for Round1<P, I> {
     }
 }
 
-impl<P: SchemeParams, I: Ord + Clone + Debug> FinalizableToResult<I> for Round1<P, I> {
+impl<P: SchemeParams, I: Ord + Clone + Debug + Serialize> FinalizableToResult<I> for Round1<P, I> {
     fn finalize_to_result(
         self,
         _rng: &mut impl CryptoRngCore,
@@ -367,13 +421,27 @@ impl<P: SchemeParams, I: Ord + Clone + Debug> FinalizableToResult<I> for Round1<
             })
             .collect();
 
+        // A party that did not hold an old share (or held one without a lineage)
+        // cannot know its history,
+        // so its lineage starts from the resharing.
+        let owner = self.my_id().clone();
+        // The parties of the session are all the old and the new holders.
+        let mut all_ids = self.other_ids.clone();
+        all_ids.insert(owner.clone());
+        let outcome = outcome_fingerprint(&all_ids, &public_shares);
+        let lineage = match self.old_lineage {
+            Some(lineage) => lineage.extend(Ceremony::KeyResharing, self.session, outcome),
+            None => Lineage::new(Ceremony::KeyResharing, self.session, outcome, vkey),
+        };
+
         Ok(Some(ThresholdKeyShare {
-            owner: self.my_id().clone(),
+            owner,
             threshold: self.new_threshold as u32,
             secret_share,
             share_ids: self.new_share_ids,
             public_shares,
             phantom: PhantomData,
+            lineage: Some(lineage),
         }))
     }
 }
@@ -388,9 +456,10 @@ mod tests {
     use super::ThresholdKeyShare;
     use super::{KeyResharingInputs, NewHolder, OldHolder, Round1};
     use crate::rounds::{
-        test_utils::{step_result, step_round, Id},
-        FirstRound,
+        test_utils::{round_info, step_result, step_round, Id},
+        FirstRound, ProtocolId,
     };
+    use crate::tools::sss::ShareId;
     use crate::TestParams;
 
     #[test]
@@ -418,6 +487,7 @@ mod tests {
                 }),
                 new_holder: None,
                 new_holders: new_holders.clone(),
+                new_share_ids: None,
                 new_threshold: 2,
             },
         )
@@ -438,6 +508,7 @@ mod tests {
                     old_holders: old_holders.clone(),
                 }),
                 new_holders: new_holders.clone(),
+                new_share_ids: None,
                 new_threshold: 2,
             },
         )
@@ -458,6 +529,7 @@ mod tests {
                     old_holders: old_holders.clone(),
                 }),
                 new_holders: new_holders.clone(),
+                new_share_ids: None,
                 new_threshold: 2,
             },
         )
@@ -476,6 +548,7 @@ mod tests {
                     old_holders: old_holders.clone(),
                 }),
                 new_holders: new_holders.clone(),
+                new_share_ids: None,
                 new_threshold: 2,
             },
         )
@@ -514,4 +587,77 @@ mod tests {
             assert_eq!(public, share.public_shares[&share.owner]);
         }
     }
+
+    #[test]
+    fn execute_key_reshare_with_share_ids() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = [Id(0), Id(1), Id(2), Id(3)];
+
+        let old_holders = BTreeSet::from([ids[0], ids[1], ids[2]]);
+        let new_holders = BTreeSet::from([ids[1], ids[2], ids[3]]);
+
+        let old_share_ids = BTreeMap::from([
+            (ids[0], ShareId::new(10)),
+            (ids[1], ShareId::new(20)),
+            (ids[2], ShareId::new(30)),
+        ]);
+        let old_key_shares = ThresholdKeyShare::<TestParams, Id>::new_centralized_with_share_ids(
+            &mut OsRng,
+            &old_share_ids,
+            2,
+            None,
+        )
+        .unwrap();
+        let old_vkey = old_key_shares[&ids[0]].verifying_key();
+
+        // The parties staying in the set retain their share IDs
+        let new_share_ids = BTreeMap::from([
+            (ids[1], ShareId::new(20)),
+            (ids[2], ShareId::new(30)),
+            (ids[3], ShareId::new(40)),
+        ]);
+
+        let r1 = ids
+            .iter()
+            .map(|id| {
+                let other_ids = ids.iter().filter(|other| other != &id).cloned().collect();
+                let inputs = KeyResharingInputs {
+                    old_holder: old_key_shares.get(id).map(|key_share| OldHolder {
+                        key_share: key_share.clone(),
+                    }),
+                    new_holder: new_holders.contains(id).then(|| NewHolder {
+                        verifying_key: old_vkey,
+                        old_threshold: 2,
+                        old_holders: old_holders.clone(),
+                    }),
+                    new_holders: new_holders.clone(),
+                    new_share_ids: Some(new_share_ids.clone()),
+                    new_threshold: 2,
+                };
+                let round =
+                    Round1::new(&mut OsRng, &shared_randomness, other_ids, *id, inputs).unwrap();
+                (*id, round)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let shares = step_result(&mut OsRng, r1a).unwrap();
+
+        for id in new_holders.iter() {
+            let share = shares[id].as_ref().unwrap();
+            assert_eq!(share.share_id(), new_share_ids[id]);
+            assert_eq!(share.share_ids(), &new_share_ids);
+            assert_eq!(share.verifying_key(), old_vkey);
+        }
+    }
+
+    #[test]
+    fn rounds_match_protocol_id() {
+        assert_eq!(
+            [round_info::<Id, Round1<TestParams, Id>>()],
+            ProtocolId::KeyResharing.rounds()
+        );
+    }
 }
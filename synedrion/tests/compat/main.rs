@@ -0,0 +1,375 @@
+//! Checks that the data produced by the previous releases of the crate
+//! can still be loaded and verified.
+//!
+//! The fixtures of each release are kept in `tests/compat/fixtures/<version>/`:
+//! - `key_share_<n>.bin` and `aux_info_<n>.bin`: the shares of the party `n`
+//!   in the storage format (see [`synedrion::compat`]);
+//! - `key_init.transcript` and `aux_gen.transcript`: the messages of the sessions
+//!   that produced the shares (see [`Transcript`]);
+//! - `presigning_public.bin`: the public presigning data created with the shares.
+//!
+//! The parties are identified by the deterministic signing keys created by [`make_signers`].
+//! The fixtures for the current version are created with
+//! `cargo test --test compat -- --ignored generate_fixtures`,
+//! and must not be modified after the release.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use rand_core::OsRng;
+
+use synedrion::{
+    compat::{decode_share, encode_share, upgrade_share},
+    make_aux_gen_session, make_interactive_signing_session, make_key_init_session,
+    make_presigning_session,
+    sessions::{LightVerifier, Transcript},
+    AuxInfo, FinalizeOutcome, KeyShare, MessageBundle, PresigningPublic, ProtocolId,
+    ProtocolResult, Session, SessionId, TestParams, VerifierBundle,
+};
+
+const NUM_PARTIES: usize = 3;
+
+fn make_signers() -> (Vec<SigningKey>, Vec<VerifyingKey>) {
+    let signers = (0..NUM_PARTIES)
+        .map(|idx| SigningKey::from_slice(&[idx as u8 + 1; 32]).unwrap())
+        .collect::<Vec<_>>();
+    let verifiers = signers
+        .iter()
+        .map(|signer| *signer.verifying_key())
+        .collect::<Vec<_>>();
+    (signers, verifiers)
+}
+
+fn fixtures_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/compat/fixtures")
+}
+
+/// Parses a `<major>.<minor>.<patch>` release version.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Returns the directories with the fixtures of all the previous releases,
+/// from the oldest to the newest.
+fn fixture_dirs() -> Vec<PathBuf> {
+    let root = fixtures_root();
+    let entries = fs::read_dir(&root)
+        .unwrap_or_else(|err| panic!("Failed to read {}: {err}", root.display()));
+    let mut dirs = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .map(|path| {
+            let name = path.file_name().unwrap().to_str().unwrap();
+            let version = parse_version(name)
+                .unwrap_or_else(|| panic!("{} is not named after a release", path.display()));
+            (version, path)
+        })
+        .collect::<Vec<_>>();
+    assert!(!dirs.is_empty(), "No fixtures found in {}", root.display());
+    dirs.sort();
+    dirs.into_iter().map(|(_version, path)| path).collect()
+}
+
+fn read_fixture(dir: &Path, name: &str) -> Vec<u8> {
+    let path = dir.join(name);
+    fs::read(&path).unwrap_or_else(|err| panic!("Failed to read {}: {err}", path.display()))
+}
+
+fn serialize<T: serde::Serialize>(value: &T) -> Vec<u8> {
+    bincode::serde::encode_to_vec(value, bincode::config::standard()).unwrap()
+}
+
+fn deserialize<T: for<'de> serde::Deserialize<'de>>(bytes: &[u8]) -> T {
+    let (value, read) =
+        bincode::serde::decode_from_slice(bytes, bincode::config::standard()).unwrap();
+    assert_eq!(read, bytes.len());
+    value
+}
+
+/// Runs the sessions to completion, delivering the messages synchronously,
+/// and returns the results along with all the messages sent.
+#[allow(clippy::type_complexity)]
+fn run_collecting_messages<Res: ProtocolResult>(
+    sessions: Vec<Session<Res, Signature, SigningKey, VerifyingKey>>,
+) -> (
+    BTreeMap<VerifyingKey, Res::Success>,
+    Vec<(VerifyingKey, MessageBundle<Signature>)>,
+) {
+    let mut sessions = sessions
+        .into_iter()
+        .map(|session| (session.verifier(), session))
+        .collect::<BTreeMap<_, _>>();
+    let mut results = BTreeMap::new();
+    let mut sent = Vec::new();
+
+    while !sessions.is_empty() {
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut messages = Vec::new();
+        for (id, session) in sessions.iter() {
+            for destination in session.message_destinations().iter() {
+                let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+                messages.push((*id, *destination, message));
+            }
+        }
+
+        for (from, to, message) in messages {
+            sent.push((from, message.clone()));
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            let preprocessed = session
+                .preprocess_message(accum, &from, message)
+                .unwrap()
+                .unwrap();
+            let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(result) => {
+                    results.insert(id, result);
+                }
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+        sessions = next_sessions;
+    }
+
+    (results, sent)
+}
+
+fn make_transcript(
+    session_id: SessionId,
+    protocol: ProtocolId,
+    verifiers: &BTreeSet<VerifyingKey>,
+    messages: &[(VerifyingKey, MessageBundle<Signature>)],
+) -> Box<[u8]> {
+    let mut transcript = Transcript::new(session_id, protocol, verifiers);
+    for (from, message) in messages.iter() {
+        transcript.add_message(from, message).unwrap();
+    }
+    transcript.to_bytes().unwrap()
+}
+
+type Shares = (
+    BTreeMap<VerifyingKey, KeyShare<TestParams, VerifyingKey>>,
+    BTreeMap<VerifyingKey, AuxInfo<TestParams, VerifyingKey>>,
+);
+
+fn load_shares(dir: &Path, verifiers: &[VerifyingKey]) -> Shares {
+    let mut key_shares = BTreeMap::new();
+    let mut aux_infos = BTreeMap::new();
+    for (idx, verifier) in verifiers.iter().enumerate() {
+        // The shares of the older releases may need to be converted to the current format first.
+        let bytes = read_fixture(dir, &format!("key_share_{idx}.bin"));
+        let key_share = decode_share::<KeyShare<TestParams, VerifyingKey>>(
+            &upgrade_share::<KeyShare<TestParams, VerifyingKey>>(&bytes).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(key_share.owner(), verifier);
+
+        let bytes = read_fixture(dir, &format!("aux_info_{idx}.bin"));
+        let aux_info = decode_share::<AuxInfo<TestParams, VerifyingKey>>(
+            &upgrade_share::<AuxInfo<TestParams, VerifyingKey>>(&bytes).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(aux_info.owner(), verifier);
+
+        key_shares.insert(*verifier, key_share);
+        aux_infos.insert(*verifier, aux_info);
+    }
+    (key_shares, aux_infos)
+}
+
+#[test]
+fn stored_shares() {
+    let (signers, verifiers) = make_signers();
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    for dir in fixture_dirs() {
+        let (key_shares, aux_infos) = load_shares(&dir, &verifiers);
+
+        let verifying_key = key_shares[&verifiers[0]].verifying_key();
+        for verifier in verifiers.iter() {
+            let key_share = &key_shares[verifier];
+            assert_eq!(key_share.verifying_key(), verifying_key);
+            assert_eq!(key_share.all_parties(), verifiers_set);
+            let bundle = VerifierBundle::new(key_share, &aux_infos[verifier]).unwrap();
+            assert_eq!(bundle.verifying_key(), Some(verifying_key));
+        }
+
+        // The loaded shares can still be used to sign
+        let message = b"abcdefghijklmnopqrstuvwxyz123456";
+        let session_id = SessionId::from_seed(b"compat-signing");
+        let sessions = signers
+            .iter()
+            .zip(verifiers.iter())
+            .map(|(signer, verifier)| {
+                make_interactive_signing_session::<_, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    signer.clone(),
+                    &verifiers_set,
+                    &key_shares[verifier],
+                    &aux_infos[verifier],
+                    message,
+                )
+                .unwrap()
+            })
+            .collect();
+        let (signatures, _messages) = run_collecting_messages(sessions);
+
+        let bundle =
+            VerifierBundle::new(&key_shares[&verifiers[0]], &aux_infos[&verifiers[0]]).unwrap();
+        for signature in signatures.into_values() {
+            assert!(bundle.verify_signature(message, &signature));
+        }
+    }
+}
+
+#[test]
+fn stored_transcripts() {
+    let (_signers, verifiers) = make_signers();
+    let verifier = LightVerifier::<TestParams>::new();
+
+    for dir in fixture_dirs() {
+        let (key_shares, aux_infos) = load_shares(&dir, &verifiers);
+
+        let transcript = Transcript::<Signature, VerifyingKey>::from_bytes(&read_fixture(
+            &dir,
+            "key_init.transcript",
+        ))
+        .unwrap();
+        let report = verifier.verify_key_init(&transcript).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(
+            report.outcome(),
+            Some(&key_shares[&verifiers[0]].verifying_key())
+        );
+
+        let transcript = Transcript::<Signature, VerifyingKey>::from_bytes(&read_fixture(
+            &dir,
+            "aux_gen.transcript",
+        ))
+        .unwrap();
+        let report = verifier.verify_aux_gen(&transcript).unwrap();
+        assert!(report.is_valid());
+        let public_aux = report.outcome().unwrap();
+        let aux_info = &aux_infos[&verifiers[0]];
+        for party in verifiers.iter() {
+            assert_eq!(
+                serialize(&public_aux[party]),
+                serialize(aux_info.public_aux(party).unwrap())
+            );
+        }
+    }
+}
+
+#[test]
+fn stored_presigning_data() {
+    for dir in fixture_dirs() {
+        let bytes = read_fixture(&dir, "presigning_public.bin");
+        let presigning = deserialize::<PresigningPublic<VerifyingKey>>(&bytes);
+        // The serialization is canonical, so the data must survive the round trip unchanged.
+        assert_eq!(serialize(&presigning), bytes);
+    }
+}
+
+#[test]
+#[ignore = "creates the fixtures for the current release"]
+fn generate_fixtures() {
+    let (signers, verifiers) = make_signers();
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let dir = fixtures_root().join(env!("CARGO_PKG_VERSION"));
+    fs::create_dir_all(&dir).unwrap();
+
+    let session_id = SessionId::from_seed(b"compat-key-init");
+    let sessions = signers
+        .iter()
+        .map(|signer| {
+            make_key_init_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &verifiers_set,
+            )
+            .unwrap()
+        })
+        .collect();
+    let (key_shares, messages) = run_collecting_messages(sessions);
+    fs::write(
+        dir.join("key_init.transcript"),
+        make_transcript(session_id, ProtocolId::KeyInit, &verifiers_set, &messages),
+    )
+    .unwrap();
+
+    let session_id = SessionId::from_seed(b"compat-aux-gen");
+    let sessions = signers
+        .iter()
+        .map(|signer| {
+            make_aux_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &verifiers_set,
+            )
+            .unwrap()
+        })
+        .collect();
+    let (aux_infos, messages) = run_collecting_messages(sessions);
+    fs::write(
+        dir.join("aux_gen.transcript"),
+        make_transcript(session_id, ProtocolId::AuxGen, &verifiers_set, &messages),
+    )
+    .unwrap();
+
+    for (idx, verifier) in verifiers.iter().enumerate() {
+        fs::write(
+            dir.join(format!("key_share_{idx}.bin")),
+            encode_share(&key_shares[verifier]).unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join(format!("aux_info_{idx}.bin")),
+            encode_share(&aux_infos[verifier]).unwrap(),
+        )
+        .unwrap();
+    }
+
+    let session_id = SessionId::from_seed(b"compat-presigning");
+    let sessions = signers
+        .iter()
+        .zip(verifiers.iter())
+        .map(|(signer, verifier)| {
+            make_presigning_session::<_, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &verifiers_set,
+                &key_shares[verifier],
+                &aux_infos[verifier],
+            )
+            .unwrap()
+        })
+        .collect();
+    let (presigning_data, _messages) = run_collecting_messages(sessions);
+    fs::write(
+        dir.join("presigning_public.bin"),
+        serialize(presigning_data[&verifiers[0]].public()),
+    )
+    .unwrap();
+}
@@ -0,0 +1,226 @@
+//! Running the sessions with different signature schemes for the message envelopes.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use signature::{
+    hazmat::{PrehashVerifier, RandomizedPrehashSigner},
+    Keypair,
+};
+
+use synedrion::{
+    make_key_init_session,
+    sessions::{Ed25519Signer, Ed25519Verifier},
+    FinalizeOutcome, KeyInitResult, KeyShare, MessageBundle, Session, SessionId, TestParams,
+};
+
+/// Runs the KeyInit sessions in lockstep in a single thread,
+/// passing the messages through a serialization round-trip.
+fn run_key_init<Sig, Signer, Verifier>(
+    signers: Vec<Signer>,
+) -> BTreeMap<Verifier, KeyShare<TestParams, Verifier>>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PrehashVerifier<Sig>
+        + Debug
+        + Clone
+        + Ord
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+{
+    let verifiers = signers
+        .iter()
+        .map(|signer| signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let mut sessions: BTreeMap<
+        Verifier,
+        Session<KeyInitResult<TestParams, Verifier>, Sig, Signer, Verifier>,
+    > = signers
+        .into_iter()
+        .map(|signer| {
+            let session = make_key_init_session::<TestParams, Sig, _, _>(
+                &mut OsRng, session_id, signer, &verifiers,
+            )
+            .unwrap();
+            (session.verifier(), session)
+        })
+        .collect();
+    let mut results = BTreeMap::new();
+
+    while !sessions.is_empty() {
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (id.clone(), session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut messages = Vec::new();
+        for (id, session) in sessions.iter() {
+            for destination in session.message_destinations().iter() {
+                let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+                let bytes =
+                    bincode::serde::encode_to_vec(&message, bincode::config::standard()).unwrap();
+                messages.push((id.clone(), destination.clone(), bytes));
+            }
+        }
+
+        for (from, to, bytes) in messages {
+            let (message, _): (MessageBundle<Sig>, _) =
+                bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).unwrap();
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            let preprocessed = session
+                .preprocess_message(accum, &from, message)
+                .unwrap()
+                .unwrap();
+            let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(key_share) => {
+                    results.insert(id, key_share);
+                }
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+        sessions = next_sessions;
+    }
+
+    results
+}
+
+fn check_key_shares<Verifier: Ord + Clone + Debug>(
+    key_shares: &BTreeMap<Verifier, KeyShare<TestParams, Verifier>>,
+) {
+    let verifiers = key_shares.keys().cloned().collect::<BTreeSet<_>>();
+    let vkey = key_shares.values().next().unwrap().verifying_key();
+    for (id, key_share) in key_shares.iter() {
+        assert_eq!(key_share.owner(), id);
+        assert_eq!(key_share.all_parties(), verifiers);
+        assert_eq!(key_share.verifying_key(), vkey);
+    }
+}
+
+#[test]
+fn secp256k1_identities() {
+    let signers = (0..3)
+        .map(|_| k256::ecdsa::SigningKey::random(&mut OsRng))
+        .collect::<Vec<_>>();
+    let key_shares = run_key_init::<k256::ecdsa::Signature, _, _>(signers);
+    check_key_shares(&key_shares);
+}
+
+#[test]
+fn p256_identities() {
+    let signers = (0..3)
+        .map(|_| p256::ecdsa::SigningKey::random(&mut OsRng))
+        .collect::<Vec<_>>();
+    let key_shares = run_key_init::<p256::ecdsa::Signature, _, _>(signers);
+    check_key_shares(&key_shares);
+}
+
+#[test]
+fn ed25519_identities() {
+    let signers = (0..3)
+        .map(|_| Ed25519Signer::new(ed25519_dalek::SigningKey::generate(&mut OsRng)))
+        .collect::<Vec<_>>();
+    let key_shares = run_key_init::<ed25519_dalek::Signature, _, Ed25519Verifier>(signers);
+    check_key_shares(&key_shares);
+}
+
+#[cfg(all(feature = "relay", feature = "aux-gen"))]
+#[test]
+fn ed25519_identities_with_encryption_and_relay() {
+    use synedrion::{make_key_gen_session, sessions::RelayedMessage};
+
+    // The encryption keys are derived from the identity keys converted to X25519 keys.
+    let signers = (0..3)
+        .map(|_| Ed25519Signer::new(ed25519_dalek::SigningKey::generate(&mut OsRng)))
+        .collect::<Vec<_>>();
+    let verifiers = signers
+        .iter()
+        .map(|signer| signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let mut sessions = signers
+        .into_iter()
+        .map(|signer| {
+            let mut session = make_key_gen_session::<TestParams, ed25519_dalek::Signature, _, _>(
+                &mut OsRng, session_id, signer, &verifiers,
+            )
+            .unwrap();
+            session.enable_direct_encryption(&verifiers).unwrap();
+            (session.verifier(), session)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let mut results = BTreeMap::new();
+
+    while !sessions.is_empty() {
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut relayed: Vec<(
+            Ed25519Verifier,
+            Ed25519Verifier,
+            RelayedMessage<ed25519_dalek::Signature>,
+        )> = Vec::new();
+        for (id, session) in sessions.iter() {
+            let (message, artifacts) = session.make_relay_message(&mut OsRng).unwrap();
+            for artifact in artifacts {
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+            }
+            for (destination, part) in message.split() {
+                relayed.push((*id, destination, part));
+            }
+        }
+
+        for (from, to, part) in relayed {
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            let message = session.open_relayed_message(&from, part).unwrap();
+            let preprocessed = session
+                .preprocess_message(accum, &from, message)
+                .unwrap()
+                .unwrap();
+            let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(result) => {
+                    results.insert(id, result);
+                }
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+        sessions = next_sessions;
+    }
+
+    let key_shares = results
+        .into_iter()
+        .map(|(id, (key_share, _aux_info))| (id, key_share))
+        .collect::<BTreeMap<_, _>>();
+    check_key_shares(&key_shares);
+}
@@ -0,0 +1,360 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use rand_core::OsRng;
+
+use synedrion::{
+    make_aux_gen_session, make_key_init_session, make_key_refresh_session,
+    sessions::{
+        verify_evidence, Claim, EchoError, Error, EvidencePacket, ProvableError, SessionInitError,
+        Verdict,
+    },
+    testing::malicious::{
+        make_malicious_aux_gen_session, make_malicious_key_init_session,
+        make_malicious_key_refresh_session, Behavior,
+    },
+    AuxGenResult, AuxInfo, FinalizeOutcome, KeyInitError, KeyInitResult, KeyRefreshResult,
+    KeyShare, ProtocolResult, Session, SessionId, TestParams, VerifierBundle,
+};
+
+type TestSession<Res> = Session<Res, Signature, SigningKey, VerifyingKey>;
+
+type MakeSession<Res> = fn(
+    SessionId,
+    SigningKey,
+    &BTreeSet<VerifyingKey>,
+) -> Result<TestSession<Res>, SessionInitError>;
+
+type MakeMaliciousSession<Res> = fn(
+    SessionId,
+    SigningKey,
+    &BTreeSet<VerifyingKey>,
+    Behavior,
+) -> Result<TestSession<Res>, SessionInitError>;
+
+/// A fault found by an honest party.
+struct Fault<Res: ProtocolResult> {
+    error: Error<Res, VerifyingKey>,
+    evidence: Option<EvidencePacket<Signature, VerifyingKey>>,
+    /// Whether the fault was found by the inexpensive checks of `Session::pre_verify_message()`.
+    pre_verify: bool,
+}
+
+/// What the honest parties observed when the protocol stopped.
+struct Outcome<Res: ProtocolResult> {
+    malicious: VerifyingKey,
+    honest: BTreeSet<VerifyingKey>,
+    faults: BTreeMap<VerifyingKey, Vec<Fault<Res>>>,
+    /// The parties that could not finalize a round, and the parties they were waiting for.
+    stalled: BTreeMap<VerifyingKey, BTreeSet<VerifyingKey>>,
+}
+
+/// Runs the sessions in lockstep, with the first party misbehaving,
+/// until the honest parties find a fault or cannot proceed.
+fn run_with_malicious<Res: ProtocolResult>(
+    num_parties: usize,
+    make_session: MakeSession<Res>,
+    make_malicious_session: MakeMaliciousSession<Res>,
+    behavior: Behavior,
+) -> Outcome<Res> {
+    let signers = (0..num_parties)
+        .map(|_| SigningKey::random(&mut OsRng))
+        .collect::<Vec<_>>();
+    let verifiers = signers
+        .iter()
+        .map(|signer| *signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+    let session_id = SessionId::from_seed(b"malicious");
+    let malicious = *signers[0].verifying_key();
+
+    let mut sessions = signers
+        .into_iter()
+        .map(|signer| {
+            let id = *signer.verifying_key();
+            let session = if id == malicious {
+                make_malicious_session(session_id, signer, &verifiers, behavior)
+            } else {
+                make_session(session_id, signer, &verifiers)
+            };
+            (id, session.unwrap())
+        })
+        .collect::<BTreeMap<_, _>>();
+    let mut cached_messages = BTreeMap::new();
+
+    loop {
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut messages = Vec::new();
+        for (id, session) in sessions.iter() {
+            for destination in session.message_destinations().iter() {
+                let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+                messages.push((*id, *destination, message));
+            }
+        }
+
+        for (id, session) in sessions.iter() {
+            let accum = accums.get_mut(id).unwrap();
+            for preprocessed in cached_messages.remove(id).unwrap_or_default() {
+                let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+                accum.add_processed_message(processed).unwrap().unwrap();
+            }
+        }
+
+        let mut faults = BTreeMap::<VerifyingKey, Vec<Fault<Res>>>::new();
+        for (from, to, message) in messages {
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            let fault = match session.preprocess_message(accum, &from, message) {
+                Ok(Some(preprocessed)) => match session.pre_verify_message(&preprocessed) {
+                    Ok(()) => match session.process_message(&mut OsRng, preprocessed.clone()) {
+                        Ok(processed) => {
+                            accum.add_processed_message(processed).unwrap().unwrap();
+                            None
+                        }
+                        Err(error) => Some(Fault {
+                            evidence: session.make_evidence(&preprocessed, &error),
+                            error,
+                            pre_verify: false,
+                        }),
+                    },
+                    Err(error) => Some(Fault {
+                        evidence: session.make_evidence(&preprocessed, &error),
+                        error,
+                        pre_verify: true,
+                    }),
+                },
+                Ok(None) => None,
+                Err(error) => Some(Fault {
+                    error,
+                    evidence: None,
+                    pre_verify: false,
+                }),
+            };
+            if let Some(fault) = fault {
+                faults.entry(to).or_default().push(fault);
+            }
+        }
+
+        // The parties that found a fault are waiting for a valid message from the malicious party,
+        // so only the others can be stalled.
+        let stalled = sessions
+            .iter()
+            .filter(|(id, _)| **id != malicious && !faults.contains_key(*id))
+            .filter_map(|(id, session)| {
+                let accum = &accums[id];
+                if session.can_finalize(accum).unwrap() {
+                    None
+                } else {
+                    Some((*id, session.missing_messages(accum).unwrap()))
+                }
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        if !faults.is_empty() || !stalled.is_empty() {
+            return Outcome {
+                malicious,
+                honest: verifiers
+                    .clone()
+                    .into_iter()
+                    .filter(|id| id != &malicious)
+                    .collect(),
+                faults,
+                stalled,
+            };
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(_) => {}
+                FinalizeOutcome::AnotherRound {
+                    session,
+                    cached_messages: new_cached_messages,
+                } => {
+                    next_sessions.insert(id, session);
+                    cached_messages.insert(id, new_cached_messages);
+                }
+            }
+        }
+
+        assert!(
+            !next_sessions.is_empty(),
+            "The protocol finished without the misbehavior being detected"
+        );
+        sessions = next_sessions;
+    }
+}
+
+impl<Res: ProtocolResult> Outcome<Res> {
+    /// Checks that every honest party found a fault, with the evidence accusing
+    /// the malicious party, and returns the faults.
+    fn blamed_faults(&self) -> Vec<&Fault<Res>> {
+        assert!(self.stalled.is_empty());
+        assert_eq!(
+            self.faults.keys().cloned().collect::<BTreeSet<_>>(),
+            self.honest
+        );
+        let faults = self.faults.values().flatten().collect::<Vec<_>>();
+        for fault in faults.iter() {
+            let evidence = fault.evidence.as_ref().unwrap();
+            assert_eq!(evidence.accused(), &self.malicious);
+        }
+        faults
+    }
+
+    /// Returns the public data of a set of shares of the parties of the session,
+    /// for checking the evidence by a third party.
+    fn bundle(&self) -> VerifierBundle<TestParams, VerifyingKey> {
+        let mut ids = self.honest.clone();
+        ids.insert(self.malicious);
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+        VerifierBundle::new(&key_shares[&self.malicious], &aux_infos[&self.malicious]).unwrap()
+    }
+}
+
+fn key_init(
+    session_id: SessionId,
+    signer: SigningKey,
+    verifiers: &BTreeSet<VerifyingKey>,
+) -> Result<TestSession<KeyInitResult<TestParams, VerifyingKey>>, SessionInitError> {
+    make_key_init_session(&mut OsRng, session_id, signer, verifiers)
+}
+
+fn malicious_key_init(
+    session_id: SessionId,
+    signer: SigningKey,
+    verifiers: &BTreeSet<VerifyingKey>,
+    behavior: Behavior,
+) -> Result<TestSession<KeyInitResult<TestParams, VerifyingKey>>, SessionInitError> {
+    make_malicious_key_init_session(&mut OsRng, session_id, signer, verifiers, behavior)
+}
+
+fn aux_gen(
+    session_id: SessionId,
+    signer: SigningKey,
+    verifiers: &BTreeSet<VerifyingKey>,
+) -> Result<TestSession<AuxGenResult<TestParams, VerifyingKey>>, SessionInitError> {
+    make_aux_gen_session(&mut OsRng, session_id, signer, verifiers)
+}
+
+fn malicious_aux_gen(
+    session_id: SessionId,
+    signer: SigningKey,
+    verifiers: &BTreeSet<VerifyingKey>,
+    behavior: Behavior,
+) -> Result<TestSession<AuxGenResult<TestParams, VerifyingKey>>, SessionInitError> {
+    make_malicious_aux_gen_session(&mut OsRng, session_id, signer, verifiers, behavior)
+}
+
+fn key_refresh(
+    session_id: SessionId,
+    signer: SigningKey,
+    verifiers: &BTreeSet<VerifyingKey>,
+) -> Result<TestSession<KeyRefreshResult<TestParams, VerifyingKey>>, SessionInitError> {
+    make_key_refresh_session(&mut OsRng, session_id, signer, verifiers)
+}
+
+fn malicious_key_refresh(
+    session_id: SessionId,
+    signer: SigningKey,
+    verifiers: &BTreeSet<VerifyingKey>,
+    behavior: Behavior,
+) -> Result<TestSession<KeyRefreshResult<TestParams, VerifyingKey>>, SessionInitError> {
+    make_malicious_key_refresh_session(&mut OsRng, session_id, signer, verifiers, behavior)
+}
+
+#[test]
+fn wrong_hash_reveal() {
+    let outcome = run_with_malicious(3, key_init, malicious_key_init, Behavior::WrongHashReveal);
+    let bundle = outcome.bundle();
+    for fault in outcome.blamed_faults() {
+        assert!(matches!(
+            fault.error,
+            Error::Provable {
+                error: ProvableError::Protocol(KeyInitError::R2HashMismatch),
+                ..
+            }
+        ));
+        // Caught without verifying any proofs
+        assert!(fault.pre_verify);
+        // A third party can check the fault against the commitment from Round 1
+        let evidence = fault.evidence.as_ref().unwrap();
+        assert_eq!(verify_evidence(evidence, &bundle), Verdict::Proven);
+    }
+}
+
+#[test]
+fn invalid_prm_proof() {
+    let outcome = run_with_malicious(3, aux_gen, malicious_aux_gen, Behavior::InvalidPrmProof);
+    for fault in outcome.blamed_faults() {
+        let evidence = fault.evidence.as_ref().unwrap();
+        match evidence.claim() {
+            Claim::InvalidMessage { description } => {
+                assert!(description.contains("PRM verification failed"))
+            }
+            claim => panic!("Unexpected claim: {claim:?}"),
+        }
+        assert!(!fault.pre_verify);
+    }
+}
+
+#[test]
+fn mismatched_secret() {
+    let outcome = run_with_malicious(
+        3,
+        key_refresh,
+        malicious_key_refresh,
+        Behavior::MismatchedSecret,
+    );
+    for fault in outcome.blamed_faults() {
+        let evidence = fault.evidence.as_ref().unwrap();
+        match evidence.claim() {
+            Claim::InvalidMessage { description } => {
+                assert!(description.contains("Round3MismatchedSecret"))
+            }
+            claim => panic!("Unexpected claim: {claim:?}"),
+        }
+    }
+}
+
+#[test]
+fn equivocating_broadcast() {
+    let outcome = run_with_malicious(
+        3,
+        key_init,
+        malicious_key_init,
+        Behavior::EquivocatingBroadcast { round: 1 },
+    );
+    for fault in outcome.blamed_faults() {
+        assert!(matches!(
+            fault.error,
+            Error::Provable {
+                error: ProvableError::Echo(EchoError::ConflictingBroadcasts),
+                ..
+            }
+        ));
+        let evidence = fault.evidence.as_ref().unwrap();
+        assert_eq!(evidence.claim(), &Claim::Equivocation);
+    }
+}
+
+#[test]
+fn stall() {
+    let outcome = run_with_malicious(
+        3,
+        key_init,
+        malicious_key_init,
+        Behavior::Stall { round: 2 },
+    );
+    assert!(outcome.faults.is_empty());
+    assert_eq!(outcome.stalled.len(), 2);
+    for missing in outcome.stalled.values() {
+        assert_eq!(missing, &BTreeSet::from([outcome.malicious]));
+    }
+}
@@ -0,0 +1,115 @@
+use rand_core::OsRng;
+
+use synedrion::{
+    crypto_bigint::{U1024, U512},
+    paillier::{EncryptedValue, ModulusProof, PaillierTest, PublicKey, SecretKey},
+    TestParams,
+};
+
+fn serialize<T: serde::Serialize>(value: &T) -> Vec<u8> {
+    bincode::serde::encode_to_vec(value, bincode::config::standard()).unwrap()
+}
+
+fn deserialize<T: for<'de> serde::Deserialize<'de>>(bytes: &[u8]) -> Result<T, String> {
+    bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+        .map(|(value, _read)| value)
+        .map_err(|err| format!("{err:?}"))
+}
+
+#[test]
+fn encrypt_and_decrypt() {
+    let sk = SecretKey::<PaillierTest>::random(&mut OsRng);
+    let pk = sk.public_key();
+
+    let plaintext = U1024::from_u64(123456789);
+    let ciphertext = pk.encrypt(&mut OsRng, &plaintext);
+    assert_eq!(sk.decrypt(&ciphertext), Some(plaintext));
+
+    // Encryption is randomized, but rerandomizing preserves the plaintext
+    let ciphertext2 = pk.rerandomize(&mut OsRng, &ciphertext).unwrap();
+    assert_ne!(serialize(&ciphertext), serialize(&ciphertext2));
+    assert_eq!(sk.decrypt(&ciphertext2), Some(plaintext));
+}
+
+#[test]
+fn homomorphic_operations() {
+    let sk = SecretKey::<PaillierTest>::random(&mut OsRng);
+    let pk = sk.public_key();
+
+    let x = U1024::from_u64(1000);
+    let y = U1024::from_u64(234);
+    let k = U1024::from_u64(7);
+
+    let cx = pk.encrypt(&mut OsRng, &x);
+    let cy = pk.encrypt(&mut OsRng, &y);
+
+    let sum = pk.add(&cx, &cy).unwrap();
+    assert_eq!(sk.decrypt(&sum), Some(U1024::from_u64(1234)));
+
+    let product = pk.mul(&cx, &k).unwrap();
+    assert_eq!(sk.decrypt(&product), Some(U1024::from_u64(7000)));
+
+    // The operations are performed modulo `N`
+    let minus_one = pk.modulus().wrapping_sub(&U1024::ONE);
+    let c_minus_one = pk.encrypt(&mut OsRng, &minus_one);
+    let sum = pk.add(&cx, &c_minus_one).unwrap();
+    assert_eq!(sk.decrypt(&sum), Some(U1024::from_u64(999)));
+}
+
+#[test]
+fn serialization() {
+    let sk = SecretKey::<PaillierTest>::random(&mut OsRng);
+    let pk = sk.public_key();
+    let plaintext = U1024::from_u64(42);
+    let ciphertext = pk.encrypt(&mut OsRng, &plaintext);
+
+    let sk_restored =
+        deserialize::<SecretKey<PaillierTest>>(&serialize(&sk.expose_for_encryption())).unwrap();
+    let pk_restored = deserialize::<PublicKey<PaillierTest>>(&serialize(&pk)).unwrap();
+    let ciphertext_restored =
+        deserialize::<EncryptedValue<PaillierTest>>(&serialize(&ciphertext)).unwrap();
+
+    assert_eq!(sk_restored.public_key(), pk);
+    assert_eq!(pk_restored, pk);
+    assert_eq!(sk_restored.decrypt(&ciphertext_restored), Some(plaintext));
+
+    // An even modulus cannot be a Paillier modulus
+    let even_modulus = pk.modulus().wrapping_add(&U1024::ONE);
+    assert!(deserialize::<PublicKey<PaillierTest>>(&serialize(&even_modulus)).is_err());
+
+    // A modulus too small for the parameters is rejected as well
+    assert!(deserialize::<PublicKey<PaillierTest>>(&serialize(&U1024::from_u64(15))).is_err());
+
+    // Secret keys with malformed primes are rejected
+    let (p, q) = deserialize::<(U512, U512)>(&serialize(&sk.expose_for_encryption())).unwrap();
+    let is_valid_secret_key =
+        |p: U512, q: U512| deserialize::<SecretKey<PaillierTest>>(&serialize(&(p, q))).is_ok();
+    assert!(is_valid_secret_key(p, q));
+    assert!(!is_valid_secret_key(U512::ZERO, q));
+    assert!(!is_valid_secret_key(p, p));
+    assert!(!is_valid_secret_key(p.wrapping_add(&U512::ONE), q));
+    assert!(!is_valid_secret_key(U512::from_u64(15), q));
+    // Both are odd and of the right size, but share a factor of 3
+    let base = U512::from_u64(3).shl_vartime(510);
+    let p3 = base.wrapping_add(&U512::from_u64(3));
+    let q3 = base.wrapping_add(&U512::from_u64(9));
+    assert!(!is_valid_secret_key(p3, q3));
+}
+
+#[test]
+fn modulus_proof() {
+    let sk = SecretKey::<PaillierTest>::random(&mut OsRng);
+    let pk = sk.public_key();
+    let other_pk = SecretKey::<PaillierTest>::random(&mut OsRng).public_key();
+
+    let proof = ModulusProof::<TestParams>::new(&mut OsRng, &sk, b"session 1");
+    assert!(proof.verify(&mut OsRng, &pk, b"session 1"));
+
+    // The proof survives the serialization
+    let proof = deserialize::<ModulusProof<TestParams>>(&serialize(&proof)).unwrap();
+    assert!(proof.verify(&mut OsRng, &pk, b"session 1"));
+
+    // The proof is bound to the auxiliary data and the public key
+    assert!(!proof.verify(&mut OsRng, &pk, b"session 2"));
+    assert!(!proof.verify(&mut OsRng, &other_pk, b"session 1"));
+}
@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
 
 use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, SigningKey, VerifyingKey};
 use rand::Rng;
@@ -7,8 +8,16 @@ use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
 
 use synedrion::{
-    make_interactive_signing_session, make_key_gen_session, AuxInfo, FinalizeOutcome, KeyShare,
-    MessageBundle, ProtocolResult, Session, SessionId, TestParams,
+    make_aux_gen_session, make_batch_signing_session, make_deterministic_key_refresh_session,
+    make_fast_key_init_session, make_handshake_session, make_interactive_signing_session,
+    make_key_gen_session, make_key_init_session, make_presigning_session,
+    sessions::{
+        AbortPolicy, Error, LightVerifier, ManualClock, ObserverOutcome, ObserverSession,
+        RemoteError, RemoteErrorEnum, SessionInitError, Transcript, TranscriptError, MAX_PARTIES,
+    },
+    AuxInfo, Ceremony, Check, FinalizeOutcome, HandshakeConfig, KeyGenCertificate, KeyGenStatement,
+    KeyShare, MessageBundle, ProductionParams, ProtocolId, ProtocolResult, Session, SessionId,
+    TestParams, UnsafeAssumeReliableBroadcast, VerifierBundle,
 };
 
 type MessageOut = (VerifyingKey, VerifyingKey, MessageBundle<Signature>);
@@ -30,8 +39,10 @@ async fn run_session<Res: ProtocolResult>(
 
     let key = session.verifier();
     let key_str = key_to_str(&key);
+    let mut round_trips = 0;
 
     loop {
+        round_trips += 1;
         println!(
             "{key_str}: *** starting round {:?} ***",
             session.current_round()
@@ -97,7 +108,10 @@ async fn run_session<Res: ProtocolResult>(
         println!("{key_str}: finalizing the round");
 
         match session.finalize_round(&mut OsRng, accum).unwrap() {
-            FinalizeOutcome::Success(res) => break res,
+            FinalizeOutcome::Success(res) => {
+                assert_eq!(round_trips, Res::round_trip_count());
+                break res;
+            }
             FinalizeOutcome::AnotherRound {
                 session: new_session,
                 cached_messages: new_cached_messages,
@@ -161,6 +175,8 @@ async fn run_nodes<Res>(
 where
     Res: ProtocolResult + Send + 'static,
     Res::Success: Send,
+    Res::ProvableError: Send,
+    Res::CorrectnessProof: Send,
 {
     let num_parties = sessions.len();
 
@@ -172,7 +188,7 @@ where
     let tx_map = sessions
         .iter()
         .map(|session| session.verifier())
-        .zip(txs.into_iter())
+        .zip(txs)
         .collect();
 
     let dispatcher_task = message_dispatcher(tx_map, dispatcher_rx);
@@ -180,7 +196,7 @@ where
 
     let handles: Vec<tokio::task::JoinHandle<Res::Success>> = rxs
         .into_iter()
-        .zip(sessions.into_iter())
+        .zip(sessions)
         .map(|(rx, session)| {
             let node_task = run_session(dispatcher_tx.clone(), rx, session);
             tokio::spawn(node_task)
@@ -208,10 +224,122 @@ async fn keygen_and_aux() {
 
     let session_id = SessionId::from_seed(b"1234567890");
 
+    let sessions = signers
+        .iter()
+        .map(|signer| {
+            make_key_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &verifiers_set,
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let (key_shares, aux_infos): (Vec<_>, Vec<_>) = run_nodes(sessions).await.into_iter().unzip();
+
+    for (idx, key_share) in key_shares.iter().enumerate() {
+        assert_eq!(key_share.owner(), &verifiers[idx]);
+        assert_eq!(key_share.all_parties(), verifiers_set);
+        assert_eq!(key_share.verifying_key(), key_shares[0].verifying_key());
+
+        // KeyGen consists of KeyInit and KeyRefresh, and both are recorded
+        let lineage = key_share.lineage().unwrap();
+        assert!(lineage.verify(&key_share.verifying_key()));
+        assert!(lineage.contains_session(&session_id));
+        assert_eq!(
+            lineage
+                .entries()
+                .iter()
+                .map(|entry| entry.ceremony())
+                .collect::<Vec<_>>(),
+            [Ceremony::KeyInit, Ceremony::KeyRefresh]
+        );
+        assert_eq!(lineage.head(), key_shares[0].lineage().unwrap().head());
+    }
+
+    // Each party signs the statement about the result with its identity key,
+    // and the signatures are assembled into a certificate verifiable by a third party.
+    let statement = KeyGenStatement::new(session_id, &key_shares[0], &aux_infos[0]);
+    let signatures = signers
+        .iter()
+        .zip(key_shares.iter().zip(aux_infos.iter()))
+        .map(|(signer, (key_share, aux_info))| {
+            let own_statement = KeyGenStatement::new(session_id, key_share, aux_info);
+            assert_eq!(own_statement, statement);
+            let signature: Signature = own_statement.sign(&mut OsRng, signer).unwrap();
+            (*signer.verifying_key(), signature)
+        })
+        .collect();
+    let certificate = KeyGenCertificate::new(statement, signatures).unwrap();
+    assert_eq!(certificate.statement().parties(), &verifiers_set);
+    assert_eq!(
+        certificate.statement().verifying_key(),
+        Some(key_shares[0].verifying_key())
+    );
+}
+
+#[tokio::test]
+async fn handshake_then_keygen() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    // Agree on the configuration first
+    let config =
+        HandshakeConfig::new::<TestParams>(verifiers_set.clone(), num_parties, b"1234567890");
+    let sessions = signers
+        .iter()
+        .map(|signer| {
+            make_handshake_session::<Signature, _, _>(
+                &mut OsRng,
+                SessionId::from_seed(b"handshake"),
+                signer.clone(),
+                &verifiers_set,
+                config.clone(),
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let configs = run_nodes(sessions).await;
+    assert!(configs.iter().all(|agreed| agreed == &config));
+
+    // Then run the protocol it describes
     let sessions = signers
         .into_iter()
         .map(|signer| {
             make_key_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                config.session_id(),
+                signer,
+                config.parties(),
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let (key_shares, _aux_infos): (Vec<_>, Vec<_>) = run_nodes(sessions).await.into_iter().unzip();
+
+    for key_share in key_shares.iter() {
+        assert_eq!(key_share.verifying_key(), key_shares[0].verifying_key());
+    }
+}
+
+#[tokio::test]
+async fn key_init_many_parties() {
+    // KeyInit is cheap, so it can be used to check that nothing breaks down at a larger scale.
+    let num_parties = MAX_PARTIES;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let sessions = signers
+        .into_iter()
+        .map(|signer| {
+            make_key_init_session::<TestParams, Signature, _, _>(
                 &mut OsRng,
                 session_id,
                 signer,
@@ -221,7 +349,7 @@ async fn keygen_and_aux() {
         })
         .collect();
 
-    let (key_shares, _aux_infos): (Vec<_>, Vec<_>) = run_nodes(sessions).await.into_iter().unzip();
+    let key_shares = run_nodes(sessions).await;
 
     for (idx, key_share) in key_shares.iter().enumerate() {
         assert_eq!(key_share.owner(), &verifiers[idx]);
@@ -230,6 +358,219 @@ async fn keygen_and_aux() {
     }
 }
 
+#[tokio::test]
+async fn fast_key_init() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let sessions = signers
+        .into_iter()
+        .map(|signer| {
+            make_fast_key_init_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+                UnsafeAssumeReliableBroadcast,
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let key_shares = run_nodes(sessions).await;
+
+    for (idx, key_share) in key_shares.iter().enumerate() {
+        assert_eq!(key_share.owner(), &verifiers[idx]);
+        assert_eq!(key_share.verifying_key(), key_shares[0].verifying_key());
+    }
+}
+
+#[tokio::test]
+async fn deterministic_key_refresh() {
+    let num_parties = 3;
+    let (signers, _verifiers) = make_signers(num_parties);
+    let verifiers_set = signers
+        .iter()
+        .map(|signer| *signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+    let seeds = (0..num_parties)
+        .map(|_| OsRng.gen::<[u8; 32]>())
+        .collect::<Vec<_>>();
+
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let make_sessions = || {
+        signers
+            .iter()
+            .zip(seeds.iter())
+            .map(|(signer, seed)| {
+                make_deterministic_key_refresh_session::<TestParams, Signature, _, _>(
+                    seed,
+                    session_id,
+                    signer.clone(),
+                    &verifiers_set,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // The messages are delivered in a different order in each run,
+    // but the results must be the same.
+    let first_run = run_nodes(make_sessions()).await;
+    let second_run = run_nodes(make_sessions()).await;
+
+    fn to_bytes<T: serde::Serialize>(value: &T) -> Vec<u8> {
+        bincode::serde::encode_to_vec(value, bincode::config::standard()).unwrap()
+    }
+    for ((change1, aux1), (change2, aux2)) in first_run.iter().zip(second_run.iter()) {
+        assert_eq!(
+            to_bytes(&change1.expose_for_encryption()),
+            to_bytes(&change2.expose_for_encryption())
+        );
+        assert_eq!(
+            to_bytes(&aux1.expose_for_encryption()),
+            to_bytes(&aux2.expose_for_encryption())
+        );
+    }
+}
+
+#[test]
+fn deterministic_key_refresh_binds_parties() {
+    let (signers, verifiers) = make_signers(4);
+    let seed = OsRng.gen::<[u8; 32]>();
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let first_message = |parties: &[usize]| {
+        let verifiers_set = parties
+            .iter()
+            .map(|idx| verifiers[*idx])
+            .collect::<BTreeSet<_>>();
+        let session = make_deterministic_key_refresh_session::<TestParams, Signature, _, _>(
+            &seed,
+            session_id,
+            signers[0].clone(),
+            &verifiers_set,
+        )
+        .unwrap();
+        let (message, _artifact) = session.make_message(&mut OsRng, &verifiers[1]).unwrap();
+        bincode::serde::encode_to_vec(message, bincode::config::standard()).unwrap()
+    };
+
+    // The same seed and parties reproduce the same message...
+    assert_eq!(first_message(&[0, 1, 2]), first_message(&[0, 1, 2]));
+    // ...but reusing the seed with a different set of parties does not.
+    assert_ne!(first_message(&[0, 1, 2]), first_message(&[0, 1, 3]));
+}
+
+#[tokio::test]
+async fn keygen_strict_mode() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let sessions = signers
+        .into_iter()
+        .map(|signer| {
+            let mut session = make_key_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+            )
+            .unwrap();
+            // All the outgoing messages will be verified before sending
+            session.set_strict_mode(true);
+            session
+        })
+        .collect();
+
+    let (key_shares, _aux_infos): (Vec<_>, Vec<_>) = run_nodes(sessions).await.into_iter().unzip();
+
+    for key_share in key_shares.iter() {
+        assert_eq!(key_share.verifying_key(), key_shares[0].verifying_key());
+    }
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn keygen_with_compression() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let sessions = signers
+        .into_iter()
+        .enumerate()
+        .map(|(idx, signer)| {
+            let mut session = make_key_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+            )
+            .unwrap();
+            // Compression is transparent for the receiver,
+            // so some of the nodes can have it disabled.
+            if idx != 0 {
+                session.set_compression(true);
+                // Check that the compressed messages are processed correctly
+                session.set_strict_mode(true);
+            }
+            session
+        })
+        .collect();
+
+    let (key_shares, _aux_infos): (Vec<_>, Vec<_>) = run_nodes(sessions).await.into_iter().unzip();
+
+    for key_share in key_shares.iter() {
+        assert_eq!(key_share.verifying_key(), key_shares[0].verifying_key());
+    }
+}
+
+#[cfg(feature = "encryption")]
+#[tokio::test]
+async fn keygen_with_encryption() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let sessions = signers
+        .into_iter()
+        .enumerate()
+        .map(|(idx, signer)| {
+            let mut session = make_key_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+            )
+            .unwrap();
+            session.enable_direct_encryption(&verifiers_set).unwrap();
+            // Check that the encryption does not interfere with the strict mode
+            if idx == 0 {
+                session.set_strict_mode(true);
+            }
+            session
+        })
+        .collect();
+
+    let (key_shares, _aux_infos): (Vec<_>, Vec<_>) = run_nodes(sessions).await.into_iter().unzip();
+
+    for key_share in key_shares.iter() {
+        assert_eq!(key_share.verifying_key(), key_shares[0].verifying_key());
+    }
+}
+
 #[tokio::test]
 async fn interactive_signing() {
     let num_parties = 3;
@@ -261,7 +602,13 @@ async fn interactive_signing() {
 
     let signatures = run_nodes(sessions).await;
 
+    // A party without a share can verify the signatures using the public data only
+    let bundle =
+        VerifierBundle::new(&key_shares[&verifiers[0]], &aux_infos[&verifiers[0]]).unwrap();
+
     for signature in signatures {
+        assert!(bundle.verify_signature(message, &signature));
+
         let (sig, rec_id) = signature.to_backend();
         let vkey = key_shares[&verifiers[0]].verifying_key();
 
@@ -273,3 +620,1208 @@ async fn interactive_signing() {
         assert_eq!(recovered_key, vkey);
     }
 }
+
+#[tokio::test]
+async fn interactive_signing_strict_mode() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let key_shares =
+        KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set, None);
+    let aux_infos =
+        AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set);
+
+    let session_id = SessionId::from_seed(b"1234567890");
+    let message = b"abcdefghijklmnopqrstuvwxyz123456";
+
+    let sessions = (0..num_parties)
+        .map(|idx| {
+            let mut session = make_interactive_signing_session::<_, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signers[idx].clone(),
+                &verifiers_set,
+                &key_shares[&verifiers[idx]],
+                &aux_infos[&verifiers[idx]],
+                message,
+            )
+            .unwrap();
+            // The proofs of the presigning rounds and the partial signatures
+            // will be verified before sending
+            session.set_strict_mode(true);
+            session
+        })
+        .collect();
+
+    let signatures = run_nodes(sessions).await;
+
+    let vkey = key_shares[&verifiers[0]].verifying_key();
+    for signature in signatures {
+        let (sig, _rec_id) = signature.to_backend();
+        vkey.verify_prehash(message, &sig).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn batch_signing() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let key_shares =
+        KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set, None);
+    let aux_infos =
+        AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set);
+
+    let messages = [
+        b"abcdefghijklmnopqrstuvwxyz123456",
+        b"123456abcdefghijklmnopqrstuvwxyz",
+    ];
+
+    let clock = ManualClock::new(Duration::from_secs(0));
+
+    // One presigning for each message
+    let mut presignings = vec![Vec::new(); num_parties];
+    for seed in [b"presigning 1", b"presigning 2"] {
+        let session_id = SessionId::from_seed(seed);
+        let sessions = (0..num_parties)
+            .map(|idx| {
+                make_presigning_session::<_, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    signers[idx].clone(),
+                    &verifiers_set,
+                    &key_shares[&verifiers[idx]],
+                    &aux_infos[&verifiers[idx]],
+                )
+                .unwrap()
+            })
+            .collect();
+        for (idx, mut presigning) in run_nodes(sessions).await.into_iter().enumerate() {
+            presigning.set_timestamp(&clock, Some(Duration::from_secs(60)));
+            presignings[idx].push(presigning);
+        }
+    }
+
+    // The expired presigning data is refused
+    let expired = {
+        let later = ManualClock::new(Duration::from_secs(60));
+        let items = vec![(*messages[0], presignings[0][0].clone())];
+        make_batch_signing_session::<_, Signature, _, _>(
+            &mut OsRng,
+            SessionId::from_seed(b"expired"),
+            signers[0].clone(),
+            &verifiers_set,
+            &key_shares[&verifiers[0]],
+            &aux_infos[&verifiers[0]],
+            items,
+            &later,
+        )
+    };
+    assert!(matches!(
+        expired,
+        Err(SessionInitError::ExpiredPresigning(0))
+    ));
+
+    // The same presigning data cannot be used for two messages
+    let repeated = {
+        let items = vec![
+            (*messages[0], presignings[0][0].clone()),
+            (*messages[1], presignings[0][1].clone()),
+            (*messages[1], presignings[0][0].clone()),
+        ];
+        make_batch_signing_session::<_, Signature, _, _>(
+            &mut OsRng,
+            SessionId::from_seed(b"repeated"),
+            signers[0].clone(),
+            &verifiers_set,
+            &key_shares[&verifiers[0]],
+            &aux_infos[&verifiers[0]],
+            items,
+            &clock,
+        )
+    };
+    assert!(matches!(
+        repeated,
+        Err(SessionInitError::RepeatedPresigning(2))
+    ));
+
+    let session_id = SessionId::from_seed(b"batch signing");
+    let sessions = presignings
+        .into_iter()
+        .enumerate()
+        .map(|(idx, presignings)| {
+            let items = messages
+                .iter()
+                .map(|message| **message)
+                .zip(presignings)
+                .collect::<Vec<_>>();
+            let mut session = make_batch_signing_session::<_, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signers[idx].clone(),
+                &verifiers_set,
+                &key_shares[&verifiers[idx]],
+                &aux_infos[&verifiers[idx]],
+                items,
+                &clock,
+            )
+            .unwrap();
+            // Each partial signature of the batch will be verified before sending
+            session.set_strict_mode(true);
+            session
+        })
+        .collect();
+
+    let batches = run_nodes(sessions).await;
+
+    let bundle =
+        VerifierBundle::new(&key_shares[&verifiers[0]], &aux_infos[&verifiers[0]]).unwrap();
+    for signatures in batches {
+        assert_eq!(signatures.len(), messages.len());
+        for (signature, message) in signatures.iter().zip(messages.iter()) {
+            assert!(bundle.verify_signature(message, signature));
+        }
+    }
+}
+
+#[tokio::test]
+async fn one_of_one() {
+    let (signers, verifiers) = make_signers(1);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let sessions = vec![make_key_gen_session::<TestParams, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signers[0].clone(),
+        &verifiers_set,
+    )
+    .unwrap()];
+
+    let (key_share, aux_info) = run_nodes(sessions).await.pop().unwrap();
+
+    // With a single party the key share is the whole signing key.
+    let signing_key = key_share.to_signing_key().unwrap();
+    assert_eq!(signing_key.verifying_key(), &key_share.verifying_key());
+
+    let message = b"abcdefghijklmnopqrstuvwxyz123456";
+
+    let sessions = vec![make_interactive_signing_session::<_, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signers[0].clone(),
+        &verifiers_set,
+        &key_share,
+        &aux_info,
+        message,
+    )
+    .unwrap()];
+
+    let signature = run_nodes(sessions).await.pop().unwrap();
+    let (sig, rec_id) = signature.to_backend();
+
+    // The result is a plain ECDSA signature verifiable with `k256`
+    signing_key
+        .verifying_key()
+        .verify_prehash(message, &sig)
+        .unwrap();
+    let recovered_key = VerifyingKey::recover_from_prehash(message, &sig, rec_id).unwrap();
+    assert_eq!(&recovered_key, signing_key.verifying_key());
+}
+
+#[test]
+fn cross_protocol_message() {
+    // The first rounds of KeyInit and AuxGen both broadcast a bare hash,
+    // but a message from one must not be accepted by the other.
+
+    let (signers, verifiers) = make_signers(2);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let session_id = SessionId::from_seed(b"abcde");
+
+    let key_init_session = make_key_init_session::<TestParams, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signers[0].clone(),
+        &verifiers_set,
+    )
+    .unwrap();
+    let aux_gen_session = make_aux_gen_session::<TestParams, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signers[1].clone(),
+        &verifiers_set,
+    )
+    .unwrap();
+
+    let (message, _artifact) = key_init_session
+        .make_message(&mut OsRng, &verifiers[1])
+        .unwrap();
+    assert_eq!(message.protocol(), ProtocolId::KeyInit);
+
+    let mut accum = aux_gen_session.make_accumulator();
+    let result = aux_gen_session.preprocess_message(&mut accum, &verifiers[0], message);
+    assert!(matches!(
+        result,
+        Err(Error::Remote(RemoteError {
+            error: RemoteErrorEnum::UnexpectedProtocol,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn mismatched_params() {
+    // A party using different scheme parameters is detected by everyone
+    // on its first message, instead of failing the verification in later rounds.
+
+    let (signers, verifiers) = make_signers(2);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let session_id = SessionId::from_seed(b"abcde");
+
+    let test_session = make_key_init_session::<TestParams, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signers[0].clone(),
+        &verifiers_set,
+    )
+    .unwrap();
+    let production_session = make_key_init_session::<ProductionParams, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signers[1].clone(),
+        &verifiers_set,
+    )
+    .unwrap();
+
+    let (message, _artifact) = test_session
+        .make_message(&mut OsRng, &verifiers[1])
+        .unwrap();
+    let mut accum = production_session.make_accumulator();
+    let result = production_session.preprocess_message(&mut accum, &verifiers[0], message);
+    assert!(matches!(
+        result,
+        Err(Error::Remote(RemoteError {
+            error: RemoteErrorEnum::MismatchedParams,
+            ..
+        }))
+    ));
+
+    let (message, _artifact) = production_session
+        .make_message(&mut OsRng, &verifiers[0])
+        .unwrap();
+    let mut accum = test_session.make_accumulator();
+    let result = test_session.preprocess_message(&mut accum, &verifiers[1], message);
+    assert!(matches!(
+        result,
+        Err(Error::Remote(RemoteError {
+            error: RemoteErrorEnum::MismatchedParams,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn collect_all_faults() {
+    let (signers, verifiers) = make_signers(3);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let session_id = SessionId::from_seed(b"abcde");
+    let other_session_id = SessionId::from_seed(b"fghij");
+
+    let make_session = |signer: &SigningKey, session_id| {
+        make_aux_gen_session::<TestParams, Signature, _, _>(
+            &mut OsRng,
+            session_id,
+            signer.clone(),
+            &verifiers_set,
+        )
+        .unwrap()
+    };
+
+    // Parties 1 and 2 send messages belonging to a different session to party 0.
+    let messages = signers[1..]
+        .iter()
+        .map(|signer| {
+            let (message, _artifact) = make_session(signer, other_session_id)
+                .make_message(&mut OsRng, &verifiers[0])
+                .unwrap();
+            (*signer.verifying_key(), message)
+        })
+        .collect::<Vec<_>>();
+
+    // With the default policy the first fault is returned right away
+    let session = make_session(&signers[0], session_id);
+    let mut accum = session.make_accumulator();
+    let (from, message) = messages[0].clone();
+    let err = session
+        .preprocess_message(&mut accum, &from, message)
+        .err()
+        .unwrap();
+    assert!(accum.add_fault(err).is_err());
+
+    // With `CollectAll` the faults of all the parties are reported on finalization
+    let mut session = make_session(&signers[0], session_id);
+    session.set_abort_policy(AbortPolicy::CollectAll);
+    let mut accum = session.make_accumulator();
+    for (from, message) in messages {
+        let err = session
+            .preprocess_message(&mut accum, &from, message)
+            .err()
+            .unwrap();
+        assert!(!session.can_finalize(&accum).unwrap());
+        accum.add_fault(err).unwrap();
+    }
+    assert!(session.can_finalize(&accum).unwrap());
+    assert!(session.missing_messages(&accum).unwrap().is_empty());
+
+    let faults = match session.finalize_round(&mut OsRng, accum) {
+        Err(Error::Faults(faults)) => faults,
+        _ => panic!("Expected a collection of faults"),
+    };
+    assert_eq!(
+        faults.keys().cloned().collect::<BTreeSet<_>>(),
+        BTreeSet::from([verifiers[1], verifiers[2]])
+    );
+    assert!(faults.values().all(|fault| matches!(
+        fault,
+        Error::Remote(RemoteError {
+            error: RemoteErrorEnum::UnexpectedSessionId,
+            ..
+        })
+    )));
+}
+
+#[cfg(feature = "relay")]
+#[test]
+fn keygen_through_relay() {
+    use synedrion::sessions::RelayedMessage;
+
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let mut sessions = signers
+        .into_iter()
+        .map(|signer| {
+            let session = make_key_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+            )
+            .unwrap();
+            (session.verifier(), session)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut results = BTreeMap::new();
+    let mut wrong_recipient_checked = false;
+
+    // The sessions are run in lockstep, with each round's messages sent to the relay first.
+    while !sessions.is_empty() {
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        // What the relay receives and forwards.
+        let mut relayed: Vec<(VerifyingKey, VerifyingKey, RelayedMessage<Signature>)> = Vec::new();
+        for (id, session) in sessions.iter() {
+            let (message, artifacts) = session.make_relay_message(&mut OsRng).unwrap();
+            for artifact in artifacts {
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+            }
+            for (destination, part) in message.split() {
+                relayed.push((*id, destination, part));
+            }
+        }
+
+        for (from, to, part) in relayed {
+            let session = &sessions[&to];
+
+            // A sealed part forwarded to a wrong recipient cannot be opened
+            // (the parts without one only contain the broadcast and can be opened by anyone).
+            let (_other, other_session) = sessions
+                .iter()
+                .find(|(other, _)| **other != from && **other != to)
+                .unwrap();
+            match other_session.open_relayed_message(&from, part.clone()) {
+                Ok(_) => {}
+                Err(Error::Remote(RemoteError {
+                    error: RemoteErrorEnum::InvalidContents(_),
+                    ..
+                })) => wrong_recipient_checked = true,
+                Err(err) => panic!("Unexpected error: {err:?}"),
+            }
+
+            let accum = accums.get_mut(&to).unwrap();
+            let message = session.open_relayed_message(&from, part).unwrap();
+            let preprocessed = session
+                .preprocess_message(accum, &from, message)
+                .unwrap()
+                .unwrap();
+            let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            assert!(session.can_finalize(&accum).unwrap());
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(result) => {
+                    results.insert(id, result);
+                }
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+        sessions = next_sessions;
+    }
+
+    assert!(wrong_recipient_checked);
+    let key_shares = results
+        .values()
+        .map(|(key_share, _aux_info)| key_share)
+        .collect::<Vec<_>>();
+    for key_share in key_shares.iter() {
+        assert_eq!(key_share.verifying_key(), key_shares[0].verifying_key());
+    }
+}
+
+#[cfg(feature = "relay")]
+#[test]
+fn keygen_through_aggregating_relay() {
+    use synedrion::sessions::RelayAggregator;
+
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let mut sessions = signers
+        .into_iter()
+        .map(|signer| {
+            let session = make_key_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+            )
+            .unwrap();
+            (session.verifier(), session)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut results = BTreeMap::new();
+
+    // Each party sends its messages to the relay in envelopes for one destination each,
+    // and receives all the messages for it in a single envelope.
+    while !sessions.is_empty() {
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut aggregator = RelayAggregator::new(num_parties - 1);
+        for (id, session) in sessions.iter() {
+            let (message, artifacts) = session.make_relay_message(&mut OsRng).unwrap();
+            for artifact in artifacts {
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+            }
+            let chunks = message.into_chunks(1);
+            assert_eq!(chunks.len(), num_parties - 1);
+            for chunk in chunks {
+                aggregator.add_message(id, chunk);
+            }
+        }
+
+        for (to, batches) in aggregator.into_batches() {
+            assert_eq!(batches.len(), 1);
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            for batch in batches {
+                for (from, message) in session.open_relayed_batch(batch) {
+                    let preprocessed = session
+                        .preprocess_message(accum, &from, message.unwrap())
+                        .unwrap()
+                        .unwrap();
+                    let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+                    accum.add_processed_message(processed).unwrap().unwrap();
+                }
+            }
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(result) => {
+                    results.insert(id, result);
+                }
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+        sessions = next_sessions;
+    }
+
+    let key_shares = results
+        .values()
+        .map(|(key_share, _aux_info)| key_share)
+        .collect::<Vec<_>>();
+    for key_share in key_shares.iter() {
+        assert_eq!(key_share.verifying_key(), key_shares[0].verifying_key());
+    }
+}
+
+#[test]
+fn key_init_with_redelivery() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let mut sessions = signers
+        .into_iter()
+        .map(|signer| {
+            let mut session = make_key_init_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+            )
+            .unwrap();
+            session.set_message_retention(true);
+            (session.verifier(), session)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut results = BTreeMap::new();
+    let mut finished_rounds = Vec::new();
+
+    // The sessions are run in lockstep, with every message delivered twice.
+    while !sessions.is_empty() {
+        let round = sessions.values().next().unwrap().current_round();
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut messages = Vec::new();
+        for (id, session) in sessions.iter() {
+            for destination in session.message_destinations().iter() {
+                let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+                messages.push((*id, *destination, message));
+            }
+        }
+
+        for (from, to, message) in messages {
+            let resent = sessions[&from]
+                .resend(&accums[&from], sessions[&from].current_round(), &to)
+                .unwrap();
+
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            let preprocessed = session
+                .preprocess_message(accum, &from, message)
+                .unwrap()
+                .unwrap();
+            let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+
+            // The repeated delivery is silently ignored
+            assert!(session
+                .preprocess_message(accum, &from, resent)
+                .unwrap()
+                .is_none());
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(result) => {
+                    results.insert(id, result);
+                }
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    // The messages of the finished rounds are still available
+                    let accum = session.make_accumulator();
+                    let destination = session.message_destinations().first().unwrap();
+                    for round in finished_rounds.iter().chain([&round]) {
+                        assert!(session.resend(&accum, *round, destination).is_some());
+                    }
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+        finished_rounds.push(round);
+        sessions = next_sessions;
+    }
+
+    let key_shares = results.values().collect::<Vec<_>>();
+    for key_share in key_shares.iter() {
+        assert_eq!(key_share.verifying_key(), key_shares[0].verifying_key());
+    }
+}
+
+#[test]
+fn key_init_with_concurrent_redelivery() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let mut sessions = signers
+        .into_iter()
+        .map(|signer| {
+            let mut session = make_key_init_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+            )
+            .unwrap();
+            session.set_message_retention(true);
+            (session.verifier(), session)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut results = BTreeMap::new();
+
+    // Every message is delivered twice, and the second copy is preprocessed
+    // before the first one is processed and added to the accumulator
+    // (as it happens when the processing is offloaded to other tasks).
+    while !sessions.is_empty() {
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut messages = Vec::new();
+        for (id, session) in sessions.iter() {
+            for destination in session.message_destinations().iter() {
+                let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+                messages.push((*id, *destination, message));
+            }
+        }
+
+        for (from, to, message) in messages {
+            let resent = sessions[&from]
+                .resend(&accums[&from], sessions[&from].current_round(), &to)
+                .unwrap();
+
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            let preprocessed = session
+                .preprocess_message(accum, &from, message)
+                .unwrap()
+                .unwrap();
+            let preprocessed_again = session
+                .preprocess_message(accum, &from, resent)
+                .unwrap()
+                .unwrap();
+
+            let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+            let processed_again = session
+                .process_message(&mut OsRng, preprocessed_again)
+                .unwrap();
+
+            // The second copy is not reported as a duplicate
+            accum.add_processed_message(processed).unwrap().unwrap();
+            accum
+                .add_processed_message(processed_again)
+                .unwrap()
+                .unwrap();
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(result) => {
+                    results.insert(id, result);
+                }
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+        sessions = next_sessions;
+    }
+
+    let key_shares = results.values().collect::<Vec<_>>();
+    for key_share in key_shares.iter() {
+        assert_eq!(key_share.verifying_key(), key_shares[0].verifying_key());
+    }
+}
+
+#[test]
+fn key_init_timeout() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let session_id = SessionId::from_seed(b"1234567890");
+    let clock = Arc::new(ManualClock::new(Duration::from_secs(1000)));
+
+    let sessions = signers
+        .into_iter()
+        .map(|signer| {
+            let mut session = make_key_init_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+            )
+            .unwrap();
+            session.set_deadlines(clock.clone(), Some(Duration::from_secs(10)), None);
+            (session.verifier(), session)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    // The messages of the last party are never delivered
+    let silent = verifiers[num_parties - 1];
+    let mut accums = sessions
+        .iter()
+        .map(|(id, session)| (*id, session.make_accumulator()))
+        .collect::<BTreeMap<_, _>>();
+    for (id, session) in sessions.iter().filter(|(id, _)| **id != silent) {
+        for destination in session.message_destinations().iter() {
+            let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+            accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+            let receiver = &sessions[destination];
+            let accum = accums.get_mut(destination).unwrap();
+            let preprocessed = receiver
+                .preprocess_message(accum, id, message)
+                .unwrap()
+                .unwrap();
+            let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+    }
+
+    let id = &verifiers[0];
+    let session = &sessions[id];
+    assert_eq!(session.deadline(), Some(Duration::from_secs(1010)));
+    assert!(session.check_deadline(&accums[id]).is_ok());
+
+    clock.advance(Duration::from_secs(10));
+    match session.check_deadline(&accums[id]) {
+        Err(Error::Timeout { missing }) => assert_eq!(missing, BTreeSet::from([silent])),
+        result => panic!("Unexpected result: {result:?}"),
+    }
+}
+
+#[test]
+fn max_parties() {
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let (signers, verifiers) = make_signers(MAX_PARTIES + 1);
+    let signer = signers[0].clone();
+
+    // The maximum number of parties is supported
+    let verifiers_set = BTreeSet::from_iter(verifiers[..MAX_PARTIES].iter().cloned());
+    assert!(make_key_init_session::<TestParams, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signer.clone(),
+        &verifiers_set,
+    )
+    .is_ok());
+
+    // One more is rejected, whether the set includes this party or not
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let result = make_key_init_session::<TestParams, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signer.clone(),
+        &verifiers_set,
+    );
+    assert!(matches!(
+        result,
+        Err(SessionInitError::TooManyParties(num_parties)) if num_parties == MAX_PARTIES + 1
+    ));
+
+    let verifiers_set = BTreeSet::from_iter(verifiers[1..].iter().cloned());
+    let result = make_key_init_session::<TestParams, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signer,
+        &verifiers_set,
+    );
+    assert!(matches!(
+        result,
+        Err(SessionInitError::TooManyParties(num_parties)) if num_parties == MAX_PARTIES + 1
+    ));
+}
+
+/// Runs the sessions in lockstep, returning the results and all the messages sent.
+#[allow(clippy::type_complexity)]
+fn run_collecting_messages<Res: ProtocolResult>(
+    sessions: Vec<Session<Res, Signature, SigningKey, VerifyingKey>>,
+) -> (
+    BTreeMap<VerifyingKey, Res::Success>,
+    Vec<(VerifyingKey, MessageBundle<Signature>)>,
+) {
+    let mut sessions = sessions
+        .into_iter()
+        .map(|session| (session.verifier(), session))
+        .collect::<BTreeMap<_, _>>();
+    let mut results = BTreeMap::new();
+    let mut sent = Vec::new();
+
+    while !sessions.is_empty() {
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut messages = Vec::new();
+        for (id, session) in sessions.iter() {
+            for destination in session.message_destinations().iter() {
+                let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+                messages.push((*id, *destination, message));
+            }
+        }
+
+        for (from, to, message) in messages {
+            sent.push((from, message.clone()));
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            let preprocessed = session
+                .preprocess_message(accum, &from, message)
+                .unwrap()
+                .unwrap();
+            let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(result) => {
+                    results.insert(id, result);
+                }
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+        sessions = next_sessions;
+    }
+
+    (results, sent)
+}
+
+#[test]
+fn light_verifier_key_init() {
+    let (signers, verifiers) = make_signers(3);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let sessions = signers
+        .into_iter()
+        .map(|signer| {
+            make_key_init_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+            )
+            .unwrap()
+        })
+        .collect();
+    let (key_shares, messages) = run_collecting_messages(sessions);
+
+    let mut transcript = Transcript::new(session_id, ProtocolId::KeyInit, &verifiers_set);
+    for (from, message) in messages.iter() {
+        transcript.add_message(from, message).unwrap();
+    }
+
+    // The transcript survives the serialization
+    let transcript: Transcript<Signature, VerifyingKey> =
+        Transcript::from_bytes(&transcript.to_bytes().unwrap()).unwrap();
+
+    let verifier = LightVerifier::<TestParams>::new();
+    let report = verifier.verify_key_init(&transcript).unwrap();
+    assert!(report.is_valid());
+    assert_eq!(
+        report.outcome(),
+        Some(&key_shares[&verifiers[0]].verifying_key())
+    );
+
+    // A transcript of a different protocol is rejected
+    assert_eq!(
+        verifier.verify_aux_gen(&transcript).unwrap_err(),
+        TranscriptError::WrongProtocol(ProtocolId::KeyInit)
+    );
+
+    // A missing broadcast is reported
+    let mut incomplete = Transcript::new(session_id, ProtocolId::KeyInit, &verifiers_set);
+    for (from, message) in messages.iter() {
+        if from != &verifiers[1] || message.round() != 3 {
+            incomplete.add_message(from, message).unwrap();
+        }
+    }
+    let report = verifier.verify_key_init(&incomplete).unwrap();
+    assert!(!report.is_valid());
+    assert_eq!(report.missing(), &[(3, verifiers[1])]);
+    assert!(report.outcome().is_none());
+}
+
+#[test]
+fn light_verifier_aux_gen() {
+    let (signers, verifiers) = make_signers(3);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let sessions = signers
+        .into_iter()
+        .map(|signer| {
+            make_aux_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer,
+                &verifiers_set,
+            )
+            .unwrap()
+        })
+        .collect();
+    let (_aux_infos, messages) = run_collecting_messages(sessions);
+
+    let mut transcript = Transcript::new(session_id, ProtocolId::AuxGen, &verifiers_set);
+    for (from, message) in messages.iter() {
+        transcript.add_message(from, message).unwrap();
+    }
+
+    let report = LightVerifier::<TestParams>::new()
+        .verify_aux_gen(&transcript)
+        .unwrap();
+    assert!(report.is_valid());
+    assert!(report.unchecked().contains(&Check::ModProof));
+    let public_aux = report.outcome().unwrap();
+    assert!(public_aux.keys().eq(verifiers_set.iter()));
+}
+
+/// Runs the sessions and the observer sessions in lockstep,
+/// returning the results of the former and the transcripts collected by the latter.
+#[allow(clippy::type_complexity)]
+fn run_with_observers<Res: ProtocolResult>(
+    sessions: Vec<Session<Res, Signature, SigningKey, VerifyingKey>>,
+    observers: Vec<ObserverSession<Signature, SigningKey, VerifyingKey>>,
+) -> (
+    BTreeMap<VerifyingKey, Res::Success>,
+    BTreeMap<VerifyingKey, Transcript<Signature, VerifyingKey>>,
+) {
+    let mut sessions = sessions
+        .into_iter()
+        .map(|session| (session.verifier(), session))
+        .collect::<BTreeMap<_, _>>();
+    let mut observers = observers
+        .into_iter()
+        .map(|observer| (observer.verifier(), observer))
+        .collect::<BTreeMap<_, _>>();
+    let mut results = BTreeMap::new();
+    let mut transcripts = BTreeMap::new();
+
+    while !sessions.is_empty() {
+        let mut accums = sessions
+            .iter()
+            .map(|(id, session)| (*id, session.make_accumulator()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut messages = Vec::new();
+        for (id, session) in sessions.iter() {
+            for destination in session.message_destinations().iter() {
+                let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+                accums.get_mut(id).unwrap().add_artifact(artifact).unwrap();
+                messages.push((*id, *destination, message));
+            }
+        }
+        for (id, observer) in observers.iter() {
+            for destination in observer.message_destinations().iter() {
+                let message = observer.make_message(&mut OsRng, destination).unwrap();
+                messages.push((*id, *destination, message));
+            }
+        }
+
+        for (from, to, message) in messages {
+            if let Some(observer) = observers.get_mut(&to) {
+                observer.receive_message(&from, message).unwrap();
+                continue;
+            }
+            let session = &sessions[&to];
+            let accum = accums.get_mut(&to).unwrap();
+            let preprocessed = session
+                .preprocess_message(accum, &from, message)
+                .unwrap()
+                .unwrap();
+            let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+
+        let mut next_sessions = BTreeMap::new();
+        for (id, session) in sessions.into_iter() {
+            let accum = accums.remove(&id).unwrap();
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                FinalizeOutcome::Success(result) => {
+                    results.insert(id, result);
+                }
+                FinalizeOutcome::AnotherRound { session, .. } => {
+                    next_sessions.insert(id, session);
+                }
+            }
+        }
+        sessions = next_sessions;
+
+        // The observers skip the rounds without broadcasts
+        let mut next_observers = BTreeMap::new();
+        for (id, observer) in observers.into_iter() {
+            if !observer.can_finalize() {
+                next_observers.insert(id, observer);
+                continue;
+            }
+            match observer.finalize_round().unwrap() {
+                ObserverOutcome::Success(transcript) => {
+                    transcripts.insert(id, transcript);
+                }
+                ObserverOutcome::AnotherRound {
+                    mut session,
+                    cached_messages,
+                } => {
+                    for (from, message) in cached_messages {
+                        session.receive_message(&from, message).unwrap();
+                    }
+                    next_observers.insert(id, session);
+                }
+            }
+        }
+        observers = next_observers;
+    }
+
+    assert!(observers.is_empty());
+    (results, transcripts)
+}
+
+#[test]
+fn key_init_with_observers() {
+    let (signers, verifiers) = make_signers(5);
+    let contributors = BTreeSet::from_iter(verifiers[..3].iter().cloned());
+    let observer_ids = BTreeSet::from_iter(verifiers[3..].iter().cloned());
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let sessions = signers[..3]
+        .iter()
+        .map(|signer| {
+            let mut session = make_key_init_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &contributors,
+            )
+            .unwrap();
+            session.set_observers(&observer_ids).unwrap();
+            session
+        })
+        .collect();
+    let observers = signers[3..]
+        .iter()
+        .map(|signer| {
+            ObserverSession::new(
+                session_id,
+                ProtocolId::KeyInit,
+                signer.clone(),
+                &contributors,
+                &observer_ids,
+            )
+            .unwrap()
+        })
+        .collect();
+    let (key_shares, transcripts) = run_with_observers(sessions, observers);
+
+    // Every observer has a complete transcript of the broadcasts
+    assert!(transcripts.keys().eq(observer_ids.iter()));
+    let verifier = LightVerifier::<TestParams>::new();
+    for transcript in transcripts.values() {
+        let report = verifier.verify_key_init(transcript).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(
+            report.outcome(),
+            Some(&key_shares[&verifiers[0]].verifying_key())
+        );
+    }
+
+    // The observers cannot be the parties of the session
+    let mut session = make_key_init_session::<TestParams, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signers[0].clone(),
+        &contributors,
+    )
+    .unwrap();
+    assert!(session
+        .set_observers(&BTreeSet::from([verifiers[1]]))
+        .is_err());
+
+    // The observers cannot be set once the messages are created
+    let _message = session.make_message(&mut OsRng, &verifiers[1]).unwrap();
+    assert!(session.set_observers(&observer_ids).is_err());
+    assert!(ObserverSession::<Signature, _, _>::new(
+        session_id,
+        ProtocolId::KeyInit,
+        signers[3].clone(),
+        &contributors,
+        &BTreeSet::from([verifiers[0], verifiers[3]]),
+    )
+    .is_err());
+}
+
+#[test]
+fn aux_gen_with_observers() {
+    let (signers, verifiers) = make_signers(4);
+    let contributors = BTreeSet::from_iter(verifiers[..3].iter().cloned());
+    let observer_ids = BTreeSet::from([verifiers[3]]);
+    let session_id = SessionId::from_seed(b"1234567890");
+
+    let sessions = signers[..3]
+        .iter()
+        .map(|signer| {
+            let mut session = make_aux_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &contributors,
+            )
+            .unwrap();
+            session.set_observers(&observer_ids).unwrap();
+            session
+        })
+        .collect();
+    let observer = ObserverSession::new(
+        session_id,
+        ProtocolId::AuxGen,
+        signers[3].clone(),
+        &contributors,
+        &observer_ids,
+    )
+    .unwrap();
+    // The last round only has direct messages, which the observer does not get
+    let (aux_infos, transcripts) = run_with_observers(sessions, vec![observer]);
+    assert_eq!(aux_infos.len(), 3);
+
+    let report = LightVerifier::<TestParams>::new()
+        .verify_aux_gen(&transcripts[&verifiers[3]])
+        .unwrap();
+    assert!(report.is_valid());
+    let public_aux = report.outcome().unwrap();
+    assert!(public_aux.keys().eq(contributors.iter()));
+}
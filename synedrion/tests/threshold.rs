@@ -8,8 +8,9 @@ use tokio::time::{sleep, Duration};
 
 use synedrion::{
     make_aux_gen_session, make_interactive_signing_session, make_key_init_session,
-    make_key_resharing_session, DeriveChildKey, FinalizeOutcome, KeyResharingInputs, MessageBundle,
-    NewHolder, OldHolder, ProtocolResult, Session, SessionId, TestParams, ThresholdKeyShare,
+    make_key_resharing_session, Ceremony, DeriveChildKey, FinalizeOutcome, KeyResharingInputs,
+    MessageBundle, NewHolder, OldHolder, ProtocolResult, Session, SessionId, TestParams,
+    ThresholdKeyShare,
 };
 
 type MessageOut = (VerifyingKey, VerifyingKey, MessageBundle<Signature>);
@@ -31,8 +32,10 @@ async fn run_session<Res: ProtocolResult>(
 
     let key = session.verifier();
     let key_str = key_to_str(&key);
+    let mut round_trips = 0;
 
     loop {
+        round_trips += 1;
         println!(
             "{key_str}: *** starting round {:?} ***",
             session.current_round()
@@ -98,7 +101,10 @@ async fn run_session<Res: ProtocolResult>(
         println!("{key_str}: finalizing the round");
 
         match session.finalize_round(&mut OsRng, accum).unwrap() {
-            FinalizeOutcome::Success(res) => break res,
+            FinalizeOutcome::Success(res) => {
+                assert_eq!(round_trips, Res::round_trip_count());
+                break res;
+            }
             FinalizeOutcome::AnotherRound {
                 session: new_session,
                 cached_messages: new_cached_messages,
@@ -162,6 +168,8 @@ async fn run_nodes<Res>(
 where
     Res: ProtocolResult + Send + 'static,
     Res::Success: Send,
+    Res::ProvableError: Send,
+    Res::CorrectnessProof: Send,
 {
     let num_parties = sessions.len();
 
@@ -173,7 +181,7 @@ where
     let tx_map = sessions
         .iter()
         .map(|session| session.verifier())
-        .zip(txs.into_iter())
+        .zip(txs)
         .collect();
 
     let dispatcher_task = message_dispatcher(tx_map, dispatcher_rx);
@@ -181,7 +189,7 @@ where
 
     let handles: Vec<tokio::task::JoinHandle<Res::Success>> = rxs
         .into_iter()
-        .zip(sessions.into_iter())
+        .zip(sessions)
         .map(|(rx, session)| {
             let node_task = run_session(dispatcher_tx.clone(), rx, session);
             tokio::spawn(node_task)
@@ -264,16 +272,21 @@ async fn full_sequence() {
                 }),
                 new_holder: Some(new_holder.clone()),
                 new_holders: all_verifiers.clone(),
+                new_share_ids: None,
                 new_threshold: t,
             };
-            make_key_resharing_session::<TestParams, Signature, SigningKey, VerifyingKey>(
-                &mut OsRng,
-                session_id,
-                signers[idx].clone(),
-                &all_verifiers,
-                inputs,
-            )
-            .unwrap()
+            let mut session =
+                make_key_resharing_session::<TestParams, Signature, SigningKey, VerifyingKey>(
+                    &mut OsRng,
+                    session_id,
+                    signers[idx].clone(),
+                    &all_verifiers,
+                    inputs,
+                )
+                .unwrap();
+            // The subshares sent by the old holders will be checked before sending
+            session.set_strict_mode(true);
+            session
         })
         .collect::<Vec<_>>();
 
@@ -284,6 +297,7 @@ async fn full_sequence() {
                 old_holder: None,
                 new_holder: Some(new_holder.clone()),
                 new_holders: all_verifiers.clone(),
+                new_share_ids: None,
                 new_threshold: t,
             };
             make_key_resharing_session::<TestParams, Signature, SigningKey, VerifyingKey>(
@@ -318,6 +332,34 @@ async fn full_sequence() {
         .unwrap();
     assert_eq!(child_vkey, child_vkey_after_resharing);
 
+    // The old holders' lineage continues from KeyInit,
+    // while the new holders' lineage starts from the resharing.
+    let vkey = new_t_key_shares[0].verifying_key();
+    for (idx, key_share) in new_t_key_shares.iter().enumerate() {
+        let lineage = key_share.lineage().unwrap();
+        assert!(lineage.verify(&vkey));
+        assert!(lineage.contains_session(&session_id));
+        let ceremonies = lineage
+            .entries()
+            .iter()
+            .map(|entry| entry.ceremony())
+            .collect::<Vec<_>>();
+        if idx < t {
+            assert_eq!(ceremonies, [Ceremony::KeyInit, Ceremony::KeyResharing]);
+            assert_eq!(
+                lineage.head(),
+                new_t_key_shares[0].lineage().unwrap().head()
+            );
+        } else {
+            assert_eq!(ceremonies, [Ceremony::KeyResharing]);
+            assert_eq!(
+                lineage.head(),
+                new_t_key_shares[t].lineage().unwrap().head()
+            );
+        }
+    }
+    assert!(child_key_shares[0].lineage().is_none());
+
     // Generate auxiliary data
 
     let sessions = (0..n)
@@ -339,9 +381,9 @@ async fn full_sequence() {
     // - derive child key shares
     // - convert their threshold key shares into regular key shares.
 
-    let selected_signers = vec![signers[0].clone(), signers[2].clone(), signers[4].clone()];
+    let selected_signers = [signers[0].clone(), signers[2].clone(), signers[4].clone()];
     let selected_parties = BTreeSet::from([verifiers[0], verifiers[2], verifiers[4]]);
-    let selected_key_shares = vec![
+    let selected_key_shares = [
         new_t_key_shares[0]
             .derive_bip32(&path)
             .unwrap()
@@ -355,7 +397,7 @@ async fn full_sequence() {
             .unwrap()
             .to_key_share(&selected_parties),
     ];
-    let selected_aux_infos = vec![
+    let selected_aux_infos = [
         aux_infos[0].clone(),
         aux_infos[2].clone(),
         aux_infos[4].clone(),